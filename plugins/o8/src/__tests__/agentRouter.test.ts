@@ -0,0 +1,115 @@
+/**
+ * Test suite for agent routing
+ *
+ * Tests:
+ * - Recommending the best agent, with runners-up
+ * - Exclusion, capability, and token budget constraints
+ * - Confidence for clear and close decisions
+ * - Designated fallback agent when nothing matches
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("ResourceLoader.routeAgent", () => {
+  let root: string;
+  let loader: ResourceLoader;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-route-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeFile(
+      join(root, "agents", "rust-security.md"),
+      "---\ntags: [rust, security]\ncapabilities: [security audit]\nlanguages: [rust]\noperations: [review]\n---\n# Rust Security\n",
+    );
+    await writeFile(
+      join(root, "agents", "ts-security.md"),
+      "---\ntags: [typescript, security]\ncapabilities: [security audit]\nlanguages: [ts]\noperations: [review]\n---\n# TS Security\n",
+    );
+    await writeFile(
+      join(root, "agents", "generalist.md"),
+      "---\ntags: [general]\nfallback: true\n---\n# Generalist\n",
+    );
+    await writeFile(
+      join(root, "agents", "rust-architect.md"),
+      `---\ntags: [rust, architecture]\nlanguages: [rust]\n---\n# Rust Architect\n\n${"Design notes. ".repeat(400)}\n`,
+    );
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should recommend the best agent with alternatives", async () => {
+    const decision = await loader.routeAgent({
+      task: "rust security audit",
+    });
+
+    assert.strictEqual(decision.agent?.id, "agents/rust-security");
+    assert.ok(decision.alternatives.length > 0);
+    assert.ok(
+      decision.alternatives.every(
+        (candidate) => candidate.score <= decision.agent!.score,
+      ),
+    );
+    assert.ok(decision.agent!.confidence > 0);
+    assert.ok(decision.agent!.confidence <= 1);
+  });
+
+  it("should skip excluded agents", async () => {
+    const decision = await loader.routeAgent({
+      task: "rust security audit",
+      exclude: ["rust-security"],
+    });
+
+    assert.notStrictEqual(decision.agent?.id, "agents/rust-security");
+    assert.strictEqual(decision.filtered.excluded, 1);
+  });
+
+  it("should require the requested capabilities", async () => {
+    const decision = await loader.routeAgent({
+      task: "security audit",
+      capabilities: { languages: ["typescript"] },
+    });
+
+    assert.strictEqual(decision.agent?.id, "agents/ts-security");
+    assert.deepStrictEqual(decision.alternatives, []);
+    assert.strictEqual(decision.filtered.capabilities, 3);
+  });
+
+  it("should leave out agents over the token budget", async () => {
+    const decision = await loader.routeAgent({
+      task: "rust architecture",
+      maxTokens: 500,
+    });
+
+    assert.notStrictEqual(decision.agent?.id, "agents/rust-architect");
+    assert.strictEqual(decision.filtered.budget, 1);
+  });
+
+  it("should be less confident when the top agents are close", async () => {
+    const clear = await loader.routeAgent({ task: "rust security audit" });
+    const close = await loader.routeAgent({ task: "security audit" });
+
+    assert.ok(close.agent!.confidence < clear.agent!.confidence);
+  });
+
+  it("should fall back to the designated agent", async () => {
+    const decision = await loader.routeAgent({ task: "kubernetes helm" });
+
+    assert.strictEqual(decision.fallback, true);
+    assert.strictEqual(decision.agent?.id, "agents/generalist");
+    assert.strictEqual(decision.agent?.confidence, 0);
+  });
+});
@@ -0,0 +1,90 @@
+/**
+ * Test suite for resource aliases
+ *
+ * Tests:
+ * - Renamed resources stay readable under their aliases
+ * - Alias resolution for provenance and canonical flags
+ * - Collisions with real ids and between aliases are validation failures
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("ResourceLoader aliases", () => {
+  let root: string;
+  let loader: ResourceLoader;
+  const writeAgent = async (name: string, frontmatter: string, body: string) => {
+    await writeFile(
+      join(root, "agents", `${name}.md`),
+      `---\n${frontmatter}\n---\n${body}\n`,
+    );
+  };
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-aliases-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeAgent(
+      "rust-expert",
+      "tags: [rust]\naliases: [rust-developer, agents/rustacean]",
+      "# Rust Expert",
+    );
+    await writeAgent("go-expert", "tags: [go]\naliases: [python-expert]", "# Go Expert");
+    await writeAgent("python-expert", "tags: [python]", "# Python Expert");
+    await writeAgent("zig-expert", "tags: [zig]\naliases: rust-developer", "# Zig Expert");
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should read a renamed resource under its old name", async () => {
+    const content = await loader.loadResourceContent("o8://agents/rust-developer");
+    assert.match(content, /# Rust Expert/);
+    assert.match(
+      await loader.loadResourceContent("o8://agents/rustacean"),
+      /# Rust Expert/,
+    );
+  });
+
+  it("should resolve aliases to the canonical id", async () => {
+    assert.strictEqual(
+      await loader.resolveAlias("o8://agents/rust-developer"),
+      "agents/rust-expert",
+    );
+    assert.strictEqual(await loader.resolveAlias("agents/rust-expert"), null);
+    assert.strictEqual(
+      (await loader.getResourceProvenance("agents/rustacean"))?.id,
+      "agents/rust-expert",
+    );
+  });
+
+  it("should report alias collisions as validation failures", async () => {
+    assert.match(
+      await loader.loadResourceContent("o8://agents/python-expert"),
+      /# Python Expert/,
+    );
+
+    const { validation } = await loader.getCatalogSummary();
+    assert.deepStrictEqual(validation.failures, [
+      {
+        path: "agents/go-expert.md",
+        error: "Alias agents/python-expert collides with an existing resource",
+      },
+      {
+        path: "agents/zig-expert.md",
+        error: "Alias agents/rust-developer is already an alias of agents/rust-expert",
+      },
+    ]);
+  });
+});
@@ -0,0 +1,61 @@
+/**
+ * Test suite for --auto discovery
+ *
+ * Tests:
+ * - Project root from .orchestr8 or .git
+ * - Resource directory conventions and their order
+ * - Explicit environment variables win
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { applyAutoConfig, discoverConfig } from "../utils/autoDiscovery.js";
+
+describe("discoverConfig", () => {
+  let root: string;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-auto-"));
+    await mkdir(join(root, ".git"));
+    await mkdir(join(root, "plugins", "o8", "resources", "agents"), {
+      recursive: true,
+    });
+    await mkdir(join(root, "plugins", "o8", "prompts"));
+    await mkdir(join(root, "src", "deep"), { recursive: true });
+  });
+
+  after(async () => {
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should find plugin resources from a nested directory", async () => {
+    const config = await discoverConfig(join(root, "src", "deep"));
+
+    assert.strictEqual(config.root, root);
+    assert.strictEqual(
+      config.resourcesPath,
+      join(root, "plugins", "o8", "resources"),
+    );
+    assert.strictEqual(config.promptsPath, join(root, "plugins", "o8", "prompts"));
+  });
+
+  it("should prefer .orchestr8 over plugin resources", async () => {
+    await mkdir(join(root, ".orchestr8", "skills"), { recursive: true });
+
+    const config = await discoverConfig(root);
+    assert.strictEqual(config.resourcesPath, join(root, ".orchestr8"));
+    assert.deepStrictEqual(config.checked, [join(root, ".orchestr8")]);
+  });
+
+  it("should only fill variables that are not set", async () => {
+    const env: NodeJS.ProcessEnv = { PROMPTS_PATH: "/custom/prompts" };
+    const { summary } = await applyAutoConfig(root, env);
+
+    assert.strictEqual(env.RESOURCES_PATH, join(root, ".orchestr8"));
+    assert.strictEqual(env.PROMPTS_PATH, "/custom/prompts");
+    assert.match(summary, /prompts: \/custom\/prompts \(from PROMPTS_PATH\)/);
+  });
+});
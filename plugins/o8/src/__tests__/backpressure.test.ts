@@ -0,0 +1,84 @@
+/**
+ * Test suite for BackpressureGate
+ *
+ * Tests:
+ * - Sending below the high-water mark
+ * - Dropping notifications for slow clients
+ * - Closing clients that stay behind
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  BackpressureGate,
+  BufferedSocket,
+  SLOW_CONSUMER_CLOSE_CODE,
+} from "../transports/backpressure.js";
+
+function createSocket(bufferedAmount = 0) {
+  const socket = {
+    bufferedAmount,
+    sent: [] as string[],
+    closedWith: null as number | null,
+    send(data: string) {
+      socket.sent.push(data);
+    },
+    close(code?: number) {
+      socket.closedWith = code ?? null;
+    },
+  };
+  return socket satisfies BufferedSocket;
+}
+
+describe("BackpressureGate", () => {
+  it("should send while the buffer is below the high-water mark", () => {
+    const gate = new BackpressureGate({ highWaterMark: 100 });
+    const socket = createSocket(100);
+
+    assert.strictEqual(gate.send(socket, "a"), "sent");
+    assert.deepStrictEqual(socket.sent, ["a"]);
+  });
+
+  it("should drop notifications when the client falls behind", () => {
+    const gate = new BackpressureGate({ highWaterMark: 100 });
+    const socket = createSocket(500);
+
+    assert.strictEqual(gate.send(socket, "a"), "dropped");
+    assert.strictEqual(gate.send(socket, "b"), "dropped");
+    assert.deepStrictEqual(socket.sent, []);
+    assert.strictEqual(gate.getStats().dropped, 2);
+
+    socket.bufferedAmount = 0;
+    assert.strictEqual(gate.send(socket, "c"), "sent");
+    assert.deepStrictEqual(socket.sent, ["c"]);
+  });
+
+  it("should close sessions that stay behind too long", () => {
+    const gate = new BackpressureGate({
+      highWaterMark: 100,
+      maxDroppedMessages: 2,
+    });
+    const socket = createSocket(500);
+
+    gate.send(socket, "a");
+    gate.send(socket, "b");
+    assert.strictEqual(gate.send(socket, "c"), "closed");
+    assert.strictEqual(socket.closedWith, SLOW_CONSUMER_CLOSE_CODE);
+    assert.strictEqual(gate.getStats().closed, 1);
+  });
+
+  it("should reset the drop count after a successful send", () => {
+    const gate = new BackpressureGate({
+      highWaterMark: 100,
+      maxDroppedMessages: 1,
+    });
+    const socket = createSocket(500);
+
+    assert.strictEqual(gate.send(socket, "a"), "dropped");
+    socket.bufferedAmount = 0;
+    gate.send(socket, "b");
+    socket.bufferedAmount = 500;
+    assert.strictEqual(gate.send(socket, "c"), "dropped");
+    assert.strictEqual(socket.closedWith, null);
+  });
+});
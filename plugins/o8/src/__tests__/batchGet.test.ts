@@ -0,0 +1,89 @@
+/**
+ * Test suite for agents/batch_get
+ *
+ * Tests:
+ * - Names, ids, and URIs in one call, in request order
+ * - Per-id errors without failing the batch
+ * - Batch size limit from the environment
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+import { payloadLimitsFromEnv } from "../utils/limits.js";
+
+describe("ResourceLoader.batchGet", () => {
+  let root: string;
+  let loader: ResourceLoader;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-batch-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await mkdir(join(root, "skills"), { recursive: true });
+    await writeFile(
+      join(root, "agents", "rust-expert.md"),
+      "---\ntags: [rust]\naliases: [rust-developer]\n---\n# Rust Expert\n",
+    );
+    await writeFile(
+      join(root, "skills", "testing.md"),
+      "---\ntags: [testing]\n---\n# Testing\n",
+    );
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should return every requested resource in order", async () => {
+    const { resources } = await loader.batchGet([
+      "rust-expert",
+      "skills/testing",
+      "o8://agents/rust-developer",
+    ]);
+
+    assert.deepStrictEqual(
+      resources.map((resource) => resource.id),
+      ["agents/rust-expert", "skills/testing", "agents/rust-developer"],
+    );
+    assert.ok(resources.every((resource) => "content" in resource));
+    const [rust, testing, alias] = resources as Array<{
+      content: string;
+      provenance: { id: string } | null;
+      canonical?: string;
+    }>;
+    assert.match(rust.content, /# Rust Expert/);
+    assert.match(testing.content, /# Testing/);
+    assert.strictEqual(alias.canonical, "agents/rust-expert");
+    assert.strictEqual(alias.provenance?.id, "agents/rust-expert");
+  });
+
+  it("should report missing ids without failing the batch", async () => {
+    const { resources } = await loader.batchGet(["rust-expert", "missing"]);
+    assert.ok("content" in resources[0]);
+    assert.deepStrictEqual(resources[1], {
+      id: "agents/missing",
+      error: "Resource not found: agents/missing",
+      code: "NOT_FOUND",
+    });
+  });
+});
+
+describe("maxBatchSize", () => {
+  it("should default to 50 and read O8_MAX_BATCH_SIZE", () => {
+    assert.strictEqual(payloadLimitsFromEnv({}).maxBatchSize, 50);
+    assert.strictEqual(
+      payloadLimitsFromEnv({ O8_MAX_BATCH_SIZE: "5" }).maxBatchSize,
+      5,
+    );
+  });
+});
@@ -0,0 +1,123 @@
+/**
+ * Test suite for BodyIndex
+ *
+ * Tests:
+ * - Tokenization
+ * - Conjunctive term matching and ranking
+ * - Category filtering and limits
+ * - Highlighted snippets and match explanations
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  BodyIndex,
+  buildSnippets,
+  matchSnippet,
+  tokenize,
+} from "../utils/bodyIndex.js";
+import type { ResourceFragment } from "../utils/fuzzyMatcher.js";
+
+function fragment(
+  id: string,
+  category: ResourceFragment["category"],
+  content: string,
+): ResourceFragment {
+  return {
+    id,
+    category,
+    tags: [],
+    capabilities: [],
+    useWhen: [],
+    estimatedTokens: Math.ceil(content.length / 4),
+    content,
+  };
+}
+
+const fragments = [
+  fragment(
+    "agents/rust-expert",
+    "agent",
+    "Run cargo clippy before review. Use cargo deny to audit licenses. cargo deny check bans.",
+  ),
+  fragment(
+    "agents/go-expert",
+    "agent",
+    "Run go vet and staticcheck. Never use cargo here.",
+  ),
+  fragment(
+    "skills/supply-chain",
+    "skill",
+    "Supply chain policy: cargo deny for Rust, npm audit for Node.",
+  ),
+];
+
+describe("BodyIndex", () => {
+  const index = new BodyIndex();
+  index.build(fragments);
+
+  it("should tokenize into lowercase words", () => {
+    assert.deepStrictEqual(tokenize("Use `cargo-deny` a Lot!"), [
+      "use",
+      "cargo-deny",
+      "lot",
+    ]);
+  });
+
+  it("should require every query term and rank by frequency", () => {
+    const hits = index.search("cargo deny");
+
+    assert.deepStrictEqual(
+      hits.map((h) => h.id),
+      ["agents/rust-expert", "skills/supply-chain"],
+    );
+    assert.strictEqual(hits[0].occurrences, 5);
+    assert.strictEqual(hits[0].uri, "o8://agents/rust-expert");
+  });
+
+  it("should filter by category and apply limits", () => {
+    assert.deepStrictEqual(
+      index.search("cargo deny", { category: "skill" }).map((h) => h.id),
+      ["skills/supply-chain"],
+    );
+    assert.strictEqual(index.search("cargo", { limit: 1 }).length, 1);
+  });
+
+  it("should return nothing for unknown terms", () => {
+    assert.deepStrictEqual(index.search("kubernetes"), []);
+    assert.deepStrictEqual(index.search("cargo kubernetes"), []);
+    assert.deepStrictEqual(index.search("  "), []);
+  });
+
+  it("should mark matched terms in snippets", () => {
+    const [hit] = index.search("staticcheck");
+
+    assert.strictEqual(hit.id, "agents/go-expert");
+    assert.match(hit.snippets[0], /\*\*staticcheck\*\*/);
+  });
+});
+
+describe("buildSnippets", () => {
+  it("should merge nearby matches and add ellipses when truncated", () => {
+    const text = `${"x ".repeat(100)}alpha beta${" y".repeat(100)}`;
+    const snippets = buildSnippets(text, ["alpha", "beta"], 3, 10);
+
+    assert.strictEqual(snippets.length, 1);
+    assert.match(snippets[0], /^\.\.\. .*\*\*alpha\*\* \*\*beta\*\*.* \.\.\.$/);
+  });
+
+  it("should cap the number of snippets", () => {
+    const text = Array(10).fill("needle").join(" filler ".repeat(40));
+    assert.strictEqual(buildSnippets(text, ["needle"], 2, 5).length, 2);
+  });
+});
+
+describe("matchSnippet", () => {
+  it("should use the first text that mentions a term", () => {
+    assert.strictEqual(
+      matchSnippet([undefined, "no match", "uses cargo"], ["cargo"]),
+      "uses **cargo**",
+    );
+    assert.strictEqual(matchSnippet(["nothing"], ["cargo"]), undefined);
+  });
+});
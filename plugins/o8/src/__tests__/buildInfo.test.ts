@@ -0,0 +1,37 @@
+/**
+ * Test suite for --version output
+ *
+ * Tests:
+ * - Features follow flags and environment
+ * - Plain and verbose output
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { buildInfo, formatBuildInfo } from "../utils/buildInfo.js";
+
+describe("buildInfo", () => {
+  it("should report features enabled by flags and environment", () => {
+    const info = buildInfo("1.2.3", ["--http", "--watch-mode", "poll"], {
+      O8_TLS_CERT: "server.crt",
+      O8_LAYERS: "none",
+      O8_QUERY_LOG: "off",
+    });
+    assert.strictEqual(info.target, `${process.platform}-${process.arch}`);
+    assert.strictEqual(info.features.http, true);
+    assert.strictEqual(info.features.tls, true);
+    assert.strictEqual(info.features.polling, true);
+    assert.strictEqual(info.features.layers, false);
+    assert.strictEqual(info.features.queryLog, false);
+    assert.strictEqual(info.features.indexManifest, false);
+  });
+
+  it("should print the version, or every field with --verbose", () => {
+    const info = buildInfo("1.2.3", [], {});
+    assert.strictEqual(formatBuildInfo(info, false), "1.2.3\n");
+    const verbose = formatBuildInfo(info, true);
+    assert.match(verbose, /^version: 1\.2\.3$/m);
+    assert.match(verbose, /^runtime: node v\d+/m);
+    assert.match(verbose, /^features: -http -tls .*\+layers/m);
+  });
+});
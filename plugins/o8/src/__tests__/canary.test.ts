@@ -0,0 +1,131 @@
+/**
+ * Test suite for the canary catalog
+ *
+ * Tests:
+ * - --canary-dir and --canary-percent from flags and environment
+ * - Queries are assigned to the canary by a stable hash
+ * - Canary queries are answered from the staged catalog and differences
+ *   from the live catalog are logged
+ * - Secrets in logged queries are redacted
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, readFile, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  canaryOptions,
+  inCanary,
+  recordCanaryDiff,
+} from "../utils/canary.js";
+import { REDACTED } from "../utils/redact.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("canaryOptions", () => {
+  it("should read the directory and percentage", () => {
+    const options = canaryOptions(
+      ["--canary-dir", "/srv/next", "--canary-percent", "25"],
+      { O8_CANARY_LOG: "/tmp/canary.jsonl" },
+    );
+    assert.deepStrictEqual(options, {
+      dir: "/srv/next",
+      percent: 25,
+      logPath: "/tmp/canary.jsonl",
+    });
+    assert.strictEqual(canaryOptions([], {}), null);
+    assert.strictEqual(
+      canaryOptions([], { O8_CANARY_DIR: "/srv/next" })?.percent,
+      10,
+    );
+  });
+
+  it("should reject invalid percentages", () => {
+    assert.throws(
+      () => canaryOptions(["--canary-dir", "x", "--canary-percent", "150"], {}),
+      /--canary-percent/,
+    );
+  });
+});
+
+describe("inCanary", () => {
+  it("should assign queries by a stable hash", () => {
+    const queries = Array.from({ length: 1000 }, (_, i) => `query ${i}`);
+    const share = queries.filter((query) => inCanary(query, 20)).length;
+
+    assert.ok(share > 150 && share < 250, `${share} of 1000`);
+    assert.strictEqual(inCanary("rust api", 20), inCanary("rust api", 20));
+    assert.ok(queries.every((query) => inCanary(query, 100)));
+    assert.ok(!queries.some((query) => inCanary(query, 0)));
+  });
+});
+
+describe("recordCanaryDiff", () => {
+  it("should redact secrets in logged queries", async () => {
+    const dir = await mkdtemp(join(tmpdir(), "o8-canary-"));
+    try {
+      const log = join(dir, "canary.jsonl");
+      const query = "deploy with sk-abcdefghijklmnopqrstuvwx";
+      assert.ok(recordCanaryDiff(log, query, ["a", "b"], ["b", "a"], 2));
+
+      const entry = JSON.parse(await readFile(log, "utf-8"));
+      assert.strictEqual(entry.query, `deploy with ${REDACTED}`);
+    } finally {
+      await rm(dir, { recursive: true, force: true });
+    }
+  });
+});
+
+describe("ResourceLoader canary catalog", () => {
+  let root: string;
+  let loader: ResourceLoader;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-canary-"));
+    for (const [dir, name] of [
+      ["resources", "rust-expert"],
+      ["canary", "rust-specialist"],
+    ]) {
+      await mkdir(join(root, dir, "agents"), { recursive: true });
+      await writeFile(
+        join(root, dir, "agents", `${name}.md`),
+        `---\ntags: [rust]\n---\n# ${name}\n`,
+      );
+    }
+    process.env.RESOURCES_PATH = join(root, "resources");
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    process.env.O8_CANARY_DIR = join(root, "canary");
+    process.env.O8_CANARY_PERCENT = "100";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    delete process.env.O8_CANARY_DIR;
+    delete process.env.O8_CANARY_PERCENT;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should answer from the staged catalog and log differences", async () => {
+    const content = await loader.loadResourceContent(
+      "o8://agents/match?query=rust&mode=catalog",
+    );
+    assert.ok(content.includes("rust-specialist"));
+    assert.ok(!content.includes("rust-expert"));
+
+    // The live catalog is matched after the response
+    const logPath = join(root, ".state", "canary.jsonl");
+    let log = "";
+    for (let i = 0; i < 50 && !log; i++) {
+      await new Promise((resolve) => setTimeout(resolve, 10));
+      log = await readFile(logPath, "utf-8").catch(() => "");
+    }
+    const entry = JSON.parse(log.trim());
+    assert.strictEqual(entry.query, "rust");
+    assert.deepStrictEqual(entry.primary, ["agents/rust-expert"]);
+    assert.deepStrictEqual(entry.shadow, ["agents/rust-specialist"]);
+  });
+});
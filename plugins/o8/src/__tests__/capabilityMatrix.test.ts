@@ -0,0 +1,170 @@
+/**
+ * Test suite for structured agent capabilities
+ *
+ * Tests:
+ * - Frontmatter matrices, case folding, and language aliases
+ * - Capability filters from request params and match URIs
+ * - Disqualifying agents that lack a required capability
+ * - agents/capabilities listing and facets
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  matchesCapabilities,
+  parseCapabilityFilter,
+  parseCapabilityMatrix,
+} from "../utils/capabilityMatrix.js";
+import { FuzzyMatcher, type ResourceFragment } from "../utils/fuzzyMatcher.js";
+import { URIParser } from "../utils/uriParser.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("parseCapabilityMatrix", () => {
+  it("should normalize declared values", () => {
+    assert.deepStrictEqual(
+      parseCapabilityMatrix({
+        languages: ["Rust", "ts", "TypeScript"],
+        domains: "Backend, cli",
+        operations: ["review"],
+      }),
+      {
+        languages: ["rust", "typescript"],
+        domains: ["backend", "cli"],
+        operations: ["review"],
+      },
+    );
+  });
+
+  it("should return undefined without any dimension", () => {
+    assert.strictEqual(parseCapabilityMatrix({ tags: ["rust"] }), undefined);
+  });
+});
+
+describe("capability filters", () => {
+  const matrix = {
+    languages: ["rust", "typescript"],
+    domains: ["backend"],
+    operations: ["refactor", "review"],
+  };
+
+  it("should require every listed value", () => {
+    assert.ok(
+      matchesCapabilities(matrix, {
+        languages: ["rust"],
+        operations: ["review"],
+      }),
+    );
+    assert.ok(!matchesCapabilities(matrix, { languages: ["rust", "go"] }));
+    assert.ok(!matchesCapabilities(undefined, { domains: ["backend"] }));
+    assert.ok(matchesCapabilities(undefined, undefined));
+  });
+
+  it("should parse request params", () => {
+    assert.deepStrictEqual(
+      parseCapabilityFilter({ languages: "RS,ts", operations: ["Review"] }),
+      { languages: ["rust", "typescript"], operations: ["review"] },
+    );
+    assert.strictEqual(parseCapabilityFilter({ query: "x" }), undefined);
+    assert.strictEqual(parseCapabilityFilter(undefined), undefined);
+  });
+
+  it("should parse match URI params", () => {
+    const parsed = new URIParser().parse(
+      "o8://agents/match?query=audit&languages=rust&operations=review",
+    );
+    assert.strictEqual(parsed.type, "dynamic");
+    if (parsed.type === "dynamic") {
+      assert.deepStrictEqual(parsed.matchParams.capabilities, {
+        languages: ["rust"],
+        operations: ["review"],
+      });
+    }
+  });
+
+  it("should disqualify agents without the required capabilities", () => {
+    const matcher = new FuzzyMatcher();
+    const fragment = (languages: string[]): ResourceFragment => ({
+      id: `agents/${languages[0]}-reviewer`,
+      category: "agent",
+      tags: ["review"],
+      capabilities: ["code review"],
+      useWhen: [],
+      estimatedTokens: 100,
+      content: "",
+      capabilityMatrix: { languages, domains: [], operations: ["review"] },
+    });
+    const request = {
+      query: "review",
+      requiredCapabilities: { languages: ["rust"] },
+    };
+
+    const score = (languages: string[]) =>
+      matcher.calculateScore(fragment(languages), ["review"], request);
+
+    assert.ok(score(["rust"]) > 0);
+    assert.strictEqual(score(["go"]), 0);
+  });
+});
+
+describe("ResourceLoader.getCapabilities", () => {
+  let root: string;
+  let loader: ResourceLoader;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-capabilities-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeFile(
+      join(root, "agents", "rust-reviewer.md"),
+      "---\nlanguages: [rust]\noperations: [review]\n---\n# Rust Reviewer\n",
+    );
+    await writeFile(
+      join(root, "agents", "ts-refactorer.md"),
+      "---\nlanguages: [ts]\ndomains: [frontend]\noperations: [refactor, review]\n---\n# TS Refactorer\n",
+    );
+    await writeFile(
+      join(root, "agents", "generalist.md"),
+      "---\ntags: [general]\n---\n# Generalist\n",
+    );
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should list agents that declare capabilities, with facets", async () => {
+    const { agents, facets } = await loader.getCapabilities();
+
+    assert.deepStrictEqual(
+      agents.map((agent) => agent.id),
+      ["agents/rust-reviewer", "agents/ts-refactorer"],
+    );
+    assert.deepStrictEqual(facets.operations, { refactor: 1, review: 2 });
+    assert.deepStrictEqual(facets.languages, { rust: 1, typescript: 1 });
+  });
+
+  it("should filter by capability", async () => {
+    const { agents } = await loader.getCapabilities({
+      languages: ["typescript"],
+      operations: ["review"],
+    });
+
+    assert.deepStrictEqual(agents, [
+      {
+        id: "agents/ts-refactorer",
+        languages: ["typescript"],
+        domains: ["frontend"],
+        operations: ["refactor", "review"],
+      },
+    ]);
+  });
+});
@@ -0,0 +1,86 @@
+/**
+ * Test suite for catalog statistics
+ *
+ * Tests:
+ * - Counts by category, model, and tag
+ * - Average sizes and modification range
+ * - Validation problem counts
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { summarizeCatalog } from "../utils/catalogSummary.js";
+import type { ResourceFragment } from "../utils/fuzzyMatcher.js";
+
+const fragments: ResourceFragment[] = [
+  {
+    id: "agents/rust-expert",
+    category: "agent",
+    tags: ["rust", "systems"],
+    capabilities: ["ownership"],
+    useWhen: ["Writing Rust"],
+    estimatedTokens: 100,
+    content: "a".repeat(400),
+    model: "opus",
+    modifiedAt: "2025-03-01T00:00:00.000Z",
+  },
+  {
+    id: "agents/go-expert",
+    category: "agent",
+    tags: ["go", "systems"],
+    capabilities: [],
+    useWhen: [],
+    estimatedTokens: 50,
+    content: "a".repeat(200),
+    modifiedAt: "2024-01-01T00:00:00.000Z",
+  },
+  {
+    id: "skills/testing",
+    category: "skill",
+    tags: [],
+    capabilities: ["unit tests"],
+    useWhen: ["Testing"],
+    estimatedTokens: 30,
+    content: "a".repeat(120),
+  },
+];
+
+describe("summarizeCatalog", () => {
+  const summary = summarizeCatalog(fragments, [
+    { path: "agents/broken.md", error: "bad YAML" },
+  ]);
+
+  it("should count by category, model, and tag", () => {
+    assert.strictEqual(summary.total, 3);
+    assert.deepStrictEqual(summary.byCategory, { agent: 2, skill: 1 });
+    assert.deepStrictEqual(summary.byModel, { opus: 1, unspecified: 2 });
+    assert.deepStrictEqual(summary.topTags[0], { tag: "systems", count: 2 });
+    assert.strictEqual(summary.distinctTags, 3);
+  });
+
+  it("should report averages and the modification range", () => {
+    assert.strictEqual(summary.averageBodyChars, 240);
+    assert.strictEqual(summary.averageTokens, 60);
+    assert.strictEqual(summary.oldest?.id, "agents/go-expert");
+    assert.strictEqual(summary.newest?.id, "agents/rust-expert");
+  });
+
+  it("should count validation problems", () => {
+    assert.strictEqual(summary.validation.failed, 1);
+    assert.strictEqual(summary.validation.missingTags, 1);
+    assert.strictEqual(summary.validation.missingCapabilities, 1);
+    assert.strictEqual(summary.validation.missingUseWhen, 1);
+  });
+
+  it("should handle an empty catalog", () => {
+    const empty = summarizeCatalog([]);
+
+    assert.strictEqual(empty.total, 0);
+    assert.strictEqual(empty.averageBodyChars, 0);
+    assert.strictEqual(empty.oldest, null);
+  });
+
+  it("should limit the tag list", () => {
+    assert.strictEqual(summarizeCatalog(fragments, [], 1).topTags.length, 1);
+  });
+});
@@ -0,0 +1,84 @@
+/**
+ * Test suite for the upstream circuit breaker
+ *
+ * Tests:
+ * - Opens after consecutive failures, not after isolated ones
+ * - Half-open trial calls close or reopen the circuit
+ * - Latency and error reporting
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { CircuitBreaker } from "../proxy/circuitBreaker.js";
+
+function breaker() {
+  let clock = 0;
+  return {
+    breaker: new CircuitBreaker({
+      failureThreshold: 3,
+      resetTimeoutMs: 1000,
+      now: () => clock,
+    }),
+    tick: (ms: number) => {
+      clock += ms;
+    },
+  };
+}
+
+describe("CircuitBreaker", () => {
+  it("should open after consecutive failures", () => {
+    const { breaker: circuit } = breaker();
+    circuit.recordFailure(new Error("a"), 10);
+    circuit.recordFailure(new Error("b"), 10);
+    circuit.recordSuccess(10);
+    circuit.recordFailure(new Error("c"), 10);
+    circuit.recordFailure(new Error("d"), 10);
+    assert.strictEqual(circuit.allowRequest(), true);
+
+    circuit.recordFailure(new Error("e"), 10);
+    assert.strictEqual(circuit.allowRequest(), false);
+    assert.strictEqual(circuit.status().state, "open");
+    assert.strictEqual(circuit.retryAfterMs(), 1000);
+  });
+
+  it("should let one trial call through after the reset timeout", () => {
+    const { breaker: circuit, tick } = breaker();
+    for (let i = 0; i < 3; i++) circuit.recordFailure(new Error("down"), 10);
+
+    tick(1000);
+    assert.strictEqual(circuit.status().state, "half-open");
+    assert.strictEqual(circuit.allowRequest(), true);
+    assert.strictEqual(circuit.allowRequest(), false);
+
+    circuit.recordSuccess(5);
+    assert.strictEqual(circuit.status().state, "closed");
+    assert.strictEqual(circuit.allowRequest(), true);
+  });
+
+  it("should reopen when the trial call fails", () => {
+    const { breaker: circuit, tick } = breaker();
+    for (let i = 0; i < 3; i++) circuit.recordFailure(new Error("down"), 10);
+
+    tick(1000);
+    assert.strictEqual(circuit.allowRequest(), true);
+    circuit.recordFailure(new Error("still down"), 10);
+    assert.strictEqual(circuit.status().state, "open");
+    assert.strictEqual(circuit.allowRequest(), false);
+  });
+
+  it("should report calls, latency, and the last error", () => {
+    const { breaker: circuit } = breaker();
+    circuit.recordSuccess(10);
+    circuit.recordFailure(new Error("timeout"), 30);
+
+    assert.deepStrictEqual(circuit.status(), {
+      state: "closed",
+      calls: 2,
+      failures: 1,
+      consecutiveFailures: 1,
+      avgLatencyMs: 20,
+      lastError: "timeout",
+      retryAt: null,
+    });
+  });
+});
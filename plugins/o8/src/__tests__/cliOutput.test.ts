@@ -0,0 +1,83 @@
+/**
+ * Test suite for CLI output formats
+ *
+ * Tests:
+ * - --format parsing
+ * - GitHub annotation escaping
+ * - text, json, and github rendering
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  githubAnnotation,
+  renderError,
+  renderReport,
+  takeFormat,
+  type CommandReport,
+} from "../utils/cliOutput.js";
+
+const report: CommandReport = {
+  lines: ["WROTE agents/a.md"],
+  summary: "Imported 1 of 1",
+  findings: [
+    { level: "warning", file: "agents/a.md", message: "add tags" },
+  ],
+  data: { written: ["agents/a.md"] },
+};
+
+describe("takeFormat", () => {
+  it("should default to text and strip the option", () => {
+    assert.deepStrictEqual(takeFormat(["x"]), { format: "text", rest: ["x"] });
+    assert.deepStrictEqual(takeFormat(["x", "--format", "json", "--force"]), {
+      format: "json",
+      rest: ["x", "--force"],
+    });
+  });
+
+  it("should reject unknown formats", () => {
+    assert.throws(() => takeFormat(["--format", "xml"]), /Invalid --format/);
+  });
+});
+
+describe("githubAnnotation", () => {
+  it("should escape data and properties", () => {
+    assert.strictEqual(
+      githubAnnotation({
+        level: "error",
+        file: "a,b.md",
+        title: "x: y",
+        message: "100%\nsure",
+      }),
+      "::error file=a%2Cb.md,title=x%3A y::100%25%0Asure",
+    );
+    assert.strictEqual(
+      githubAnnotation({ level: "notice", message: "ok" }),
+      "::notice::ok",
+    );
+  });
+});
+
+describe("renderReport", () => {
+  it("should render each format", () => {
+    assert.strictEqual(
+      renderReport("text", report),
+      "WROTE agents/a.md\nImported 1 of 1\n",
+    );
+    assert.deepStrictEqual(JSON.parse(renderReport("json", report)), report.data);
+    assert.strictEqual(
+      renderReport("github", report),
+      "::warning file=agents/a.md::add tags\nImported 1 of 1\n",
+    );
+  });
+
+  it("should render fatal errors with their code", () => {
+    const error = Object.assign(new Error("boom"), { code: "NO_DEFINITIONS" });
+
+    assert.deepStrictEqual(JSON.parse(renderError("json", error)), {
+      error: "boom",
+      code: "NO_DEFINITIONS",
+    });
+    assert.strictEqual(renderError("github", error), "::error::boom\n");
+  });
+});
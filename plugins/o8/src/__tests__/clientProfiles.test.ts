@@ -0,0 +1,80 @@
+/**
+ * Test suite for per-client profiles
+ *
+ * Tests:
+ * - Selecting a profile by clientInfo name, with a "*" fallback
+ * - Schema defaults for the clients section
+ * - Match defaults applied to URIs that leave parameters out
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { selectClientProfile } from "../config/clients.js";
+import { validateConfig } from "../config/schema.js";
+import { URIParser } from "../utils/uriParser.js";
+
+describe("selectClientProfile", () => {
+  const { clients } = validateConfig({
+    clients: {
+      "Claude-AI": {},
+      "team-orchestrator": { prompts: false, maxResults: 50 },
+    },
+  });
+
+  it("should match client names case-insensitively", () => {
+    assert.deepStrictEqual(selectClientProfile(clients, "claude-ai"), {
+      key: "Claude-AI",
+      profile: { prompts: true },
+    });
+    assert.strictEqual(
+      selectClientProfile(clients, "team-orchestrator")?.profile.prompts,
+      false,
+    );
+  });
+
+  it("should fall back to the wildcard profile", () => {
+    assert.strictEqual(selectClientProfile(clients, "other"), null);
+    assert.strictEqual(selectClientProfile(clients, undefined), null);
+
+    const withWildcard = validateConfig({ clients: { "*": { maxTokens: 1000 } } })
+      .clients;
+    assert.strictEqual(
+      selectClientProfile(withWildcard, "other")?.key,
+      "*",
+    );
+  });
+
+  it("should default to no profiles", () => {
+    assert.deepStrictEqual(validateConfig({}).clients, {});
+    assert.throws(() =>
+      validateConfig({ clients: { cli: { mode: "minimal" } } }),
+    );
+  });
+});
+
+describe("URIParser.setMatchDefaults", () => {
+  it("should fill in parameters the URI leaves out", () => {
+    const parser = new URIParser();
+    parser.setMatchDefaults({ maxResults: 50, maxTokens: 8000, mode: "index" });
+
+    const parsed = parser.parse("o8://match?query=react");
+    assert.strictEqual(parsed.type, "dynamic");
+    if (parsed.type === "dynamic") {
+      assert.strictEqual(parsed.matchParams.maxResults, 50);
+      assert.strictEqual(parsed.matchParams.maxTokens, 8000);
+      assert.strictEqual(parsed.matchParams.mode, "index");
+    }
+  });
+
+  it("should let URI parameters win", () => {
+    const parser = new URIParser();
+    parser.setMatchDefaults({ maxResults: 50, mode: "index" });
+
+    const parsed = parser.parse("o8://match?query=react&maxResults=3&mode=catalog");
+    assert.strictEqual(parsed.type, "dynamic");
+    if (parsed.type === "dynamic") {
+      assert.strictEqual(parsed.matchParams.maxResults, 3);
+      assert.strictEqual(parsed.matchParams.mode, "catalog");
+    }
+  });
+});
@@ -0,0 +1,77 @@
+/**
+ * Test suite for ConnectionLimiter
+ *
+ * Tests:
+ * - Limits from the environment
+ * - Refusing connections past the per-IP and per-token limits
+ * - Releasing connections, once each
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  ConnectionLimitError,
+  ConnectionLimiter,
+  connectionLimitsFromEnv,
+} from "../transports/connectionLimits.js";
+
+describe("connectionLimitsFromEnv", () => {
+  it("should read limits and default to unlimited", () => {
+    assert.deepStrictEqual(
+      connectionLimitsFromEnv({
+        O8_MAX_CONNECTIONS_PER_IP: "20",
+        O8_MAX_CONNECTIONS_PER_TOKEN: "nope",
+      }),
+      { perIp: 20, perToken: 0 },
+    );
+  });
+});
+
+describe("ConnectionLimiter", () => {
+  it("should refuse connections past the per-IP limit", () => {
+    const limiter = new ConnectionLimiter({ perIp: 2 });
+    limiter.acquire("10.0.0.1");
+    limiter.acquire("10.0.0.1");
+
+    assert.throws(
+      () => limiter.acquire("10.0.0.1"),
+      (error: unknown) =>
+        error instanceof ConnectionLimitError &&
+        error.scope === "ip" &&
+        error.limit === 2,
+    );
+    limiter.acquire("10.0.0.2");
+    assert.strictEqual(limiter.getStats().rejectedTotal, 1);
+  });
+
+  it("should refuse connections past the per-token limit across IPs", () => {
+    const limiter = new ConnectionLimiter({ perToken: 1 });
+    limiter.acquire("10.0.0.1", "ci-secret");
+
+    assert.throws(
+      () => limiter.acquire("10.0.0.2", "ci-secret"),
+      (error: unknown) =>
+        error instanceof ConnectionLimitError && error.scope === "token",
+    );
+    limiter.acquire("10.0.0.2", "other-secret");
+    limiter.acquire("10.0.0.2");
+  });
+
+  it("should free a slot once per release", () => {
+    const limiter = new ConnectionLimiter({ perIp: 1, perToken: 1 });
+    const release = limiter.acquire("10.0.0.1", "ci-secret");
+    release();
+    release();
+
+    const again = limiter.acquire("10.0.0.1", "ci-secret");
+    assert.throws(() => limiter.acquire("10.0.0.1"), ConnectionLimitError);
+    again();
+    assert.deepStrictEqual(limiter.getStats(), {
+      perIp: 1,
+      perToken: 1,
+      ips: 0,
+      tokens: 0,
+      rejectedTotal: 1,
+    });
+  });
+});
@@ -0,0 +1,91 @@
+/**
+ * Test suite for crash reports
+ *
+ * Tests:
+ * - Error details, recent requests, and the configuration summary
+ * - Secrets are left out of the configuration summary
+ * - Reports are written under <state dir>/crashes
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, readFile, rm } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  CRASH_REPORT_REQUESTS,
+  buildCrashReport,
+  writeCrashReport,
+} from "../state/crashReport.js";
+
+const requests = Array.from({ length: 30 }, (_, i) => ({
+  type: "mcp_request",
+  timestamp: i,
+  data: { method: `dynamic:${i}`, client: "cli/2.0.0", latency: i },
+}));
+
+describe("buildCrashReport", () => {
+  it("should include the error, recent requests, and config", () => {
+    const report = buildCrashReport(
+      new TypeError("boom"),
+      "uncaughtException",
+      {
+        version: "1.0.0",
+        mode: "stdio",
+        activity: [...requests, { type: "error", timestamp: 99 }],
+      },
+      {
+        RESOURCES_PATH: "/srv/resources",
+        O8_ADMIN_TOKEN: "secret",
+        O8_WEBHOOK_URL: "https://hooks.example.com/secret",
+      },
+    );
+
+    assert.strictEqual(report.version, "1.0.0");
+    assert.strictEqual(report.origin, "uncaughtException");
+    assert.strictEqual(report.mode, "stdio");
+    assert.strictEqual(report.error.name, "TypeError");
+    assert.strictEqual(report.error.message, "boom");
+    assert.match(report.error.stack ?? "", /TypeError: boom/);
+    assert.deepStrictEqual(report.config, { RESOURCES_PATH: "/srv/resources" });
+    assert.strictEqual(report.recentRequests.length, CRASH_REPORT_REQUESTS);
+    assert.deepStrictEqual(report.recentRequests.at(-1), {
+      timestamp: new Date(29).toISOString(),
+      method: "dynamic:29",
+      client: "cli/2.0.0",
+      latency: 29,
+    });
+  });
+
+  it("should describe values that are not errors", () => {
+    const report = buildCrashReport("disk full", "unhandledRejection", {
+      version: "1.0.0",
+      mode: null,
+      activity: [],
+    });
+    assert.deepStrictEqual(report.error, {
+      name: "NonError",
+      message: "disk full",
+      stack: null,
+    });
+  });
+});
+
+describe("writeCrashReport", () => {
+  it("should write the report under crashes/", async () => {
+    const stateDir = await mkdtemp(join(tmpdir(), "o8-crash-"));
+    try {
+      const report = buildCrashReport(new Error("boom"), "uncaughtException", {
+        version: "1.0.0",
+        mode: "dual",
+        activity: [],
+      });
+      const path = writeCrashReport(stateDir, report);
+
+      assert.ok(path.startsWith(join(stateDir, "crashes")));
+      assert.deepStrictEqual(JSON.parse(await readFile(path, "utf-8")).error.message, "boom");
+    } finally {
+      await rm(stateDir, { recursive: true, force: true });
+    }
+  });
+});
@@ -0,0 +1,72 @@
+/**
+ * Test suite for the admin dashboard
+ *
+ * Tests:
+ * - Catalog, load errors, queries, and recent requests in the page
+ * - Escaping of resource paths and queries
+ * - Query log off
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { DashboardData, renderDashboard } from "../web/dashboard.js";
+
+function data(overrides: Partial<DashboardData> = {}): DashboardData {
+  return {
+    catalog: {
+      generation: 3,
+      loadedAt: "2026-01-01T00:00:00.000Z",
+      resources: 42,
+      pinned: null,
+      reloading: false,
+    },
+    byCategory: { agents: 40, skills: 2 },
+    failures: [],
+    topQueries: [],
+    activity: [],
+    ...overrides,
+  };
+}
+
+describe("renderDashboard", () => {
+  it("should show catalog state, queries, and recent requests", () => {
+    const html = renderDashboard(
+      data({
+        topQueries: [{ query: "react hooks", count: 7, zeroResults: 1 }],
+        activity: [
+          { type: "error", timestamp: 0 },
+          {
+            type: "mcp_request",
+            timestamp: 0,
+            data: { method: "dynamic:agents", client: "cli/2.0.0", latency: 12 },
+          },
+        ],
+      }),
+    );
+
+    assert.match(html, /<td>42<\/td>/);
+    assert.match(html, /<td>agents<\/td><td>40<\/td>/);
+    assert.match(html, /<td>react hooks<\/td><td>7<\/td><td>1<\/td>/);
+    assert.match(html, /<td>dynamic:agents<\/td><td>cli\/2.0.0<\/td><td>12 ms<\/td>/);
+    assert.match(html, /No load errors/);
+    assert.doesNotMatch(html, /<script|https?:\/\//);
+  });
+
+  it("should escape load errors and queries", () => {
+    const html = renderDashboard(
+      data({
+        failures: [{ path: "agents/<bad>.md", error: 'Unexpected "&"' }],
+        topQueries: [{ query: "<img src=x>", count: 1, zeroResults: 1 }],
+      }),
+    );
+
+    assert.match(html, /Load errors \(1\)/);
+    assert.match(html, /agents\/&lt;bad&gt;\.md/);
+    assert.match(html, /Unexpected &quot;&amp;&quot;/);
+    assert.doesNotMatch(html, /<img/);
+  });
+
+  it("should say when the query log is off", () => {
+    assert.match(renderDashboard(data({ topQueries: null })), /query log is off/);
+  });
+});
@@ -0,0 +1,52 @@
+/**
+ * Test suite for deterministic output helpers
+ *
+ * Tests:
+ * - Mode detection from flags and environment
+ * - Name ordering for directory entries
+ * - Clock-free stats snapshots
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  isDeterministic,
+  sortByName,
+  compareStrings,
+} from "../utils/deterministic.js";
+import { StatsCollector } from "../stats/collector.js";
+
+describe("Deterministic mode", () => {
+  it("should be enabled by flag or environment", () => {
+    assert.strictEqual(isDeterministic(["node", "index.js"], {}), false);
+    assert.strictEqual(
+      isDeterministic(["node", "index.js", "--deterministic"], {}),
+      true,
+    );
+    assert.strictEqual(isDeterministic([], { O8_DETERMINISTIC: "1" }), true);
+  });
+
+  it("should order entries by code point regardless of locale", () => {
+    const entries = [{ name: "b.md" }, { name: "B.md" }, { name: "a.md" }];
+
+    assert.deepStrictEqual(
+      sortByName(entries).map((e) => e.name),
+      ["B.md", "a.md", "b.md"],
+    );
+    assert.deepStrictEqual(sortByName(["z", "y"]), ["y", "z"]);
+    assert.strictEqual(compareStrings("a", "a"), 0);
+  });
+
+  it("should zero clock-derived stats in deterministic mode", async () => {
+    const stats = new StatsCollector(undefined, true);
+    stats.trackRequest("get_resource", 42);
+
+    const snapshot = await stats.getSnapshot();
+
+    assert.strictEqual(snapshot.uptime, 0);
+    assert.strictEqual(snapshot.latency.avg, 0);
+    assert.strictEqual(snapshot.memory.rss, 0);
+    assert.strictEqual(snapshot.lastActivity, 0);
+    assert.strictEqual(snapshot.requests.total, 1);
+  });
+});
@@ -0,0 +1,59 @@
+/**
+ * Test suite for unified diff
+ *
+ * Tests:
+ * - Identical inputs
+ * - Hunk headers and context
+ * - Separate hunks for distant changes
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { unifiedDiff } from "../utils/diff.js";
+
+const lines = (count: number) =>
+  Array.from({ length: count }, (_, i) => `line ${i + 1}`);
+
+describe("unifiedDiff", () => {
+  it("should return an empty string for identical texts", () => {
+    assert.strictEqual(unifiedDiff("a\nb\n", "a\nb\n"), "");
+  });
+
+  it("should produce a hunk with context", () => {
+    const before = lines(5).join("\n") + "\n";
+    const after = before.replace("line 3", "line three");
+
+    assert.strictEqual(
+      unifiedDiff(before, after, { fromLabel: "x@HEAD", toLabel: "x@working" }),
+      [
+        "--- x@HEAD",
+        "+++ x@working",
+        "@@ -1,5 +1,5 @@",
+        " line 1",
+        " line 2",
+        "-line 3",
+        "+line three",
+        " line 4",
+        " line 5",
+        "",
+      ].join("\n"),
+    );
+  });
+
+  it("should split distant changes into separate hunks", () => {
+    const before = lines(30);
+    const after = [...before];
+    after[1] = "changed";
+    after.splice(25, 1);
+
+    const diff = unifiedDiff(before.join("\n"), after.join("\n"));
+    const headers = diff.split("\n").filter((line) => line.startsWith("@@"));
+
+    assert.deepStrictEqual(headers, ["@@ -1,5 +1,5 @@", "@@ -23,7 +23,6 @@"]);
+  });
+
+  it("should handle added and removed files", () => {
+    assert.match(unifiedDiff("", "new\n"), /@@ -0,0 \+1,1 @@\n\+new\n$/);
+    assert.match(unifiedDiff("old\n", ""), /@@ -1,1 \+0,0 @@\n-old\n$/);
+  });
+});
@@ -0,0 +1,117 @@
+/**
+ * Test suite for per-directory frontmatter defaults
+ *
+ * Tests:
+ * - Parsing _defaults.yaml, without per-resource fields
+ * - Deeper directories override shallower ones
+ * - Resources inherit defaults and override them with their own frontmatter
+ * - Snapshots keep the defaults in effect when they were taken
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  inheritedDefaults,
+  parseDefaults,
+} from "../utils/directoryDefaults.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("parseDefaults", () => {
+  it("should parse a mapping and drop per-resource fields", () => {
+    assert.deepStrictEqual(
+      parseDefaults("model: sonnet\ntags: [ops]\naliases: [old]\n"),
+      { model: "sonnet", tags: ["ops"] },
+    );
+    assert.deepStrictEqual(parseDefaults(""), {});
+    assert.throws(() => parseDefaults("- a\n- b\n"), /mapping/);
+  });
+});
+
+describe("inheritedDefaults", () => {
+  it("should let deeper directories override shallower ones", () => {
+    const byDir = new Map([
+      ["agents", { model: "haiku", tags: ["all"] }],
+      ["agents/infra", { model: "sonnet" }],
+    ]);
+    assert.deepStrictEqual(
+      inheritedDefaults("agents/infra/k8s/operator.md", byDir),
+      { model: "sonnet", tags: ["all"] },
+    );
+    assert.deepStrictEqual(inheritedDefaults("skills/a.md", byDir), {});
+  });
+});
+
+describe("ResourceLoader directory defaults", () => {
+  let root: string;
+  let loader: ResourceLoader;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-defaults-"));
+    const infra = join(root, "agents", "infra");
+    await mkdir(join(infra, "k8s"), { recursive: true });
+    await writeFile(
+      join(infra, "_defaults.yaml"),
+      "model: sonnet\ntags: [infrastructure]\n",
+    );
+    await writeFile(
+      join(infra, "k8s", "_defaults.yaml"),
+      "tags: [kubernetes]\n",
+    );
+    await writeFile(join(infra, "terraform.md"), "# Terraform\n");
+    await writeFile(
+      join(infra, "k8s", "operator.md"),
+      "---\nmodel: opus\n---\n# Operator\n",
+    );
+    await writeFile(join(root, "agents", "writer.md"), "# Writer\n");
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  const byId = async () =>
+    new Map(
+      (await loader.loadResourceIndex()).map((fragment) => [
+        fragment.id,
+        fragment,
+      ]),
+    );
+
+  it("should apply defaults beneath their directory", async () => {
+    const fragments = await byId();
+
+    const terraform = fragments.get("agents/infra/terraform");
+    assert.strictEqual(terraform?.model, "sonnet");
+    assert.deepStrictEqual(terraform?.tags, ["infrastructure"]);
+    const operator = fragments.get("agents/infra/k8s/operator");
+    assert.strictEqual(operator?.model, "opus");
+    assert.deepStrictEqual(operator?.tags, ["kubernetes"]);
+    assert.strictEqual(fragments.get("agents/writer")?.model, undefined);
+  });
+
+  it("should keep defaults in snapshots", async () => {
+    await loader.snapshotCatalog("with-defaults");
+    await writeFile(join(root, "agents", "infra", "_defaults.yaml"), "{}\n");
+    await loader.rollbackCatalog("with-defaults");
+
+    assert.strictEqual(
+      (await byId()).get("agents/infra/terraform")?.model,
+      "sonnet",
+    );
+    await loader.rollbackCatalog("live");
+    assert.strictEqual(
+      (await byId()).get("agents/infra/terraform")?.model,
+      undefined,
+    );
+  });
+});
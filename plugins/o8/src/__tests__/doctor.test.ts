@@ -0,0 +1,111 @@
+/**
+ * Test suite for the doctor subcommand checks
+ *
+ * Tests:
+ * - A healthy environment passes
+ * - Missing directories, broken config, and bad settings are reported with a fix
+ * - Busy ports and a wrong clock
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { createServer, type AddressInfo } from "net";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { runDoctor, type DoctorCheck } from "../utils/doctor.js";
+
+function byName(checks: DoctorCheck[], name: string): DoctorCheck {
+  const check = checks.find((candidate) => candidate.name === name);
+  assert.ok(check, `missing check ${name}`);
+  return check;
+}
+
+describe("runDoctor", () => {
+  let root: string;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-doctor-"));
+    await mkdir(join(root, "resources", "agents"), { recursive: true });
+    await mkdir(join(root, "prompts"), { recursive: true });
+  });
+
+  after(async () => {
+    await rm(root, { recursive: true, force: true });
+  });
+
+  const env = () => ({
+    RESOURCES_PATH: join(root, "resources"),
+    PROMPTS_PATH: join(root, "prompts"),
+    O8_STATE_DIR: join(root, "state"),
+  });
+
+  it("should pass a healthy environment", async () => {
+    const checks = await runDoctor({
+      env: env(),
+      cwd: root,
+      home: root,
+      port: null,
+      now: new Date("2026-06-01T00:00:00Z"),
+    });
+
+    assert.deepStrictEqual(
+      checks.filter((check) => check.status !== "ok"),
+      [],
+    );
+    assert.deepStrictEqual(
+      checks.map((check) => check.name),
+      ["resources", "prompts", "config", "settings", "state", "watcher", "clock"],
+    );
+  });
+
+  it("should report problems with a remedy", async () => {
+    await writeFile(join(root, ".orchestr8rc.json"), "{ not json");
+    try {
+      const checks = await runDoctor({
+        env: {
+          ...env(),
+          RESOURCES_PATH: join(root, "missing"),
+          PROMPTS_PATH: join(root, "missing-prompts"),
+          O8_QUERY_LOG: "everything",
+        },
+        cwd: root,
+        home: root,
+        port: null,
+      });
+
+      for (const [name, status] of [
+        ["resources", "fail"],
+        ["prompts", "warn"],
+        ["config", "fail"],
+        ["settings", "fail"],
+      ] as const) {
+        const check = byName(checks, name);
+        assert.strictEqual(check.status, status, name);
+        assert.ok(check.remedy, `${name} has no remedy`);
+      }
+      assert.match(byName(checks, "settings").message, /O8_QUERY_LOG/);
+    } finally {
+      await rm(join(root, ".orchestr8rc.json"));
+    }
+  });
+
+  it("should fail on a busy port and warn on a wrong clock", async () => {
+    const server = createServer();
+    await new Promise<void>((resolve) => server.listen(0, resolve));
+    const { port } = server.address() as AddressInfo;
+    try {
+      const checks = await runDoctor({
+        env: env(),
+        cwd: root,
+        home: root,
+        port,
+        now: new Date("1970-01-01T00:00:00Z"),
+      });
+      assert.strictEqual(byName(checks, "port").status, "fail");
+      assert.strictEqual(byName(checks, "clock").status, "warn");
+    } finally {
+      server.close();
+    }
+  });
+});
@@ -0,0 +1,111 @@
+/**
+ * Test suite for the server event bus and webhook sink
+ *
+ * Tests:
+ * - Event publication and subscription
+ * - Webhook delivery and event filtering
+ * - Delivery failure handling
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { createServer, Server } from "http";
+import { AddressInfo } from "net";
+import { ServerEventBus, ServerEvent } from "../events/bus.js";
+import { WebhookSink } from "../events/webhook.js";
+import { Logger } from "../utils/logger.js";
+
+describe("ServerEventBus", () => {
+  it("should deliver events to typed and catch-all listeners", () => {
+    const bus = new ServerEventBus();
+    const typed: ServerEvent[] = [];
+    const all: ServerEvent[] = [];
+
+    bus.on("index_loaded", (event) => typed.push(event));
+    const unsubscribe = bus.subscribe((event) => all.push(event));
+
+    bus.publish("index_loaded", { fragments: 3 });
+    bus.publish("validation_failed", { path: "agents/x.md" });
+    unsubscribe();
+    bus.publish("index_loaded", { fragments: 4 });
+
+    assert.strictEqual(typed.length, 2);
+    assert.strictEqual(all.length, 2);
+    assert.deepStrictEqual(all[0].data, { fragments: 3 });
+    assert.strictEqual(all[1].type, "validation_failed");
+  });
+});
+
+describe("WebhookSink", () => {
+  let server: Server;
+  let url: string;
+  const received: any[] = [];
+  const logger = new Logger("WebhookSink:Test");
+
+  before(async () => {
+    server = createServer((req, res) => {
+      let body = "";
+      req.on("data", (chunk) => (body += chunk));
+      req.on("end", () => {
+        received.push(JSON.parse(body));
+        res.statusCode = req.url === "/fail" ? 500 : 204;
+        res.end();
+      });
+    });
+    await new Promise<void>((resolve) => server.listen(0, resolve));
+    url = `http://127.0.0.1:${(server.address() as AddressInfo).port}`;
+  });
+
+  after(async () => {
+    await new Promise<void>((resolve) => server.close(() => resolve()));
+  });
+
+  it("should POST events as JSON", async () => {
+    const sink = new WebhookSink({ urls: [`${url}/hook`] }, logger);
+    const delivered = await sink.deliver({
+      type: "server_started",
+      timestamp: 1,
+      data: { mode: "stdio" },
+    });
+
+    assert.strictEqual(delivered, 1);
+    assert.deepStrictEqual(received.pop(), {
+      source: "orchestr8",
+      type: "server_started",
+      timestamp: 1,
+      data: { mode: "stdio" },
+    });
+  });
+
+  it("should report failed deliveries without throwing", async () => {
+    const sink = new WebhookSink(
+      { urls: [`${url}/fail`, "http://127.0.0.1:1/unreachable"] },
+      logger,
+    );
+    const delivered = await sink.deliver({
+      type: "index_loaded",
+      timestamp: 2,
+      data: {},
+    });
+
+    assert.strictEqual(delivered, 0);
+  });
+
+  it("should only forward configured event types from the bus", async () => {
+    const bus = new ServerEventBus();
+    const sink = new WebhookSink(
+      { urls: [`${url}/hook`], events: ["validation_failed"] },
+      logger,
+    );
+    received.length = 0;
+    sink.attach(bus);
+
+    bus.publish("index_loaded", { fragments: 1 });
+    bus.publish("validation_failed", { path: "agents/bad.md" });
+    await new Promise((resolve) => setTimeout(resolve, 100));
+    sink.detach();
+
+    assert.strictEqual(received.length, 1);
+    assert.strictEqual(received[0].type, "validation_failed");
+  });
+});
@@ -0,0 +1,107 @@
+/**
+ * Test suite for Claude Code export
+ *
+ * Tests:
+ * - Skill folders and command files
+ * - Descriptions from capabilities and useWhen
+ * - Name collisions across categories
+ * - Round trip through the claude-skills importer
+ */
+
+import { describe, it, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { dirname, join } from "path";
+import matter from "gray-matter";
+import { exportCatalog } from "../convert/exporters.js";
+import { importDefinitions } from "../convert/importers.js";
+import type { ResourceFragment } from "../utils/fuzzyMatcher.js";
+
+const fragment = (
+  id: string,
+  category: ResourceFragment["category"],
+  extra: Partial<ResourceFragment> = {},
+): ResourceFragment => ({
+  id,
+  category,
+  tags: [],
+  capabilities: ["Review Rust code"],
+  useWhen: ["Auditing unsafe blocks"],
+  estimatedTokens: 10,
+  content: "\n# Rust Reviewer\n\nCheck lifetimes.\n",
+  ...extra,
+});
+
+describe("exportCatalog", () => {
+  const dirs: string[] = [];
+
+  after(async () => {
+    for (const dir of dirs) {
+      await rm(dir, { recursive: true, force: true });
+    }
+  });
+
+  it("should write skills as SKILL.md folders", () => {
+    const [file] = exportCatalog(
+      [fragment("agents/rust-reviewer", "agent")],
+      "claude-skills",
+    );
+    const parsed = matter(file.content);
+
+    assert.strictEqual(file.path, "rust-reviewer/SKILL.md");
+    assert.strictEqual(parsed.data.name, "rust-reviewer");
+    assert.strictEqual(
+      parsed.data.description,
+      "Review Rust code. Use when: Auditing unsafe blocks",
+    );
+    assert.match(parsed.content, /^# Rust Reviewer\n\nCheck lifetimes\.$/m);
+  });
+
+  it("should write commands with the model", () => {
+    const [file] = exportCatalog(
+      [fragment("workflows/review", "workflow", { model: "sonnet" })],
+      "claude-commands",
+    );
+    const parsed = matter(file.content);
+
+    assert.strictEqual(file.path, "review.md");
+    assert.strictEqual(parsed.data.model, "sonnet");
+    assert.ok(!("name" in parsed.data));
+  });
+
+  it("should keep both resources when names collide", () => {
+    const files = exportCatalog(
+      [fragment("agents/review", "agent"), fragment("skills/review", "skill")],
+      "claude-skills",
+    );
+
+    assert.deepStrictEqual(
+      files.map((f) => f.path),
+      ["review-agent/SKILL.md", "review-skill/SKILL.md"],
+    );
+  });
+
+  it("should reject unknown targets", () => {
+    assert.throws(() => exportCatalog([], "cursor-rules"), /Unknown export target/);
+  });
+
+  it("should round-trip through the claude-skills importer", async () => {
+    const dir = await mkdtemp(join(tmpdir(), "o8-export-"));
+    dirs.push(dir);
+    for (const file of exportCatalog(
+      [fragment("skills/rust-reviewer", "skill")],
+      "claude-skills",
+    )) {
+      await mkdir(join(dir, dirname(file.path)), { recursive: true });
+      await writeFile(join(dir, file.path), file.content);
+    }
+
+    const [imported] = await importDefinitions("claude-skills", dir);
+    const parsed = matter(imported.content);
+
+    assert.strictEqual(imported.path, "skills/rust-reviewer.md");
+    assert.match(parsed.content, /^# Rust Reviewer$/m);
+    assert.match(parsed.content, /Check lifetimes\./);
+  });
+});
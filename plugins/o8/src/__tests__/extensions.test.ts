@@ -0,0 +1,115 @@
+/**
+ * Test suite for the extension registry
+ *
+ * Tests:
+ * - Method registration and dispatch
+ * - Invalid and duplicate extensions
+ * - Loading extension modules from disk
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, writeFile, rm } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { ExtensionRegistry, ExtensionError } from "../extensions/registry.js";
+import { Logger } from "../utils/logger.js";
+
+function createRegistry(): ExtensionRegistry {
+  return new ExtensionRegistry({
+    logger: new Logger("test"),
+    getResourceIndex: async () => [],
+    getResourceContent: async (uri) => `content of ${uri}`,
+  });
+}
+
+describe("ExtensionRegistry", () => {
+  it("should dispatch namespaced methods with params and context", async () => {
+    const registry = createRegistry();
+    registry.register({
+      name: "acme",
+      methods: {
+        echo: (params) => params,
+        read: (params, ctx) => ctx.getResourceContent(params.uri),
+      },
+    });
+
+    assert.deepStrictEqual(registry.listMethods(), ["acme/echo", "acme/read"]);
+    assert.deepStrictEqual(await registry.dispatch("acme/echo", { a: 1 }), {
+      a: 1,
+    });
+    assert.strictEqual(
+      await registry.dispatch("acme/read", { uri: "o8://agents/x" }),
+      "content of o8://agents/x",
+    );
+  });
+
+  it("should reject unknown methods", async () => {
+    const registry = createRegistry();
+    await assert.rejects(
+      () => registry.dispatch("acme/missing", {}),
+      (error: any) => {
+        assert.ok(error instanceof ExtensionError);
+        assert.strictEqual(error.code, "METHOD_NOT_FOUND");
+        return true;
+      },
+    );
+  });
+
+  it("should reject malformed extensions", () => {
+    const registry = createRegistry();
+    assert.throws(
+      () => registry.register({ name: "bad name", methods: {} }),
+      (error: any) => error.code === "INVALID_EXTENSION",
+    );
+    assert.throws(
+      () =>
+        registry.register({ name: "acme", methods: { run: "nope" as any } }),
+      (error: any) => error.code === "INVALID_EXTENSION",
+    );
+  });
+
+  it("should reject duplicate method names", () => {
+    const registry = createRegistry();
+    registry.register({ name: "acme", methods: { run: () => 1 } });
+    assert.throws(
+      () => registry.register({ name: "acme", methods: { run: () => 2 } }),
+      (error: any) => error.code === "DUPLICATE_METHOD",
+    );
+  });
+
+  describe("loadFromPaths", () => {
+    let dir: string;
+
+    before(async () => {
+      dir = await mkdtemp(join(tmpdir(), "o8-ext-"));
+      await writeFile(
+        join(dir, "good.mjs"),
+        'export default { name: "good", methods: { ping: () => "pong" } };',
+      );
+      await writeFile(
+        join(dir, "named.mjs"),
+        'export const extension = { name: "named", methods: { id: (p) => p.id } };',
+      );
+      await writeFile(join(dir, "broken.mjs"), "export default {};");
+    });
+
+    after(async () => {
+      await rm(dir, { recursive: true, force: true });
+    });
+
+    it("should load valid modules and skip broken ones", async () => {
+      const registry = createRegistry();
+      const loaded = await registry.loadFromPaths([
+        join(dir, "good.mjs"),
+        join(dir, "broken.mjs"),
+        join(dir, "missing.mjs"),
+        join(dir, "named.mjs"),
+      ]);
+
+      assert.deepStrictEqual(loaded, ["good", "named"]);
+      assert.strictEqual(await registry.dispatch("good/ping", {}), "pong");
+      assert.strictEqual(await registry.dispatch("named/id", { id: 7 }), 7);
+    });
+  });
+});
@@ -0,0 +1,49 @@
+/**
+ * Test suite for sparse fieldsets
+ *
+ * Tests:
+ * - Parsing comma-separated field lists
+ * - Selecting fields in request order
+ * - Unknown fields
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { parseFields, selectFields } from "../utils/fields.js";
+
+const items = [
+  { id: "agents/a", tags: ["rust"], tokens: 100, snippet: "x" },
+  { id: "agents/b", tags: [], tokens: 50 },
+];
+
+describe("parseFields", () => {
+  it("should split, trim, and drop empty names", () => {
+    assert.deepStrictEqual(parseFields(" id, tokens ,,"), ["id", "tokens"]);
+  });
+
+  it("should return undefined when nothing is selected", () => {
+    assert.strictEqual(parseFields(undefined), undefined);
+    assert.strictEqual(parseFields(""), undefined);
+    assert.strictEqual(parseFields(" , "), undefined);
+  });
+});
+
+describe("selectFields", () => {
+  it("should keep only the requested fields", () => {
+    assert.deepStrictEqual(selectFields(items, ["tokens", "id"]), [
+      { tokens: 100, id: "agents/a" },
+      { tokens: 50, id: "agents/b" },
+    ]);
+  });
+
+  it("should skip fields an item does not have", () => {
+    assert.deepStrictEqual(selectFields(items, ["snippet", "missing"]), [
+      { snippet: "x" },
+      {},
+    ]);
+  });
+
+  it("should return items unchanged without a selection", () => {
+    assert.strictEqual(selectFields(items, undefined), items);
+  });
+});
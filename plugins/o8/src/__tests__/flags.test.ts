@@ -0,0 +1,91 @@
+/**
+ * Test suite for flags with environment equivalents
+ *
+ * Tests:
+ * - Flag to environment variable names (ORCHESTR8_, and the short O8_)
+ * - Command line over environment precedence
+ * - Subcommand argument parsing
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { envName, flagEnabled, flagValue, parseArgs } from "../utils/flags.js";
+
+describe("envName", () => {
+  it("should map flags to ORCHESTR8_ variables", () => {
+    assert.strictEqual(envName("stay-alive"), "ORCHESTR8_STAY_ALIVE");
+    assert.strictEqual(envName("http-port"), "ORCHESTR8_HTTP_PORT");
+  });
+});
+
+describe("flagValue", () => {
+  it("should read both argument forms", () => {
+    assert.strictEqual(flagValue("record", ["--record", "a.jsonl"], {}), "a.jsonl");
+    assert.strictEqual(flagValue("record", ["--record=b.jsonl"], {}), "b.jsonl");
+  });
+
+  it("should prefer the command line over the environment", () => {
+    const env = { ORCHESTR8_RECORD: "env.jsonl" };
+
+    assert.strictEqual(flagValue("record", ["--record", "cli.jsonl"], env), "cli.jsonl");
+    assert.strictEqual(flagValue("record", [], env), "env.jsonl");
+    assert.strictEqual(flagValue("record", [], {}), undefined);
+  });
+
+  it("should accept the short O8_ name, with ORCHESTR8_ winning", () => {
+    assert.strictEqual(flagValue("record", [], { O8_RECORD: "o8.jsonl" }), "o8.jsonl");
+    assert.strictEqual(
+      flagValue("record", [], {
+        ORCHESTR8_RECORD: "orchestr8.jsonl",
+        O8_RECORD: "o8.jsonl",
+      }),
+      "orchestr8.jsonl",
+    );
+  });
+});
+
+describe("flagEnabled", () => {
+  it("should accept common true values from the environment", () => {
+    for (const value of ["true", "1", "YES", "on"]) {
+      assert.strictEqual(flagEnabled("http", [], { ORCHESTR8_HTTP: value }), true);
+    }
+    assert.strictEqual(flagEnabled("http", [], { ORCHESTR8_HTTP: "0" }), false);
+    assert.strictEqual(flagEnabled("http", [], { O8_HTTP: "true" }), true);
+  });
+
+  it("should let --no-flag override the environment", () => {
+    assert.strictEqual(
+      flagEnabled("stay-alive", ["--no-stay-alive"], {
+        ORCHESTR8_STAY_ALIVE: "true",
+      }),
+      false,
+    );
+    assert.strictEqual(flagEnabled("stay-alive", ["--stay-alive"], {}), true);
+  });
+});
+
+describe("parseArgs", () => {
+  it("should separate values, switches, and positionals", () => {
+    const parsed = parseArgs(
+      ["--from", "crewai", "./crew", "--out=./resources", "--force"],
+      { values: ["from", "out"], switches: ["force"] },
+      {},
+    );
+
+    assert.deepStrictEqual(parsed, {
+      values: { from: "crewai", out: "./resources" },
+      switches: { force: true },
+      positional: ["./crew"],
+    });
+  });
+
+  it("should fill missing values from the environment", () => {
+    const parsed = parseArgs(["./crew"], { values: ["from"], switches: ["force"] }, {
+      ORCHESTR8_FROM: "autogen",
+      ORCHESTR8_FORCE: "1",
+    });
+
+    assert.strictEqual(parsed.values.from, "autogen");
+    assert.strictEqual(parsed.switches.force, true);
+  });
+});
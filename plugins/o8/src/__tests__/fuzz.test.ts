@@ -0,0 +1,258 @@
+/**
+ * Randomized fuzz tests for untrusted-input parsers
+ *
+ * Resource files can come from remote providers (GitHub, AITMPL), and stdio
+ * framing sees whatever the client writes, so these parsers must never hang
+ * or throw anything other than an Error. Inputs are mutations of a corpus of
+ * real resource files plus hand-picked edge cases.
+ *
+ * Runs are reproducible: set O8_FUZZ_SEED to replay a failure and
+ * O8_FUZZ_ITERATIONS to run longer locally.
+ *
+ * Targets:
+ * - Frontmatter + body extraction (ResourceLoader, FuzzyMatcher)
+ * - URI parsing
+ * - Template rendering
+ * - stdio JSON-RPC framing (ReadBuffer)
+ */
+
+import { describe, it, before } from "node:test";
+import assert from "node:assert";
+import { promises as fs } from "fs";
+import { join } from "path";
+import {
+  ReadBuffer,
+  serializeMessage,
+} from "@modelcontextprotocol/sdk/shared/stdio.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { FuzzyMatcher } from "../utils/fuzzyMatcher.js";
+import { URIParser } from "../utils/uriParser.js";
+import { TemplateEngine } from "../utils/templateEngine.js";
+import { Logger } from "../utils/logger.js";
+import { createRandom } from "../utils/sampling.js";
+
+const SEED = parseInt(process.env.O8_FUZZ_SEED || "20251015", 10);
+const ITERATIONS = parseInt(process.env.O8_FUZZ_ITERATIONS || "300", 10);
+
+/**
+ * Seeded random helpers
+ */
+function createFuzzRandom(seed: number) {
+  const next = createRandom(seed);
+  const int = (max: number) => Math.floor(next() * max);
+  const pick = <T>(items: T[]): T => items[int(items.length)];
+  return { next, int, pick };
+}
+
+type Random = ReturnType<typeof createFuzzRandom>;
+
+const TOKENS = [
+  "---",
+  "\n",
+  ":",
+  "- ",
+  "{{",
+  "}}",
+  "{{#if x}}",
+  "{{/if}}",
+  "{{> a}}",
+  "tags: [",
+  "]",
+  "null",
+  "~",
+  "&a",
+  "*a",
+  "!!binary",
+  "\u0000",
+  "�",
+  "é",
+  "🦀",
+  "%",
+  "?",
+  "&",
+  "=",
+  "#",
+  "\\",
+  '"',
+  "'",
+];
+
+/**
+ * Mutate a string by inserting, deleting, duplicating, or truncating
+ */
+function mutate(input: string, random: Random): string {
+  let output = input;
+  const rounds = 1 + random.int(4);
+  for (let i = 0; i < rounds; i++) {
+    const at = random.int(output.length + 1);
+    switch (random.int(4)) {
+      case 0:
+        output = output.slice(0, at) + random.pick(TOKENS) + output.slice(at);
+        break;
+      case 1:
+        output = output.slice(0, at) + output.slice(at + 1 + random.int(16));
+        break;
+      case 2:
+        output =
+          output.slice(0, at) + output.slice(at, at + 32) + output.slice(at);
+        break;
+      default:
+        output = output.slice(0, at);
+    }
+  }
+  return output;
+}
+
+/**
+ * Assert that a parser either returns or throws an Error
+ */
+async function assertFailsCleanly(
+  input: string,
+  parse: () => unknown,
+): Promise<void> {
+  try {
+    await parse();
+  } catch (error) {
+    assert.ok(
+      error instanceof Error,
+      `non-Error thrown for input ${JSON.stringify(input.slice(0, 200))} (seed ${SEED})`,
+    );
+  }
+}
+
+describe(`Fuzz (seed ${SEED}, ${ITERATIONS} iterations)`, () => {
+  const corpus: string[] = [
+    "",
+    "---\n---\n",
+    "---\nnull\n---\nbody",
+    "---\n42\n---\nbody",
+    "---\ntags: not-a-list\nuseWhen: 7\n---\n# Title",
+    "---\ntags:\n  - [nested]\n---\n",
+    "no frontmatter at all",
+  ];
+
+  before(async () => {
+    const dir = join(process.cwd(), "resources", "agents");
+    const files = (await fs.readdir(dir))
+      .filter((f) => f.endsWith(".md"))
+      .sort();
+    for (const file of files.slice(0, 10)) {
+      corpus.push(await fs.readFile(join(dir, file), "utf-8"));
+    }
+  });
+
+  it("should parse resource fragments without crashing", async () => {
+    const random = createFuzzRandom(SEED);
+    const loader = new ResourceLoader(new Logger("fuzz")) as any;
+    const matcher = new FuzzyMatcher() as any;
+
+    for (let i = 0; i < ITERATIONS; i++) {
+      const input = mutate(random.pick(corpus), random);
+
+      await assertFailsCleanly(input, async () => {
+        const fragment = await loader._parseResourceFragment(
+          input,
+          "agents",
+          "agents/fuzz.md",
+        );
+        assert.ok(Array.isArray(fragment.tags));
+        assert.ok(Array.isArray(fragment.useWhen));
+        assert.strictEqual(typeof fragment.content, "string");
+      });
+      await assertFailsCleanly(input, () =>
+        matcher._parseResourceFragment(
+          input,
+          "agent",
+          "agents",
+          "/tmp/resources/agents/fuzz.md",
+        ),
+      );
+    }
+  });
+
+  it("should parse arbitrary URIs without crashing", async () => {
+    const random = createFuzzRandom(SEED + 1);
+    const parser = new URIParser();
+    const seeds = [
+      "o8://agents/typescript-developer",
+      "o8://agents/match?query=build+api&maxTokens=2000&tags=a,b",
+      "o8://match?query=x&categories=agents,skills&mode=index&maxResults=5",
+      "o8://agents/code-reviewer?lang=rust&strict=true",
+    ];
+
+    for (let i = 0; i < ITERATIONS; i++) {
+      const input = mutate(random.pick(seeds), random);
+      await assertFailsCleanly(input, () => parser.parse(input));
+    }
+  });
+
+  it("should render arbitrary templates without crashing", async () => {
+    const random = createFuzzRandom(SEED + 2);
+    const engine = new TemplateEngine();
+    const declared = [{ name: "x", type: "string" as const }];
+    const seeds = [
+      "{{#if x}}a{{else}}b{{/if}} {{x}}",
+      '{{#if x == "1"}}{{#if !x}}n{{/if}}{{/if}}',
+      "plain {{ y }} ${{ secrets.T }}",
+    ];
+
+    for (let i = 0; i < ITERATIONS; i++) {
+      const input = mutate(random.pick(seeds), random);
+      await assertFailsCleanly(input, () =>
+        engine.render(input, declared, { x: "1" }),
+      );
+    }
+  });
+
+  it("should reassemble JSON-RPC messages split at any point", () => {
+    const random = createFuzzRandom(SEED + 3);
+    const messages = [
+      { jsonrpc: "2.0" as const, id: 1, method: "resources/list" },
+      {
+        jsonrpc: "2.0" as const,
+        id: 2,
+        method: "resources/read",
+        params: { uri: "o8://agents/é🦀?x=1" },
+      },
+      { jsonrpc: "2.0" as const, method: "notifications/initialized" },
+    ];
+    const stream = Buffer.from(messages.map(serializeMessage).join(""));
+
+    for (let i = 0; i < ITERATIONS; i++) {
+      const buffer = new ReadBuffer();
+      const received: unknown[] = [];
+      let offset = 0;
+      while (offset < stream.length) {
+        const size = 1 + random.int(24);
+        buffer.append(stream.subarray(offset, offset + size));
+        offset += size;
+        let message;
+        while ((message = buffer.readMessage()) !== null) {
+          received.push(message);
+        }
+      }
+      assert.deepStrictEqual(received, messages);
+    }
+  });
+
+  it("should reject garbage frames with an Error", async () => {
+    const random = createFuzzRandom(SEED + 4);
+    const valid = serializeMessage({
+      jsonrpc: "2.0",
+      id: 1,
+      method: "resources/read",
+      params: { uri: "o8://registry" },
+    });
+
+    for (let i = 0; i < ITERATIONS; i++) {
+      const input = mutate(valid, random);
+      const buffer = new ReadBuffer();
+      buffer.append(Buffer.from(input + "\n"));
+      await assertFailsCleanly(input, () => {
+        while (buffer.readMessage() !== null) {
+          // drain
+        }
+      });
+    }
+  });
+});
@@ -0,0 +1,105 @@
+/**
+ * Test suite for git history
+ *
+ * Tests:
+ * - Per-file commit history, newest first
+ * - Limits
+ * - Reading files at a revision
+ * - Directories outside a repository
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { execFileSync } from "child_process";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  fileAtRevision,
+  fileHistory,
+  GitHistoryError,
+} from "../utils/gitHistory.js";
+
+const gitEnv = {
+  ...process.env,
+  GIT_AUTHOR_NAME: "Ada",
+  GIT_AUTHOR_EMAIL: "ada@example.com",
+  GIT_COMMITTER_NAME: "Ada",
+  GIT_COMMITTER_EMAIL: "ada@example.com",
+};
+
+function git(cwd: string, ...args: string[]): void {
+  execFileSync("git", args, { cwd, env: gitEnv, stdio: "ignore" });
+}
+
+describe("fileHistory", () => {
+  let repo: string;
+  let outside: string;
+
+  before(async () => {
+    repo = await mkdtemp(join(tmpdir(), "o8-git-"));
+    outside = await mkdtemp(join(tmpdir(), "o8-nogit-"));
+    await mkdir(join(repo, "agents"));
+    git(repo, "init", "-q");
+
+    await writeFile(join(repo, "agents", "rust.md"), "v1\n");
+    git(repo, "add", ".");
+    git(repo, "commit", "-q", "-m", "Add rust agent");
+
+    await writeFile(join(repo, "agents", "other.md"), "x\n");
+    git(repo, "add", ".");
+    git(repo, "commit", "-q", "-m", "Add other agent");
+
+    await writeFile(join(repo, "agents", "rust.md"), "v2\n");
+    git(repo, "commit", "-q", "-am", "Tighten rust prompt");
+  });
+
+  after(async () => {
+    await rm(repo, { recursive: true, force: true });
+    await rm(outside, { recursive: true, force: true });
+  });
+
+  it("should list commits touching the file, newest first", async () => {
+    const history = await fileHistory(repo, "agents/rust.md");
+
+    assert.deepStrictEqual(
+      history.map((entry) => entry.message),
+      ["Tighten rust prompt", "Add rust agent"],
+    );
+    assert.strictEqual(history[0].author, "Ada");
+    assert.strictEqual(history[0].email, "ada@example.com");
+    assert.match(history[0].commit, /^[0-9a-f]{40}$/);
+    assert.ok(!Number.isNaN(Date.parse(history[0].date)));
+  });
+
+  it("should apply the limit", async () => {
+    assert.strictEqual((await fileHistory(repo, "agents/rust.md", 1)).length, 1);
+    await assert.rejects(fileHistory(repo, "agents/rust.md", -1), RangeError);
+  });
+
+  it("should return nothing for uncommitted files", async () => {
+    assert.deepStrictEqual(await fileHistory(repo, "agents/missing.md"), []);
+  });
+
+  it("should read a file at a revision", async () => {
+    assert.strictEqual(
+      await fileAtRevision(repo, "agents/rust.md", "HEAD~2"),
+      "v1\n",
+    );
+    assert.strictEqual(await fileAtRevision(repo, "agents/rust.md", "HEAD"), "v2\n");
+  });
+
+  it("should reject option-like revisions", async () => {
+    await assert.rejects(
+      fileAtRevision(repo, "agents/rust.md", "--output=/tmp/x"),
+      (error: GitHistoryError) => error.code === "GIT_FAILED",
+    );
+  });
+
+  it("should fail with NOT_A_GIT_REPO outside a repository", async () => {
+    await assert.rejects(
+      fileHistory(outside, "agents/rust.md"),
+      (error: GitHistoryError) => error.code === "NOT_A_GIT_REPO",
+    );
+  });
+});
@@ -0,0 +1,107 @@
+/**
+ * Test suite for regex search over resource files
+ *
+ * Tests:
+ * - Invalid and oversized patterns are rejected
+ * - Hits carry file line numbers, frontmatter included
+ * - Category filter and hit limit
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  GrepPatternError,
+  MAX_GREP_PATTERN,
+  compilePattern,
+  grepLines,
+} from "../utils/grep.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("compilePattern", () => {
+  it("should reject invalid and oversized patterns", () => {
+    assert.throws(() => compilePattern("deploy("), GrepPatternError);
+    assert.throws(() => compilePattern(""), GrepPatternError);
+    assert.throws(
+      () => compilePattern("a".repeat(MAX_GREP_PATTERN + 1)),
+      GrepPatternError,
+    );
+    assert.ok(compilePattern("DEPLOY", { ignoreCase: true }).test("deploy"));
+  });
+});
+
+describe("grepLines", () => {
+  it("should report 1-based line numbers up to the maximum", () => {
+    const hits = grepLines(
+      "agents/a",
+      "one\r\ndeploy-v1\nthree\ndeploy-v1 again",
+      /deploy-v1/,
+      1,
+    );
+    assert.deepStrictEqual(hits, [
+      { id: "agents/a", line: 2, text: "deploy-v1" },
+    ]);
+  });
+});
+
+describe("ResourceLoader.grepResources", () => {
+  let root: string;
+  let loader: ResourceLoader;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-grep-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await mkdir(join(root, "skills"), { recursive: true });
+    await writeFile(
+      join(root, "agents", "release-manager.md"),
+      "---\ntools: [deploy-v1]\n---\n# Release Manager\n\nRun deploy-v1 last.\n",
+    );
+    await writeFile(
+      join(root, "agents", "reviewer.md"),
+      "---\ntags: [review]\n---\n# Reviewer\n\nNever run Deploy-V1.\n",
+    );
+    await writeFile(
+      join(root, "skills", "shipping.md"),
+      "# Shipping\n\nUse deploy-v1.\n",
+    );
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should find agents referencing a pattern, frontmatter included", async () => {
+    const { hits, truncated } = await loader.grepResources("deploy-v1");
+    assert.deepStrictEqual(hits, [
+      { id: "agents/release-manager", line: 2, text: "tools: [deploy-v1]" },
+      { id: "agents/release-manager", line: 6, text: "Run deploy-v1 last." },
+    ]);
+    assert.strictEqual(truncated, false);
+  });
+
+  it("should honor case, category, and limit options", async () => {
+    const { hits, truncated } = await loader.grepResources("deploy-v1", {
+      ignoreCase: true,
+      limit: 2,
+    });
+    assert.strictEqual(hits.length, 2);
+    assert.strictEqual(truncated, true);
+
+    const skills = await loader.grepResources("deploy-v1", {
+      category: "skills",
+    });
+    assert.deepStrictEqual(
+      skills.hits.map((hit) => [hit.id, hit.line]),
+      [["skills/shipping", 3]],
+    );
+  });
+});
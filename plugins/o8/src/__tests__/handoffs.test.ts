@@ -0,0 +1,112 @@
+/**
+ * Test suite for agent handoff rules
+ *
+ * Tests:
+ * - handoff_to frontmatter: names, ids, and conditional entries
+ * - Handoff graph with alias resolution and dangling targets
+ * - agents/handoffs for a single agent
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { parseHandoffs } from "../utils/handoffs.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("parseHandoffs", () => {
+  it("should read names, ids, and conditional entries", () => {
+    assert.deepStrictEqual(
+      parseHandoffs(
+        [
+          "code-reviewer",
+          "skills/testing.md",
+          { to: "security-auditor", when: " auth changes " },
+          { when: "no target" },
+          42,
+        ],
+        "agents/code-writer",
+      ),
+      [
+        { to: "agents/code-reviewer" },
+        { to: "skills/testing" },
+        { to: "agents/security-auditor", when: "auth changes" },
+      ],
+    );
+  });
+
+  it("should accept a single name", () => {
+    assert.deepStrictEqual(parseHandoffs("reviewer", "agents/writer"), [
+      { to: "agents/reviewer" },
+    ]);
+  });
+});
+
+describe("ResourceLoader.getHandoffs", () => {
+  let root: string;
+  let loader: ResourceLoader;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-handoffs-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeFile(
+      join(root, "agents", "code-writer.md"),
+      "---\nhandoff_to:\n  - reviewer\n  - to: security-auditor\n    when: auth changes\n---\n# Code Writer\n",
+    );
+    await writeFile(
+      join(root, "agents", "code-reviewer.md"),
+      "---\naliases: [reviewer]\nhandoff_to: [release-manager]\n---\n# Code Reviewer\n",
+    );
+    await writeFile(
+      join(root, "agents", "release-manager.md"),
+      "# Release Manager\n",
+    );
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should build the graph and report dangling targets", async () => {
+    assert.deepStrictEqual(await loader.getHandoffs(), {
+      nodes: [
+        "agents/code-reviewer",
+        "agents/code-writer",
+        "agents/release-manager",
+      ],
+      edges: [
+        { from: "agents/code-reviewer", to: "agents/release-manager" },
+        { from: "agents/code-writer", to: "agents/code-reviewer" },
+      ],
+      dangling: [
+        {
+          from: "agents/code-writer",
+          to: "agents/security-auditor",
+          when: "auth changes",
+        },
+      ],
+    });
+  });
+
+  it("should list the handoffs of one agent", async () => {
+    const graph = await loader.getHandoffs("reviewer");
+    assert.deepStrictEqual(graph.edges, [
+      { from: "agents/code-reviewer", to: "agents/release-manager" },
+      { from: "agents/code-writer", to: "agents/code-reviewer" },
+    ]);
+
+    const leaf = await loader.getHandoffs("o8://agents/release-manager");
+    assert.deepStrictEqual(leaf.nodes, [
+      "agents/code-reviewer",
+      "agents/release-manager",
+    ]);
+  });
+});
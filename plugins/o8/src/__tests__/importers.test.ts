@@ -0,0 +1,133 @@
+/**
+ * Test suite for definition import
+ *
+ * Tests:
+ * - CrewAI agents.yaml mapping
+ * - AutoGen component and llm_config shapes
+ * - Claude Code SKILL.md folders
+ * - TODO comments for unmapped fields
+ * - Unknown formats and empty directories
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import matter from "gray-matter";
+import { importDefinitions, ImportError } from "../convert/importers.js";
+
+describe("importDefinitions", () => {
+  let root: string;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-import-"));
+
+    await mkdir(join(root, "crew", "config"), { recursive: true });
+    await writeFile(
+      join(root, "crew", "config", "agents.yaml"),
+      [
+        "researcher:",
+        "  role: Senior Data Researcher",
+        "  goal: Uncover cutting-edge developments",
+        "  backstory: You find the latest news.",
+        "  llm: gpt-4o",
+        "  tools: [search]",
+      ].join("\n"),
+    );
+
+    await mkdir(join(root, "autogen"));
+    await writeFile(
+      join(root, "autogen", "planner.json"),
+      JSON.stringify({
+        provider: "autogen_agentchat.agents.AssistantAgent",
+        label: "Planner",
+        config: {
+          name: "planner",
+          description: "Breaks goals into steps",
+          system_message: "You plan work.",
+          model_client: { config: { model: "gpt-4o-mini" } },
+          reflect_on_tool_use: false,
+        },
+      }),
+    );
+    await writeFile(
+      join(root, "autogen", "coder.json"),
+      JSON.stringify({
+        name: "coder",
+        system_message: "You write code.",
+        llm_config: { config_list: [{ model: "gpt-4" }] },
+      }),
+    );
+
+    await mkdir(join(root, "skills", "pdf-tools"), { recursive: true });
+    await writeFile(
+      join(root, "skills", "pdf-tools", "SKILL.md"),
+      "---\nname: pdf-tools\ndescription: Extract text from PDFs\nallowed-tools: Read\n---\n# PDF Tools\n\nUse pdftotext.\n",
+    );
+    await mkdir(join(root, "empty"));
+  });
+
+  after(async () => {
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should convert CrewAI agents", async () => {
+    const [agent] = await importDefinitions("crewai", join(root, "crew"));
+    const parsed = matter(agent.content);
+
+    assert.strictEqual(agent.path, "agents/researcher.md");
+    assert.strictEqual(parsed.data.id, "researcher");
+    assert.strictEqual(parsed.data.category, "agent");
+    assert.strictEqual(parsed.data.model, "gpt-4o");
+    assert.deepStrictEqual(parsed.data.capabilities, [
+      "Uncover cutting-edge developments",
+    ]);
+    assert.match(parsed.content, /^# Senior Data Researcher$/m);
+    assert.match(parsed.content, /## Backstory\n\nYou find the latest news\./);
+    assert.match(
+      parsed.content,
+      /<!-- TODO\(import\): crewai field "tools" has no orchestr8 equivalent: \["search"\] -->/,
+    );
+  });
+
+  it("should convert both AutoGen shapes", async () => {
+    const resources = await importDefinitions("autogen", join(root, "autogen"));
+    const [coder, planner] = resources.map((r) => matter(r.content));
+
+    assert.deepStrictEqual(
+      resources.map((r) => r.path),
+      ["agents/coder.md", "agents/planner.md"],
+    );
+    assert.strictEqual(coder.data.model, "gpt-4");
+    assert.strictEqual(planner.data.model, "gpt-4o-mini");
+    assert.deepStrictEqual(planner.data.useWhen, ["Breaks goals into steps"]);
+    assert.match(planner.content, /You plan work\./);
+    assert.ok(
+      resources[1].todos.some((todo) => todo.includes("reflect_on_tool_use")),
+    );
+  });
+
+  it("should convert Claude Code skills", async () => {
+    const [skill] = await importDefinitions("claude-skills", join(root, "skills"));
+    const parsed = matter(skill.content);
+
+    assert.strictEqual(skill.path, "skills/pdf-tools.md");
+    assert.strictEqual(parsed.data.category, "skill");
+    assert.deepStrictEqual(parsed.data.useWhen, ["Extract text from PDFs"]);
+    assert.strictEqual(parsed.content.match(/^# PDF Tools$/gm)?.length, 1);
+    assert.ok(skill.todos.some((todo) => todo.includes('"allowed-tools"')));
+    assert.ok(skill.todos.includes("add tags so the resource can be matched"));
+  });
+
+  it("should reject unknown formats and empty directories", async () => {
+    await assert.rejects(
+      importDefinitions("langchain", root),
+      (error: ImportError) => error.code === "UNKNOWN_FORMAT",
+    );
+    await assert.rejects(
+      importDefinitions("crewai", join(root, "empty")),
+      (error: ImportError) => error.code === "NO_DEFINITIONS",
+    );
+  });
+});
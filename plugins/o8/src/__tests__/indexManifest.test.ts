@@ -0,0 +1,101 @@
+/**
+ * Test suite for index manifests
+ *
+ * Tests:
+ * - Manifest validation
+ * - The manifest lists resource and _defaults.yaml files with hashes
+ * - With O8_INDEX_MANIFEST only listed files are indexed, with defaults
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { parseIndexManifest } from "../utils/indexManifest.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("parseIndexManifest", () => {
+  it("should reject other versions and paths outside the root", () => {
+    assert.throws(
+      () => parseIndexManifest('{"version":2,"files":[]}', "m.json"),
+      /version/,
+    );
+    assert.throws(
+      () =>
+        parseIndexManifest(
+          '{"version":1,"files":[{"path":"../etc/passwd","hash":"x"}]}',
+          "m.json",
+        ),
+      /bad entry/,
+    );
+    assert.deepStrictEqual(
+      parseIndexManifest('{"version":1,"files":[]}', "m.json").files,
+      [],
+    );
+  });
+});
+
+describe("ResourceLoader index manifest", () => {
+  let root: string;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-manifest-"));
+    await mkdir(join(root, "agents", "infra"), { recursive: true });
+    await writeFile(
+      join(root, "agents", "infra", "_defaults.yaml"),
+      "model: sonnet\n",
+    );
+    await writeFile(join(root, "agents", "infra", "terraform.md"), "# TF\n");
+    await writeFile(join(root, "agents", "writer.md"), "# Writer\n");
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    delete process.env.O8_INDEX_MANIFEST;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should list resource and defaults files", async () => {
+    const manifest = await new ResourceLoader(
+      new Logger("test"),
+    ).buildIndexManifest();
+    assert.deepStrictEqual(
+      manifest.files.map((file) => file.path),
+      [
+        "agents/infra/_defaults.yaml",
+        "agents/infra/terraform.md",
+        "agents/writer.md",
+      ],
+    );
+    assert.match(manifest.files[0].hash, /^[0-9a-f]{64}$/);
+  });
+
+  it("should index only the files the manifest lists", async () => {
+    const manifest = await new ResourceLoader(
+      new Logger("test"),
+    ).buildIndexManifest();
+    const path = join(root, "index-manifest.json");
+    await writeFile(path, JSON.stringify(manifest));
+    await writeFile(join(root, "agents", "late.md"), "# Added later\n");
+    await writeFile(join(root, "agents", "writer.md"), "# Writer v2\n");
+
+    process.env.O8_INDEX_MANIFEST = path;
+    const fragments = await new ResourceLoader(
+      new Logger("test"),
+    ).loadResourceIndex();
+    const byId = new Map(fragments.map((fragment) => [fragment.id, fragment]));
+
+    assert.deepStrictEqual([...byId.keys()].sort(), [
+      "agents/infra/terraform",
+      "agents/writer",
+    ]);
+    assert.strictEqual(byId.get("agents/infra/terraform")?.model, "sonnet");
+    assert.match(byId.get("agents/writer")!.content, /Writer v2/);
+  });
+});
@@ -0,0 +1,87 @@
+/**
+ * Test suite for indexing progress
+ *
+ * Tests:
+ * - Reports are throttled to one per interval
+ * - Percent complete, including empty catalogs
+ * - Builds that finish within the first interval stay quiet
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { IndexProgress, type IndexingState } from "../utils/indexProgress.js";
+
+function tracker(total: number) {
+  let clock = 0;
+  const reports: IndexingState[] = [];
+  const progress = new IndexProgress(
+    total,
+    (state) => reports.push(state),
+    1000,
+    () => clock,
+  );
+  return {
+    progress,
+    reports,
+    tick: (ms: number) => {
+      clock += ms;
+    },
+  };
+}
+
+describe("IndexProgress", () => {
+  it("should report at most once per interval", () => {
+    const { progress, reports, tick } = tracker(10);
+
+    progress.advance();
+    progress.advance();
+    assert.strictEqual(reports.length, 0);
+
+    tick(1000);
+    progress.advance();
+    tick(500);
+    progress.advance();
+    assert.deepStrictEqual(reports, [
+      { processed: 3, total: 10, percent: 30 },
+    ]);
+  });
+
+  it("should report 100% on finish after interim reports", () => {
+    const { progress, reports, tick } = tracker(3);
+
+    tick(1000);
+    progress.advance();
+    progress.finish();
+    assert.deepStrictEqual(reports.at(-1), {
+      processed: 3,
+      total: 3,
+      percent: 100,
+    });
+  });
+
+  it("should stay quiet when indexing finishes quickly", () => {
+    const { progress, reports } = tracker(3);
+
+    progress.advance(3);
+    progress.finish();
+    assert.strictEqual(reports.length, 0);
+    assert.strictEqual(progress.state().percent, 100);
+  });
+
+  it("should round percent down and never pass the total", () => {
+    const { progress } = tracker(3);
+
+    progress.advance();
+    assert.strictEqual(progress.state().percent, 33);
+    progress.advance(5);
+    assert.deepStrictEqual(progress.state(), {
+      processed: 3,
+      total: 3,
+      percent: 100,
+    });
+  });
+
+  it("should treat an empty catalog as complete", () => {
+    assert.strictEqual(tracker(0).progress.state().percent, 100);
+  });
+});
@@ -0,0 +1,62 @@
+/**
+ * Test suite for the index build watchdog
+ *
+ * Tests:
+ * - Stall timeout from the environment
+ * - Builds that keep making progress finish normally
+ * - Builds that stop making progress fail with IndexStallError
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  DEFAULT_STALL_TIMEOUT,
+  IndexStallError,
+  stallTimeout,
+  watchBuild,
+} from "../utils/indexWatchdog.js";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("stallTimeout", () => {
+  it("should read the timeout from the environment", () => {
+    assert.strictEqual(stallTimeout({}), DEFAULT_STALL_TIMEOUT);
+    assert.strictEqual(stallTimeout({ O8_INDEX_STALL_TIMEOUT: "0" }), 0);
+    assert.throws(
+      () => stallTimeout({ O8_INDEX_STALL_TIMEOUT: "soon" }),
+      /O8_INDEX_STALL_TIMEOUT/,
+    );
+  });
+});
+
+describe("watchBuild", () => {
+  it("should return the result of a build that makes progress", async () => {
+    let processed = 0;
+    const build = (async () => {
+      for (let i = 0; i < 5; i++) {
+        await sleep(20);
+        processed++;
+      }
+      return "done";
+    })();
+
+    assert.strictEqual(await watchBuild(build, () => processed, 60), "done");
+  });
+
+  it("should fail a build that stops making progress", async () => {
+    const stuck = new Promise<string>(() => {});
+
+    await assert.rejects(
+      watchBuild(stuck, () => 3, 40),
+      (error: unknown) =>
+        error instanceof IndexStallError &&
+        error.progress === 3 &&
+        error.code === "INDEX_STALLED",
+    );
+  });
+
+  it("should not watch with a timeout of 0", async () => {
+    const build = sleep(30).then(() => 1);
+    assert.strictEqual(await watchBuild(build, () => null, 0), 1);
+  });
+});
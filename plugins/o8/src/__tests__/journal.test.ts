@@ -0,0 +1,119 @@
+/**
+ * Test suite for the mutation journal
+ *
+ * Tests:
+ * - Rewrites are journaled with their actor and file hashes
+ * - Interrupted rewrites roll forward when every file was staged
+ * - Interrupted rewrites roll back when a file was not staged
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, readFile, readdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { Journal, hashContent } from "../state/journal.js";
+import { stagingPath } from "../utils/rewrite.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("Journal.recover", () => {
+  let dir: string;
+
+  before(async () => {
+    dir = await mkdtemp(join(tmpdir(), "o8-journal-"));
+  });
+
+  after(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  /** Two files with a pending rewrite; `staged` of them are staged */
+  const interrupted = async (name: string, staged: number) => {
+    const journal = new Journal(join(dir, name, "state"));
+    const files = ["a.md", "b.md"].map((file) => {
+      const path = join(dir, name, file);
+      return {
+        path,
+        staged: stagingPath(path),
+        before: hashContent("old"),
+        after: hashContent("new"),
+      };
+    });
+    for (const [i, file] of files.entries()) {
+      await writeFile(file.path, "old");
+      if (i < staged) {
+        await writeFile(file.staged, "new");
+      }
+    }
+    await journal.begin("rewrite", "cli:test", files);
+    return { journal, files };
+  };
+
+  it("should roll forward when every file was staged", async () => {
+    await mkdir(join(dir, "forward"));
+    const { journal, files } = await interrupted("forward", 2);
+
+    const [entry] = await journal.recover();
+    assert.strictEqual(entry.status, "rolled-forward");
+    for (const file of files) {
+      assert.strictEqual(await readFile(file.path, "utf-8"), "new");
+    }
+    assert.deepStrictEqual(await journal.recover(), []);
+  });
+
+  it("should roll back when a file was not staged", async () => {
+    await mkdir(join(dir, "back"));
+    const { journal, files } = await interrupted("back", 1);
+
+    const [entry] = await journal.recover();
+    assert.strictEqual(entry.status, "rolled-back");
+    for (const file of files) {
+      assert.strictEqual(await readFile(file.path, "utf-8"), "old");
+    }
+    assert.deepStrictEqual(
+      (await readdir(join(dir, "back"))).sort(),
+      ["a.md", "b.md", "state"],
+    );
+  });
+});
+
+describe("ResourceLoader journal", () => {
+  let root: string;
+  let loader: ResourceLoader;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-journal-loader-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeFile(join(root, "agents", "deployer.md"), "# Uses deploy-v1\n");
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should journal rewrites with who made them", async () => {
+    await loader.rewriteResources(
+      { pattern: "deploy-v1", replacement: "deploy-v2", literal: true },
+      "http:admin",
+    );
+
+    const { entries } = await loader.getJournal();
+    assert.strictEqual(entries.length, 1);
+    assert.strictEqual(entries[0].op, "rewrite");
+    assert.strictEqual(entries[0].actor, "http:admin");
+    assert.strictEqual(entries[0].status, "committed");
+    assert.deepStrictEqual(entries[0].files[0], {
+      path: join(root, "agents", "deployer.md"),
+      staged: stagingPath(join(root, "agents", "deployer.md")),
+      before: hashContent("# Uses deploy-v1\n"),
+      after: hashContent("# Uses deploy-v2\n"),
+    });
+  });
+});
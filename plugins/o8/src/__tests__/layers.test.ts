@@ -0,0 +1,145 @@
+/**
+ * Test suite for layered resource directories
+ *
+ * Tests:
+ * - Default search order and O8_LAYERS
+ * - Later layers override earlier ones by id
+ * - Static reads and provenance follow the winning layer
+ * - Provenance on query results
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, readFile, rm, writeFile } from "fs/promises";
+import { createHash } from "crypto";
+import { tmpdir } from "os";
+import { join } from "path";
+import { resourceLayers } from "../utils/layers.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("resourceLayers", () => {
+  it("should search builtin, system, user, then project", async () => {
+    const root = await mkdtemp(join(tmpdir(), "o8-layers-"));
+    try {
+      await mkdir(join(root, ".git"));
+      await mkdir(join(root, "src"));
+
+      const layers = await resourceLayers(
+        "/app/resources",
+        { XDG_CONFIG_HOME: "/home/me/.config" },
+        join(root, "src"),
+      );
+      assert.deepStrictEqual(layers, [
+        { name: "builtin", path: "/app/resources" },
+        { name: "system", path: "/etc/orchestr8" },
+        { name: "user", path: "/home/me/.config/orchestr8" },
+        { name: "project", path: join(root, ".orchestr8") },
+      ]);
+    } finally {
+      await rm(root, { recursive: true, force: true });
+    }
+  });
+
+  it("should replace the extra layers with O8_LAYERS", async () => {
+    const layers = await resourceLayers("/app/resources", {
+      O8_LAYERS: "team=/srv/agents, app=/app/resources",
+    });
+    assert.deepStrictEqual(layers, [
+      { name: "builtin", path: "/app/resources" },
+      { name: "team", path: "/srv/agents" },
+    ]);
+
+    assert.deepStrictEqual(
+      await resourceLayers("/app/resources", { O8_LAYERS: "none" }),
+      [{ name: "builtin", path: "/app/resources" }],
+    );
+    await assert.rejects(
+      resourceLayers("/app/resources", { O8_LAYERS: "/srv/agents" }),
+      /Invalid O8_LAYERS entry/,
+    );
+  });
+});
+
+describe("ResourceLoader layers", () => {
+  let root: string;
+  let loader: ResourceLoader;
+  const writeAgent = async (layer: string, name: string, body: string) => {
+    await mkdir(join(root, layer, "agents"), { recursive: true });
+    await writeFile(
+      join(root, layer, "agents", `${name}.md`),
+      `---\ntags: [${name}]\n---\n${body}\n`,
+    );
+  };
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-layers-"));
+    await writeAgent("builtin", "alpha", "# Alpha (builtin)");
+    await writeAgent("builtin", "beta", "# Beta (builtin)");
+    await writeAgent("user", "alpha", "# Alpha (user)");
+    await writeAgent("project", "alpha", "# Alpha (project)");
+    await writeAgent("project", "gamma", "# Gamma (project)");
+    process.env.RESOURCES_PATH = join(root, "builtin");
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = `user=${join(root, "user")},project=${join(root, "project")}`;
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should merge layers by id", async () => {
+    const index = await loader.loadResourceIndex();
+    const byId = new Map(index.map((fragment) => [fragment.id, fragment]));
+
+    assert.deepStrictEqual([...byId.keys()].sort(), [
+      "agents/alpha",
+      "agents/beta",
+      "agents/gamma",
+    ]);
+    assert.match(byId.get("agents/alpha")!.content, /Alpha \(project\)/);
+    assert.strictEqual(byId.get("agents/beta")!.origin?.layer, "builtin");
+  });
+
+  it("should read static resources from the winning layer", async () => {
+    const content = await loader.loadResourceContent("o8://agents/alpha");
+    assert.match(content, /Alpha \(project\)/);
+  });
+
+  it("should report the layers a resource overrides", async () => {
+    const provenance = await loader.getResourceProvenance("o8://agents/alpha");
+    const filePath = join(root, "project", "agents", "alpha.md");
+
+    assert.deepStrictEqual(provenance, {
+      id: "agents/alpha",
+      layer: "project",
+      sourceDir: join(root, "project"),
+      filePath,
+      hash: createHash("sha256")
+        .update(await readFile(filePath, "utf-8"))
+        .digest("hex"),
+      overrides: [
+        { layer: "builtin", sourceDir: join(root, "builtin") },
+        { layer: "user", sourceDir: join(root, "user") },
+      ],
+    });
+    assert.strictEqual(await loader.getResourceProvenance("agents/missing"), null);
+  });
+
+  it("should attach provenance to query results", async () => {
+    const [result] = await loader.searchResources("gamma");
+    assert.strictEqual(result.provenance.layer, "project");
+    assert.strictEqual(
+      result.provenance.filePath,
+      join(root, "project", "agents", "gamma.md"),
+    );
+    assert.match(result.provenance.hash, /^[0-9a-f]{64}$/);
+    assert.ok(!Number.isNaN(Date.parse(result.provenance.lastModified)));
+
+    const [hit] = await loader.searchBodies("Beta");
+    assert.strictEqual(hit.provenance?.layer, "builtin");
+  });
+});
@@ -0,0 +1,37 @@
+/**
+ * Test suite for request payload limits
+ *
+ * Tests:
+ * - Counts must be positive integers within their limit
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  InvalidLimitError,
+  PayloadLimitError,
+  checkCount,
+} from "../utils/limits.js";
+
+describe("checkCount", () => {
+  it("should accept positive integers up to the limit", () => {
+    assert.strictEqual(checkCount("count", 1, 100), 1);
+    assert.strictEqual(checkCount("count", 100, 100), 100);
+  });
+
+  it("should reject negative, zero, fractional, and non-numeric counts", () => {
+    for (const value of [-3, 0, 1.5, NaN, "5", undefined]) {
+      assert.throws(
+        () => checkCount("count", value, 100),
+        (error: InvalidLimitError) =>
+          error instanceof InvalidLimitError &&
+          error.message === "count must be a positive integer",
+        String(value),
+      );
+    }
+  });
+
+  it("should reject counts over the limit", () => {
+    assert.throws(() => checkCount("perCategory", 101, 100), PayloadLimitError);
+  });
+});
@@ -0,0 +1,88 @@
+/**
+ * Test suite for the state lock
+ *
+ * Tests:
+ * - Holders run one at a time and the lock is released afterwards
+ * - A held lock fails with LOCKED after the timeout, naming the holder
+ * - A lock of an exited process is taken over
+ * - O8_LOCK_TIMEOUT parsing
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdir, mkdtemp, rm, writeFile } from "fs/promises";
+import { hostname, tmpdir } from "os";
+import { dirname, join } from "path";
+import {
+  DEFAULT_LOCK_TIMEOUT,
+  LockError,
+  lockPath,
+  lockTimeout,
+  readLockHolder,
+  withStateLock,
+} from "../state/lock.js";
+
+describe("withStateLock", () => {
+  let stateDir: string;
+
+  before(async () => {
+    stateDir = await mkdtemp(join(tmpdir(), "o8-lock-"));
+  });
+
+  after(async () => {
+    await rm(stateDir, { recursive: true, force: true });
+  });
+
+  it("should run holders one at a time", async () => {
+    const order: string[] = [];
+    const hold = (name: string) =>
+      withStateLock(stateDir, name, async () => {
+        order.push(`${name}:start`);
+        await new Promise((resolve) => setTimeout(resolve, 20));
+        order.push(`${name}:end`);
+      });
+
+    await Promise.all([hold("a"), hold("b")]);
+    assert.deepStrictEqual(order, ["a:start", "a:end", "b:start", "b:end"]);
+    assert.strictEqual(await readLockHolder(stateDir), null);
+  });
+
+  it("should fail with the holder after the timeout", async () => {
+    await withStateLock(stateDir, "rewrite", async () => {
+      await assert.rejects(
+        withStateLock(stateDir, "gc", async () => {}, 0),
+        (error: LockError) =>
+          error.code === "LOCKED" &&
+          error.holder?.operation === "rewrite" &&
+          error.holder.pid === process.pid,
+      );
+    });
+  });
+
+  it("should take over the lock of an exited process", async () => {
+    await mkdir(dirname(lockPath(stateDir)), { recursive: true });
+    await writeFile(
+      lockPath(stateDir),
+      JSON.stringify({
+        // Above the default pid_max, so no such process exists
+        pid: 2 ** 22 + 1,
+        host: hostname(),
+        operation: "rewrite",
+        acquiredAt: "2026-01-01T00:00:00.000Z",
+      }),
+    );
+
+    assert.strictEqual(
+      await withStateLock(stateDir, "gc", async () => "ran", 0),
+      "ran",
+    );
+  });
+});
+
+describe("lockTimeout", () => {
+  it("should parse O8_LOCK_TIMEOUT", () => {
+    assert.strictEqual(lockTimeout({}), DEFAULT_LOCK_TIMEOUT);
+    assert.strictEqual(lockTimeout({ O8_LOCK_TIMEOUT: "0" }), 0);
+    assert.throws(() => lockTimeout({ O8_LOCK_TIMEOUT: "soon" }), /O8_LOCK_TIMEOUT/);
+  });
+});
@@ -0,0 +1,104 @@
+/**
+ * Test suite for zero-result query tracking
+ *
+ * Tests:
+ * - Grouping queries after case folding and whitespace collapsing
+ * - Ordering by count
+ * - Bounded memory (least recently seen dropped first)
+ * - Secrets in queries are redacted
+ * - Repeated misses answered from the loader cache are counted
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { MissTracker } from "../utils/missTracker.js";
+import { REDACTED } from "../utils/redact.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("MissTracker", () => {
+  it("should group equivalent queries and order by count", () => {
+    const tracker = new MissTracker();
+    tracker.record("Kubernetes  Helm");
+    tracker.record("cobol");
+    tracker.record("kubernetes helm");
+    tracker.record("   ");
+
+    const misses = tracker.list();
+    assert.deepStrictEqual(
+      misses.map((miss) => [miss.query, miss.count]),
+      [
+        ["kubernetes helm", 2],
+        ["cobol", 1],
+      ],
+    );
+    assert.ok(misses[0].firstSeen <= misses[0].lastSeen);
+  });
+
+  it("should drop the least recently seen query when full", () => {
+    const tracker = new MissTracker(2);
+    tracker.record("first");
+    tracker.record("second");
+    tracker.record("first");
+    tracker.record("third");
+
+    assert.deepStrictEqual(
+      tracker.list().map((miss) => miss.query).sort(),
+      ["first", "third"],
+    );
+  });
+
+  it("should redact secrets before storing queries", () => {
+    const tracker = new MissTracker();
+    tracker.record("deploy with sk-abcdefghijklmnopqrstuvwx");
+
+    assert.deepStrictEqual(
+      tracker.list().map((miss) => miss.query),
+      [`deploy with ${REDACTED.toLowerCase()}`],
+    );
+  });
+
+  it("should respect the limit", () => {
+    const tracker = new MissTracker();
+    ["a1", "b2", "c3"].forEach((query) => tracker.record(query));
+    assert.strictEqual(tracker.list(2).length, 2);
+  });
+});
+
+describe("ResourceLoader misses", () => {
+  let root: string;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-misses-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeFile(
+      join(root, "agents", "rust-expert.md"),
+      "---\ntags: [rust]\n---\n# Rust\n",
+    );
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should count every request, including cached ones", async () => {
+    const loader = new ResourceLoader(new Logger("test"));
+    const uri = "o8://agents/match?query=cobol&mode=catalog";
+    await loader.loadResourceContent(uri);
+    await loader.loadResourceContent(uri);
+
+    const { misses } = await loader.getMisses();
+    assert.deepStrictEqual(
+      misses.map((miss) => [miss.query, miss.count]),
+      [["cobol", 2]],
+    );
+  });
+});
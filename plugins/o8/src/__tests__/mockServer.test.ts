@@ -0,0 +1,159 @@
+/**
+ * Test suite for the fixtures-backed mock server
+ *
+ * Tests:
+ * - Fixture validation
+ * - Candidate selection by params
+ * - Template placeholders
+ * - Results, errors, and unknown methods over a real MCP client
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { Client } from "@modelcontextprotocol/sdk/client/index.js";
+import { InMemoryTransport } from "@modelcontextprotocol/sdk/inMemory.js";
+import {
+  createMockServer,
+  loadFixtures,
+  renderTemplate,
+  resolveFixture,
+  type MockFixtures,
+} from "../session/mockServer.js";
+
+const fixtures: MockFixtures = {
+  serverInfo: { name: "o8-fixtures", version: "9.9.9" },
+  methods: {
+    "resources/list": {
+      result: { resources: [{ uri: "o8://agents/ts", name: "ts" }] },
+    },
+    "resources/read": [
+      {
+        params: { uri: "o8://agents/ts" },
+        result: {
+          contents: [
+            { uri: "{{params.uri}}", text: "read at {{now}} for {{id}}" },
+          ],
+        },
+      },
+      { error: { code: -32002, message: "Not found: {{params.uri}}" } },
+    ],
+  },
+};
+
+describe("loadFixtures", () => {
+  let dir: string;
+
+  before(async () => {
+    dir = await mkdtemp(join(tmpdir(), "o8-mock-"));
+  });
+
+  after(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should load a fixtures file", async () => {
+    const file = join(dir, "ok.json");
+    await writeFile(file, JSON.stringify(fixtures));
+    assert.deepStrictEqual(await loadFixtures(file), fixtures);
+  });
+
+  it("should reject fixtures without methods or responses", async () => {
+    const noMethods = join(dir, "none.json");
+    await writeFile(noMethods, "{}");
+    await assert.rejects(() => loadFixtures(noMethods), /"methods"/);
+
+    const empty = join(dir, "empty.json");
+    await writeFile(empty, JSON.stringify({ methods: { "tools/list": {} } }));
+    await assert.rejects(() => loadFixtures(empty), /tools\/list/);
+  });
+});
+
+describe("resolveFixture", () => {
+  it("should pick the first candidate whose params match", () => {
+    assert.ok(
+      resolveFixture(fixtures, "resources/read", { uri: "o8://agents/ts" })
+        ?.result,
+    );
+    assert.ok(
+      resolveFixture(fixtures, "resources/read", { uri: "o8://agents/go" })
+        ?.error,
+    );
+  });
+
+  it("should return undefined for unknown methods", () => {
+    assert.strictEqual(resolveFixture(fixtures, "tools/list"), undefined);
+    assert.strictEqual(resolveFixture(fixtures, "toString"), undefined);
+  });
+});
+
+describe("renderTemplate", () => {
+  it("should fill placeholders throughout a value", () => {
+    const context = {
+      id: 7,
+      params: { uri: "o8://x", limit: 3 },
+      now: () => "2024-01-01T00:00:00.000Z",
+      uuid: () => "u-1",
+    };
+    assert.deepStrictEqual(
+      renderTemplate(
+        { a: ["{{id}}-{{uuid}}", "{{params.limit}}"], b: "{{ now }}", n: 1 },
+        context,
+      ),
+      { a: ["7-u-1", "3"], b: "2024-01-01T00:00:00.000Z", n: 1 },
+    );
+    assert.strictEqual(
+      renderTemplate("{{params.missing}} {{other}}", context),
+      "{{params.missing}} {{other}}",
+    );
+  });
+});
+
+describe("createMockServer", () => {
+  let client: Client;
+
+  before(async () => {
+    const server = createMockServer(
+      fixtures,
+      () => "2024-01-01T00:00:00.000Z",
+    );
+    const [clientTransport, serverTransport] =
+      InMemoryTransport.createLinkedPair();
+    await server.connect(serverTransport);
+    client = new Client({ name: "o8-test", version: "1.0.0" });
+    await client.connect(clientTransport);
+  });
+
+  after(async () => {
+    await client.close();
+  });
+
+  it("should identify as the fixtures server", () => {
+    assert.strictEqual(client.getServerVersion()?.name, "o8-fixtures");
+  });
+
+  it("should answer from fixtures with templates filled in", async () => {
+    const { resources } = await client.listResources();
+    assert.strictEqual(resources[0].uri, "o8://agents/ts");
+
+    const result = await client.readResource({ uri: "o8://agents/ts" });
+    assert.strictEqual(result.contents[0].uri, "o8://agents/ts");
+    assert.match(
+      result.contents[0].text as string,
+      /^read at 2024-01-01T00:00:00\.000Z for \d+$/,
+    );
+  });
+
+  it("should return fixture errors", async () => {
+    await assert.rejects(
+      () => client.readResource({ uri: "o8://agents/go" }),
+      /Not found: o8:\/\/agents\/go/,
+    );
+  });
+
+  it("should reject methods without fixtures", async () => {
+    await assert.rejects(() => client.listPrompts(), /No fixture/);
+  });
+});
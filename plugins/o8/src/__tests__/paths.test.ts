@@ -0,0 +1,110 @@
+/**
+ * Test suite for cross-platform path helpers
+ *
+ * Tests:
+ * - Long-path prefixes, drive letters, and UNC shares on Windows
+ * - Containment checks that reject escapes and other drives
+ * - Case-insensitive file lookup
+ * - Windows layer defaults and duplicate detection
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  findCaseInsensitive,
+  isWithin,
+  pathKey,
+  stripLongPathPrefix,
+} from "../utils/paths.js";
+import { resourceLayers } from "../utils/layers.js";
+
+describe("stripLongPathPrefix", () => {
+  it("should strip drive and UNC long-path prefixes", () => {
+    assert.strictEqual(stripLongPathPrefix("\\\\?\\C:\\o8\\agents"), "C:\\o8\\agents");
+    assert.strictEqual(
+      stripLongPathPrefix("\\\\?\\UNC\\server\\share\\o8"),
+      "\\\\server\\share\\o8",
+    );
+    assert.strictEqual(stripLongPathPrefix("/srv/o8"), "/srv/o8");
+  });
+});
+
+describe("pathKey", () => {
+  it("should compare Windows paths case-insensitively, however they are spelled", () => {
+    assert.strictEqual(
+      pathKey("\\\\?\\C:\\O8\\Agents\\..\\Skills", "win32"),
+      pathKey("c:/o8/skills", "win32"),
+    );
+    assert.notStrictEqual(pathKey("/srv/O8", "linux"), pathKey("/srv/o8", "linux"));
+  });
+});
+
+describe("isWithin", () => {
+  it("should accept paths inside the root on Windows", () => {
+    assert.ok(isWithin("C:\\o8", "c:\\O8\\agents\\rust.md", "win32"));
+    assert.ok(isWithin("\\\\?\\C:\\o8", "C:\\o8\\agents\\rust.md", "win32"));
+    assert.ok(
+      isWithin("\\\\server\\share\\o8", "\\\\?\\UNC\\server\\share\\o8\\a.md", "win32"),
+    );
+  });
+
+  it("should reject the root itself, escapes, and other drives", () => {
+    assert.ok(!isWithin("C:\\o8", "C:\\o8", "win32"));
+    assert.ok(!isWithin("C:\\o8", "C:\\o8\\..\\secrets.md", "win32"));
+    assert.ok(!isWithin("C:\\o8", "D:\\o8\\agents\\rust.md", "win32"));
+    assert.ok(!isWithin("/srv/o8", "/srv/o8-other/a.md", "linux"));
+    assert.ok(isWithin("/srv/o8", "/srv/o8/..hidden.md", "linux"));
+  });
+});
+
+describe("findCaseInsensitive", () => {
+  let root: string;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-paths-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeFile(join(root, "agents", "rust-expert.md"), "# Rust");
+    await writeFile(join(root, "agents", "Go.md"), "# Go");
+    await writeFile(join(root, "agents", "go.md"), "# go");
+  });
+
+  after(async () => {
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should find files whose names differ in case", async () => {
+    assert.strictEqual(
+      await findCaseInsensitive(root, "Agents/Rust-Expert.md"),
+      join(root, "agents", "rust-expert.md"),
+    );
+    assert.strictEqual(
+      await findCaseInsensitive(root, "agents\\go.md"),
+      join(root, "agents", "go.md"),
+    );
+  });
+
+  it("should give null for missing or ambiguous names", async () => {
+    assert.strictEqual(await findCaseInsensitive(root, "agents/missing.md"), null);
+    assert.strictEqual(await findCaseInsensitive(root, "agents/GO.md"), null);
+  });
+});
+
+describe("resourceLayers on Windows", () => {
+  it("should use ProgramData and drop case-only duplicates", async () => {
+    const layers = await resourceLayers(
+      "C:\\o8\\orchestr8",
+      { ProgramData: "D:\\ProgramData", XDG_CONFIG_HOME: "c:\\O8\\Config\\.." },
+      tmpdir(),
+      "win32",
+    );
+    assert.deepStrictEqual(
+      layers.slice(0, 2).map((layer) => layer.name),
+      ["builtin", "system"],
+    );
+    assert.strictEqual(layers[1].path, "D:\\ProgramData\\orchestr8");
+    assert.ok(!layers.some((layer) => layer.name === "user"));
+  });
+});
@@ -0,0 +1,198 @@
+/**
+ * Test suite for the request authorization policy
+ *
+ * Tests:
+ * - Rule expressions: comparisons, lists, params, boolean logic
+ * - Invalid expressions are rejected when the policy is loaded
+ * - Roles from bearer tokens and client certificates
+ * - First matching rule decides; built-in rule for admin methods
+ * - Catalog and state mutations are admin methods; catalog/snapshots is not
+ * - Policy files with token variables
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  ANONYMOUS_ROLE,
+  PolicyError,
+  RequestPolicy,
+  compileExpression,
+  policyFromEnv,
+} from "../utils/policy.js";
+
+describe("compileExpression", () => {
+  const evaluate = (source: string, params?: unknown, role = "reader") =>
+    compileExpression(source)({ method: "agents/route", role, params });
+
+  it("should compare roles, methods, and params", () => {
+    assert.strictEqual(evaluate("role == 'reader'"), true);
+    assert.strictEqual(evaluate("role in ['admin', \"operator\"]"), false);
+    assert.strictEqual(evaluate("method.startsWith('agents/')"), true);
+    assert.strictEqual(
+      evaluate("params.maxTokens < 2000", { maxTokens: 500 }),
+      true,
+    );
+    assert.strictEqual(evaluate("params.maxTokens < 2000", {}), false);
+    assert.strictEqual(evaluate("params.filter.name == null", {}), true);
+  });
+
+  it("should combine conditions", () => {
+    assert.strictEqual(
+      evaluate("!(role == 'anonymous') && (method == 'x' || true)"),
+      true,
+    );
+    assert.strictEqual(evaluate("role == 'reader' && false"), false);
+  });
+
+  it("should reject invalid expressions", () => {
+    for (const source of [
+      "role ==",
+      "user == 'x'",
+      "role.toUpperCase()",
+      "role == 'x' extra",
+      "role = 'x'",
+    ]) {
+      assert.throws(() => compileExpression(source), PolicyError, source);
+    }
+  });
+});
+
+describe("RequestPolicy", () => {
+  const policy = new RequestPolicy({
+    adminToken: "admin-secret",
+    tokens: [{ token: "ops-secret", role: "operator" }],
+    certificates: { "orchestrator.internal": "operator" },
+    rules: [
+      { methods: ["catalog/*", "state/gc"], allow: "role == 'operator'" },
+      { methods: ["sessions/list"], allow: "role != 'anonymous'" },
+    ],
+  });
+
+  it("should map tokens to roles", () => {
+    assert.strictEqual(policy.roleFor("admin-secret"), "admin");
+    assert.strictEqual(policy.roleFor("ops-secret"), "operator");
+    assert.strictEqual(policy.roleFor(undefined), ANONYMOUS_ROLE);
+    assert.strictEqual(policy.roleFor("guess"), null);
+  });
+
+  it("should map client certificate subjects to roles", () => {
+    assert.strictEqual(
+      policy.roleForCertificate("orchestrator.internal"),
+      "operator",
+    );
+    assert.strictEqual(policy.roleForCertificate("other"), ANONYMOUS_ROLE);
+  });
+
+  it("should let the first matching rule decide", () => {
+    const allowed = (method: string, role: string) =>
+      policy.authorize({ method, role }).allowed;
+
+    assert.strictEqual(allowed("catalog/rollback", "operator"), true);
+    assert.strictEqual(allowed("catalog/rollback", "admin"), false);
+    assert.strictEqual(allowed("sessions/list", "operator"), true);
+    assert.deepStrictEqual(
+      policy.authorize({ method: "state/gc", role: "reader" }),
+      { allowed: false, role: "reader", rule: ["catalog/*", "state/gc"] },
+    );
+  });
+
+  it("should require the admin role for admin methods by default", () => {
+    assert.deepStrictEqual(
+      policy.authorize({ method: "sessions/terminate", role: "operator" }),
+      { allowed: false, role: "operator", rule: null },
+    );
+    assert.strictEqual(
+      policy.authorize({ method: "sessions/terminate", role: "admin" }).allowed,
+      true,
+    );
+    assert.strictEqual(
+      policy.authorize({ method: "agents/grep", role: ANONYMOUS_ROLE }).allowed,
+      false,
+    );
+    assert.strictEqual(
+      policy.authorize({ method: "agents/route", role: ANONYMOUS_ROLE })
+        .allowed,
+      true,
+    );
+  });
+
+  it("should refuse catalog and state mutations to anonymous callers", () => {
+    const builtIn = new RequestPolicy({ adminToken: "admin-secret" });
+    for (const method of [
+      "catalog/snapshot",
+      "catalog/rollback",
+      "catalog/reload",
+      "state/gc",
+    ]) {
+      assert.deepStrictEqual(
+        builtIn.authorize({ method, role: ANONYMOUS_ROLE }),
+        { allowed: false, role: ANONYMOUS_ROLE, rule: null },
+        method,
+      );
+    }
+    assert.strictEqual(
+      builtIn.authorize({ method: "catalog/rollback", role: "admin" }).allowed,
+      true,
+    );
+    assert.strictEqual(
+      builtIn.authorize({ method: "catalog/snapshots", role: ANONYMOUS_ROLE })
+        .allowed,
+      true,
+    );
+  });
+});
+
+describe("policyFromEnv", () => {
+  let dir: string;
+
+  before(async () => {
+    dir = await mkdtemp(join(tmpdir(), "o8-policy-"));
+  });
+
+  after(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should read token variables and rules from the policy file", async () => {
+    const path = join(dir, "policy.json");
+    await writeFile(
+      path,
+      JSON.stringify({
+        tokens: { O8_CI_TOKEN: "reader", O8_UNSET_TOKEN: "operator" },
+        rules: [{ methods: ["catalog/*"], allow: "role == 'admin'" }],
+      }),
+    );
+    const policy = policyFromEnv({
+      O8_POLICY: path,
+      O8_CI_TOKEN: "ci-secret",
+      O8_ADMIN_TOKEN: "admin-secret",
+    });
+
+    assert.strictEqual(policy.roleFor("ci-secret"), "reader");
+    assert.strictEqual(policy.adminEnabled, true);
+    assert.strictEqual(
+      policy.authorize({ method: "catalog/reload", role: "reader" }).allowed,
+      false,
+    );
+  });
+
+  it("should reject invalid policy files", async () => {
+    const path = join(dir, "invalid.json");
+    await writeFile(path, JSON.stringify({ rules: [{ methods: "x" }] }));
+
+    assert.throws(() => policyFromEnv({ O8_POLICY: path }), PolicyError);
+    assert.throws(
+      () => policyFromEnv({ O8_POLICY: join(dir, "missing.json") }),
+      PolicyError,
+    );
+  });
+
+  it("should treat every caller as anonymous without tokens", () => {
+    const policy = policyFromEnv({});
+    assert.strictEqual(policy.adminEnabled, false);
+    assert.strictEqual(policy.roleFor("anything"), ANONYMOUS_ROLE);
+  });
+});
@@ -0,0 +1,173 @@
+/**
+ * Test suite for the opt-in query log
+ *
+ * Tests:
+ * - Settings from the environment (off by default, invalid values)
+ * - Truncating and hashing queries
+ * - Sampling
+ * - Summaries of frequent and zero-result queries
+ * - Match responses served from the loader cache are logged too
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, readFile, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  MAX_QUERY_CHARS,
+  QueryLog,
+  anonymizeQuery,
+  queryLogOptions,
+} from "../state/queryLog.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+async function withDir(fn: (dir: string) => Promise<void>): Promise<void> {
+  const dir = await mkdtemp(join(tmpdir(), "o8-query-log-"));
+  try {
+    await fn(dir);
+  } finally {
+    await rm(dir, { recursive: true, force: true });
+  }
+}
+
+describe("queryLogOptions", () => {
+  it("should be off unless O8_QUERY_LOG is set", () => {
+    assert.strictEqual(queryLogOptions({}), null);
+    assert.strictEqual(queryLogOptions({ O8_QUERY_LOG: "off" }), null);
+  });
+
+  it("should default to logging every query in the state directory", () => {
+    assert.deepStrictEqual(
+      queryLogOptions({ O8_QUERY_LOG: "hash", O8_STATE_DIR: "/state" }),
+      { mode: "hash", sampleRate: 1, path: join("/state", "queries.jsonl") },
+    );
+  });
+
+  it("should reject invalid modes and sample rates", () => {
+    assert.throws(() => queryLogOptions({ O8_QUERY_LOG: "full" }), /O8_QUERY_LOG/);
+    assert.throws(
+      () => queryLogOptions({ O8_QUERY_LOG: "hash", O8_QUERY_LOG_SAMPLE: "2" }),
+      /O8_QUERY_LOG_SAMPLE/,
+    );
+  });
+});
+
+describe("anonymizeQuery", () => {
+  it("should truncate long queries", () => {
+    const query = "kubernetes ".repeat(20);
+    assert.strictEqual(anonymizeQuery(query, "truncate").length, MAX_QUERY_CHARS);
+  });
+
+  it("should hash queries so repeats still group", () => {
+    const hashed = anonymizeQuery("React  Hooks", "hash");
+    assert.match(hashed, /^sha256:[0-9a-f]{16}$/);
+    assert.strictEqual(anonymizeQuery("react hooks", "hash"), hashed);
+    assert.ok(!hashed.includes("react"));
+  });
+});
+
+describe("QueryLog", () => {
+  it("should record nothing when off", async () => {
+    const log = new QueryLog(null);
+    log.record("react", 3, "fuzzy");
+    assert.deepStrictEqual(await log.summary(), {
+      enabled: false,
+      entries: 0,
+      zeroResults: 0,
+      topQueries: [],
+      topZeroResultQueries: [],
+    });
+  });
+
+  it("should sample queries", async () => {
+    await withDir(async (dir) => {
+      const path = join(dir, "queries.jsonl");
+      const rolls = [0.05, 0.5, 0.09];
+      const log = new QueryLog(
+        { mode: "truncate", sampleRate: 0.1, path },
+        () => rolls.shift() ?? 1,
+      );
+      log.record("first", 1, "index");
+      log.record("second", 1, "index");
+      log.record("third", 1, "index");
+      await log.flush();
+
+      const lines = (await readFile(path, "utf-8")).trim().split("\n");
+      assert.deepStrictEqual(
+        lines.map((line) => JSON.parse(line).query),
+        ["first", "third"],
+      );
+    });
+  });
+
+  it("should summarize frequent and zero-result queries", async () => {
+    await withDir(async (dir) => {
+      const log = new QueryLog({
+        mode: "truncate",
+        sampleRate: 1,
+        path: join(dir, "state", "queries.jsonl"),
+      });
+      log.record("react", 4, "index");
+      log.record("react", 2, "quick");
+      log.record("cobol", 0, "fuzzy", "agents");
+      log.record("react", 0, "fuzzy");
+
+      const summary = await log.summary(5);
+      assert.strictEqual(summary.enabled, true);
+      assert.strictEqual(summary.entries, 4);
+      assert.strictEqual(summary.zeroResults, 2);
+      assert.deepStrictEqual(summary.topQueries, [
+        { query: "react", count: 3, zeroResults: 1 },
+        { query: "cobol", count: 1, zeroResults: 1 },
+      ]);
+      assert.deepStrictEqual(summary.topZeroResultQueries, [
+        { query: "cobol", count: 1 },
+        { query: "react", count: 1 },
+      ]);
+    });
+  });
+});
+
+describe("ResourceLoader query log", () => {
+  let root: string;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-query-log-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeFile(
+      join(root, "agents", "rust-expert.md"),
+      "---\ntags: [rust]\n---\n# Rust\n",
+    );
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    process.env.O8_QUERY_LOG = "truncate";
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    delete process.env.O8_QUERY_LOG;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should log repeated queries answered from the cache", async () => {
+    const loader = new ResourceLoader(new Logger("test"));
+    const uri = "o8://agents/match?query=rust&mode=catalog";
+    const first = await loader.loadResourceContent(uri);
+    assert.strictEqual(await loader.loadResourceContent(uri), first);
+
+    const summary = await loader.getQueryLogSummary();
+    assert.deepStrictEqual(summary.topQueries, [
+      { query: "rust", count: 2, zeroResults: 0 },
+    ]);
+    const log = await readFile(join(root, ".state", "queries.jsonl"), "utf-8");
+    const tiers = log
+      .trim()
+      .split("\n")
+      .map((line) => JSON.parse(line).tier);
+    assert.deepStrictEqual(tiers, ["fuzzy", "cache"]);
+  });
+});
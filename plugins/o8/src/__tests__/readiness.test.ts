@@ -0,0 +1,63 @@
+/**
+ * Test suite for catalog readiness
+ *
+ * Tests:
+ * - --ready-timeout from flags and environment
+ * - Queries wait for an index that finishes in time
+ * - Queries fail with a retriable error when it does not
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  CATALOG_NOT_READY_CODE,
+  CatalogNotReadyError,
+  DEFAULT_READY_TIMEOUT,
+  readyTimeout,
+  waitForReady,
+} from "../utils/readiness.js";
+
+const delay = (ms: number) =>
+  new Promise<void>((resolve) => setTimeout(resolve, ms));
+
+describe("readyTimeout", () => {
+  it("should default to ten seconds", () => {
+    assert.strictEqual(readyTimeout([], {}), DEFAULT_READY_TIMEOUT);
+  });
+
+  it("should read the flag and the environment", () => {
+    assert.strictEqual(readyTimeout(["--ready-timeout", "0"], {}), 0);
+    assert.strictEqual(readyTimeout([], { O8_READY_TIMEOUT: "2500" }), 2500);
+  });
+
+  it("should reject invalid values", () => {
+    assert.throws(() => readyTimeout([], { O8_READY_TIMEOUT: "soon" }));
+    assert.throws(() => readyTimeout([], { O8_READY_TIMEOUT: "-1" }));
+  });
+});
+
+describe("waitForReady", () => {
+  it("should resolve once the index is ready", async () => {
+    await waitForReady(delay(10), 1000);
+  });
+
+  it("should not throw when the index load failed", async () => {
+    await waitForReady(Promise.reject(new Error("scan failed")), 1000);
+  });
+
+  it("should throw a retriable error after the timeout", async () => {
+    await assert.rejects(
+      () => waitForReady(delay(1000), 10),
+      (error: unknown) => {
+        assert.ok(error instanceof CatalogNotReadyError);
+        assert.strictEqual(error.code, CATALOG_NOT_READY_CODE);
+        assert.deepStrictEqual(error.data, {
+          reason: "CATALOG_NOT_READY",
+          retryable: true,
+          retryAfterMs: 1000,
+        });
+        return true;
+      },
+    );
+  });
+});
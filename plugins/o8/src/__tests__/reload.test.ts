@@ -0,0 +1,77 @@
+/**
+ * Test suite for blue/green catalog reloads
+ *
+ * Tests:
+ * - Generation numbers
+ * - The old index keeps serving until the new one is complete
+ * - Serialized concurrent reloads
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("ResourceLoader reloads", () => {
+  let root: string;
+  let loader: ResourceLoader;
+  const writeAgent = (name: string) =>
+    writeFile(
+      join(root, "agents", `${name}.md`),
+      `---\ntags: [${name}]\n---\n# ${name}\n`,
+    );
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-reload-"));
+    await mkdir(join(root, "agents"));
+    await writeAgent("alpha");
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should start at generation 1 after the first load", async () => {
+    assert.strictEqual(loader.getCatalogState().generation, 0);
+    await loader.loadResourceIndex();
+
+    const state = loader.getCatalogState();
+    assert.strictEqual(state.generation, 1);
+    assert.strictEqual(state.resources, 1);
+    assert.strictEqual(state.reloading, false);
+  });
+
+  it("should keep serving the old index until the swap", async () => {
+    const before = await loader.loadResourceIndex();
+    await writeAgent("beta");
+
+    const reload = loader.reloadIndex();
+    assert.strictEqual(await loader.loadResourceIndex(), before);
+    assert.strictEqual(loader.getCatalogState().generation, 1);
+
+    assert.deepStrictEqual(await reload, { generation: 2, resources: 2 });
+    assert.deepStrictEqual(
+      (await loader.loadResourceIndex()).map((f) => f.id).sort(),
+      ["agents/alpha", "agents/beta"],
+    );
+  });
+
+  it("should run concurrent reloads one after another", async () => {
+    const results = await Promise.all([
+      loader.reloadIndex(),
+      loader.reloadIndex(),
+    ]);
+
+    assert.deepStrictEqual(
+      results.map((r) => r.generation),
+      [3, 4],
+    );
+  });
+});
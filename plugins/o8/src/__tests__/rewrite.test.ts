@@ -0,0 +1,164 @@
+/**
+ * Test suite for bulk find-and-replace
+ *
+ * Tests:
+ * - Regex replacements with group references, and literal replacements
+ * - Request validation
+ * - Dry runs leave files untouched; rewrites write all files and reindex
+ * - Rewrites fail without writing when expected hashes do not match
+ * - Writes are all or nothing
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, readFile, readdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  applyRewrite,
+  compileRewrite,
+  parseRewriteRequest,
+  writeFilesAtomically,
+  RewriteConflictError,
+} from "../utils/rewrite.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("applyRewrite", () => {
+  it("should replace every match and count replacements", () => {
+    const regex = compileRewrite("docs\\.old\\.dev/(\\w+)");
+    assert.deepStrictEqual(
+      applyRewrite("docs.old.dev/a and docs.old.dev/b", regex, "new.dev/$1"),
+      { content: "new.dev/a and new.dev/b", replacements: 2 },
+    );
+  });
+
+  it("should treat literal patterns and replacements as plain text", () => {
+    const regex = compileRewrite("a.b(", { literal: true });
+    assert.deepStrictEqual(applyRewrite("a.b( axb(", regex, "$1", true), {
+      content: "$1 axb(",
+      replacements: 1,
+    });
+  });
+
+  it("should validate request params", () => {
+    assert.throws(() => parseRewriteRequest({ replacement: "x" }), /pattern/);
+    assert.throws(
+      () => parseRewriteRequest({ pattern: "x", replacement: "y", ids: "a" }),
+      /ids/,
+    );
+    assert.deepStrictEqual(
+      parseRewriteRequest({ pattern: "x", replacement: "", dryRun: true }),
+      {
+        pattern: "x",
+        replacement: "",
+        literal: false,
+        ignoreCase: false,
+        dryRun: true,
+      },
+    );
+  });
+});
+
+describe("writeFilesAtomically", () => {
+  it("should leave every file untouched when one write fails", async () => {
+    const dir = await mkdtemp(join(tmpdir(), "o8-atomic-"));
+    try {
+      await writeFile(join(dir, "a.md"), "old");
+      await assert.rejects(
+        writeFilesAtomically([
+          { path: join(dir, "a.md"), content: "new" },
+          { path: join(dir, "missing", "b.md"), content: "new" },
+        ]),
+      );
+      assert.strictEqual(await readFile(join(dir, "a.md"), "utf-8"), "old");
+      assert.deepStrictEqual(await readdir(dir), ["a.md"]);
+    } finally {
+      await rm(dir, { recursive: true, force: true });
+    }
+  });
+});
+
+describe("ResourceLoader.rewriteResources", () => {
+  let root: string;
+  let loader: ResourceLoader;
+  const agentPath = (name: string) => join(root, "agents", `${name}.md`);
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-rewrite-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeFile(
+      agentPath("release-manager"),
+      "---\ntools: [deploy-v1]\n---\n# Release Manager\n\nRun deploy-v1 last.\n",
+    );
+    await writeFile(
+      agentPath("reviewer"),
+      "---\ntags: [review]\n---\n# Reviewer\n",
+    );
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should preview a rewrite without writing", async () => {
+    const result = await loader.rewriteResources({
+      pattern: "deploy-v1",
+      replacement: "deploy-v2",
+      dryRun: true,
+    });
+    assert.strictEqual(result.replacements, 2);
+    assert.deepStrictEqual(
+      result.changes.map((change) => change.id),
+      ["agents/release-manager"],
+    );
+    assert.match(result.changes[0].diff, /^\+tools: \[deploy-v2\]$/m);
+    assert.match(
+      await readFile(agentPath("release-manager"), "utf-8"),
+      /deploy-v1/,
+    );
+  });
+
+  it("should refuse to rewrite files that changed", async () => {
+    const request = { pattern: "deploy-v1", replacement: "deploy-v2" };
+    const preview = await loader.rewriteResources({ ...request, dryRun: true });
+    const { id, hash } = preview.changes[0];
+
+    await assert.rejects(
+      loader.rewriteResources({
+        ...request,
+        expectedHashes: { [id]: "0".repeat(64), "agents/gone": hash },
+      }),
+      (error: RewriteConflictError) =>
+        error.code === "CONFLICT" &&
+        error.conflicts[0].actual === hash &&
+        error.conflicts[1].actual === null,
+    );
+    assert.match(
+      await readFile(agentPath("release-manager"), "utf-8"),
+      /deploy-v1/,
+    );
+  });
+
+  it("should rewrite selected files and reindex", async () => {
+    const { generation } = loader.getCatalogState();
+    const result = await loader.rewriteResources({
+      pattern: "deploy-v1",
+      replacement: "deploy-v2",
+      ids: ["release-manager", "agents/missing"],
+    });
+    assert.deepStrictEqual(result.notFound, ["agents/missing"]);
+    assert.ok(
+      !(await readFile(agentPath("release-manager"), "utf-8")).includes(
+        "deploy-v1",
+      ),
+    );
+    assert.strictEqual(loader.getCatalogState().generation, generation + 1);
+  });
+});
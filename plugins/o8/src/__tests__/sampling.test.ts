@@ -0,0 +1,55 @@
+/**
+ * Test suite for sampling helpers
+ *
+ * Tests:
+ * - Seeded reproducibility
+ * - Sampling without replacement
+ * - Stratified samples per category
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { createRandom, sampleItems } from "../utils/sampling.js";
+
+const items = Array.from({ length: 30 }, (_, i) => ({
+  id: `item-${i}`,
+  category: ["agent", "skill", "pattern"][i % 3],
+}));
+
+describe("sampleItems", () => {
+  it("should produce the same sample for the same seed", () => {
+    const a = sampleItems(items, { count: 5, seed: 42 });
+    const b = sampleItems(items, { count: 5, seed: 42 });
+
+    assert.deepStrictEqual(a, b);
+    assert.notDeepStrictEqual(a, sampleItems(items, { count: 5, seed: 43 }));
+  });
+
+  it("should draw without replacement and cap at the population", () => {
+    const sample = sampleItems(items, { count: 100, seed: 1 });
+
+    assert.strictEqual(sample.length, items.length);
+    assert.strictEqual(new Set(sample.map((i) => i.id)).size, items.length);
+  });
+
+  it("should draw perCategory items from each category", () => {
+    const sample = sampleItems(items, {
+      perCategory: 2,
+      categoryOf: (item) => item.category,
+      seed: 7,
+    });
+
+    assert.deepStrictEqual(
+      sample.map((i) => i.category),
+      ["agent", "agent", "pattern", "pattern", "skill", "skill"],
+    );
+  });
+
+  it("should generate floats in [0, 1)", () => {
+    const random = createRandom(0);
+    for (let i = 0; i < 1000; i++) {
+      const value = random();
+      assert.ok(value >= 0 && value < 1);
+    }
+  });
+});
@@ -0,0 +1,120 @@
+/**
+ * Test suite for markdown sections
+ *
+ * Tests:
+ * - Sections include subsections; headings in code fences are ignored
+ * - Sections are found by heading text or slug
+ * - ?section= loads one section of a resource
+ * - Body search reports the sections that mention a term
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  SectionNotFoundError,
+  findSection,
+  parseSections,
+} from "../utils/sections.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+const body = [
+  "# Reviewer",
+  "",
+  "Reviews pull requests.",
+  "",
+  "## Output Format",
+  "",
+  "Return a JSON verdict.",
+  "",
+  "### Examples",
+  "",
+  "```markdown",
+  "## Not a heading",
+  "```",
+  "",
+  "## Checklist",
+  "",
+  "Run cargo clippy first.",
+  "",
+].join("\n");
+
+describe("parseSections", () => {
+  it("should nest subsections and skip headings in code fences", () => {
+    const sections = parseSections(body);
+    assert.deepStrictEqual(
+      sections.map((section) => [section.level, section.slug]),
+      [
+        [1, "reviewer"],
+        [2, "output-format"],
+        [3, "examples"],
+        [2, "checklist"],
+      ],
+    );
+    assert.match(sections[1].content, /### Examples[\s\S]*Not a heading/);
+    assert.ok(!sections[1].content.includes("Checklist"));
+    assert.ok(!sections[1].ownContent.includes("Examples"));
+  });
+
+  it("should find sections by heading or slug", () => {
+    for (const name of ["## Output Format", "output format", "output-format"]) {
+      assert.strictEqual(findSection(body, name)?.heading, "Output Format");
+    }
+    assert.strictEqual(findSection(body, "Not a heading"), null);
+  });
+});
+
+describe("ResourceLoader sections", () => {
+  let root: string;
+  let loader: ResourceLoader;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-sections-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeFile(
+      join(root, "agents", "reviewer.md"),
+      `---\ntags: [review]\n---\n${body}`,
+    );
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should load a single section", async () => {
+    const content = await loader.loadResourceContent(
+      "o8://agents/reviewer?section=output-format",
+    );
+    assert.match(content, /^## Output Format\n\nReturn a JSON verdict\./);
+    assert.ok(!content.includes("tags:"));
+    assert.ok(!content.includes("Checklist"));
+  });
+
+  it("should list available sections for an unknown one", async () => {
+    await assert.rejects(
+      loader.loadResourceContent("o8://agents/reviewer?section=usage"),
+      (error: SectionNotFoundError) =>
+        error.code === "SECTION_NOT_FOUND" &&
+        error.available.includes("Checklist"),
+    );
+  });
+
+  it("should report matching sections in body search", async () => {
+    const [hit] = await loader.searchBodies("clippy");
+    assert.deepStrictEqual(hit.sections, [
+      {
+        heading: "Checklist",
+        uri: "o8://agents/reviewer?section=checklist",
+      },
+    ]);
+  });
+});
@@ -0,0 +1,89 @@
+/**
+ * In-process test suite for Orchestr8Server
+ *
+ * Drives the real server through an in-memory transport pair instead of
+ * spawning dist/index.js and sleeping for startup. The spawned-process
+ * tests in tests/integration/ remain as the end-to-end check of the binary.
+ *
+ * Tests:
+ * - MCP handshake and resource listing
+ * - Registry and static resource reads
+ * - Dynamic match reads
+ * - Errors for unknown resources
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { join } from "path";
+import { Client } from "@modelcontextprotocol/sdk/client/index.js";
+import { InMemoryTransport } from "@modelcontextprotocol/sdk/inMemory.js";
+
+// Local resources only: no remote providers during tests
+process.env.RESOURCES_PATH = join(process.cwd(), "resources");
+process.env.PROMPTS_PATH = join(process.cwd(), "prompts");
+process.env.ORCHESTR8_AITMPL_ENABLED = "false";
+process.env.ORCHESTR8_GITHUB_ENABLED = "false";
+process.env.ORCHESTR8_CUSTOM_ENABLED = "false";
+process.env.LOG_LEVEL = "error";
+
+const { Orchestr8Server } = await import("../server.js");
+
+describe("Orchestr8Server (in-process)", () => {
+  let server: InstanceType<typeof Orchestr8Server>;
+  let client: Client;
+
+  before(async () => {
+    server = new Orchestr8Server({ watch: false });
+    await server.initialize();
+
+    const [clientTransport, serverTransport] =
+      InMemoryTransport.createLinkedPair();
+    await server.connect(serverTransport);
+
+    client = new Client({ name: "o8-test", version: "1.0.0" });
+    await client.connect(clientTransport);
+  });
+
+  after(async () => {
+    await client.close();
+    await server.shutdown();
+  });
+
+  it("should list resources after the handshake", async () => {
+    const { resources } = await client.listResources();
+
+    assert.ok(resources.some((r) => r.uri === "o8://registry"));
+    assert.ok(resources.some((r) => r.uri.startsWith("o8://agents/")));
+  });
+
+  it("should read the resource registry", async () => {
+    const result = await client.readResource({ uri: "o8://registry" });
+    const catalog = JSON.parse(result.contents[0].text as string);
+
+    assert.ok(catalog.totalResources > 0);
+    assert.ok(catalog.categories.agents > 0);
+  });
+
+  it("should read a static resource", async () => {
+    const result = await client.readResource({
+      uri: "o8://agents/agent-architect",
+    });
+
+    assert.strictEqual(result.contents[0].mimeType, "text/markdown");
+    assert.ok((result.contents[0].text as string).length > 0);
+  });
+
+  it("should read a dynamic match resource", async () => {
+    const result = await client.readResource({
+      uri: "o8://agents/match?query=typescript+api&maxResults=3",
+    });
+
+    assert.ok((result.contents[0].text as string).length > 0);
+  });
+
+  it("should reject unknown resources", async () => {
+    await assert.rejects(() =>
+      client.readResource({ uri: "o8://agents/does-not-exist-anywhere" }),
+    );
+  });
+});
@@ -0,0 +1,100 @@
+/**
+ * Test suite for session record and replay
+ *
+ * Tests:
+ * - Recording transport traffic to JSONL
+ * - Replaying matching responses
+ * - Reporting changed responses
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, rm } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import type { Transport } from "@modelcontextprotocol/sdk/shared/transport.js";
+import {
+  SessionRecorder,
+  readSession,
+  replaySession,
+  RecordedMessage,
+} from "../session/recorder.js";
+
+/**
+ * Minimal server that answers every request with its params
+ */
+function echoServer(suffix = "") {
+  return async (transport: Transport) => {
+    transport.onmessage = (message: any) => {
+      if (message.id !== undefined) {
+        transport.send({
+          jsonrpc: "2.0",
+          id: message.id,
+          result: { echo: `${message.params.text}${suffix}` },
+        });
+      }
+    };
+    await transport.start();
+  };
+}
+
+const session: RecordedMessage[] = [
+  {
+    dir: "in",
+    message: { jsonrpc: "2.0", id: 1, method: "echo", params: { text: "a" } },
+  },
+  { dir: "out", message: { jsonrpc: "2.0", id: 1, result: { echo: "a" } } },
+  { dir: "in", message: { jsonrpc: "2.0", method: "notifications/ping" } },
+  {
+    dir: "in",
+    message: { jsonrpc: "2.0", id: 2, method: "echo", params: { text: "b" } },
+  },
+  { dir: "out", message: { jsonrpc: "2.0", id: 2, result: { echo: "b" } } },
+];
+
+describe("SessionRecorder", () => {
+  let dir: string;
+
+  before(async () => {
+    dir = await mkdtemp(join(tmpdir(), "o8-session-"));
+  });
+
+  after(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should record incoming and outgoing messages in order", async () => {
+    const file = join(dir, "session.jsonl");
+    const transport = {
+      onmessage: (_message: any) => {},
+      send: async (_message: any) => {},
+    };
+    const recorder = new SessionRecorder(file);
+    recorder.attach(transport);
+
+    transport.onmessage(session[0].message);
+    await transport.send(session[1].message);
+    await recorder.close();
+
+    assert.deepStrictEqual(await readSession(file), session.slice(0, 2));
+  });
+});
+
+describe("replaySession", () => {
+  it("should match identical responses", async () => {
+    const report = await replaySession(session, echoServer());
+
+    assert.strictEqual(report.total, 2);
+    assert.strictEqual(report.matched, 2);
+    assert.deepStrictEqual(report.mismatches, []);
+  });
+
+  it("should report responses that changed", async () => {
+    const report = await replaySession(session, echoServer("!"));
+
+    assert.strictEqual(report.matched, 0);
+    assert.strictEqual(report.mismatches.length, 2);
+    assert.deepStrictEqual(report.mismatches[0].expected.result, { echo: "a" });
+    assert.deepStrictEqual(report.mismatches[0].actual.result, { echo: "a!" });
+  });
+});
@@ -0,0 +1,94 @@
+/**
+ * Test suite for the session registry
+ *
+ * Tests:
+ * - Storing clientInfo and counting requests per session
+ * - Listing and closing sessions
+ * - Terminating network sessions
+ * - Per-client request counts in the stats snapshot
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  SessionError,
+  SessionRegistry,
+  clientLabel,
+} from "../session/registry.js";
+import { StatsCollector } from "../stats/collector.js";
+
+describe("SessionRegistry", () => {
+  it("should store clientInfo and count requests", () => {
+    const registry = new SessionRegistry();
+    const id = registry.open("stdio");
+    assert.strictEqual(registry.get(id)?.client, null);
+
+    registry.identify(id, { name: "claude-ai", version: "0.1.0" });
+    registry.recordRequest(id);
+    registry.recordRequest(id);
+
+    const session = registry.get(id);
+    assert.deepStrictEqual(session?.client, { name: "claude-ai", version: "0.1.0" });
+    assert.strictEqual(session?.requests, 2);
+    assert.strictEqual(session?.transport, "stdio");
+    assert.strictEqual(registry.recordRequest("missing"), null);
+  });
+
+  it("should list open sessions and forget closed ones", () => {
+    const registry = new SessionRegistry();
+    const first = registry.open("stdio");
+    const second = registry.open("memory", { name: "cli", version: "2.0.0" });
+
+    assert.deepStrictEqual(
+      registry.list().map((session) => session.id).sort(),
+      [first, second].sort(),
+    );
+    assert.strictEqual(registry.close(first), true);
+    assert.deepStrictEqual(
+      registry.list().map((session) => session.id),
+      [second],
+    );
+  });
+
+  it("should terminate sessions that registered a terminator", () => {
+    const registry = new SessionRegistry();
+    let closed = 0;
+    const socket = registry.open("websocket", null, () => closed++);
+    const stdio = registry.open("stdio");
+
+    assert.strictEqual(registry.terminate(socket).id, socket);
+    assert.strictEqual(closed, 1);
+    assert.strictEqual(registry.get(socket), null);
+
+    assert.throws(
+      () => registry.terminate(stdio),
+      (error: unknown) =>
+        error instanceof SessionError && error.code === "SESSION_NOT_TERMINABLE",
+    );
+    assert.throws(
+      () => registry.terminate(socket),
+      (error: unknown) =>
+        error instanceof SessionError && error.code === "SESSION_NOT_FOUND",
+    );
+  });
+
+  it("should label clients by name and version", () => {
+    assert.strictEqual(clientLabel({ name: "cli", version: "2.0.0" }), "cli/2.0.0");
+    assert.strictEqual(clientLabel(null), "unknown");
+  });
+});
+
+describe("StatsCollector client labels", () => {
+  it("should count requests by client", async () => {
+    const stats = new StatsCollector(undefined, true);
+    stats.trackRequest("summary", 1, undefined, "cli/2.0.0");
+    stats.trackRequest("summary", 1, undefined, "cli/2.0.0");
+    stats.trackRequest("history", 1);
+
+    const snapshot = await stats.getSnapshot();
+    assert.deepStrictEqual(snapshot.requests.byClient, { "cli/2.0.0": 2 });
+    assert.strictEqual(snapshot.requests.total, 3);
+    assert.strictEqual(stats.getActivityLog(1)[0].data.client, undefined);
+    assert.strictEqual(stats.getActivityLog(2)[0].data.client, "cli/2.0.0");
+  });
+});
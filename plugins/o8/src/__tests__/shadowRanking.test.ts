@@ -0,0 +1,138 @@
+/**
+ * Test suite for shadow ranking
+ *
+ * Tests:
+ * - Comparing top-k rankings
+ * - Parsing experimental weights (inline JSON, files, invalid input)
+ * - Logging disagreements without changing match responses
+ * - Secrets in logged queries are redacted
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, readFile, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  ShadowRanker,
+  compareRankings,
+  parseWeights,
+  shadowRankerFromEnv,
+} from "../utils/shadowRanking.js";
+import { DEFAULT_WEIGHTS, FuzzyMatcher } from "../utils/fuzzyMatcher.js";
+import { REDACTED } from "../utils/redact.js";
+
+describe("compareRankings", () => {
+  it("should return null when the top k agree", () => {
+    assert.strictEqual(compareRankings("q", ["a", "b", "c"], ["a", "b", "d"], 2), null);
+  });
+
+  it("should report moved ids and overlap", () => {
+    const result = compareRankings("q", ["a", "b", "c"], ["b", "a", "d"], 3);
+    assert.ok(result);
+    assert.strictEqual(result.topChanged, true);
+    assert.strictEqual(result.overlap, 0.5);
+    assert.deepStrictEqual(result.moved, [
+      { id: "a", primary: 1, shadow: 2 },
+      { id: "b", primary: 2, shadow: 1 },
+      { id: "c", primary: 3, shadow: null },
+      { id: "d", primary: null, shadow: 3 },
+    ]);
+  });
+});
+
+describe("parseWeights", () => {
+  it("should override defaults from inline JSON", () => {
+    assert.deepStrictEqual(parseWeights('{"phrase": 30}'), {
+      ...DEFAULT_WEIGHTS,
+      phrase: 30,
+    });
+  });
+
+  it("should read weights from a file", async () => {
+    const dir = await mkdtemp(join(tmpdir(), "o8-shadow-"));
+    try {
+      const file = join(dir, "weights.json");
+      await writeFile(file, '{"useWhen": 9}');
+      assert.strictEqual(parseWeights(file).useWhen, 9);
+    } finally {
+      await rm(dir, { recursive: true, force: true });
+    }
+  });
+
+  it("should reject unknown weights and non-numbers", () => {
+    assert.throws(() => parseWeights('{"bogus": 1}'), /Unknown scoring weight: bogus/);
+    assert.throws(() => parseWeights('{"phrase": "high"}'), /must be a number/);
+    assert.throws(() => parseWeights("[1]"), /JSON object/);
+  });
+
+  it("should be disabled without O8_SHADOW_WEIGHTS", () => {
+    assert.strictEqual(shadowRankerFromEnv({}), null);
+  });
+});
+
+describe("ShadowRanker", () => {
+  it("should redact secrets in logged queries", async () => {
+    const dir = await mkdtemp(join(tmpdir(), "o8-shadow-"));
+    try {
+      const log = join(dir, "shadow.jsonl");
+      const ranker = new ShadowRanker(DEFAULT_WEIGHTS, log);
+      const query = "deploy with sk-abcdefghijklmnopqrstuvwx";
+      assert.ok(ranker.record(query, ["a", "b"], ["b", "a"], 2));
+
+      const entry = JSON.parse(await readFile(log, "utf-8"));
+      assert.strictEqual(entry.query, `deploy with ${REDACTED}`);
+    } finally {
+      await rm(dir, { recursive: true, force: true });
+    }
+  });
+});
+
+describe("FuzzyMatcher shadow ranking", () => {
+  it("should log disagreements without changing the response", async () => {
+    const dir = await mkdtemp(join(tmpdir(), "o8-shadow-"));
+    try {
+      const log = join(dir, "shadow.jsonl");
+      const matcher = new FuzzyMatcher();
+      matcher.setResourceIndex([
+        {
+          id: "agents/tagged",
+          category: "agent",
+          tags: ["react"],
+          capabilities: [],
+          useWhen: [],
+          estimatedTokens: 2000,
+          content: "# Tagged",
+        },
+        {
+          id: "agents/capable",
+          category: "agent",
+          tags: [],
+          capabilities: ["react"],
+          useWhen: [],
+          estimatedTokens: 2000,
+          content: "# Capable",
+        },
+      ]);
+      const request = { query: "react", mode: "catalog" as const, minScore: 10 };
+      const before = await matcher.match(request);
+
+      matcher.setShadowRanker(
+        new ShadowRanker({ ...DEFAULT_WEIGHTS, tagExact: 0, tagSubstring: 0 }, log),
+      );
+      const after = await matcher.match(request);
+      await new Promise((resolve) => setImmediate(resolve));
+
+      assert.deepStrictEqual(after, before);
+      const lines = (await readFile(log, "utf-8")).trim().split("\n");
+      assert.strictEqual(lines.length, 1);
+      const entry = JSON.parse(lines[0]);
+      assert.strictEqual(entry.query, "react");
+      assert.deepStrictEqual(entry.primary, ["agents/tagged", "agents/capable"]);
+      assert.deepStrictEqual(entry.shadow, ["agents/capable"]);
+      assert.strictEqual(entry.topChanged, true);
+    } finally {
+      await rm(dir, { recursive: true, force: true });
+    }
+  });
+});
@@ -0,0 +1,151 @@
+/**
+ * Test suite for catalog snapshots
+ *
+ * Tests:
+ * - Content-addressed storage and manifests
+ * - Snapshot name validation
+ * - Rolling the served catalog back and forward
+ * - Querying a snapshot by name or time without pinning it
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, readdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { SnapshotStore, SnapshotError } from "../state/snapshotStore.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("SnapshotStore", () => {
+  let dir: string;
+
+  before(async () => {
+    dir = await mkdtemp(join(tmpdir(), "o8-state-"));
+  });
+
+  after(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should store identical content once", async () => {
+    const store = new SnapshotStore(dir);
+    const manifest = await store.save("one", [
+      { path: "agents/a.md", content: "same" },
+      { path: "agents/b.md", content: "same" },
+    ]);
+
+    assert.strictEqual(manifest.files[0].hash, manifest.files[1].hash);
+    assert.strictEqual((await readdir(join(dir, "blobs"))).length, 1);
+    assert.strictEqual(await store.readBlob(manifest.files[0].hash), "same");
+  });
+
+  it("should list snapshots and persist the pin", async () => {
+    const store = new SnapshotStore(dir);
+
+    assert.deepStrictEqual(
+      (await store.list()).map((s) => [s.name, s.files]),
+      [["one", 2]],
+    );
+    await store.setPinned("one");
+    assert.strictEqual(await new SnapshotStore(dir).getPinned(), "one");
+    await store.setPinned(null);
+    assert.strictEqual(await store.getPinned(), null);
+  });
+
+  it("should reject unknown and invalid names", async () => {
+    const store = new SnapshotStore(dir);
+
+    await assert.rejects(
+      store.load("missing"),
+      (error: SnapshotError) => error.code === "SNAPSHOT_NOT_FOUND",
+    );
+    await assert.rejects(
+      store.save("../escape", []),
+      (error: SnapshotError) => error.code === "INVALID_SNAPSHOT_NAME",
+    );
+  });
+});
+
+describe("ResourceLoader snapshots", () => {
+  let root: string;
+  let loader: ResourceLoader;
+  const agentPath = () => join(root, "resources", "agents", "reviewer.md");
+  const agent = (body: string) =>
+    `---\ntags: [review]\n---\n# Reviewer\n\n${body}\n`;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-snapshot-"));
+    await mkdir(join(root, "resources", "agents"), { recursive: true });
+    await writeFile(agentPath(), agent("Check tests first."));
+    process.env.RESOURCES_PATH = join(root, "resources");
+    process.env.O8_STATE_DIR = join(root, "state");
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should serve a snapshot after a bad edit and return to live files", async () => {
+    const info = await loader.snapshotCatalog("good");
+    assert.strictEqual(info.files, 1);
+
+    await writeFile(agentPath(), agent("Approve everything."));
+    const rolledBack = await loader.rollbackCatalog("good");
+    assert.deepStrictEqual(rolledBack, { pinned: "good", resources: 1 });
+    assert.match(
+      await loader.loadResourceContent("o8://agents/reviewer"),
+      /Check tests first/,
+    );
+
+    await loader.rollbackCatalog("live");
+    assert.match(
+      await loader.loadResourceContent("o8://agents/reviewer"),
+      /Approve everything/,
+    );
+  });
+
+  it("should diff the working copy against a snapshot", async () => {
+    const { diff } = await loader.diffResource(
+      "agents/reviewer",
+      "snapshot:good",
+      "working",
+    );
+
+    assert.match(diff, /^-Check tests first\.$/m);
+    assert.match(diff, /^\+Approve everything\.$/m);
+  });
+
+  it("should query a snapshot by name or time without pinning it", async () => {
+    const content = await loader.loadResourceContent(
+      `o8://agents/match?query=reviewer&mode=full&asOf=${new Date().toISOString()}`,
+    );
+    assert.match(content, /Check tests first/);
+
+    const { resources } = await loader.batchGet(["reviewer"], {
+      snapshot: "good",
+    });
+    const [resource] = resources;
+    assert.ok("content" in resource);
+    assert.match(resource.content, /Check tests first/);
+    assert.strictEqual(resource.provenance?.layer, "snapshot:good");
+    assert.match(
+      await loader.loadResourceContent("o8://agents/reviewer"),
+      /Approve everything/,
+    );
+
+    await assert.rejects(
+      loader.batchGet(["reviewer"], { asOf: "2000-01-01T00:00:00Z" }),
+      (error: SnapshotError) => error.code === "SNAPSHOT_NOT_FOUND",
+    );
+  });
+
+  it("should refuse the reserved name", async () => {
+    await assert.rejects(
+      loader.snapshotCatalog("live"),
+      (error: SnapshotError) => error.code === "INVALID_SNAPSHOT_NAME",
+    );
+  });
+});
@@ -0,0 +1,89 @@
+/**
+ * Test suite for startup profiling
+ *
+ * Tests:
+ * - Repeated phases add up; counts and totals are reported
+ * - The first index build records its phases and counts
+ * - --profile-startup / O8_PROFILE_STARTUP
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  StartupProfile,
+  startupProfilingEnabled,
+} from "../utils/startupProfile.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+describe("StartupProfile", () => {
+  it("should add up repeated phases", async () => {
+    const profile = new StartupProfile();
+    profile.add("parse", 1.25);
+    profile.add("parse", 2);
+    assert.strictEqual(await profile.time("walk", async () => "done"), "done");
+    profile.count("files", 3);
+
+    const report = profile.report();
+    assert.deepStrictEqual(Object.keys(report.phases), ["parse", "walk"]);
+    assert.strictEqual(report.phases.parse, 3.3);
+    assert.deepStrictEqual(report.counts, { files: 3 });
+    assert.ok(report.sinceProcessStartMs >= report.totalMs);
+  });
+
+  it("should read the flag and its environment variable", () => {
+    assert.strictEqual(startupProfilingEnabled(["--profile-startup"], {}), true);
+    assert.strictEqual(
+      startupProfilingEnabled([], { O8_PROFILE_STARTUP: "1" }),
+      true,
+    );
+    assert.strictEqual(startupProfilingEnabled([], {}), false);
+  });
+});
+
+describe("ResourceLoader startup profile", () => {
+  let root: string;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-profile-"));
+    await mkdir(join(root, "agents"), { recursive: true });
+    await writeFile(join(root, "agents", "a.md"), "# A\n");
+    await writeFile(
+      join(root, "agents", "b.md"),
+      "---\ntags: [unclosed\n---\n# B\n",
+    );
+    process.env.RESOURCES_PATH = root;
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should record the phases of the first build", async () => {
+    const loader = new ResourceLoader(new Logger("test"));
+    const profile = new StartupProfile();
+    loader.setStartupProfile(profile);
+    await loader.loadResourceIndex();
+
+    const report = profile.report();
+    for (const phase of ["state", "layers", "walk", "scan", "read", "parse"]) {
+      assert.ok(`index.${phase}` in report.phases, phase);
+    }
+    assert.ok("index.validate" in report.phases);
+    assert.deepStrictEqual(report.counts, {
+      files: 2,
+      resources: 1,
+      failures: 1,
+    });
+
+    await loader.reloadIndex();
+    assert.deepStrictEqual(profile.report().counts, report.counts);
+  });
+});
@@ -0,0 +1,120 @@
+/**
+ * Test suite for state directory retention
+ *
+ * Tests:
+ * - Retention policy from the environment
+ * - Count and age limits, keeping the pinned snapshot
+ * - Blobs shared with kept snapshots survive; orphans are removed
+ * - Size limit removes the oldest entries first
+ */
+
+import { describe, it, beforeEach, afterEach } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, readdir, rm, utimes, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  BLOB_GRACE_MS,
+  collectGarbage,
+  retentionPolicy,
+} from "../state/gc.js";
+import { SnapshotStore } from "../state/snapshotStore.js";
+
+const DAY = 24 * 60 * 60 * 1000;
+
+describe("retentionPolicy", () => {
+  it("should read limits from the environment", () => {
+    assert.deepStrictEqual(
+      retentionPolicy({
+        O8_STATE_MAX_SNAPSHOTS: "5",
+        O8_STATE_MAX_SIZE_MB: "0.5",
+      }),
+      { maxSnapshots: 5, maxSizeMb: 0.5 },
+    );
+    assert.strictEqual(retentionPolicy({}), null);
+  });
+
+  it("should reject invalid limits", () => {
+    assert.throws(
+      () => retentionPolicy({ O8_STATE_MAX_AGE_DAYS: "-1" }),
+      /O8_STATE_MAX_AGE_DAYS/,
+    );
+  });
+});
+
+describe("collectGarbage", () => {
+  let dir: string;
+  let store: SnapshotStore;
+
+  beforeEach(async () => {
+    dir = await mkdtemp(join(tmpdir(), "o8-gc-"));
+    store = new SnapshotStore(dir);
+    // Snapshots are ordered by creation time, which has ms resolution
+    for (const name of ["one", "two", "three"]) {
+      await store.save(name, [
+        { path: "agents/shared.md", content: "shared" },
+        { path: `agents/${name}.md`, content: `only in ${name}` },
+      ]);
+      await new Promise((resolve) => setTimeout(resolve, 5));
+    }
+  });
+
+  afterEach(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should keep the newest snapshots and the pinned one", async () => {
+    await store.setPinned("one");
+
+    const result = await collectGarbage(dir, { maxSnapshots: 2 });
+
+    assert.deepStrictEqual(result.removed, {
+      snapshots: ["two"],
+      crashReports: 0,
+      blobs: 1,
+    });
+    assert.deepStrictEqual(
+      (await store.list()).map((snapshot) => snapshot.name),
+      ["one", "three"],
+    );
+    // The shared blob is still referenced
+    assert.strictEqual((await readdir(join(dir, "blobs"))).length, 3);
+  });
+
+  it("should remove entries past the age limit", async () => {
+    await mkdir(join(dir, "crashes"));
+    const crash = join(dir, "crashes", "crash-old.json");
+    await writeFile(crash, "{}");
+    const old = new Date(Date.now() - 10 * DAY);
+    await utimes(crash, old, old);
+
+    const result = await collectGarbage(
+      dir,
+      { maxAgeDays: 7 },
+      Date.now() + 8 * DAY,
+    );
+
+    assert.deepStrictEqual(result.removed.snapshots, ["one", "two", "three"]);
+    assert.strictEqual(result.removed.crashReports, 1);
+    assert.deepStrictEqual(await readdir(join(dir, "blobs")), []);
+  });
+
+  it("should remove orphaned blobs after the grace period", async () => {
+    await writeFile(join(dir, "blobs", "orphan"), "left over");
+
+    assert.strictEqual((await collectGarbage(dir)).removed.blobs, 0);
+    const result = await collectGarbage(dir, {}, Date.now() + BLOB_GRACE_MS);
+    assert.strictEqual(result.removed.blobs, 1);
+    assert.deepStrictEqual(result.removed.snapshots, []);
+  });
+
+  it("should remove the oldest entries until the size limit fits", async () => {
+    const before = (await collectGarbage(dir)).sizeBytes;
+    const result = await collectGarbage(dir, {
+      maxSizeMb: (before - 1) / (1024 * 1024),
+    });
+
+    assert.deepStrictEqual(result.removed.snapshots, ["one"]);
+    assert.ok(result.sizeBytes < before);
+  });
+});
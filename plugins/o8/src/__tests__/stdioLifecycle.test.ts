@@ -0,0 +1,103 @@
+/**
+ * Test suite for StdioLifecycle
+ *
+ * Tests:
+ * - In-flight request tracking
+ * - Draining before exit on stdin EOF
+ * - Broken pipe handling
+ * - Stay-alive mode
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { PassThrough } from "stream";
+import {
+  StdioLifecycle,
+  StdioCloseReason,
+  EXIT_CODES,
+} from "../transports/stdioLifecycle.js";
+import { Logger } from "../utils/logger.js";
+
+function createHarness(stayAlive = false) {
+  const input = new PassThrough();
+  const output = new PassThrough();
+  const exits: Array<{ reason: StdioCloseReason; code: number }> = [];
+  const sent: any[] = [];
+  const transport = {
+    onmessage: (_message: any) => {},
+    send: async (message: any) => {
+      sent.push(message);
+    },
+  };
+
+  const lifecycle = new StdioLifecycle(new Logger("test"), {
+    stayAlive,
+    drainTimeoutMs: 200,
+    input,
+    output,
+    onExit: (reason, code) => {
+      exits.push({ reason, code });
+    },
+  });
+  lifecycle.attach(transport);
+
+  return { input, output, exits, sent, transport, lifecycle };
+}
+
+const tick = () => new Promise((resolve) => setTimeout(resolve, 10));
+
+describe("StdioLifecycle", () => {
+  it("should track requests until their response is sent", async () => {
+    const { transport, lifecycle } = createHarness();
+
+    transport.onmessage({ jsonrpc: "2.0", id: 1, method: "resources/read" });
+    transport.onmessage({ jsonrpc: "2.0", method: "notifications/initialized" });
+    assert.strictEqual(lifecycle.inFlight, 1);
+
+    await transport.send({ jsonrpc: "2.0", id: 1, result: {} });
+    assert.strictEqual(lifecycle.inFlight, 0);
+  });
+
+  it("should finish in-flight requests before exiting on stdin EOF", async () => {
+    const { input, exits, transport } = createHarness();
+
+    transport.onmessage({ jsonrpc: "2.0", id: 7, method: "prompts/get" });
+    input.end();
+    input.resume();
+    await tick();
+    assert.strictEqual(exits.length, 0);
+
+    await transport.send({ jsonrpc: "2.0", id: 7, result: {} });
+    await tick();
+    assert.deepStrictEqual(exits, [
+      { reason: "stdin_closed", code: EXIT_CODES.STDIN_CLOSED },
+    ]);
+  });
+
+  it("should exit with a distinct code on broken pipe", async () => {
+    const { output, exits, sent, transport } = createHarness();
+
+    const error: NodeJS.ErrnoException = new Error("write EPIPE");
+    error.code = "EPIPE";
+    output.emit("error", error);
+    await tick();
+
+    assert.deepStrictEqual(exits, [
+      { reason: "stdout_broken", code: EXIT_CODES.STDOUT_BROKEN },
+    ]);
+
+    await transport.send({ jsonrpc: "2.0", id: 1, result: {} });
+    assert.strictEqual(sent.length, 0);
+  });
+
+  it("should keep running in stay-alive mode", async () => {
+    const { input, exits, lifecycle } = createHarness(true);
+
+    input.end();
+    input.resume();
+    await tick();
+
+    assert.strictEqual(exits.length, 0);
+    assert.strictEqual(lifecycle.closeReason, "stdin_closed");
+  });
+});
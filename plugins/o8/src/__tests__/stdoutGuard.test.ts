@@ -0,0 +1,69 @@
+/**
+ * Test suite for stdout hygiene
+ *
+ * Tests:
+ * - Protocol frames reach stdout, other writes go to stderr
+ * - Strict mode throws on stray writes
+ * - Uninstalling restores stdout
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { PassThrough } from "stream";
+import { StdoutGuard, StdoutViolationError } from "../transports/stdoutGuard.js";
+
+/**
+ * Stream that remembers everything written to it
+ */
+function capture() {
+  const stream = new PassThrough();
+  const chunks: string[] = [];
+  stream.on("data", (chunk) => chunks.push(chunk.toString()));
+  return { stream: stream as unknown as NodeJS.WriteStream, text: () => chunks.join("") };
+}
+
+function writeFrame(guard: StdoutGuard, frame: string): Promise<void> {
+  return new Promise((resolve, reject) =>
+    guard.protocolOut.write(frame, (error) => (error ? reject(error) : resolve())),
+  );
+}
+
+describe("StdoutGuard", () => {
+  it("should keep stdout for protocol frames", async () => {
+    const stdout = capture();
+    const stderr = capture();
+    const guard = new StdoutGuard({ stdout: stdout.stream, stderr: stderr.stream });
+    guard.install();
+
+    await writeFrame(guard, '{"jsonrpc":"2.0","id":1,"result":{}}\n');
+    stdout.stream.write("Server ready!\n");
+
+    assert.strictEqual(stdout.text(), '{"jsonrpc":"2.0","id":1,"result":{}}\n');
+    assert.strictEqual(stderr.text(), "Server ready!\n");
+    assert.deepStrictEqual(guard.getViolations(), ["Server ready!\n"]);
+  });
+
+  it("should throw on stray writes in strict mode", () => {
+    const stdout = capture();
+    const guard = new StdoutGuard({ strict: true, stdout: stdout.stream });
+    guard.install();
+
+    assert.throws(
+      () => stdout.stream.write("debug output\n"),
+      (error: unknown) =>
+        error instanceof StdoutViolationError && error.output === "debug output\n",
+    );
+    assert.strictEqual(stdout.text(), "");
+  });
+
+  it("should restore stdout when uninstalled", async () => {
+    const stdout = capture();
+    const guard = new StdoutGuard({ strict: true, stdout: stdout.stream });
+    guard.install();
+    guard.uninstall();
+
+    stdout.stream.write("plain\n");
+    await new Promise((resolve) => setImmediate(resolve));
+    assert.strictEqual(stdout.text(), "plain\n");
+  });
+});
@@ -0,0 +1,86 @@
+/**
+ * Test suite for streamed results
+ *
+ * Tests:
+ * - Whole-catalog export without a query
+ * - Scored matches without a result limit
+ * - Category filter
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+async function collect(
+  iterable: AsyncIterable<Record<string, unknown>>,
+): Promise<Array<Record<string, unknown>>> {
+  const items = [];
+  for await (const item of iterable) {
+    items.push(item);
+  }
+  return items;
+}
+
+describe("ResourceLoader.streamResources", () => {
+  let root: string;
+  let loader: ResourceLoader;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-stream-"));
+    await mkdir(join(root, "agents"));
+    await mkdir(join(root, "skills"));
+    for (const [dir, name, tag] of [
+      ["agents", "rust-expert", "rust"],
+      ["agents", "go-expert", "go"],
+      ["skills", "rust-testing", "rust"],
+    ]) {
+      await writeFile(
+        join(root, dir, `${name}.md`),
+        `---\ntags: [${tag}]\ncapabilities: [${tag} development]\n---\n# ${name}\n`,
+      );
+    }
+    process.env.RESOURCES_PATH = root;
+    loader = new ResourceLoader(new Logger("test"));
+  });
+
+  after(async () => {
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should export every resource without a query", async () => {
+    const items = await collect(loader.streamResources());
+
+    assert.deepStrictEqual(
+      items.map((item) => item.id).sort(),
+      ["agents/go-expert", "agents/rust-expert", "skills/rust-testing"],
+    );
+    assert.ok(items.every((item) => !("score" in item)));
+  });
+
+  it("should yield scored matches above minScore", async () => {
+    const items = await collect(
+      loader.streamResources({ query: "rust", minScore: 10 }),
+    );
+
+    assert.deepStrictEqual(
+      items.map((item) => item.id).sort(),
+      ["agents/rust-expert", "skills/rust-testing"],
+    );
+    assert.ok(items.every((item) => typeof item.score === "number"));
+  });
+
+  it("should apply the category filter", async () => {
+    const items = await collect(
+      loader.streamResources({ query: "rust", category: "skills", minScore: 10 }),
+    );
+
+    assert.deepStrictEqual(
+      items.map((item) => item.id),
+      ["skills/rust-testing"],
+    );
+  });
+});
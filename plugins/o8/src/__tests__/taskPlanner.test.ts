@@ -0,0 +1,120 @@
+/**
+ * Test suite for multi-agent task plans
+ *
+ * Tests:
+ * - Splitting task descriptions into phases
+ * - One routed step per phase, chained by dependencies
+ * - Steps without a qualifying agent
+ * - Route params validation
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import { MAX_PLAN_STEPS, planTask, splitPhases } from "../utils/taskPlanner.js";
+import { parseRouteRequest } from "../utils/agentRouter.js";
+import { FuzzyMatcher, type ResourceFragment } from "../utils/fuzzyMatcher.js";
+
+function agent(name: string, tags: string[]): ResourceFragment {
+  return {
+    id: `agents/${name}`,
+    category: "agent",
+    tags,
+    capabilities: [],
+    useWhen: [],
+    estimatedTokens: 200,
+    content: "",
+  };
+}
+
+describe("splitPhases", () => {
+  it("should split sentences and then clauses", () => {
+    assert.deepStrictEqual(
+      splitPhases(
+        "Implement the parser, then review it for security. Write the docs.",
+      ),
+      ["Implement the parser", "review it for security", "Write the docs"],
+    );
+  });
+
+  it("should split list items", () => {
+    assert.deepStrictEqual(
+      splitPhases("1. design the api\n2. implement it\n- test it"),
+      ["design the api", "implement it", "test it"],
+    );
+  });
+
+  it("should keep a single phase whole", () => {
+    assert.deepStrictEqual(splitPhases("refactor the auth module"), [
+      "refactor the auth module",
+    ]);
+  });
+
+  it("should merge phases beyond the limit into the last step", () => {
+    const task = Array.from({ length: 12 }, (_, i) => `step ${i}`).join("\n");
+    const phases = splitPhases(task);
+    assert.strictEqual(phases.length, MAX_PLAN_STEPS);
+    assert.strictEqual(phases.at(-1), "step 9; step 10; step 11");
+  });
+});
+
+describe("planTask", () => {
+  const fragments = [
+    agent("rust-developer", ["rust", "implement"]),
+    agent("security-reviewer", ["security", "review"]),
+    agent("technical-writer", ["docs", "write"]),
+  ];
+
+  it("should route each phase and chain the steps", () => {
+    const plan = planTask(fragments, new FuzzyMatcher(), {
+      task: "implement rust parser, then security review",
+    });
+
+    assert.deepStrictEqual(
+      plan.steps.map((step) => [step.id, step.agent?.id, step.dependsOn]),
+      [
+        ["step-1", "agents/rust-developer", []],
+        ["step-2", "agents/security-reviewer", ["step-1"]],
+      ],
+    );
+    assert.strictEqual(plan.estimatedTokens, 400);
+    assert.deepStrictEqual(plan.unassigned, []);
+  });
+
+  it("should report steps no agent qualifies for", () => {
+    const plan = planTask(fragments, new FuzzyMatcher(), {
+      task: "write docs. deploy kubernetes cluster",
+    });
+
+    assert.strictEqual(plan.steps[0].agent?.id, "agents/technical-writer");
+    assert.strictEqual(plan.steps[1].agent, null);
+    assert.deepStrictEqual(plan.unassigned, ["step-2"]);
+  });
+});
+
+describe("parseRouteRequest", () => {
+  it("should read task and constraints", () => {
+    assert.deepStrictEqual(
+      parseRouteRequest("agents/route", {
+        task: "audit",
+        capabilities: { languages: "rs" },
+        maxTokens: 2000,
+        exclude: ["generalist"],
+      }),
+      {
+        task: "audit",
+        capabilities: { languages: ["rust"] },
+        maxTokens: 2000,
+        exclude: ["generalist"],
+        alternatives: undefined,
+      },
+    );
+  });
+
+  it("should reject requests without a task or with a bad exclude", () => {
+    assert.throws(() => parseRouteRequest("agents/route", {}), /"task"/);
+    assert.throws(
+      () => parseRouteRequest("orchestrate/plan", { task: "x", exclude: "a" }),
+      /orchestrate\/plan "exclude"/,
+    );
+  });
+});
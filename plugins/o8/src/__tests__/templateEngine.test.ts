@@ -0,0 +1,217 @@
+/**
+ * Test suite for TemplateEngine
+ *
+ * Tests template rendering for resource bodies including:
+ * - Variable declaration parsing
+ * - Placeholder substitution and defaults
+ * - Type validation
+ * - Structured errors for missing variables
+ * - Conditional sections
+ * - Partial includes and cycle detection
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  TemplateEngine,
+  TemplateError,
+  TemplateSyntaxError,
+  TemplateVariable,
+} from "../utils/templateEngine.js";
+
+describe("TemplateEngine", () => {
+  const engine = new TemplateEngine();
+
+  const declared: TemplateVariable[] = [
+    { name: "lang", type: "string", required: true },
+    { name: "maxFiles", type: "number", default: 10 },
+    { name: "strict", type: "boolean" },
+  ];
+
+  describe("Declaration Parsing", () => {
+    it("should parse variables from frontmatter", () => {
+      const variables = engine.parseDeclarations({
+        variables: [
+          { name: "lang", required: true },
+          { name: "count", type: "number", default: 3 },
+          { type: "string" },
+        ],
+      });
+
+      assert.strictEqual(variables.length, 2);
+      assert.strictEqual(variables[0].type, "string");
+      assert.strictEqual(variables[0].required, true);
+      assert.strictEqual(variables[1].default, 3);
+    });
+
+    it("should return empty array when no variables are declared", () => {
+      assert.deepStrictEqual(engine.parseDeclarations({ tags: ["a"] }), []);
+    });
+  });
+
+  describe("Rendering", () => {
+    it("should substitute provided values and defaults", () => {
+      const body = "Review {{lang}} code, at most {{ maxFiles }} files.";
+      const result = engine.render(body, declared, { lang: "rust" });
+
+      assert.strictEqual(result, "Review rust code, at most 10 files.");
+    });
+
+    it("should leave undeclared placeholders untouched", () => {
+      const body = "Use ${{ secrets.TOKEN }} with {{lang}} and {{other}}";
+      const result = engine.render(body, declared, { lang: "go" });
+
+      assert.strictEqual(
+        result,
+        "Use ${{ secrets.TOKEN }} with go and {{other}}",
+      );
+    });
+
+    it("should render optional variables without defaults as empty", () => {
+      const result = engine.render("strict={{strict}}", declared, {
+        lang: "ts",
+      });
+
+      assert.strictEqual(result, "strict=");
+    });
+  });
+
+  describe("Validation", () => {
+    it("should list missing required variables", () => {
+      assert.throws(
+        () => engine.render("{{lang}}", declared, {}),
+        (error: any) => {
+          assert.ok(error instanceof TemplateError);
+          assert.deepStrictEqual(error.missing, ["lang"]);
+          assert.deepStrictEqual(error.invalid, []);
+          return true;
+        },
+      );
+    });
+
+    it("should reject values of the wrong type", () => {
+      assert.throws(
+        () =>
+          engine.render("{{lang}}", declared, {
+            lang: "rust",
+            maxFiles: "many",
+            strict: "yes",
+          }),
+        (error: any) => {
+          assert.ok(error instanceof TemplateError);
+          assert.deepStrictEqual(
+            error.invalid.map((v: any) => v.name),
+            ["maxFiles", "strict"],
+          );
+          return true;
+        },
+      );
+    });
+
+    it("should coerce number and boolean values", () => {
+      const values = engine.resolve(declared, {
+        lang: "py",
+        maxFiles: "5",
+        strict: "true",
+      });
+
+      assert.deepStrictEqual(values, { lang: "py", maxFiles: 5, strict: true });
+    });
+  });
+
+  describe("Conditional Sections", () => {
+    it("should select the branch matching an equality condition", () => {
+      const body =
+        '{{#if lang == "rust"}}Run cargo clippy.{{else}}Run the {{lang}} linter.{{/if}}';
+
+      assert.strictEqual(
+        engine.render(body, declared, { lang: "rust" }),
+        "Run cargo clippy.",
+      );
+      assert.strictEqual(
+        engine.render(body, declared, { lang: "go" }),
+        "Run the go linter.",
+      );
+    });
+
+    it("should evaluate truthiness, negation, and inequality", () => {
+      const body =
+        "{{#if strict}}A{{/if}}{{#if !strict}}B{{/if}}{{#if maxFiles != 10}}C{{/if}}";
+
+      assert.strictEqual(
+        engine.render(body, declared, { lang: "ts", strict: "true" }),
+        "A",
+      );
+      assert.strictEqual(
+        engine.render(body, declared, { lang: "ts", maxFiles: "3" }),
+        "BC",
+      );
+    });
+
+    it("should support nested blocks", () => {
+      const body =
+        "{{#if lang == 'rust'}}rust{{#if strict}}-strict{{/if}}{{/if}}";
+
+      assert.strictEqual(
+        engine.render(body, declared, { lang: "rust", strict: "true" }),
+        "rust-strict",
+      );
+      assert.strictEqual(
+        engine.render(body, declared, { lang: "rust", strict: "false" }),
+        "rust",
+      );
+    });
+
+    it("should reject unbalanced blocks", () => {
+      assert.throws(
+        () => engine.render("{{#if strict}}open", declared, { lang: "ts" }),
+        TemplateSyntaxError,
+      );
+      assert.throws(
+        () => engine.render("close{{/if}}", declared, { lang: "ts" }),
+        TemplateSyntaxError,
+      );
+    });
+  });
+
+  describe("Partial Includes", () => {
+    const partials: Record<string, string> = {
+      "shared/code-style": "Follow the style guide.\n{{> shared/footer}}",
+      "shared/footer": "-- orchestr8",
+      "loop/a": "A {{> loop/b}}",
+      "loop/b": "B {{> loop/a}}",
+    };
+    const load = async (name: string) => {
+      if (!(name in partials)) throw new Error(`unknown partial ${name}`);
+      return partials[name];
+    };
+
+    it("should expand nested partials", async () => {
+      const result = await engine.expandPartials(
+        "# Agent\n{{> shared/code-style}}",
+        load,
+      );
+
+      assert.strictEqual(
+        result,
+        "# Agent\nFollow the style guide.\n-- orchestr8",
+      );
+    });
+
+    it("should return bodies without includes unchanged", async () => {
+      const body = "No includes here {{lang}}";
+      assert.strictEqual(await engine.expandPartials(body, load), body);
+    });
+
+    it("should detect include cycles", async () => {
+      await assert.rejects(
+        () => engine.expandPartials("{{> loop/a}}", load),
+        (error: any) => {
+          assert.ok(error instanceof TemplateSyntaxError);
+          assert.match(error.message, /loop\/a -> loop\/b -> loop\/a/);
+          return true;
+        },
+      );
+    });
+  });
+});
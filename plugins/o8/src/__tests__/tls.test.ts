@@ -0,0 +1,85 @@
+/**
+ * Test suite for HTTP transport TLS
+ *
+ * Tests:
+ * - Server options from the environment, with and without a client CA
+ * - Incomplete TLS configuration is rejected
+ * - Client identity from verified certificates only
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { clientCertificateSubject, tlsFromEnv } from "../transports/tls.js";
+
+describe("tlsFromEnv", () => {
+  let dir: string;
+  let env: NodeJS.ProcessEnv;
+
+  before(async () => {
+    dir = await mkdtemp(join(tmpdir(), "o8-tls-"));
+    for (const name of ["cert.pem", "key.pem", "ca.pem"]) {
+      await writeFile(join(dir, name), name);
+    }
+    env = {
+      O8_TLS_CERT: join(dir, "cert.pem"),
+      O8_TLS_KEY: join(dir, "key.pem"),
+    };
+  });
+
+  after(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should serve plain HTTP without TLS variables", () => {
+    assert.strictEqual(tlsFromEnv({}), null);
+  });
+
+  it("should require client certificates with a client CA", () => {
+    const options = tlsFromEnv(env);
+    assert.strictEqual(String(options?.cert), "cert.pem");
+    assert.strictEqual(options?.requestCert, undefined);
+
+    const mutual = tlsFromEnv({
+      ...env,
+      O8_TLS_CLIENT_CA: join(dir, "ca.pem"),
+    });
+    assert.strictEqual(String(mutual?.ca), "ca.pem");
+    assert.strictEqual(mutual?.requestCert, true);
+    assert.strictEqual(mutual?.rejectUnauthorized, true);
+  });
+
+  it("should reject incomplete configuration", () => {
+    assert.throws(
+      () => tlsFromEnv({ O8_TLS_CLIENT_CA: join(dir, "ca.pem") }),
+      /O8_TLS_CERT and O8_TLS_KEY/,
+    );
+    assert.throws(
+      () => tlsFromEnv({ ...env, O8_TLS_KEY: join(dir, "missing.pem") }),
+      /Cannot read O8_TLS_KEY/,
+    );
+  });
+});
+
+describe("clientCertificateSubject", () => {
+  const socket = (authorized: boolean, CN?: string) => ({
+    encrypted: true,
+    authorized,
+    getPeerCertificate: () => ({ subject: CN ? { CN } : undefined }),
+  });
+
+  it("should return the common name of a verified certificate", () => {
+    assert.strictEqual(
+      clientCertificateSubject(socket(true, "orchestrator.internal")),
+      "orchestrator.internal",
+    );
+  });
+
+  it("should ignore unverified certificates and plain sockets", () => {
+    assert.strictEqual(clientCertificateSubject(socket(false, "x")), null);
+    assert.strictEqual(clientCertificateSubject(socket(true)), null);
+    assert.strictEqual(clientCertificateSubject({}), null);
+  });
+});
@@ -0,0 +1,174 @@
+/**
+ * Test suite for Unicode tokenization
+ *
+ * Tests:
+ * - Case folding (NFKC, ß, final sigma, locale)
+ * - Non-Latin scripts and unspaced scripts (segmenter and bigrams)
+ * - Snippet highlighting outside ASCII
+ * - Matching non-English resources
+ * - Stop words and markdown stripping, applied to queries and fields alike
+ */
+
+import { describe, it } from "node:test";
+import assert from "node:assert";
+import {
+  DEFAULT_STOP_WORDS,
+  foldCase,
+  normalizeText,
+  stopWords,
+  stripMarkdown,
+  tokenizeText,
+  tokenizerOptions,
+} from "../utils/tokenize.js";
+import { buildSnippets } from "../utils/bodyIndex.js";
+import { FuzzyMatcher } from "../utils/fuzzyMatcher.js";
+
+describe("foldCase", () => {
+  it("should fold compatibility forms and special cases", () => {
+    assert.strictEqual(foldCase("ＡＰＩ"), "api");
+    assert.strictEqual(foldCase("STRASSE"), foldCase("Straße"));
+    assert.strictEqual(foldCase("ΟΔΟΣ"), foldCase("οδος"));
+  });
+
+  it("should use the locale when given", () => {
+    assert.strictEqual(foldCase("ISTANBUL", "tr"), "ıstanbul");
+    assert.strictEqual(foldCase("ISTANBUL", undefined), "istanbul");
+  });
+});
+
+describe("tokenizeText", () => {
+  it("should keep letters from every script", () => {
+    assert.deepStrictEqual(
+      tokenizeText("Créer une API REST — сервис на Go, ci-cd!", {}),
+      ["créer", "une", "api", "rest", "сервис", "на", "go", "ci-cd"],
+    );
+  });
+
+  it("should segment unspaced scripts into words", () => {
+    assert.deepStrictEqual(tokenizeText("日本語の翻訳エージェント", {}), [
+      "日本語",
+      "の",
+      "翻訳",
+      "エージェント",
+    ]);
+    assert.deepStrictEqual(tokenizeText("react入門", {}), ["react", "入門"]);
+  });
+
+  it("should split unspaced scripts into bigrams when enabled", () => {
+    assert.deepStrictEqual(tokenizeText("数据库", { cjkBigrams: true }), [
+      "数据",
+      "据库",
+    ]);
+    assert.deepStrictEqual(tokenizeText("猫", { cjkBigrams: true }), ["猫"]);
+  });
+
+  it("should read options from the environment", () => {
+    assert.deepStrictEqual(
+      tokenizerOptions({ O8_LOCALE: "tr", O8_CJK_BIGRAMS: "true" }),
+      { locale: "tr", cjkBigrams: true },
+    );
+  });
+});
+
+describe("buildSnippets", () => {
+  it("should highlight whole words outside ASCII", () => {
+    assert.deepStrictEqual(
+      buildSnippets("Le café et les cafés", ["café"], 1),
+      ["Le **café** et les cafés"],
+    );
+    assert.deepStrictEqual(buildSnippets("日本語の翻訳です", ["翻訳"], 1), [
+      "日本語の**翻訳**です",
+    ]);
+  });
+});
+
+describe("FuzzyMatcher with non-English resources", () => {
+  it("should match queries in the resource's language", async () => {
+    const matcher = new FuzzyMatcher();
+    matcher.setResourceIndex([
+      {
+        id: "agents/traducteur",
+        category: "agent",
+        tags: ["traduction", "français"],
+        capabilities: ["Traduction de documents techniques"],
+        useWhen: ["Traduire une documentation en français"],
+        estimatedTokens: 200,
+        content: "# Traducteur",
+      },
+      {
+        id: "agents/翻訳者",
+        category: "agent",
+        tags: ["翻訳"],
+        capabilities: ["技術文書の翻訳"],
+        useWhen: ["日本語のドキュメントを翻訳する"],
+        estimatedTokens: 200,
+        content: "# 翻訳者",
+      },
+    ]);
+
+    const french = await matcher.match({
+      query: "TRADUCTION Français",
+      mode: "catalog",
+      minScore: 10,
+    });
+    assert.deepStrictEqual(
+      french.fragments.map((fragment) => fragment.id),
+      ["agents/traducteur"],
+    );
+
+    const japanese = await matcher.match({
+      query: "ドキュメントの翻訳",
+      mode: "catalog",
+      minScore: 10,
+    });
+    assert.strictEqual(japanese.fragments[0]?.id, "agents/翻訳者");
+  });
+});
+
+describe("normalizeText", () => {
+  it("should strip markdown and stop words", () => {
+    assert.deepStrictEqual(
+      normalizeText("Build **the** `React` app - see [the docs](https://x.dev/y)", {}),
+      ["build", "react", "app", "see", "docs"],
+    );
+    assert.deepStrictEqual(normalizeText("the react app", {}), [
+      "react",
+      "app",
+    ]);
+  });
+
+  it("should keep snake_case and drop emphasis underscores", () => {
+    assert.strictEqual(
+      stripMarkdown("_Use_ snake_case\n## Title").trim(),
+      "Use snake_case\nTitle",
+    );
+  });
+
+  it("should configure stop words with O8_STOP_WORDS", () => {
+    assert.strictEqual(stopWords({}).size, DEFAULT_STOP_WORDS.length);
+    assert.ok(stopWords({ O8_STOP_WORDS: "default,Please" }).has("please"));
+    assert.ok(stopWords({ O8_STOP_WORDS: "default,please" }).has("the"));
+    assert.deepStrictEqual([...stopWords({ O8_STOP_WORDS: "app" })], ["app"]);
+    assert.strictEqual(stopWords({ O8_STOP_WORDS: "none" }).size, 0);
+  });
+
+  it("should score queries the same with or without stop words", () => {
+    const matcher = new FuzzyMatcher();
+    const resource = {
+      id: "agents/react",
+      category: "agent" as const,
+      tags: ["frontend"],
+      capabilities: ["Build the **React** app"],
+      useWhen: ["Starting a new React app"],
+      estimatedTokens: 500,
+      content: "# React",
+    };
+    const score = (query: string) =>
+      matcher.calculateScore(resource, matcher.extractKeywords(query), {
+        query,
+      });
+
+    assert.strictEqual(score("the react app"), score("React app"));
+    assert.ok(score("react app") > score("react"));
+  });
+});
@@ -0,0 +1,262 @@
+/**
+ * Test suite for upstream MCP server aggregation
+ *
+ * Tests:
+ * - Namespaced tool names and resource URIs
+ * - Listing, calling, and reading through the pool
+ * - Upstreams that fail to connect are left out
+ * - Repeated failures open the circuit and show up in the status
+ * - Resource reads are cached until the upstream reports a change
+ * - Expired reads are served with serveStale while the upstream is down
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { z } from "zod";
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { InMemoryTransport } from "@modelcontextprotocol/sdk/inMemory.js";
+import type { Transport } from "@modelcontextprotocol/sdk/shared/transport.js";
+import type { UpstreamConfig } from "../config/schema.js";
+import {
+  UpstreamError,
+  UpstreamPool,
+  UpstreamUnavailableError,
+  splitUpstreamName,
+  upstreamResourceUri,
+  upstreamToolName,
+} from "../proxy/upstreams.js";
+import { Logger } from "../utils/logger.js";
+
+describe("upstream names", () => {
+  it("should namespace and split tool names", () => {
+    assert.strictEqual(upstreamToolName("docs", "search"), "docs__search");
+    assert.deepStrictEqual(splitUpstreamName("docs__search_all"), {
+      upstream: "docs",
+      name: "search_all",
+    });
+  });
+
+  it("should namespace and split resource URIs", () => {
+    const uri = upstreamResourceUri("docs", "file:///a/b.md");
+    assert.strictEqual(uri, "upstream://docs/file:///a/b.md");
+    assert.deepStrictEqual(splitUpstreamName(uri), {
+      upstream: "docs",
+      name: "file:///a/b.md",
+    });
+  });
+
+  it("should not split names without a namespace", () => {
+    assert.strictEqual(splitUpstreamName("search"), null);
+    assert.strictEqual(splitUpstreamName("upstream://docs"), null);
+  });
+});
+
+function upstreamConfig(command: string): UpstreamConfig {
+  return {
+    command,
+    args: [],
+    timeout: 1000,
+    failureThreshold: 2,
+    resetTimeout: 60000,
+    cacheTTL: 60000,
+    serveStale: false,
+  };
+}
+
+/**
+ * A pool with a working "docs" upstream and a "broken" one that fails to
+ * connect
+ */
+async function connectPool(
+  overrides: Partial<UpstreamConfig> = {},
+): Promise<{
+  pool: UpstreamPool;
+  docs: McpServer;
+}> {
+  const docs = new McpServer({ name: "docs", version: "1.0.0" });
+  docs.registerTool(
+    "search",
+    { description: "Search docs", inputSchema: { query: z.string() } },
+    async ({ query }) => ({
+      content: [{ type: "text", text: `found ${query}` }],
+    }),
+  );
+  docs.registerResource(
+    "readme",
+    "file:///readme.md",
+    { mimeType: "text/markdown" },
+    async (uri) => ({ contents: [{ uri: uri.href, text: "# Readme" }] }),
+  );
+
+  const [clientTransport, serverTransport] =
+    InMemoryTransport.createLinkedPair();
+  await docs.connect(serverTransport);
+
+  const transports: Record<string, () => Transport> = {
+    docs: () => clientTransport,
+    broken: () => {
+      throw new Error("spawn failed");
+    },
+  };
+  const pool = new UpstreamPool(new Logger("test"), (name) =>
+    transports[name](),
+  );
+  await pool.connect({
+    docs: { ...upstreamConfig("docs-mcp"), ...overrides },
+    broken: upstreamConfig("missing"),
+  });
+  return { pool, docs };
+}
+
+describe("UpstreamPool", () => {
+  let pool: UpstreamPool;
+
+  before(async () => {
+    ({ pool } = await connectPool());
+  });
+
+  after(async () => {
+    await pool.close();
+  });
+
+  it("should leave out upstreams that fail to connect", () => {
+    assert.deepStrictEqual(pool.names(), ["docs"]);
+  });
+
+  it("should list namespaced tools and resources", async () => {
+    assert.deepStrictEqual(
+      (await pool.listTools()).map((tool) => tool.name),
+      ["docs__search"],
+    );
+    assert.deepStrictEqual(
+      pool.listResources().map((resource) => resource.uri),
+      ["upstream://docs/file:///readme.md"],
+    );
+  });
+
+  it("should call tools on their upstream", async () => {
+    const result = await pool.callTool("docs__search", { query: "auth" });
+    assert.deepStrictEqual(result.content, [
+      { type: "text", text: "found auth" },
+    ]);
+  });
+
+  it("should read resources under the namespaced URI", async () => {
+    const result = await pool.readResource("upstream://docs/file:///readme.md");
+    assert.strictEqual(
+      result.contents[0].uri,
+      "upstream://docs/file:///readme.md",
+    );
+    assert.strictEqual(result.contents[0].text, "# Readme");
+  });
+
+  it("should reject names of unknown upstreams", async () => {
+    await assert.rejects(() => pool.callTool("tickets__open"), UpstreamError);
+    await assert.rejects(() => pool.callTool("search"), UpstreamError);
+  });
+});
+
+describe("UpstreamPool circuit breaking", () => {
+  let pool: UpstreamPool;
+
+  before(async () => {
+    ({ pool } = await connectPool());
+  });
+
+  after(async () => {
+    await pool.close();
+  });
+
+  it("should report connected and failed upstreams", () => {
+    const status = pool.status();
+    assert.strictEqual(status.docs.connected, true);
+    assert.strictEqual(status.docs.tools, 1);
+    assert.strictEqual(status.docs.circuit?.state, "closed");
+    assert.deepStrictEqual(status.broken, {
+      connected: false,
+      tools: 0,
+      resources: 0,
+      circuit: null,
+      cache: null,
+      error: "spawn failed",
+    });
+  });
+
+  it("should fail fast once repeated calls have failed", async () => {
+    const missing = "upstream://docs/file:///missing.md";
+    await assert.rejects(() => pool.readResource(missing));
+    await assert.rejects(() => pool.readResource(missing));
+
+    await assert.rejects(
+      () => pool.callTool("docs__search", { query: "auth" }),
+      UpstreamUnavailableError,
+    );
+    const circuit = pool.status().docs.circuit!;
+    assert.strictEqual(circuit.state, "open");
+    assert.strictEqual(circuit.failures, 2);
+    assert.ok(circuit.lastError);
+    assert.ok(circuit.retryAt);
+  });
+});
+
+describe("UpstreamPool caching", () => {
+  let pool: UpstreamPool;
+  let docs: McpServer;
+
+  before(async () => {
+    ({ pool, docs } = await connectPool());
+  });
+
+  after(async () => {
+    await pool.close();
+  });
+
+  it("should serve repeated reads from the cache", async () => {
+    const uri = "upstream://docs/file:///readme.md";
+    await pool.readResource(uri);
+    const cached = await pool.readResource(uri);
+
+    assert.strictEqual(cached.contents[0].uri, uri);
+    assert.deepStrictEqual(pool.status().docs.cache, {
+      entries: 1,
+      hits: 1,
+      misses: 1,
+      stale: 0,
+    });
+    assert.strictEqual(pool.status().docs.circuit?.calls, 1);
+  });
+
+  it("should drop a cached read when the upstream updates it", async () => {
+    await docs.server.sendResourceUpdated({ uri: "file:///readme.md" });
+    // Let the notification reach the pool's client
+    await new Promise((resolve) => setImmediate(resolve));
+
+    assert.strictEqual(pool.status().docs.cache?.entries, 0);
+    await pool.readResource("upstream://docs/file:///readme.md");
+    assert.strictEqual(pool.status().docs.cache?.misses, 2);
+  });
+});
+
+describe("UpstreamPool stale reads", () => {
+  it("should serve expired reads while the upstream is unreachable", async () => {
+    const { pool, docs } = await connectPool({
+      cacheTTL: 20,
+      serveStale: true,
+    });
+    try {
+      const uri = "upstream://docs/file:///readme.md";
+      await pool.readResource(uri);
+      await new Promise((resolve) => setTimeout(resolve, 40));
+      await docs.close();
+
+      const stale = await pool.readResource(uri);
+      assert.strictEqual(stale.contents[0].text, "# Readme");
+      assert.strictEqual(pool.status().docs.cache?.stale, 1);
+      await assert.rejects(() =>
+        pool.readResource("upstream://docs/file:///other.md"),
+      );
+    } finally {
+      await pool.close();
+    }
+  });
+});
@@ -0,0 +1,121 @@
+/**
+ * Test suite for file watcher settings and symlinked directories
+ *
+ * Tests:
+ * - --watch-mode and --poll-interval from flags and environment
+ * - Polling in dev containers when the mode is auto
+ * - Polling trees that need more watch descriptors than allowed
+ * - Symlinked resource directories are scanned, link loops are not
+ */
+
+import { describe, it, before, after } from "node:test";
+import assert from "node:assert";
+import { mkdtemp, mkdir, rm, symlink, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import {
+  DEFAULT_POLL_INTERVAL,
+  chokidarOptions,
+  settingsForTree,
+  watchSettings,
+} from "../utils/watchOptions.js";
+import { ResourceLoader } from "../loaders/resourceLoader.js";
+import { Logger } from "../utils/logger.js";
+
+const noContainer = () => false;
+
+describe("watchSettings", () => {
+  it("should use native events outside containers by default", () => {
+    assert.deepStrictEqual(watchSettings([], {}, noContainer), {
+      mode: "native",
+      pollInterval: DEFAULT_POLL_INTERVAL,
+    });
+    assert.strictEqual(watchSettings([], {}, () => true).mode, "poll");
+  });
+
+  it("should read the mode and interval from flags and environment", () => {
+    assert.deepStrictEqual(
+      watchSettings(["--watch-mode", "poll", "--poll-interval", "2500"], {}, noContainer),
+      { mode: "poll", pollInterval: 2500 },
+    );
+    assert.strictEqual(
+      watchSettings([], { O8_WATCH_MODE: "native" }, () => true).mode,
+      "native",
+    );
+  });
+
+  it("should reject invalid modes and intervals", () => {
+    assert.throws(() => watchSettings(["--watch-mode", "inotify"], {}), /--watch-mode/);
+    assert.throws(() => watchSettings(["--poll-interval", "10"], {}), /--poll-interval/);
+  });
+
+  it("should poll and follow symlinks in chokidar", () => {
+    const options = chokidarOptions({ mode: "poll", pollInterval: 2000 });
+    assert.strictEqual(options.usePolling, true);
+    assert.strictEqual(options.interval, 2000);
+    assert.strictEqual(options.followSymlinks, true);
+  });
+
+  it("should poll trees with more directories than the descriptor limit", async () => {
+    const root = await mkdtemp(join(tmpdir(), "o8-watch-"));
+    try {
+      await mkdir(join(root, "a", "b"), { recursive: true });
+      const settings = watchSettings(
+        [],
+        { O8_MAX_WATCH_DESCRIPTORS: "2" },
+        noContainer,
+      );
+      assert.strictEqual(settings.maxDescriptors, 2);
+      assert.strictEqual(settingsForTree(settings, root).mode, "poll");
+      assert.strictEqual(
+        settingsForTree({ ...settings, maxDescriptors: 3 }, root).mode,
+        "native",
+      );
+      assert.throws(
+        () => watchSettings(["--max-watch-descriptors", "0"], {}),
+        /--max-watch-descriptors/,
+      );
+    } finally {
+      await rm(root, { recursive: true, force: true });
+    }
+  });
+});
+
+describe("symlinked resource directories", () => {
+  let root: string;
+
+  before(async () => {
+    root = await mkdtemp(join(tmpdir(), "o8-symlink-"));
+    await mkdir(join(root, "resources", "agents"), { recursive: true });
+    await mkdir(join(root, "team"), { recursive: true });
+    await writeFile(
+      join(root, "team", "go-expert.md"),
+      "---\ntags: [go]\n---\n# Go Expert\n",
+    );
+    await symlink(join(root, "team"), join(root, "resources", "agents", "team"), "dir");
+    // A loop back to the category directory must not be followed
+    await symlink(
+      join(root, "resources", "agents"),
+      join(root, "resources", "agents", "loop"),
+      "dir",
+    );
+    process.env.RESOURCES_PATH = join(root, "resources");
+    process.env.O8_STATE_DIR = join(root, ".state");
+    process.env.O8_LAYERS = "none";
+  });
+
+  after(async () => {
+    delete process.env.O8_STATE_DIR;
+    delete process.env.O8_LAYERS;
+    await rm(root, { recursive: true, force: true });
+  });
+
+  it("should index resources in symlinked directories", async () => {
+    const loader = new ResourceLoader(new Logger("test"));
+    const index = await loader.loadResourceIndex();
+    assert.deepStrictEqual(
+      index.map((fragment) => fragment.id),
+      ["agents/team/go-expert"],
+    );
+  });
+});
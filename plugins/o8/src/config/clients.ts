@@ -0,0 +1,39 @@
+/**
+ * Per-client profiles
+ *
+ * The `clients` section of the configuration maps the clientInfo name a
+ * client sends in `initialize` to a profile, so an interactive desktop
+ * client can get prompts and compact defaults while a headless
+ * orchestrator gets larger default limits and no prompts. Names match
+ * case-insensitively; `"*"` applies to every other client.
+ *
+ * @example
+ * ```json
+ * {
+ *   "clients": {
+ *     "claude-ai": { "prompts": true },
+ *     "team-orchestrator": { "prompts": false, "maxResults": 50, "mode": "catalog" }
+ *   }
+ * }
+ * ```
+ */
+
+import type { ClientProfile } from "./schema.js";
+
+/**
+ * Profile for a client, if one is configured
+ *
+ * @param clients - Configured profiles by client name
+ * @param clientName - clientInfo.name from `initialize`
+ * @returns The matching profile and the key it was configured under
+ */
+export function selectClientProfile(
+  clients: Record<string, ClientProfile>,
+  clientName: string | undefined,
+): { key: string; profile: ClientProfile } | null {
+  const name = clientName?.toLowerCase();
+  const key =
+    Object.keys(clients).find((candidate) => candidate.toLowerCase() === name) ??
+    (Object.hasOwn(clients, "*") ? "*" : undefined);
+  return key === undefined ? null : { key, profile: clients[key] };
+}
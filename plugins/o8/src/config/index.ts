@@ -17,9 +17,13 @@ export {
   type CustomProviderConfig,
   type ResourceProvidersConfig,
   type ProviderDefaultsConfig,
+  type ClientProfile,
   type Config,
 } from "./schema.js";
 
+// Client profile exports
+export { selectClientProfile } from "./clients.js";
+
 // Loader exports
 export {
   ConfigLoader,
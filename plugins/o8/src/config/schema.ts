@@ -96,12 +96,50 @@ const providerDefaultsSchema = z.object({
   retryAttempts: z.number().int().min(0).default(3),
 });
 
+/**
+ * Per-client profile schema, selected by the clientInfo name sent in
+ * `initialize`
+ */
+const clientProfileSchema = z.object({
+  prompts: z.boolean().default(true),
+  maxResults: z.number().int().positive().optional(),
+  maxTokens: z.number().int().positive().optional(),
+  mode: z.enum(["index", "catalog", "full"]).optional(),
+});
+
+/**
+ * Upstream MCP server schema: a command to spawn or a Streamable HTTP URL
+ */
+const upstreamSchema = z
+  .object({
+    command: z.string().min(1).optional(),
+    args: z.array(z.string()).default([]),
+    env: z.record(z.string()).optional(),
+    url: z.string().url().optional(),
+    timeout: z.number().int().positive().default(30000),
+    // Consecutive failures before calls fail fast, and for how long
+    failureThreshold: z.number().int().positive().default(3),
+    resetTimeout: z.number().int().positive().default(30000),
+    // How long tools/list and resources/read results are reused (0: off)
+    cacheTTL: z.number().int().min(0).default(60000),
+    // Serve expired cached reads while the upstream is unreachable
+    serveStale: z.boolean().default(false),
+  })
+  .refine((upstream) => Boolean(upstream.command) !== Boolean(upstream.url), {
+    message: 'An upstream needs either "command" or "url"',
+  });
+
 /**
  * Complete configuration schema
  */
 export const configSchema = z.object({
   resourceProviders: resourceProvidersSchema.default({}),
   providerDefaults: providerDefaultsSchema.default({}),
+  clients: z.record(z.string(), clientProfileSchema).default({}),
+  // Names become tool and URI prefixes, so "__" and "/" are not allowed
+  upstreams: z
+    .record(z.string().regex(/^[A-Za-z][A-Za-z0-9-]*$/), upstreamSchema)
+    .default({}),
 });
 
 /**
@@ -115,6 +153,8 @@ export type CustomEndpointConfig = z.infer<typeof customEndpointSchema>;
 export type CustomProviderConfig = z.infer<typeof customProviderSchema>;
 export type ResourceProvidersConfig = z.infer<typeof resourceProvidersSchema>;
 export type ProviderDefaultsConfig = z.infer<typeof providerDefaultsSchema>;
+export type ClientProfile = z.infer<typeof clientProfileSchema>;
+export type UpstreamConfig = z.infer<typeof upstreamSchema>;
 export type Config = z.infer<typeof configSchema>;
 
 /**
@@ -0,0 +1,122 @@
+/**
+ * Export the catalog to Claude Code skills and slash commands
+ *
+ * Teams that maintain definitions here can publish them to Claude Code
+ * without keeping a second copy by hand. Skills are written as
+ * <name>/SKILL.md folders and commands as <name>.md files; the body is
+ * copied unchanged and the frontmatter is rebuilt from capabilities and
+ * useWhen.
+ *
+ * @example
+ * ```typescript
+ * exportCatalog(fragments, "claude-skills");
+ * // [{ path: "rust-expert/SKILL.md", content: "---\nname: rust-expert\ndescription: ..." }]
+ * ```
+ */
+
+import matter from "gray-matter";
+import type { ResourceFragment } from "../utils/fuzzyMatcher.js";
+
+export const EXPORT_TARGETS = ["claude-skills", "claude-commands"] as const;
+
+export type ExportTarget = (typeof EXPORT_TARGETS)[number];
+
+/** Claude Code limits for skill frontmatter */
+const MAX_NAME_LENGTH = 64;
+const MAX_DESCRIPTION_LENGTH = 1024;
+
+/**
+ * A file to write under the export directory
+ */
+export interface ExportedFile {
+  /** Path relative to the export directory */
+  path: string;
+  content: string;
+  /** Catalog id the file was generated from */
+  id: string;
+}
+
+/**
+ * Convert catalog resources to Claude Code files
+ *
+ * Names come from the last segment of each id; when two resources share
+ * one, the category is appended to keep both.
+ *
+ * @param fragments - Resources to export (with content)
+ * @param target - claude-skills or claude-commands
+ * @returns Files sorted by path
+ * @throws {Error} If the target is unknown
+ */
+export function exportCatalog(
+  fragments: ResourceFragment[],
+  target: string,
+): ExportedFile[] {
+  if (!(EXPORT_TARGETS as readonly string[]).includes(target)) {
+    throw new Error(
+      `Unknown export target: ${target} (expected ${EXPORT_TARGETS.join(", ")})`,
+    );
+  }
+
+  const counts = new Map<string, number>();
+  for (const fragment of fragments) {
+    const name = baseName(fragment);
+    counts.set(name, (counts.get(name) ?? 0) + 1);
+  }
+
+  return fragments
+    .map((fragment) => {
+      const base = baseName(fragment);
+      const name = truncateName(
+        counts.get(base)! > 1 ? `${base}-${fragment.category}` : base,
+      );
+      const body = `\n${fragment.content.trim()}\n`;
+
+      if (target === "claude-commands") {
+        return {
+          id: fragment.id,
+          path: `${name}.md`,
+          content: matter.stringify(body, {
+            description: describe(fragment),
+            ...(fragment.model ? { model: fragment.model } : {}),
+          }),
+        };
+      }
+      return {
+        id: fragment.id,
+        path: `${name}/SKILL.md`,
+        content: matter.stringify(body, {
+          name,
+          description: describe(fragment),
+        }),
+      };
+    })
+    .sort((a, b) => a.path.localeCompare(b.path));
+}
+
+/**
+ * Build a description saying what the resource does and when to use it,
+ * which is what Claude Code reads to decide whether to load a skill
+ */
+function describe(fragment: ResourceFragment): string {
+  const what = fragment.capabilities.slice(0, 3).join("; ");
+  const when = fragment.useWhen.slice(0, 2).join("; ");
+  const description =
+    [what, when && `Use when: ${when}`].filter(Boolean).join(". ") ||
+    fragment.id;
+  return description.length > MAX_DESCRIPTION_LENGTH
+    ? `${description.slice(0, MAX_DESCRIPTION_LENGTH - 3)}...`
+    : description;
+}
+
+function baseName(fragment: ResourceFragment): string {
+  return (
+    (fragment.id.split("/").pop() ?? fragment.id)
+      .toLowerCase()
+      .replace(/[^a-z0-9]+/g, "-")
+      .replace(/^-+|-+$/g, "") || "resource"
+  );
+}
+
+function truncateName(name: string): string {
+  return name.slice(0, MAX_NAME_LENGTH).replace(/-+$/, "");
+}
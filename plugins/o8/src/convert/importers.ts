@@ -0,0 +1,346 @@
+/**
+ * Import agent and skill definitions from other ecosystems
+ *
+ * Converts CrewAI agents.yaml, AutoGen agent JSON, and Claude Code
+ * SKILL.md folders into orchestr8 resource files. Fields with an
+ * orchestr8 equivalent are mapped; everything else is kept as a TODO
+ * comment in the body so nothing is silently dropped.
+ *
+ * @example
+ * ```typescript
+ * const resources = await importDefinitions("crewai", "./my-crew");
+ * // [{ path: "agents/researcher.md", content: "---\nid: researcher\n...", todos: [...] }]
+ * ```
+ */
+
+import { promises as fs } from "fs";
+import { join, relative, sep } from "path";
+import matter from "gray-matter";
+
+export const IMPORT_SOURCES = ["crewai", "autogen", "claude-skills"] as const;
+
+export type ImportSource = (typeof IMPORT_SOURCES)[number];
+
+/**
+ * A converted resource file
+ */
+export interface ImportedResource {
+  /** Path relative to the resources root (e.g. agents/researcher.md) */
+  path: string;
+  /** Markdown with orchestr8 frontmatter */
+  content: string;
+  /** Source fields that could not be mapped */
+  todos: string[];
+  /** Source file the resource came from */
+  source: string;
+}
+
+/**
+ * Error raised for unknown formats or directories without definitions
+ */
+export class ImportError extends Error {
+  public readonly code: "UNKNOWN_FORMAT" | "NO_DEFINITIONS";
+
+  constructor(code: "UNKNOWN_FORMAT" | "NO_DEFINITIONS", message: string) {
+    super(message);
+    this.name = "ImportError";
+    this.code = code;
+  }
+}
+
+/**
+ * Fields of an orchestr8 resource before rendering
+ */
+interface Draft {
+  id: string;
+  category: "agent" | "skill";
+  title: string;
+  tags?: string[];
+  capabilities?: string[];
+  useWhen?: string[];
+  model?: string;
+  sections: Array<{ heading?: string; text: string }>;
+  /** Unmapped source fields and their values */
+  unmapped: Record<string, unknown>;
+}
+
+/**
+ * Convert every definition found in a directory
+ *
+ * @param from - Source ecosystem
+ * @param dir - Directory containing the source definitions
+ * @returns Converted resources, sorted by path
+ * @throws {ImportError} If the format is unknown or nothing was found
+ */
+export async function importDefinitions(
+  from: string,
+  dir: string,
+): Promise<ImportedResource[]> {
+  let drafts: Array<{ draft: Draft; source: string }>;
+  switch (from) {
+    case "crewai":
+      drafts = await readCrewAI(dir);
+      break;
+    case "autogen":
+      drafts = await readAutoGen(dir);
+      break;
+    case "claude-skills":
+      drafts = await readClaudeSkills(dir);
+      break;
+    default:
+      throw new ImportError(
+        "UNKNOWN_FORMAT",
+        `Unknown import format: ${from} (expected ${IMPORT_SOURCES.join(", ")})`,
+      );
+  }
+
+  if (drafts.length === 0) {
+    throw new ImportError(
+      "NO_DEFINITIONS",
+      `No ${from} definitions found in ${dir}`,
+    );
+  }
+
+  return drafts
+    .map(({ draft, source }) => ({ ...render(draft, from), source }))
+    .sort((a, b) => a.path.localeCompare(b.path));
+}
+
+/**
+ * CrewAI: agents.yaml (or config/agents.yaml) mapping names to
+ * role, goal, backstory, llm, and tool settings
+ */
+async function readCrewAI(
+  dir: string,
+): Promise<Array<{ draft: Draft; source: string }>> {
+  const source = await firstExisting([
+    join(dir, "agents.yaml"),
+    join(dir, "agents.yml"),
+    join(dir, "config", "agents.yaml"),
+    join(dir, "config", "agents.yml"),
+  ]);
+  if (!source) {
+    return [];
+  }
+
+  const agents = parseYaml(await fs.readFile(source, "utf-8"));
+  return Object.entries(agents).map(([name, value]): {
+    draft: Draft;
+    source: string;
+  } => {
+    const { role, goal, backstory, llm, ...rest } = asRecord(value);
+    const sections: Draft["sections"] = [];
+    if (goal) sections.push({ heading: "Goal", text: String(goal).trim() });
+    if (backstory) {
+      sections.push({ heading: "Backstory", text: String(backstory).trim() });
+    }
+    return {
+      source,
+      draft: {
+        id: slugify(name),
+        category: "agent",
+        title: role ? String(role).trim() : name,
+        capabilities: goal ? [oneLine(goal)] : undefined,
+        model: typeof llm === "string" ? llm : undefined,
+        sections,
+        unmapped: llm && typeof llm !== "string" ? { llm, ...rest } : rest,
+      },
+    };
+  });
+}
+
+/**
+ * AutoGen: agent JSON files, either AutoGen Studio component exports
+ * ({ config: { name, system_message, model_client } }) or the older
+ * { name, system_message, llm_config } shape
+ */
+async function readAutoGen(
+  dir: string,
+): Promise<Array<{ draft: Draft; source: string }>> {
+  const drafts: Array<{ draft: Draft; source: string }> = [];
+  for (const source of await listFiles(dir, (name) => name.endsWith(".json"))) {
+    let json: unknown;
+    try {
+      json = JSON.parse(await fs.readFile(source, "utf-8"));
+    } catch {
+      continue;
+    }
+    const outer = asRecord(json);
+    const config = outer.config ? asRecord(outer.config) : outer;
+    const {
+      name,
+      system_message: systemMessage,
+      description,
+      model_client: modelClient,
+      llm_config: llmConfig,
+      ...rest
+    } = config;
+    if (!name && !outer.label) {
+      continue;
+    }
+
+    const id = String(name ?? outer.label);
+    drafts.push({
+      source,
+      draft: {
+        id: slugify(id),
+        category: "agent",
+        title: String(outer.label ?? name),
+        useWhen: description || outer.description
+          ? [oneLine(description ?? outer.description)]
+          : undefined,
+        model: autoGenModel(modelClient, llmConfig),
+        sections: systemMessage
+          ? [{ text: String(systemMessage).trim() }]
+          : [],
+        unmapped: rest,
+      },
+    });
+  }
+  return drafts;
+}
+
+/**
+ * Claude Code skills: <name>/SKILL.md with name and description
+ * frontmatter
+ */
+async function readClaudeSkills(
+  dir: string,
+): Promise<Array<{ draft: Draft; source: string }>> {
+  const drafts: Array<{ draft: Draft; source: string }> = [];
+  for (const source of await listFiles(dir, (name) => name === "SKILL.md")) {
+    const parsed = matter(await fs.readFile(source, "utf-8"));
+    const { name, description, ...rest } = asRecord(parsed.data);
+    const folder = relative(dir, source).split(sep).slice(-2, -1)[0];
+    const id = String(name ?? folder ?? "skill");
+    // Reuse the body's own heading as the title instead of repeating it
+    const heading = /^#\s+(.+)\n*/.exec(parsed.content.trim());
+    const text = heading
+      ? parsed.content.trim().slice(heading[0].length)
+      : parsed.content.trim();
+
+    drafts.push({
+      source,
+      draft: {
+        id: slugify(id),
+        category: "skill",
+        title: heading ? heading[1].trim() : id,
+        useWhen: description ? [oneLine(description)] : undefined,
+        sections: text ? [{ text }] : [],
+        unmapped: rest,
+      },
+    });
+  }
+  return drafts;
+}
+
+/**
+ * Render a draft as an orchestr8 markdown file
+ */
+function render(
+  draft: Draft,
+  from: string,
+): Omit<ImportedResource, "source"> {
+  const todos = Object.entries(draft.unmapped)
+    .filter(([, value]) => value !== undefined)
+    .map(
+      ([field, value]) =>
+        `${from} field "${field}" has no orchestr8 equivalent: ${JSON.stringify(value).replace(/-->/g, "--\\>")}`,
+    );
+  if (!draft.tags?.length) {
+    todos.push("add tags so the resource can be matched");
+  }
+  if (!draft.useWhen?.length) {
+    todos.push("add useWhen scenarios");
+  }
+
+  const body = [
+    `# ${draft.title}`,
+    ...todos.map((todo) => `<!-- TODO(import): ${todo} -->`),
+    ...draft.sections.map(({ heading, text }) =>
+      heading ? `## ${heading}\n\n${text}` : text,
+    ),
+  ].join("\n\n");
+
+  const frontmatter: Record<string, unknown> = {
+    id: draft.id,
+    category: draft.category,
+    tags: draft.tags ?? [],
+    capabilities: draft.capabilities ?? [],
+    useWhen: draft.useWhen ?? [],
+    ...(draft.model ? { model: draft.model } : {}),
+    estimatedTokens: Math.ceil(body.length / 4),
+  };
+
+  return {
+    path: `${draft.category}s/${draft.id}.md`,
+    content: matter.stringify(`\n${body}\n`, frontmatter),
+    todos,
+  };
+}
+
+function autoGenModel(modelClient: unknown, llmConfig: unknown): string | undefined {
+  const client = asRecord(modelClient);
+  const clientModel = asRecord(client.config).model;
+  if (typeof clientModel === "string") {
+    return clientModel;
+  }
+  const llm = asRecord(llmConfig);
+  if (typeof llm.model === "string") {
+    return llm.model;
+  }
+  const [first] = Array.isArray(llm.config_list) ? llm.config_list : [];
+  const listModel = asRecord(first).model;
+  return typeof listModel === "string" ? listModel : undefined;
+}
+
+function parseYaml(text: string): Record<string, unknown> {
+  return asRecord(matter(`---\n${text}\n---\n`).data);
+}
+
+function asRecord(value: unknown): Record<string, any> {
+  return value && typeof value === "object" && !Array.isArray(value)
+    ? (value as Record<string, any>)
+    : {};
+}
+
+function oneLine(value: unknown): string {
+  return String(value).replace(/\s+/g, " ").trim();
+}
+
+function slugify(name: string): string {
+  return (
+    name
+      .toLowerCase()
+      .replace(/[^a-z0-9]+/g, "-")
+      .replace(/^-+|-+$/g, "") || "imported"
+  );
+}
+
+async function firstExisting(paths: string[]): Promise<string | undefined> {
+  for (const path of paths) {
+    try {
+      await fs.access(path);
+      return path;
+    } catch {
+      // Try the next candidate
+    }
+  }
+  return undefined;
+}
+
+async function listFiles(
+  dir: string,
+  include: (name: string) => boolean,
+): Promise<string[]> {
+  const files: string[] = [];
+  for (const entry of await fs.readdir(dir, { withFileTypes: true })) {
+    const path = join(dir, entry.name);
+    if (entry.isDirectory() && !entry.name.startsWith(".")) {
+      files.push(...(await listFiles(path, include)));
+    } else if (entry.isFile() && include(entry.name)) {
+      files.push(path);
+    }
+  }
+  return files.sort();
+}
@@ -0,0 +1,19 @@
+/**
+ * Environment loading
+ *
+ * Imported first by the entry point so .env values are in place before any
+ * other module reads process.env at load time.
+ */
+
+import dotenv from "dotenv";
+
+// Load environment variables (suppress output in test mode to avoid stdout pollution)
+if (process.env.NODE_ENV !== "test") {
+  dotenv.config();
+} else {
+  // In test mode, load silently by capturing stdout
+  const originalWrite = process.stdout.write;
+  process.stdout.write = () => true;
+  dotenv.config();
+  process.stdout.write = originalWrite;
+}
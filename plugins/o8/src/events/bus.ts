@@ -0,0 +1,74 @@
+/**
+ * Server Event Bus
+ *
+ * Publishes server lifecycle events (index loads, resource invalidation,
+ * validation failures) to in-process subscribers such as the webhook sink
+ * and the stats activity log.
+ *
+ * @module events/bus
+ */
+
+import { EventEmitter } from "events";
+
+/**
+ * Server event types
+ */
+export type ServerEventType =
+  | "server_started"
+  | "index_loaded"
+  | "index_stalled"
+  | "resource_invalidated"
+  | "validation_failed";
+
+/**
+ * Event published on the server event bus
+ */
+export interface ServerEvent {
+  /** Event type */
+  type: ServerEventType;
+  /** Event timestamp (ms since epoch) */
+  timestamp: number;
+  /** Event payload */
+  data: Record<string, any>;
+}
+
+/**
+ * Server event bus
+ *
+ * Emits each event under its own type and under the catch-all "event" name.
+ *
+ * @example
+ * ```typescript
+ * const bus = new ServerEventBus();
+ * bus.on("event", (event) => console.error(event.type));
+ * bus.publish("index_loaded", { fragments: 383 });
+ * ```
+ */
+export class ServerEventBus extends EventEmitter {
+  /**
+   * Publish an event to all subscribers
+   *
+   * @param type - Event type
+   * @param data - Event payload
+   * @returns The published event
+   */
+  publish(type: ServerEventType, data: Record<string, any> = {}): ServerEvent {
+    const event: ServerEvent = { type, timestamp: Date.now(), data };
+    this.emit(type, event);
+    this.emit("event", event);
+    return event;
+  }
+
+  /**
+   * Subscribe to every event
+   *
+   * @param listener - Called for each published event
+   * @returns Function that removes the subscription
+   */
+  subscribe(listener: (event: ServerEvent) => void): () => void {
+    this.on("event", listener);
+    return () => {
+      this.off("event", listener);
+    };
+  }
+}
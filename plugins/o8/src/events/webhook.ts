@@ -0,0 +1,133 @@
+/**
+ * Webhook Sink
+ *
+ * POSTs server events as JSON to configured webhook URLs so external
+ * systems (chat bots, CI) can react to orchestr8 activity.
+ *
+ * @module events/webhook
+ */
+
+import { Logger } from "../utils/logger.js";
+import type { ServerEvent, ServerEventBus, ServerEventType } from "./bus.js";
+
+/**
+ * Webhook sink configuration
+ */
+export interface WebhookConfig {
+  /** Webhook URLs to POST events to */
+  urls: string[];
+  /** Only deliver these event types (default: all) */
+  events?: ServerEventType[];
+  /** Request timeout in milliseconds (default: 5000) */
+  timeout?: number;
+}
+
+/**
+ * Delivers server events to webhook URLs
+ *
+ * Delivery is fire-and-forget: failures are logged and never propagate
+ * back to the code that published the event.
+ */
+export class WebhookSink {
+  private config: Required<Omit<WebhookConfig, "events">> & {
+    events?: ServerEventType[];
+  };
+  private logger: Logger;
+  private unsubscribe: (() => void) | null = null;
+
+  constructor(config: WebhookConfig, logger: Logger) {
+    this.config = {
+      urls: config.urls,
+      events: config.events,
+      timeout: config.timeout ?? 5000,
+    };
+    this.logger = logger;
+  }
+
+  /**
+   * Build a sink from O8_WEBHOOK_URL / O8_WEBHOOK_EVENTS
+   *
+   * @returns WebhookSink, or null if no webhook URL is configured
+   */
+  static fromEnv(logger: Logger): WebhookSink | null {
+    const urls = (process.env.O8_WEBHOOK_URL || "")
+      .split(",")
+      .map((url) => url.trim())
+      .filter(Boolean);
+
+    if (urls.length === 0) {
+      return null;
+    }
+
+    const events = process.env.O8_WEBHOOK_EVENTS
+      ? (process.env.O8_WEBHOOK_EVENTS.split(",")
+          .map((event) => event.trim())
+          .filter(Boolean) as ServerEventType[])
+      : undefined;
+
+    return new WebhookSink({ urls, events }, logger);
+  }
+
+  /**
+   * Start delivering events published on the bus
+   */
+  attach(bus: ServerEventBus): void {
+    this.detach();
+    this.unsubscribe = bus.subscribe((event) => {
+      if (this.config.events && !this.config.events.includes(event.type)) {
+        return;
+      }
+      void this.deliver(event);
+    });
+  }
+
+  /**
+   * Stop delivering events
+   */
+  detach(): void {
+    if (this.unsubscribe) {
+      this.unsubscribe();
+      this.unsubscribe = null;
+    }
+  }
+
+  /**
+   * POST an event to every configured URL
+   *
+   * @returns Number of successful deliveries
+   */
+  async deliver(event: ServerEvent): Promise<number> {
+    const body = JSON.stringify({ source: "orchestr8", ...event });
+
+    const results = await Promise.all(
+      this.config.urls.map(async (url) => {
+        const controller = new AbortController();
+        const timer = setTimeout(() => controller.abort(), this.config.timeout);
+        try {
+          const response = await fetch(url, {
+            method: "POST",
+            headers: { "Content-Type": "application/json" },
+            body,
+            signal: controller.signal,
+          });
+          if (!response.ok) {
+            this.logger.warn(
+              `Webhook ${url} rejected ${event.type} event: HTTP ${response.status}`,
+            );
+            return false;
+          }
+          return true;
+        } catch (error: any) {
+          this.logger.warn(
+            `Webhook ${url} failed for ${event.type} event: ${error.message}`,
+          );
+          return false;
+        } finally {
+          clearTimeout(timer);
+        }
+      }),
+    );
+
+    return results.filter(Boolean).length;
+  }
+}
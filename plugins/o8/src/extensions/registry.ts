@@ -0,0 +1,189 @@
+/**
+ * Extension Registry
+ *
+ * Lets downstream users add custom request methods (e.g. company-specific
+ * resource scoring) without forking the server. Extensions are ES modules
+ * listed in O8_EXTENSIONS and are loaded once at startup.
+ *
+ * @module extensions/registry
+ *
+ * @example Extension module (acme-scoring.mjs)
+ * ```javascript
+ * export default {
+ *   name: "acme",
+ *   methods: {
+ *     async score(params, ctx) {
+ *       const index = await ctx.getResourceIndex();
+ *       return index.filter((r) => r.tags.includes(params.tag)).length;
+ *     },
+ *   },
+ * };
+ * ```
+ * Methods are exposed namespaced by extension name: `acme/score`.
+ */
+
+import { resolve } from "path";
+import { pathToFileURL } from "url";
+import { Logger } from "../utils/logger.js";
+import type { ResourceFragment } from "../utils/fuzzyMatcher.js";
+
+/**
+ * Server capabilities available to extension methods
+ */
+export interface ExtensionContext {
+  logger: Logger;
+  getResourceIndex(): Promise<ResourceFragment[]>;
+  getResourceContent(uri: string): Promise<string>;
+}
+
+/**
+ * Handler for a custom request method
+ */
+export type MethodHandler = (
+  params: any,
+  context: ExtensionContext,
+) => Promise<any> | any;
+
+/**
+ * Extension module contract
+ */
+export interface Extension {
+  /** Extension name, used as the method namespace */
+  name: string;
+  /** Methods keyed by name (exposed as `<extension>/<method>`) */
+  methods: Record<string, MethodHandler>;
+}
+
+/**
+ * Error raised for unknown methods or invalid extensions
+ */
+export class ExtensionError extends Error {
+  constructor(
+    message: string,
+    public code: "METHOD_NOT_FOUND" | "INVALID_EXTENSION" | "DUPLICATE_METHOD",
+  ) {
+    super(message);
+    this.name = "ExtensionError";
+  }
+}
+
+/**
+ * Registry of extension-provided methods
+ */
+export class ExtensionRegistry {
+  private handlers: Map<string, MethodHandler> = new Map();
+  private extensions: string[] = [];
+  private context: ExtensionContext;
+
+  constructor(context: ExtensionContext) {
+    this.context = context;
+  }
+
+  /**
+   * Register an extension's methods
+   *
+   * @throws {ExtensionError} If the extension is malformed or a method name collides
+   */
+  register(extension: Extension): void {
+    if (
+      !extension ||
+      typeof extension.name !== "string" ||
+      !/^[\w-]+$/.test(extension.name) ||
+      typeof extension.methods !== "object" ||
+      extension.methods === null
+    ) {
+      throw new ExtensionError(
+        "Extension must export { name, methods } with a name of letters, digits, '-' or '_'",
+        "INVALID_EXTENSION",
+      );
+    }
+
+    const entries = Object.entries(extension.methods);
+    for (const [method, handler] of entries) {
+      const qualified = `${extension.name}/${method}`;
+      if (typeof handler !== "function") {
+        throw new ExtensionError(
+          `Extension method ${qualified} is not a function`,
+          "INVALID_EXTENSION",
+        );
+      }
+      if (this.handlers.has(qualified)) {
+        throw new ExtensionError(
+          `Extension method ${qualified} is already registered`,
+          "DUPLICATE_METHOD",
+        );
+      }
+    }
+
+    for (const [method, handler] of entries) {
+      this.handlers.set(`${extension.name}/${method}`, handler);
+    }
+    this.extensions.push(extension.name);
+    this.context.logger.info(
+      `Registered extension ${extension.name} with ${entries.length} methods`,
+    );
+  }
+
+  /**
+   * Load extension modules from file paths
+   *
+   * Modules may use a default export or a named `extension` export.
+   * A module that fails to load is logged and skipped.
+   *
+   * @param paths - Module paths (relative paths resolve against the working directory)
+   * @returns Names of extensions that loaded successfully
+   */
+  async loadFromPaths(paths: string[]): Promise<string[]> {
+    const loaded: string[] = [];
+
+    for (const modulePath of paths) {
+      try {
+        const mod = await import(pathToFileURL(resolve(modulePath)).href);
+        const extension: Extension = mod.default ?? mod.extension;
+        this.register(extension);
+        loaded.push(extension.name);
+      } catch (error) {
+        this.context.logger.error(
+          `Failed to load extension ${modulePath}:`,
+          error,
+        );
+      }
+    }
+
+    return loaded;
+  }
+
+  /**
+   * Check if a method is registered
+   */
+  has(method: string): boolean {
+    return this.handlers.has(method);
+  }
+
+  /**
+   * List registered method names
+   */
+  listMethods(): string[] {
+    return Array.from(this.handlers.keys()).sort();
+  }
+
+  /**
+   * List loaded extension names
+   */
+  listExtensions(): string[] {
+    return [...this.extensions];
+  }
+
+  /**
+   * Invoke a registered method
+   *
+   * @throws {ExtensionError} If the method is not registered
+   */
+  async dispatch(method: string, params: any): Promise<any> {
+    const handler = this.handlers.get(method);
+    if (!handler) {
+      throw new ExtensionError(`Unknown method: ${method}`, "METHOD_NOT_FOUND");
+    }
+    return handler(params ?? {}, this.context);
+  }
+}
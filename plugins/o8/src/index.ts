@@ -1,796 +1,478 @@
 #!/usr/bin/env node
 
-import {
-  McpServer,
-  ResourceTemplate,
-} from "@modelcontextprotocol/sdk/server/mcp.js";
+import "./env.js";
+import { Orchestr8Server, SERVER_VERSION } from "./server.js";
+import { readSession, replaySession } from "./session/recorder.js";
+import { createMockServer, loadFixtures } from "./session/mockServer.js";
 import { StdioServerTransport } from "@modelcontextprotocol/sdk/server/stdio.js";
-import { PromptLoader } from "./loaders/promptLoader.js";
+import { importDefinitions, IMPORT_SOURCES } from "./convert/importers.js";
+import { exportCatalog, EXPORT_TARGETS } from "./convert/exporters.js";
 import { ResourceLoader } from "./loaders/resourceLoader.js";
+import {
+  renderError,
+  renderReport,
+  takeFormat,
+  type CommandReport,
+} from "./utils/cliOutput.js";
+import { flagEnabled, flagValue, parseArgs } from "./utils/flags.js";
+import { runDoctor } from "./utils/doctor.js";
+import { installCrashHandler } from "./state/crashReport.js";
+import { defaultStateDir } from "./state/snapshotStore.js";
+import { applyAutoConfig } from "./utils/autoDiscovery.js";
+import { buildInfo, formatBuildInfo } from "./utils/buildInfo.js";
 import { Logger } from "./utils/logger.js";
-import { StatsCollector } from "./stats/collector.js";
-import { HTTPTransport } from "./transports/http.js";
-import { createTokenSystem } from "./token/index.js";
-import type { TokenTracker } from "./token/tracker.js";
-import type { TokenStore } from "./token/store.js";
-import type { TokenMetrics } from "./token/metrics.js";
-import type { EfficiencyEngine } from "./token/efficiency.js";
-import { z } from "zod";
-import dotenv from "dotenv";
-import path from "path";
-import { fileURLToPath } from "url";
-
-// Load environment variables (suppress output in test mode to avoid stdout pollution)
-if (process.env.NODE_ENV !== "test") {
-  dotenv.config();
-} else {
-  // In test mode, load silently by capturing stdout
-  const originalWrite = process.stdout.write;
-  process.stdout.write = () => true;
-  dotenv.config();
-  process.stdout.write = originalWrite;
-}
-
-// Get current file directory for static files
-const __filename = fileURLToPath(import.meta.url);
-const __dirname = path.dirname(__filename);
+import { promises as fs } from "fs";
+import { userInfo } from "os";
+import { dirname, join, resolve } from "path";
 
 // Initialize logger (writes to stderr to avoid corrupting MCP protocol)
 const logger = new Logger("orchestr8-mcp");
 
-// Check if HTTP mode is enabled via environment variable
-const HTTP_MODE =
-  process.env.O8_HTTP === "true" || process.env.O8_HTTP === "1";
-const HTTP_PORT = parseInt(process.env.O8_HTTP_PORT || "1337", 10);
-
-class Orchestr8Server {
-  private server: McpServer;
-  private promptLoader: PromptLoader;
-  private resourceLoader: ResourceLoader;
-  private stats: StatsCollector;
-  private httpTransport: HTTPTransport | null = null;
-
-  // Token tracking system
-  private tokenTracker!: TokenTracker;
-  private tokenStore!: TokenStore;
-  private tokenMetrics!: TokenMetrics;
-  private efficiencyEngine!: EfficiencyEngine;
-
-  constructor() {
-    this.server = new McpServer({
-      name: "o8",
-      version: "1.0.0",
-    });
-    this.promptLoader = new PromptLoader(logger);
-    // ResourceLoader and StatsCollector will be initialized in initialize() with token system
-    this.resourceLoader = null as any; // Temporary
-    this.stats = null as any; // Temporary - will be initialized with token metrics
-  }
-
-  async initialize(): Promise<void> {
-    logger.info("Starting orchestr8 MCP server v1.0.0");
-
-    // ============================================================================
-    // NEW: Initialize token tracking system
-    // ============================================================================
-    logger.info("Initializing token efficiency monitoring...");
-    const tokenSystem = createTokenSystem({
-      tracking: {
-        enabled: true,
-        baselineStrategy: "no_jit",
-        deduplication: true,
-        retentionDays: 7,
-        enableTrends: true,
-      },
-      storage: {
-        maxRecords: 10000,
-        retentionDays: 7,
-        autoCleanup: true,
-        cleanupIntervalMs: 60 * 60 * 1000, // 1 hour
-      },
-    });
-
-    this.tokenTracker = tokenSystem.tracker;
-    this.tokenStore = tokenSystem.store;
-    this.tokenMetrics = tokenSystem.metrics;
-    this.efficiencyEngine = tokenSystem.efficiency;
-
-    logger.info("Token efficiency monitoring initialized");
-
-    // Initialize StatsCollector with token metrics
-    this.stats = new StatsCollector(this.tokenMetrics);
-
-    // Initialize ResourceLoader with token system
-    this.resourceLoader = new ResourceLoader(
-      logger,
-      this.tokenTracker,
-      this.tokenStore,
-    );
-
-    // Load all prompts and resources
-    const prompts = await this.promptLoader.loadAllPrompts();
-    const resources = await this.resourceLoader.loadAllResources();
-
-    logger.info(`Loaded ${prompts.length} prompts`);
-    logger.info(`Loaded ${resources.length} resources`);
-
-    // Pre-load resource index for faster first query
-    try {
-      await this.resourceLoader.loadResourceIndex();
-      logger.info("Resource index pre-loaded for dynamic matching");
-    } catch (error) {
-      logger.warn(
-        "Failed to pre-load resource index, will load on-demand:",
-        error,
-      );
-    }
-
-    // ============================================================================
-    // NEW: Initialize provider system
-    // ============================================================================
-    try {
-      await this.resourceLoader.initializeProviders();
-      logger.info("Provider system initialized successfully");
-    } catch (error) {
-      logger.error("Failed to initialize provider system:", error);
-      // Don't fail startup - continue with local resources only
-      logger.warn("Continuing with local resources only");
-    }
-
-    // Register prompts
-    this.registerPrompts(prompts);
-
-    // Register resources
-    this.registerResources(resources);
-
-    // Register dynamic resource templates
-    this.registerDynamicTemplates();
+// Check if HTTP mode is enabled (--http or O8_HTTP)
+const HTTP_MODE = flagEnabled("http");
+
+// Serve canned responses from a fixtures file (--mock or ORCHESTR8_MOCK)
+const MOCK_FIXTURES = flagValue("mock");
+
+/**
+ * Serve fixtures over stdio instead of the resource catalog
+ */
+async function mock(fixturesPath: string) {
+  const fixtures = await loadFixtures(fixturesPath);
+  const server = createMockServer(fixtures);
+  await server.connect(new StdioServerTransport());
+  logger.info(
+    `Mock server answering ${Object.keys(fixtures.methods).length} methods from ${fixturesPath}`,
+  );
+}
 
-    // Set up hot reload in development
-    if (process.env.NODE_ENV !== "production") {
-      this.promptLoader.watchForChanges(() => {
-        logger.info("Prompts changed - restart server to reload");
-      });
-    }
+async function main() {
+  if (MOCK_FIXTURES) {
+    return mock(MOCK_FIXTURES);
   }
+  try {
+    const orchestr8 = new Orchestr8Server();
+    let mode: string | null = null;
+    installCrashHandler(defaultStateDir(), () => orchestr8.getCrashContext(mode));
+    await orchestr8.initialize();
 
-  private registerPrompts(prompts: any[]): void {
-    for (const prompt of prompts) {
-      // Convert prompt arguments to Zod schema
-      const argsSchema: any = {};
-      if (prompt.arguments && prompt.arguments.length > 0) {
-        for (const arg of prompt.arguments) {
-          const zodType = arg.required ? z.string() : z.string().optional();
-          argsSchema[arg.name] = zodType;
-        }
-      }
-
-      this.server.registerPrompt(
-        prompt.name,
-        {
-          title: prompt.title,
-          description: prompt.description,
-          argsSchema:
-            Object.keys(argsSchema).length > 0 ? argsSchema : undefined,
-        },
-        async (args: any, _extra: any) => {
-          const startTime = Date.now();
-          logger.debug(`Loading prompt: ${prompt.name}`);
-
-          // Track MCP prompt request
-          this.stats.logActivity("prompt_get", {
-            name: prompt.name,
-            args: args,
-          });
-
-          try {
-            // Convert args to the format expected by loader
-            const argValues: Record<string, any> = args || {};
-
-            // Load prompt content with argument substitution
-            const content = await this.promptLoader.loadPromptContent(
-              prompt,
-              argValues,
-            );
-
-            const latency = Date.now() - startTime;
-            this.stats.trackRequest(`prompt:${prompt.name}`, latency);
-
-            return {
-              messages: [
-                {
-                  role: "user" as const,
-                  content: {
-                    type: "text" as const,
-                    text: content,
-                  },
-                },
-              ],
-            };
-          } catch (error) {
-            this.stats.trackError(error);
-            throw error;
-          }
-        },
-      );
+    // Determine which mode to run in
+    if (HTTP_MODE) {
+      // HTTP only mode (for development/testing)
+      await orchestr8.startHttp();
+      mode = "http";
+    } else if (process.env.NODE_ENV === "test") {
+      // Test mode: stdio only (no HTTP to avoid stdout pollution)
+      await orchestr8.startStdio();
+      mode = "stdio";
+    } else {
+      // Default: Dual mode (stdio for Claude + HTTP for web UI)
+      await orchestr8.startDual();
+      mode = "dual";
     }
-  }
-
-  private registerResources(resources: any[]): void {
-    // Register lightweight resource registry for discovery
-    this.registerResourceRegistry(resources);
+    orchestr8.publishEvent("server_started", { mode });
 
-    // Register aggregate list resources for each category
-    this.registerAggregateListResources(resources);
+    // Handle graceful shutdown
+    const shutdown = async () => {
+      await orchestr8.shutdown();
+      process.exit(0);
+    };
 
-    // Register individual resources so they appear in resources/list
-    this.registerIndividualResources(resources);
+    process.on("SIGINT", shutdown);
+    process.on("SIGTERM", shutdown);
+  } catch (error) {
+    logger.error("Failed to start server:", error);
+    process.exit(1);
   }
+}
 
-  private registerResourceRegistry(resources: any[]): void {
-    this.server.registerResource(
-      "resource-registry",
-      "o8://registry",
-      {
-        mimeType: "application/json",
-        description: "Lightweight resource catalog for discovery",
-      },
-      async () => {
-        // Track MCP registry request
-        this.stats.logActivity("resource_read", {
-          uri: "o8://registry",
-          category: "registry",
-        });
-
-        const catalog = {
-          version: "1.0.0",
-          totalResources: resources.length,
-          categories: {
-            agents: resources.filter((r) => r.category === "agents").length,
-            skills: resources.filter((r) => r.category === "skills").length,
-            patterns: resources.filter((r) => r.category === "patterns").length,
-            examples: resources.filter((r) => r.category === "examples").length,
-            guides: resources.filter((r) => r.category === "guides").length,
-            workflows: resources.filter((r) => r.category === "workflows")
-              .length,
-          },
-          searchUri:
-            "o8://match?query=<keywords>&mode=index&maxResults=5",
-          usage:
-            "Use o8://match?query=... for resource discovery. Default mode is 'index' for optimal efficiency.",
-        };
-
-        return {
-          contents: [
-            {
-              uri: "o8://registry",
-              mimeType: "application/json",
-              text: JSON.stringify(catalog, null, 2),
-            },
-          ],
-        };
-      },
-    );
+/**
+ * Result of a subcommand and the process exit code
+ */
+interface SubcommandResult {
+  report: CommandReport;
+  exitCode: number;
+}
 
-    logger.debug(
-      `Registered resource registry with ${resources.length} total resources`,
+/**
+ * Replay a recorded session against this build and report differences
+ */
+async function replay(args: string[]): Promise<SubcommandResult> {
+  const [sessionPath] = args;
+  if (!sessionPath) {
+    process.stderr.write(
+      "Usage: orchestr8 replay <session.jsonl> [--format text|json|github]\n",
     );
+    process.exit(2);
   }
 
-  private registerAggregateListResources(resources: any[]): void {
-    // Group resources by category
-    const byCategory = resources.reduce(
-      (acc, resource) => {
-        const category = resource.category || "other";
-        if (!acc[category]) {
-          acc[category] = [];
-        }
-        acc[category].push(resource);
-        return acc;
-      },
-      {} as Record<string, any[]>,
-    );
-
-    // Register aggregate list resource for each category
-    const categories = [
-      {
-        name: "workflows",
-        description: "Complete list of all available workflows",
-      },
-      { name: "agents", description: "Complete list of all available agents" },
-      { name: "skills", description: "Complete list of all available skills" },
-      {
-        name: "patterns",
-        description: "Complete list of all available patterns",
-      },
-      {
-        name: "examples",
-        description: "Complete list of all available examples",
-      },
-      { name: "guides", description: "Complete list of all available guides" },
-    ];
-
-    for (const { name, description } of categories) {
-      const categoryResources = byCategory[name] || [];
-      const aggregateUri = `o8://${name}`;
-
-      this.server.registerResource(
-        `${name}-list`,
-        aggregateUri,
-        {
-          mimeType: "application/json",
-          description: description,
-        },
-        async (uri) => {
-          const startTime = Date.now();
-          logger.debug(`Loading aggregate list: ${aggregateUri}`);
-
-          // Track MCP resource list request
-          this.stats.logActivity("resources_list", {
-            category: name,
-            count: categoryResources.length,
-          });
-
-          try {
-            // Build JSON list of all resources in this category
-            const resourceList = categoryResources.map((r: any) => ({
-              name: r.name,
-              uri: r.uri,
-              description: r.description,
-              mimeType: r.mimeType,
-            }));
-
-            const jsonContent = JSON.stringify(
-              {
-                category: name,
-                count: resourceList.length,
-                resources: resourceList,
-              },
-              null,
-              2,
-            );
-
-            const latency = Date.now() - startTime;
-            this.stats.trackRequest(`aggregate:${name}`, latency);
-
-            return {
-              contents: [
-                {
-                  uri: uri.toString(),
-                  mimeType: "application/json",
-                  text: jsonContent,
-                },
-              ],
-            };
-          } catch (error) {
-            this.stats.trackError(error);
-            throw error;
-          }
-        },
-      );
+  const orchestr8 = new Orchestr8Server({ watch: false });
+  await orchestr8.initialize();
+  await orchestr8.whenCatalogReady();
+
+  const session = await readSession(sessionPath);
+  const result = await replaySession(session, (transport) =>
+    orchestr8.connect(transport),
+  );
+  await orchestr8.shutdown();
+
+  return {
+    report: {
+      lines: result.mismatches.map(
+        (mismatch) =>
+          `MISMATCH ${mismatch.method} (id ${mismatch.id})\n` +
+          `  expected: ${JSON.stringify(mismatch.expected)}\n` +
+          `  actual:   ${JSON.stringify(mismatch.actual)}`,
+      ),
+      summary: `Replayed ${result.total} requests: ${result.matched} matched, ${result.mismatches.length} differ`,
+      findings: result.mismatches.map((mismatch) => ({
+        level: "error" as const,
+        file: sessionPath,
+        title: `Replay mismatch: ${mismatch.method} (id ${mismatch.id})`,
+        message: `expected ${JSON.stringify(mismatch.expected)}, got ${JSON.stringify(mismatch.actual)}`,
+      })),
+      data: { ...result },
+    },
+    exitCode: result.mismatches.length > 0 ? 1 : 0,
+  };
+}
 
-      logger.debug(`Registered aggregate list resource: ${aggregateUri}`);
+/**
+ * Write generated files, skipping existing ones unless forced
+ *
+ * @returns Paths written and skipped, relative to the output directory
+ */
+async function writeFiles(
+  out: string,
+  files: Array<{ path: string; content: string }>,
+  force: boolean,
+): Promise<{ written: string[]; skipped: string[] }> {
+  const written: string[] = [];
+  const skipped: string[] = [];
+  for (const file of files) {
+    const target = join(resolve(out), file.path);
+    if (!force) {
+      try {
+        await fs.access(target);
+        skipped.push(file.path);
+        continue;
+      } catch {
+        // Not there yet
+      }
     }
+    await fs.mkdir(dirname(target), { recursive: true });
+    await fs.writeFile(target, file.content);
+    written.push(file.path);
   }
+  return { written, skipped };
+}
 
-  private registerIndividualResources(resources: any[]): void {
-    logger.info(
-      `Registering ${resources.length} individual resources for discovery`,
-    );
-
-    for (const resource of resources) {
-      this.server.registerResource(
-        resource.uri,
-        resource.uri,
-        {
-          mimeType: resource.mimeType,
-          description: resource.description,
-        },
-        async (uri) => {
-          const startTime = Date.now();
-          logger.debug(`Loading individual resource: ${uri.toString()}`);
-
-          // Track MCP resource request
-          this.stats.logActivity("resource_read", {
-            uri: uri.toString(),
-            category: resource.category,
-          });
-
-          try {
-            const content = await this.resourceLoader.loadResourceContent(
-              uri.toString(),
-            );
-
-            const latency = Date.now() - startTime;
-            this.stats.trackRequest(`individual:${resource.category}`, latency);
-
-            return {
-              contents: [
-                {
-                  uri: uri.toString(),
-                  mimeType: resource.mimeType,
-                  text: content,
-                },
-              ],
-            };
-          } catch (error) {
-            this.stats.trackError(error);
-            throw error;
-          }
-        },
-      );
-    }
-
-    logger.info(
-      `Successfully registered ${resources.length} individual resources`,
+/**
+ * Convert another framework's agent or skill definitions into resource files
+ */
+async function importCommand(args: string[]): Promise<SubcommandResult> {
+  const { values, switches, positional } = parseArgs(args, {
+    values: ["from", "out"],
+    switches: ["force"],
+  });
+  const from = values.from;
+  const out =
+    values.out ||
+    process.env.RESOURCES_PATH ||
+    join(process.cwd(), "resources");
+  if (!from || positional.length !== 1) {
+    process.stderr.write(
+      `Usage: orchestr8 import --from ${IMPORT_SOURCES.join("|")} <dir> [--out <resources-dir>] [--force] [--format text|json|github]\n`,
     );
+    process.exit(2);
   }
 
-  private registerDynamicTemplates(): void {
-    const dynamicCategories = [
-      {
-        category: "agents",
-        description:
-          "Dynamic agent matching - finds and assembles relevant agent resources based on query",
-      },
-      {
-        category: "skills",
-        description:
-          "Dynamic skill matching - finds and assembles relevant skill resources based on query",
-      },
-      {
-        category: "examples",
-        description:
-          "Dynamic example matching - finds and assembles relevant example resources based on query",
-      },
-      {
-        category: "patterns",
-        description:
-          "Dynamic pattern matching - finds and assembles relevant pattern resources based on query",
-      },
-      {
-        category: "guides",
-        description:
-          "Dynamic guide matching - finds and assembles relevant guide resources based on query",
-      },
-      {
-        category: "best-practices",
-        description:
-          "Dynamic best practice matching - finds and assembles relevant best practice resources based on query",
-      },
-      {
-        category: "workflows",
-        description:
-          "Dynamic workflow matching - finds and assembles relevant workflow resources based on query",
-      },
-    ];
-
-    for (const { category, description } of dynamicCategories) {
-      // Register dynamic matching template (e.g., o8://agents/match?query=...)
-      const dynamicTemplateUri = `o8://${category}/match{+rest}`;
-
-      this.server.registerResource(
-        `${category}-dynamic`,
-        new ResourceTemplate(dynamicTemplateUri, { list: undefined }),
-        {
-          mimeType: "text/markdown",
-          description: description,
-        },
-        async (uri, params: Record<string, any>) => {
-          const startTime = Date.now();
-          const fullUri = uri.toString();
-          logger.debug(`Loading dynamic resource: ${fullUri}`);
-
-          // Track MCP resource request
-          this.stats.logActivity("resource_read", {
-            uri: fullUri,
-            category: category,
-          });
-
-          try {
-            const content =
-              await this.resourceLoader.loadResourceContent(fullUri);
-
-            const latency = Date.now() - startTime;
-            this.stats.trackRequest(`dynamic:${category}`, latency);
-
-            return {
-              contents: [
-                {
-                  uri: fullUri,
-                  mimeType: "text/markdown",
-                  text: content,
-                },
-              ],
-            };
-          } catch (error) {
-            this.stats.trackError(error);
-            throw error;
-          }
-        },
-      );
-
-      logger.debug(
-        `Registered dynamic resource template: ${dynamicTemplateUri}`,
-      );
-
-      // Register static resource template (e.g., o8://agents/medium-writer-expert)
-      const staticTemplateUri = `o8://${category}/{+resourceId}`;
-
-      this.server.registerResource(
-        `${category}-static`,
-        new ResourceTemplate(staticTemplateUri, { list: undefined }),
-        {
-          mimeType: "text/markdown",
-          description: `Access individual ${category} resources by ID`,
-        },
-        async (uri, params: Record<string, any>) => {
-          const startTime = Date.now();
-          const fullUri = uri.toString();
-
-          // Skip if this is a /match URI (handled by dynamic template)
-          if (fullUri.includes("/match")) {
-            throw new Error(
-              `URI ${fullUri} should be handled by dynamic template`,
-            );
-          }
-
-          logger.debug(`Loading static resource: ${fullUri}`);
-
-          // Track MCP resource request
-          this.stats.logActivity("resource_read", {
-            uri: fullUri,
-            category: category,
-          });
-
-          try {
-            const content =
-              await this.resourceLoader.loadResourceContent(fullUri);
-
-            const latency = Date.now() - startTime;
-            this.stats.trackRequest(`static:${category}`, latency);
-
-            return {
-              contents: [
-                {
-                  uri: fullUri,
-                  mimeType: "text/markdown",
-                  text: content,
-                },
-              ],
-            };
-          } catch (error) {
-            this.stats.trackError(error);
-            throw error;
-          }
-        },
-      );
-
-      logger.debug(`Registered static resource template: ${staticTemplateUri}`);
-    }
+  const resources = await importDefinitions(from, positional[0]);
+  const { written, skipped } = await writeFiles(
+    out,
+    resources,
+    switches.force,
+  );
+  const todoCount = (n: number) => `${n} TODO${n === 1 ? "" : "s"}`;
+
+  return {
+    report: {
+      lines: resources.map((resource) =>
+        written.includes(resource.path)
+          ? `WROTE ${resource.path} (${todoCount(resource.todos.length)})`
+          : `SKIP ${resource.path} (exists; use --force)`,
+      ),
+      summary: `Imported ${written.length} of ${resources.length} ${from} definitions into ${out}`,
+      findings: resources.flatMap((resource) =>
+        written.includes(resource.path)
+          ? resource.todos.map((todo) => ({
+              level: "warning" as const,
+              file: join(out, resource.path),
+              title: "Imported field needs review",
+              message: todo,
+            }))
+          : [],
+      ),
+      data: {
+        from,
+        out,
+        written,
+        skipped,
+        resources: resources.map(({ path, source, todos }) => ({
+          path,
+          source,
+          todos,
+        })),
+      },
+    },
+    exitCode: 0,
+  };
+}
 
-    // Register global catch-all template
-    const globalTemplateUri = "o8://match{+rest}";
-    this.server.registerResource(
-      "global-dynamic",
-      new ResourceTemplate(globalTemplateUri, { list: undefined }),
-      {
-        mimeType: "text/markdown",
-        description: "Dynamic resource matching across all categories",
-      },
-      async (uri, params: Record<string, any>) => {
-        const startTime = Date.now();
-        const fullUri = uri.toString();
-        logger.debug(`Loading global dynamic resource: ${fullUri}`);
-
-        // Track MCP resource request
-        this.stats.logActivity("resource_read", {
-          uri: fullUri,
-          category: "global",
-        });
-
-        try {
-          const content =
-            await this.resourceLoader.loadResourceContent(fullUri);
-
-          const latency = Date.now() - startTime;
-          this.stats.trackRequest("dynamic:global", latency);
-
-          return {
-            contents: [
-              {
-                uri: fullUri,
-                mimeType: "text/markdown",
-                text: content,
-              },
-            ],
-          };
-        } catch (error) {
-          this.stats.trackError(error);
-          throw error;
-        }
-      },
-    );
-    logger.debug(
-      `Registered global dynamic resource template: ${globalTemplateUri}`,
+/**
+ * Write the catalog out as Claude Code skills or slash commands
+ */
+async function exportCommand(args: string[]): Promise<SubcommandResult> {
+  const { values, switches } = parseArgs(args, {
+    values: ["to", "out", "category"],
+    switches: ["force"],
+  });
+  const { to, category } = values;
+  if (!to || !(EXPORT_TARGETS as readonly string[]).includes(to)) {
+    process.stderr.write(
+      `Usage: orchestr8 export --to ${EXPORT_TARGETS.join("|")} [--out <dir>] [--category <category>] [--force] [--format text|json|github]\n`,
     );
+    process.exit(2);
   }
+  const out =
+    values.out ||
+    join(".claude", to === "claude-commands" ? "commands" : "skills");
+
+  const loader = new ResourceLoader(logger);
+  const singular = category?.replace(/s$/, "");
+  const fragments = (await loader.loadResourceIndex()).filter(
+    (fragment) => !singular || fragment.category === singular,
+  );
+
+  const files = exportCatalog(fragments, to);
+  const { written, skipped } = await writeFiles(out, files, switches.force);
+
+  return {
+    report: {
+      lines: files.map((file) =>
+        written.includes(file.path)
+          ? `WROTE ${file.path} (from ${file.id})`
+          : `SKIP ${file.path} (exists; use --force)`,
+      ),
+      summary: `Exported ${written.length} of ${files.length} resources to ${out}`,
+      findings: skipped.map((path) => ({
+        level: "notice" as const,
+        file: join(out, path),
+        message: "Not exported: file exists (use --force)",
+      })),
+      data: { to, out, written, skipped },
+    },
+    exitCode: 0,
+  };
+}
 
-  async startStdio(): Promise<void> {
-    logger.info("Starting in stdio mode (Claude Desktop integration)");
-
-    const transport = new StdioServerTransport();
-    await this.server.connect(transport);
-
-    logger.info("orchestr8 MCP server started successfully in stdio mode");
+/**
+ * Name of the user running the CLI, for the journal
+ */
+function cliUser(): string {
+  try {
+    return userInfo().username;
+  } catch {
+    return process.env.USER ?? "unknown";
   }
+}
 
-  async startHttp(): Promise<void> {
-    logger.info(`Starting in HTTP mode on port ${HTTP_PORT}`);
-
-    // Create HTTP transport with methods to access MCP functionality
-    const mcpInterface = {
-      handleRequest: async (method: string, params: any) => {
-        // This would need to be implemented based on MCP server internals
-        throw new Error("Direct MCP request handling not yet implemented");
-      },
-      getAvailableAgents: async () => {
-        return this.resourceLoader.getResourcesByCategory("agents");
-      },
-      getAvailableSkills: async () => {
-        return this.resourceLoader.getResourcesByCategory("skills");
-      },
-      getAvailableWorkflows: async () => {
-        return this.resourceLoader.getResourcesByCategory("workflows");
-      },
-      getAvailablePatterns: async () => {
-        return this.resourceLoader.getResourcesByCategory("patterns");
-      },
-      searchResources: async (query: string) => {
-        return this.resourceLoader.searchResources(query);
-      },
-      getResourceContent: async (uri: string) => {
-        return this.resourceLoader.loadResourceContent(uri);
-      },
-      // ============================================================================
-      // NEW: Provider system endpoints
-      // ============================================================================
-      getProviderNames: () => {
-        return this.resourceLoader.getProviderNames(true); // Only enabled providers
-      },
-      getProvidersHealth: async () => {
-        return this.resourceLoader.getProvidersHealth();
-      },
-      getProvidersStats: () => {
-        return this.resourceLoader.getProvidersStats();
-      },
-      getAggregateProviderStats: () => {
-        return this.resourceLoader.getAggregateProviderStats();
-      },
-      searchAllProviders: async (query: string, options?: any) => {
-        return this.resourceLoader.searchAllProviders(query, options);
-      },
-      searchProvider: async (
-        providerName: string,
-        query: string,
-        options?: any,
-      ) => {
-        return this.resourceLoader.searchProvider(providerName, query, options);
-      },
-      // Provider methods (Wave 4: HTTP Transport Integration)
-      getProviders: async () => {
-        return this.resourceLoader.getProviders();
-      },
-      getProviderIndex: async (name: string) => {
-        return this.resourceLoader.getProviderIndex(name);
-      },
-      getProviderHealth: async (name: string) => {
-        return this.resourceLoader.getProviderHealth(name);
-      },
-      getAllProvidersHealth: async () => {
-        return this.resourceLoader.getProvidersHealth();
-      },
-      getProviderStats: (name: string) => {
-        return this.resourceLoader.getProviderStats(name);
-      },
-      enableProvider: async (name: string) => {
-        return this.resourceLoader.enableProvider(name);
-      },
-      disableProvider: async (name: string) => {
-        return this.resourceLoader.disableProvider(name);
-      },
-      // ============================================================================
-      // NEW: Token system interface
-      // ============================================================================
-      tokenSystem: {
-        tracker: this.tokenTracker,
-        store: this.tokenStore,
-        metrics: this.tokenMetrics,
-        efficiency: this.efficiencyEngine,
-      },
-    };
-
-    this.httpTransport = new HTTPTransport(
-      {
-        port: HTTP_PORT,
-        staticPath: path.join(__dirname, "web", "static"),
-        enableCORS: true,
-      },
-      mcpInterface,
-      this.stats,
+/**
+ * Find and replace across agent definitions, or preview it with --dry-run
+ */
+async function rewriteCommand(args: string[]): Promise<SubcommandResult> {
+  const { values, switches, positional } = parseArgs(args, {
+    values: ["replace", "category", "ids"],
+    switches: ["literal", "ignore-case", "dry-run"],
+  });
+  if (positional.length !== 1 || values.replace === undefined) {
+    process.stderr.write(
+      "Usage: orchestr8 rewrite <pattern> --replace <text> [--literal] [--ignore-case] [--category <category>] [--ids <a,b>] [--dry-run] [--format text|json|github]\n",
     );
-
-    await this.httpTransport.start();
-
-    // Log server startup activity
-    this.stats.logActivity("server_start", {
-      mode: "HTTP",
-      port: HTTP_PORT,
-      timestamp: Date.now(),
-    });
-
-    logger.info(`orchestr8 MCP server started successfully in HTTP mode`);
-    logger.info(`Web UI available at: http://localhost:${HTTP_PORT}`);
+    process.exit(2);
   }
 
-  async startDual(): Promise<void> {
-    logger.info("Starting in dual mode (stdio + HTTP)");
-
-    // Start stdio for Claude Desktop
-    const stdioTransport = new StdioServerTransport();
-    await this.server.connect(stdioTransport);
-    logger.info("stdio transport ready");
-
-    // Start HTTP for web UI
-    await this.startHttp();
+  const loader = new ResourceLoader(logger);
+  const dryRun = switches["dry-run"];
+  const result = await loader.rewriteResources(
+    {
+      pattern: positional[0],
+      replacement: values.replace,
+      literal: switches.literal,
+      ignoreCase: switches["ignore-case"],
+      ...(values.ids
+        ? {
+            ids: values.ids
+              .split(",")
+              .map((id) => id.trim())
+              .filter(Boolean),
+          }
+        : {}),
+      ...(values.category ? { category: values.category } : {}),
+      dryRun,
+    },
+    `cli:${cliUser()}`,
+  );
+
+  return {
+    report: {
+      lines: [
+        ...result.changes.map((change) =>
+          dryRun
+            ? change.diff.trimEnd()
+            : `WROTE ${change.path} (${change.replacements} replaced)`,
+        ),
+        ...result.notFound.map((id) => `MISSING ${id}`),
+      ],
+      summary: `${dryRun ? "Would replace" : "Replaced"} ${result.replacements} occurrences in ${result.changes.length} files`,
+      findings: result.notFound.map((id) => ({
+        level: "warning" as const,
+        title: "Resource not found",
+        message: `${id} is not in the catalog; nothing was rewritten in it`,
+      })),
+      data: { ...result },
+    },
+    exitCode: result.notFound.length > 0 ? 1 : 0,
+  };
+}
 
-    logger.info("orchestr8 MCP server started successfully in dual mode");
+/**
+ * Write an index manifest of RESOURCES_PATH for --index-manifest
+ */
+async function manifestCommand(args: string[]): Promise<SubcommandResult> {
+  const { values, positional } = parseArgs(args, { values: ["out"] });
+  if (positional.length > 0) {
+    process.stderr.write(
+      "Usage: orchestr8 manifest [--out <path>] [--format text|json|github]\n",
+    );
+    process.exit(2);
   }
+  const out = values.out || "index-manifest.json";
+
+  const loader = new ResourceLoader(logger);
+  const manifest = await loader.buildIndexManifest();
+  await fs.mkdir(dirname(resolve(out)), { recursive: true });
+  await fs.writeFile(out, `${JSON.stringify(manifest, null, 2)}\n`);
+
+  return {
+    report: {
+      lines: [`WROTE ${out}`],
+      summary: `Listed ${manifest.files.length} files in ${out}`,
+      findings: [],
+      data: { out, files: manifest.files.length },
+    },
+    exitCode: 0,
+  };
+}
 
-  async shutdown(): Promise<void> {
-    logger.info("Shutting down orchestr8 MCP server");
-
-    if (this.httpTransport) {
-      await this.httpTransport.stop();
-    }
-
-    await this.server.close();
-  }
+/**
+ * Check the environment and suggest fixes
+ */
+async function doctor(): Promise<SubcommandResult> {
+  const checks = await runDoctor({
+    port: parseInt(flagValue("http-port") || "1337", 10),
+  });
+  const label = { ok: "OK  ", warn: "WARN", fail: "FAIL" } as const;
+  const failed = checks.filter((check) => check.status === "fail").length;
+  const warned = checks.filter((check) => check.status === "warn").length;
+
+  return {
+    report: {
+      lines: checks.map(
+        (check) =>
+          `${label[check.status]} ${check.name}: ${check.message}` +
+          (check.remedy ? `\n       fix: ${check.remedy}` : ""),
+      ),
+      summary: `${checks.length} checks: ${failed} failed, ${warned} warnings`,
+      findings: checks
+        .filter((check) => check.status !== "ok")
+        .map((check) => ({
+          level: check.status === "fail" ? ("error" as const) : ("warning" as const),
+          title: `doctor: ${check.name}`,
+          message: `${check.message}. ${check.remedy}`,
+        })),
+      data: { checks },
+    },
+    exitCode: failed > 0 ? 1 : 0,
+  };
 }
 
-async function main() {
+/**
+ * Run a subcommand and print its report in the requested format
+ */
+async function runSubcommand(
+  command: (args: string[]) => Promise<SubcommandResult>,
+  argv: string[],
+) {
+  const { format, rest } = takeFormat(argv);
   try {
-    const orchestr8 = new Orchestr8Server();
-    await orchestr8.initialize();
-
-    // Determine which mode to run in
-    if (HTTP_MODE) {
-      // HTTP only mode (for development/testing)
-      await orchestr8.startHttp();
-    } else if (process.env.NODE_ENV === "test") {
-      // Test mode: stdio only (no HTTP to avoid stdout pollution)
-      await orchestr8.startStdio();
-    } else {
-      // Default: Dual mode (stdio for Claude + HTTP for web UI)
-      await orchestr8.startDual();
-    }
-
-    // Handle graceful shutdown
-    const shutdown = async () => {
-      await orchestr8.shutdown();
-      process.exit(0);
-    };
-
-    process.on("SIGINT", shutdown);
-    process.on("SIGTERM", shutdown);
+    const { report, exitCode } = await command(rest);
+    process.stdout.write(renderReport(format, report));
+    process.exit(exitCode);
   } catch (error) {
-    logger.error("Failed to start server:", error);
+    if (format === "text") {
+      throw error;
+    }
+    process.stdout.write(renderError(format, error));
     process.exit(1);
   }
 }
 
-// Run the server
-main().catch((error) => {
+// Run the server, or a subcommand (replay, import, export, rewrite,
+// manifest, doctor)
+const subcommands: Record<
+  string,
+  (args: string[]) => Promise<SubcommandResult>
+> = {
+  replay,
+  import: importCommand,
+  export: exportCommand,
+  rewrite: rewriteCommand,
+  manifest: manifestCommand,
+  doctor,
+};
+const subcommand = Object.hasOwn(subcommands, process.argv[2])
+  ? subcommands[process.argv[2]]
+  : undefined;
+
+/**
+ * Print --version, or apply --auto discovery and run the server or
+ * subcommand
+ */
+async function start() {
+  // Only on the command line: O8_VERSION must not stop a server
+  if (process.argv.includes("--version")) {
+    process.stdout.write(
+      formatBuildInfo(
+        buildInfo(SERVER_VERSION),
+        process.argv.includes("--verbose"),
+      ),
+    );
+    return;
+  }
+  if (flagEnabled("auto")) {
+    const { summary } = await applyAutoConfig();
+    // stderr: stdout carries the MCP protocol
+    process.stderr.write(`${summary}\n`);
+  }
+  const args = process.argv
+    .slice(3)
+    .filter((arg) => arg !== "--auto" && arg !== "--no-auto");
+  return subcommand ? runSubcommand(subcommand, args) : main();
+}
+
+const run = start();
+run.catch((error) => {
   console.error("Fatal error:", error);
   process.exit(1);
 });
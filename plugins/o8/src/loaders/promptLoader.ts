@@ -2,9 +2,16 @@ import fs from "fs/promises";
 import path from "path";
 import { LRUCache } from "lru-cache";
 import chokidar, { FSWatcher } from "chokidar";
+import {
+  chokidarOptions,
+  settingsForTree,
+  watchSettings,
+  type WatchSettings,
+} from "../utils/watchOptions.js";
 import matter from "gray-matter";
 import { Logger } from "../utils/logger.js";
 import { PromptMetadata } from "../types.js";
+import { sortByName } from "../utils/deterministic.js";
 
 export class PromptLoader {
   private logger: Logger;
@@ -75,7 +82,7 @@ export class PromptLoader {
       await fs.access(categoryPath);
 
       // Read all .md files in the directory
-      const files = await fs.readdir(categoryPath);
+      const files = sortByName(await fs.readdir(categoryPath));
       const mdFiles = files.filter((f) => f.endsWith(".md"));
 
       for (const file of mdFiles) {
@@ -195,16 +202,28 @@ export class PromptLoader {
 
   /**
    * Watch for changes in prompt files
+   *
+   * @param settings - Native events or polling (default: from
+   *   --watch-mode and --poll-interval)
    */
-  watchForChanges(callback: () => void): void {
+  watchForChanges(
+    callback: () => void,
+    settings: WatchSettings = watchSettings(),
+  ): void {
     if (this.watcher) {
       return; // Already watching
     }
 
-    this.watcher = chokidar.watch(`${this.promptsPath}/**/*.md`, {
-      persistent: true,
-      ignoreInitial: true,
-    });
+    const treeSettings = settingsForTree(settings, this.promptsPath);
+    if (treeSettings.mode !== settings.mode) {
+      this.logger.warn(
+        `Prompts need more than ${settings.maxDescriptors} watch descriptors; polling instead`,
+      );
+    }
+    this.watcher = chokidar.watch(
+      `${this.promptsPath}/**/*.md`,
+      chokidarOptions(treeSettings),
+    );
 
     this.watcher.on("all", (event: string, filePath: string) => {
       this.logger.info(`Prompt file ${event}: ${filePath}`);
@@ -212,7 +231,9 @@ export class PromptLoader {
       callback();
     });
 
-    this.logger.info("Hot reload enabled for prompt files");
+    this.logger.info(
+      `Hot reload enabled for prompt files (${treeSettings.mode})`,
+    );
   }
 
   /**
@@ -1,15 +1,104 @@
 import { promises as fs } from "fs";
-import { join } from "path";
+import { createHash } from "crypto";
+import { join, relative, resolve } from "path";
+import { performance } from "perf_hooks";
 import { LRUCache } from "lru-cache";
+import chokidar, { FSWatcher } from "chokidar";
 import matter from "gray-matter";
 import { Logger } from "../utils/logger.js";
 import { ResourceMetadata } from "../types.js";
 // ============================================================================
 // NEW IMPORTS: Dynamic resource matching support
 // ============================================================================
-import { URIParser, ParsedURI } from "../utils/uriParser.js";
-import { FuzzyMatcher, ResourceFragment } from "../utils/fuzzyMatcher.js";
+import { URIParser, ParsedURI, MatchDefaults } from "../utils/uriParser.js";
+import {
+  FuzzyMatcher,
+  MatchRequest,
+  MatchResult,
+  ResourceFragment,
+  ResourceOrigin,
+} from "../utils/fuzzyMatcher.js";
 import { IndexLookup } from "../utils/indexLookup.js";
+import { payloadLimitsFromEnv } from "../utils/limits.js";
+import {
+  compilePattern,
+  grepLines,
+  DEFAULT_GREP_LIMIT,
+  GrepHit,
+} from "../utils/grep.js";
+import {
+  findSection,
+  parseSections,
+  SectionNotFoundError,
+} from "../utils/sections.js";
+import {
+  DEFAULTS_FILE,
+  inheritedDefaults,
+  parseDefaults,
+  FrontmatterDefaults,
+} from "../utils/directoryDefaults.js";
+import {
+  applyRewrite,
+  compileRewrite,
+  stagingPath,
+  writeFilesAtomically,
+  RewriteChange,
+  RewriteConflict,
+  RewriteConflictError,
+  RewriteRequest,
+} from "../utils/rewrite.js";
+import { shadowRankerFromEnv } from "../utils/shadowRanking.js";
+import {
+  canaryOptions,
+  inCanary,
+  recordCanaryDiff,
+  type CanaryOptions,
+} from "../utils/canary.js";
+import { MissTracker, QueryMiss } from "../utils/missTracker.js";
+import {
+  compareStrings,
+  isDeterministic,
+  sortByName,
+} from "../utils/deterministic.js";
+import { sampleItems } from "../utils/sampling.js";
+import { resourceLayers, ResourceLayer } from "../utils/layers.js";
+import { withProvenance } from "../utils/provenance.js";
+import { foldCase, tokenizeText } from "../utils/tokenize.js";
+import { summarizeCatalog, CatalogSummary } from "../utils/catalogSummary.js";
+import {
+  fileAtRevision,
+  fileHistory,
+  HistoryEntry,
+} from "../utils/gitHistory.js";
+import { unifiedDiff } from "../utils/diff.js";
+import {
+  SnapshotStore,
+  SnapshotFile,
+  SnapshotInfo,
+  SnapshotError,
+  defaultStateDir,
+} from "../state/snapshotStore.js";
+import {
+  QueryLog,
+  QueryLogSummary,
+  queryLogOptions,
+} from "../state/queryLog.js";
+import { withStateLock } from "../state/lock.js";
+import {
+  Journal,
+  JournalEntry,
+  JournalFile,
+  hashContent,
+} from "../state/journal.js";
+import {
+  BodyIndex,
+  BodySearchHit,
+  BodySearchOptions,
+} from "../utils/bodyIndex.js";
+import {
+  TemplateEngine,
+  TemplateSyntaxError,
+} from "../utils/templateEngine.js";
 // ============================================================================
 // NEW IMPORTS: Provider system integration
 // ============================================================================
@@ -19,6 +108,59 @@ import { AITMPLProvider } from "../providers/aitmpl.js";
 import { GitHubProvider } from "../providers/github.js";
 import { ProviderConfigManager } from "../config/providers.js";
 import { ConfigLoader } from "../config/loader.js";
+import type { ClientProfile, UpstreamConfig } from "../config/schema.js";
+import {
+  findCaseInsensitive,
+  isDirectoryEntry,
+  isWithin,
+} from "../utils/paths.js";
+import {
+  chokidarOptions,
+  settingsForTree,
+  watchSettings,
+  type WatchSettings,
+} from "../utils/watchOptions.js";
+import {
+  capabilityFacets,
+  matchesCapabilities,
+  parseCapabilityMatrix,
+  type CapabilityDimension,
+  type CapabilityFilter,
+} from "../utils/capabilityMatrix.js";
+import {
+  IndexProgress,
+  type IndexProgressListener,
+  type IndexingState,
+} from "../utils/indexProgress.js";
+import { StartupProfile } from "../utils/startupProfile.js";
+import {
+  IndexStallError,
+  MAX_INDEX_RESTARTS,
+  stallTimeout,
+  watchBuild,
+  type WatchdogState,
+} from "../utils/indexWatchdog.js";
+import {
+  INDEX_MANIFEST_VERSION,
+  IndexManifest,
+  indexManifestPath,
+  readIndexManifest,
+} from "../utils/indexManifest.js";
+import {
+  routeAgent,
+  type RouteDecision,
+  type RouteRequest,
+} from "../utils/agentRouter.js";
+import {
+  planTask,
+  type PlanRequest,
+  type TaskPlan,
+} from "../utils/taskPlanner.js";
+import {
+  handoffGraph,
+  parseHandoffs,
+  type HandoffGraph,
+} from "../utils/handoffs.js";
 import type {
   SearchOptions,
   SearchResult,
@@ -30,6 +172,49 @@ import type {
 // ============================================================================
 import { TokenTracker } from "../token/tracker.js";
 import { TokenStore } from "../token/store.js";
+import type { ServerEventBus } from "../events/bus.js";
+
+/**
+ * Snapshot the catalog is pinned to (relative path -> content hash)
+ */
+type PinnedSnapshot = { name: string; files: Map<string, string> };
+
+/**
+ * A snapshot loaded for time-travel queries, next to the served catalog
+ */
+type SnapshotCatalog = {
+  matcher: FuzzyMatcher;
+  fragments: Map<string, ResourceFragment>;
+  aliases: Map<string, string>;
+};
+
+/**
+ * What a cached match response answered, so cache hits are logged and
+ * counted as misses too
+ */
+type MatchOutcome = { query: string; results: number; category?: string };
+
+/**
+ * Snapshot to read from instead of the served catalog
+ */
+export interface SnapshotRef {
+  /** Snapshot name */
+  snapshot?: string;
+  /** Latest snapshot taken at or before this time (ISO 8601) */
+  asOf?: string;
+}
+
+/**
+ * Category directories scanned for the resource index
+ */
+const FRAGMENT_CATEGORIES = [
+  "agents",
+  "skills",
+  "examples",
+  "patterns",
+  "guides",
+  "workflows",
+];
 
 export class ResourceLoader {
   private logger: Logger;
@@ -42,8 +227,59 @@ export class ResourceLoader {
   private uriParser: URIParser;
   private fuzzyMatcher: FuzzyMatcher;
   private indexLookup: IndexLookup;
+  private templateEngine: TemplateEngine;
+  private partialCache: Map<string, string> = new Map();
+  private partialDependents: Map<string, Set<string>> = new Map();
+  private partialWatcher?: FSWatcher;
   private resourceIndex: ResourceFragment[] | null = null;
+  private scanFailures: Array<{ path: string; error: string }> = [];
+  // Former ids of renamed resources (alias id -> canonical id)
+  private aliases: Map<string, string> = new Map();
+  private bodyIndex: BodyIndex = new BodyIndex();
+  private bodyIndexSource: ResourceFragment[] | null = null;
   private indexLoadPromise: Promise<ResourceFragment[]> | null = null;
+  private layers: Promise<ResourceLayer[]> | null = null;
+
+  // Catalog snapshots: when pinned, resources are served from the snapshot
+  // store (relative path -> content hash) instead of the resources directory
+  private stateDir: string;
+  private snapshotStore: SnapshotStore;
+  private pinned: PinnedSnapshot | null = null;
+  private pinRestore: Promise<void> | null = null;
+  // Write-ahead journal of rewrites, replayed after a crash
+  private journal: Journal;
+  // Snapshots queried with snapshot= or asOf=, parsed on first use
+  private snapshotCatalogs = new LRUCache<string, Promise<SnapshotCatalog>>({
+    max: 4,
+  });
+
+  // Opt-in, sampled query log (O8_QUERY_LOG) for telemetry/summary
+  private queryLog: QueryLog;
+  // Match responses in the cache, by URI, replayed into the query log and
+  // the miss tracker
+  private matchOutcomes: LRUCache<string, MatchOutcome>;
+  // Queries that found nothing, for agents/misses
+  private missTracker = new MissTracker();
+
+  // Blue/green reloads: a new index is built off to the side and swapped in
+  // synchronously, so readers only ever see a complete generation
+  private generation = 0;
+  private loadedAt: string | null = null;
+  private reloadQueue: Promise<unknown> = Promise.resolve();
+  private reloading = false;
+  // Progress of the index build in flight, for health and notifications
+  private indexProgress: IndexProgress | null = null;
+  private indexProgressListener: IndexProgressListener | null = null;
+  // Phase timings of the first index build (--profile-startup)
+  private startupProfile: StartupProfile | null = null;
+  // Staged catalog answering a share of fuzzy matches (--canary-dir)
+  private canary: CanaryOptions | null;
+  private canaryMatcher: Promise<FuzzyMatcher> | null = null;
+  // Restarts builds that stop making progress (O8_INDEX_STALL_TIMEOUT)
+  private stallTimeout: number;
+  private watchdog: WatchdogState = { restarts: 0, lastStall: null };
+  // Files to index from RESOURCES_PATH instead of walking it
+  private indexManifestPath: string | null;
 
   // ============================================================================
   // NEW: Provider system components
@@ -57,7 +293,15 @@ export class ResourceLoader {
   private tokenTracker: TokenTracker | null = null;
   private tokenStore: TokenStore | null = null;
 
-  constructor(logger: Logger, tokenTracker?: TokenTracker, tokenStore?: TokenStore) {
+  // Server event bus (optional)
+  private eventBus: ServerEventBus | null = null;
+
+  constructor(
+    logger: Logger,
+    tokenTracker?: TokenTracker,
+    tokenStore?: TokenStore,
+    eventBus?: ServerEventBus,
+  ) {
     this.logger = logger;
     this.resourcesPath =
       process.env.RESOURCES_PATH || join(process.cwd(), "resources");
@@ -68,13 +312,31 @@ export class ResourceLoader {
       ttl: 1000 * 60 * 60 * 4, // 4 hour TTL for resources (changed less frequently)
       updateAgeOnGet: true,
     });
+    this.matchOutcomes = new LRUCache<string, MatchOutcome>({
+      max: cacheSize,
+    });
 
     // ============================================================================
     // NEW: Initialize dynamic matching components
     // ============================================================================
-    this.uriParser = new URIParser();
+    this.uriParser = new URIParser(payloadLimitsFromEnv());
     this.fuzzyMatcher = new FuzzyMatcher();
+    this.fuzzyMatcher.setShadowRanker(shadowRankerFromEnv());
+    this.canary = canaryOptions();
     this.indexLookup = new IndexLookup(this.resourcesPath);
+    this.queryLog = new QueryLog(queryLogOptions());
+    this.stallTimeout = stallTimeout();
+    this.indexManifestPath = indexManifestPath();
+    this.indexLookup.setMetricsListener((metrics) => {
+      this.queryLog.record(metrics.query, metrics.resultsCount, metrics.tier);
+      if (metrics.resultsCount === 0) {
+        this.missTracker.record(metrics.query);
+      }
+    });
+    this.templateEngine = new TemplateEngine();
+    this.stateDir = defaultStateDir();
+    this.snapshotStore = new SnapshotStore(this.stateDir);
+    this.journal = new Journal(this.stateDir);
 
     // ============================================================================
     // NEW: Initialize provider registry
@@ -92,6 +354,7 @@ export class ResourceLoader {
     // ============================================================================
     this.tokenTracker = tokenTracker || null;
     this.tokenStore = tokenStore || null;
+    this.eventBus = eventBus || null;
 
     this.logger.debug(
       `Resource loader initialized with path: ${this.resourcesPath}`,
@@ -238,18 +501,21 @@ export class ResourceLoader {
 
     try {
       await fs.access(fullPath);
-      const entries = await fs.readdir(fullPath, { withFileTypes: true });
+      const entries = sortByName(
+        await fs.readdir(fullPath, { withFileTypes: true }),
+      );
 
       for (const entry of entries) {
         const entryPath = join(relativePath, entry.name);
         const uri = `${uriPrefix}/${entry.name.replace(/\.(md|json|yaml)$/, "")}`;
 
-        if (entry.isDirectory()) {
+        if (await isDirectoryEntry(entry, join(fullPath, entry.name))) {
           await this.scanDirectory(entryPath, uri, resources);
         } else if (
-          entry.name.endsWith(".md") ||
-          entry.name.endsWith(".json") ||
-          entry.name.endsWith(".yaml")
+          entry.name !== DEFAULTS_FILE &&
+          (entry.name.endsWith(".md") ||
+            entry.name.endsWith(".json") ||
+            entry.name.endsWith(".yaml"))
         ) {
           const mimeType = entry.name.endsWith(".json")
             ? "application/json"
@@ -296,70 +562,579 @@ export class ResourceLoader {
 
     // Start loading
     this.logger.info("Loading resource index...");
-    this.indexLoadPromise = this._loadResourceIndexImpl();
+    this.indexLoadPromise = this._profiled("state", () =>
+      this._ensurePinRestored(),
+    )
+      .then(() => this._queueReload(() => this.pinned, false))
+      .then(() => this.resourceIndex!);
 
     try {
-      this.resourceIndex = await this.indexLoadPromise;
-      this.logger.info(
-        `Resource index loaded with ${this.resourceIndex.length} fragments`,
-      );
-      return this.resourceIndex;
+      return await this.indexLoadPromise;
     } finally {
       this.indexLoadPromise = null;
     }
   }
 
   /**
-   * Internal implementation of resource index loading
-   * OPTIMIZED: Parallel directory scanning for faster initial load
+   * Rebuild the resource index and swap it in atomically
+   *
+   * The new index (and its body search index) is built completely while the
+   * current one keeps serving. Reloads are serialized; each successful one
+   * increments the catalog generation.
+   *
+   * @returns New generation and resource count
+   */
+  async reloadIndex(): Promise<{ generation: number; resources: number }> {
+    await this._ensurePinRestored();
+    return this._queueReload(() => this.pinned, true);
+  }
+
+  /**
+   * Current catalog generation, for health checks
+   */
+  getCatalogState(): {
+    generation: number;
+    loadedAt: string | null;
+    resources: number;
+    pinned: string | null;
+    reloading: boolean;
+    indexing: IndexingState | null;
+    watchdog: WatchdogState;
+  } {
+    return {
+      generation: this.generation,
+      loadedAt: isDeterministic() ? null : this.loadedAt,
+      resources: this.resourceIndex?.length ?? 0,
+      pinned: this.pinned?.name ?? null,
+      reloading: this.reloading,
+      indexing: this.indexProgress?.state() ?? null,
+      watchdog: this.watchdog,
+    };
+  }
+
+  /**
+   * Receive periodic progress while a large index is built
+   */
+  setIndexProgressListener(listener: IndexProgressListener | null): void {
+    this.indexProgressListener = listener;
+  }
+
+  /**
+   * Record the phases of the first index build (--profile-startup)
+   */
+  setStartupProfile(profile: StartupProfile | null): void {
+    this.startupProfile = profile;
+  }
+
+  /**
+   * Run a step, timed under `index.<phase>` while profiling startup
    * @private
    */
-  private async _loadResourceIndexImpl(): Promise<ResourceFragment[]> {
+  private _profiled<T>(phase: string, fn: () => Promise<T>): Promise<T> {
+    return this.startupProfile
+      ? this.startupProfile.time(`index.${phase}`, fn)
+      : fn();
+  }
+
+  /**
+   * Client profiles from the `clients` configuration section
+   */
+  getClientProfiles(): Record<string, ClientProfile> {
     try {
-      // Scan all resource directories in parallel
-      const categories = [
-        "agents",
-        "skills",
-        "examples",
-        "patterns",
-        "guides",
-        "workflows",
-      ];
-
-      // Parallel scan all categories - reduces initial load time
-      const categoryPromises = categories.map(async (category) => {
-        const categoryPath = join(this.resourcesPath, category);
-        const categoryFragments: ResourceFragment[] = [];
+      return this.providerConfigManager?.getConfig().clients ?? {};
+    } catch {
+      // Configuration failed to load; no profiles
+      return {};
+    }
+  }
 
-        try {
-          await fs.access(categoryPath);
-          await this._scanForFragments(
-            categoryPath,
-            category,
-            category,
-            categoryFragments,
-          );
-        } catch (error) {
-          this.logger.debug(`Category directory not found: ${category}`);
+  /**
+   * Upstream MCP servers from the configuration, by name
+   */
+  getUpstreams(): Record<string, UpstreamConfig> {
+    try {
+      return this.providerConfigManager?.getConfig().upstreams ?? {};
+    } catch {
+      // Configuration failed to load; no upstreams
+      return {};
+    }
+  }
+
+  /**
+   * Defaults for match parameters a URI leaves out (from a client profile)
+   */
+  setMatchDefaults(defaults: MatchDefaults): void {
+    this.uriParser.setMatchDefaults(defaults);
+    this.cache.clear();
+  }
+
+  /**
+   * Aggregate of the opt-in query log, for telemetry/summary
+   *
+   * @param limit - Entries per top list (default: 20)
+   */
+  async getQueryLogSummary(limit?: number): Promise<QueryLogSummary> {
+    return this.queryLog.summary(limit);
+  }
+
+  /**
+   * Queries that found nothing, most frequent first, with suggestions
+   *
+   * @param limit - Maximum entries (default: 50)
+   */
+  async getMisses(
+    limit?: number,
+  ): Promise<{ misses: Array<QueryMiss & { didYouMean: string[] }> }> {
+    await this.loadResourceIndex();
+    const misses = await Promise.all(
+      this.missTracker.list(limit).map(async (miss) => ({
+        ...miss,
+        didYouMean: await this.fuzzyMatcher.suggest(miss.query),
+      })),
+    );
+    return { misses };
+  }
+
+  /**
+   * Recent catalog mutations from the journal, newest first
+   *
+   * @param limit - Maximum entries (default: DEFAULT_JOURNAL_LIMIT)
+   */
+  async getJournal(limit?: number): Promise<{ entries: JournalEntry[] }> {
+    await this._ensurePinRestored();
+    return { entries: await this.journal.recent(limit) };
+  }
+
+  /**
+   * Run a reload after any reload already in progress
+   *
+   * @param pin - Snapshot to serve, evaluated when the reload starts
+   * @param prebuildBodyIndex - Build the body search index before the swap
+   *   (otherwise it is built on first body search)
+   * @private
+   */
+  private _queueReload(
+    pin: () => PinnedSnapshot | null,
+    prebuildBodyIndex: boolean,
+  ): Promise<{ generation: number; resources: number }> {
+    const run = async () => {
+      this.reloading = true;
+      try {
+        const pinned = pin();
+        const { fragments, failures } = await this._buildIndex(pinned);
+        let bodyIndex: BodyIndex | undefined;
+        if (prebuildBodyIndex) {
+          bodyIndex = new BodyIndex();
+          bodyIndex.build(fragments);
         }
+        this._swapIndex(fragments, failures, pinned, bodyIndex);
+        return { generation: this.generation, resources: fragments.length };
+      } finally {
+        this.reloading = false;
+      }
+    };
+    const result = this.reloadQueue.then(run, run);
+    this.reloadQueue = result.catch(() => undefined);
+    return result;
+  }
 
-        return categoryFragments;
-      });
+  /**
+   * Build an index, restarting builds that stall
+   *
+   * @throws {IndexStallError} If the last allowed restart stalls too
+   * @private
+   */
+  private async _buildIndex(
+    pinned: PinnedSnapshot | null,
+  ): Promise<{
+    fragments: ResourceFragment[];
+    failures: Array<{ path: string; error: string }>;
+  }> {
+    for (let attempt = 0; ; attempt++) {
+      const build = this._loadResourceIndexImpl(pinned);
+      try {
+        // Pinned snapshots load from the local state directory and report
+        // no progress, so only builds from the resources are watched
+        return pinned
+          ? await build
+          : await watchBuild(
+              build,
+              () => this.indexProgress?.state().processed ?? null,
+              this.stallTimeout,
+            );
+      } catch (error) {
+        if (!(error instanceof IndexStallError)) {
+          throw error;
+        }
+        this.indexProgress = null;
+        this.watchdog = {
+          restarts: this.watchdog.restarts + 1,
+          lastStall: {
+            at: new Date().toISOString(),
+            progress: error.progress,
+          },
+        };
+        this.eventBus?.publish("index_stalled", {
+          progress: error.progress,
+          restart: attempt < MAX_INDEX_RESTARTS,
+        });
+        if (attempt >= MAX_INDEX_RESTARTS) {
+          this.logger.error(`${error.message}; giving up`);
+          throw error;
+        }
+        this.logger.warn(`${error.message}; restarting the build`);
+      }
+    }
+  }
 
-      // Wait for all categories to be scanned
-      const fragmentArrays = await Promise.all(categoryPromises);
+  /**
+   * Make a fully built index current (synchronous, so never half-applied)
+   * @private
+   */
+  private _swapIndex(
+    fragments: ResourceFragment[],
+    failures: Array<{ path: string; error: string }>,
+    pinned: PinnedSnapshot | null,
+    bodyIndex?: BodyIndex,
+  ): void {
+    const isReload = this.resourceIndex !== null;
+
+    this.pinned = pinned;
+    this.resourceIndex = fragments;
+    const validateStart = performance.now();
+    this.aliases = this._collectAliases(fragments, failures);
+    if (this.startupProfile) {
+      this.startupProfile.add(
+        "index.validate",
+        performance.now() - validateStart,
+      );
+      this.startupProfile.count("resources", fragments.length);
+      this.startupProfile.count("failures", failures.length);
+      this.startupProfile = null;
+    }
+    this.scanFailures = failures;
+    this.fuzzyMatcher.setResourceIndex(fragments);
+    if (bodyIndex) {
+      this.bodyIndex = bodyIndex;
+      this.bodyIndexSource = fragments;
+    }
+    if (isReload) {
+      this.cache.clear();
+      this.partialCache.clear();
+      this.partialDependents.clear();
+      // Rescan the staged catalog too, on the next canary query
+      this.canaryMatcher = null;
+    }
+    this.generation++;
+    this.loadedAt = new Date().toISOString();
 
-      // Flatten results
-      const fragments = fragmentArrays.flat();
+    this.logger.info(
+      `Resource index generation ${this.generation} loaded with ${fragments.length} fragments`,
+    );
+    this.eventBus?.publish("index_loaded", {
+      fragments: fragments.length,
+      generation: this.generation,
+    });
+  }
 
-      // Set the index in the fuzzy matcher
-      this.fuzzyMatcher.setResourceIndex(fragments);
+  /**
+   * Internal implementation of resource index loading
+   * OPTIMIZED: Parallel directory scanning for faster initial load
+   * @private
+   */
+  private async _loadResourceIndexImpl(
+    pinned: PinnedSnapshot | null,
+  ): Promise<{
+    fragments: ResourceFragment[];
+    failures: Array<{ path: string; error: string }>;
+  }> {
+    const failures: Array<{ path: string; error: string }> = [];
+    if (pinned) {
+      return {
+        fragments: await this._loadPinnedIndex(pinned.name, failures),
+        failures,
+      };
+    }
+    let progress: IndexProgress | null = null;
+    try {
+      const layers = await this._profiled("layers", () => this._getLayers());
+      const manifest = await this._profiled("walk", () =>
+        this._readIndexManifest(),
+      );
+      const merged = new Map<string, ResourceFragment>();
+      const files = await this._profiled("walk", () =>
+        this._countIndexFiles(layers, manifest),
+      );
+      this.startupProfile?.count("files", files);
+      progress = new IndexProgress(
+        files,
+        (state) => {
+          this.logger.info(
+            `Indexing resources: ${state.processed}/${state.total} files (${state.percent}%)`,
+          );
+          this.indexProgressListener?.(state);
+        },
+      );
+      this.indexProgress = progress;
+
+      // Later layers override earlier ones by id
+      for (const layer of layers) {
+        const scanned = await this._profiled("scan", () =>
+          manifest && layer.name === "builtin"
+            ? this._scanManifest(layer, manifest, failures)
+            : this._scanLayer(layer, failures),
+        );
+        for (const fragment of scanned) {
+          const previous = merged.get(fragment.id)?.origin;
+          if (previous) {
+            fragment.origin!.overrides = [
+              ...previous.overrides,
+              { layer: previous.layer, sourceDir: previous.sourceDir },
+            ];
+            this.logger.debug(
+              `Layer ${layer.name} overrides ${fragment.id} from ${previous.layer}`,
+            );
+          }
+          merged.set(fragment.id, fragment);
+        }
+      }
 
-      return fragments;
+      progress.finish();
+      return { fragments: [...merged.values()], failures };
     } catch (error) {
       this.logger.error("Error loading resource index:", error);
-      return [];
+      return { fragments: [], failures };
+    } finally {
+      // A build the watchdog abandoned may finish after its replacement
+      // started
+      if (this.indexProgress === progress) {
+        this.indexProgress = null;
+      }
+    }
+  }
+
+  /**
+   * Index manifest from --index-manifest, re-read on every build; null
+   * without one or when it cannot be used (directories are walked instead)
+   * @private
+   */
+  private async _readIndexManifest(): Promise<IndexManifest | null> {
+    if (!this.indexManifestPath) {
+      return null;
+    }
+    try {
+      return await readIndexManifest(this.indexManifestPath);
+    } catch (error) {
+      this.logger.error(
+        "Index manifest unusable; scanning resource directories",
+        error,
+      );
+      return null;
+    }
+  }
+
+  /**
+   * Number of markdown files the index build will parse
+   * @private
+   */
+  private async _countIndexFiles(
+    layers: ResourceLayer[],
+    manifest: IndexManifest | null,
+  ): Promise<number> {
+    const count = async (dirPath: string): Promise<number> => {
+      let entries;
+      try {
+        entries = await fs.readdir(dirPath, { withFileTypes: true });
+      } catch {
+        return 0;
+      }
+      let total = 0;
+      for (const entry of entries) {
+        const fullPath = join(dirPath, entry.name);
+        if (await isDirectoryEntry(entry, fullPath)) {
+          total += await count(fullPath);
+        } else if (entry.name.endsWith(".md")) {
+          total++;
+        }
+      }
+      return total;
+    };
+
+    let total = 0;
+    for (const layer of layers) {
+      if (manifest && layer.name === "builtin") {
+        total += this._manifestFragmentFiles(manifest).length;
+        continue;
+      }
+      for (const category of FRAGMENT_CATEGORIES) {
+        total += await count(join(layer.path, category));
+      }
+    }
+    return total;
+  }
+
+  /**
+   * Resource layers, lowest priority first (see utils/layers.ts)
+   * @private
+   */
+  private _getLayers(): Promise<ResourceLayer[]> {
+    if (!this.layers) {
+      this.layers = resourceLayers(this.resourcesPath);
+    }
+    return this.layers;
+  }
+
+  /**
+   * Scan the category directories of one layer
+   * OPTIMIZED: Parallel directory scanning for faster initial load
+   * @private
+   */
+  private async _scanLayer(
+    layer: ResourceLayer,
+    failures: Array<{ path: string; error: string }>,
+  ): Promise<ResourceFragment[]> {
+    // Parallel scan all categories - reduces initial load time
+    const categoryPromises = FRAGMENT_CATEGORIES.map(async (category) => {
+      const categoryPath = join(layer.path, category);
+      const categoryFragments: ResourceFragment[] = [];
+
+      try {
+        await fs.access(categoryPath);
+        await this._scanForFragments(
+          categoryPath,
+          category,
+          category,
+          categoryFragments,
+          failures,
+          layer,
+        );
+      } catch (error) {
+        this.logger.debug(
+          `Category directory not found: ${category} (${layer.name})`,
+        );
+      }
+
+      return categoryFragments;
+    });
+
+    // Wait for all categories to be scanned
+    return (await Promise.all(categoryPromises)).flat();
+  }
+
+  /**
+   * Resource files of a manifest that belong to a category
+   * @private
+   */
+  private _manifestFragmentFiles(manifest: IndexManifest): SnapshotFile[] {
+    const categories = new Set(FRAGMENT_CATEGORIES);
+    return manifest.files.filter(
+      (file) =>
+        categories.has(file.path.split("/")[0]) && file.path.endsWith(".md"),
+    );
+  }
+
+  /**
+   * Parse the files an index manifest lists, without listing directories
+   * @private
+   */
+  private async _scanManifest(
+    layer: ResourceLayer,
+    manifest: IndexManifest,
+    failures: Array<{ path: string; error: string }>,
+  ): Promise<ResourceFragment[]> {
+    const fail = (path: string, error: any) => {
+      this.logger.warn(`Failed to parse resource: ${path}`, error);
+      const failure = { path, error: error?.message ?? String(error) };
+      failures.push(failure);
+      this.eventBus?.publish("validation_failed", failure);
+    };
+
+    // _defaults.yaml files by directory
+    const defaults = new Map<string, FrontmatterDefaults>();
+    for (const file of manifest.files) {
+      if (!file.path.endsWith(`/${DEFAULTS_FILE}`)) {
+        continue;
+      }
+      try {
+        defaults.set(
+          file.path.slice(0, -DEFAULTS_FILE.length - 1),
+          parseDefaults(
+            await fs.readFile(join(layer.path, file.path), "utf-8"),
+          ),
+        );
+      } catch (error) {
+        fail(file.path, error);
+      }
+    }
+
+    const fragments: ResourceFragment[] = [];
+    let stale = 0;
+    for (const file of this._manifestFragmentFiles(manifest)) {
+      const fullPath = join(layer.path, file.path);
+      try {
+        const content = await this._profiled("read", () =>
+          fs.readFile(fullPath, "utf-8"),
+        );
+        const hash = createHash("sha256").update(content).digest("hex");
+        if (hash !== file.hash) {
+          stale++;
+        }
+        const fragment = await this._profiled("parse", () =>
+          this._parseResourceFragment(
+            content,
+            file.path.split("/")[0],
+            file.path,
+            inheritedDefaults(file.path, defaults),
+          ),
+        );
+        if (file.modifiedAt) {
+          fragment.modifiedAt = file.modifiedAt;
+        }
+        fragment.origin = {
+          layer: layer.name,
+          sourceDir: layer.path,
+          filePath: fullPath,
+          hash,
+          overrides: [],
+        };
+        fragments.push(fragment);
+      } catch (error) {
+        fail(file.path, error);
+      }
+      this.indexProgress?.advance();
+    }
+
+    if (stale > 0) {
+      this.logger.warn(
+        `${stale} files changed since index manifest ${this.indexManifestPath} was generated; regenerate it with orchestr8 manifest`,
+      );
     }
+    return fragments;
+  }
+
+  /**
+   * Manifest of the resource files, for --index-manifest
+   *
+   * Lists every resource and _defaults.yaml file in the category
+   * directories of RESOURCES_PATH (other layers are not included).
+   */
+  async buildIndexManifest(): Promise<IndexManifest> {
+    const files: Array<{ path: string; content: string; modifiedAt?: string }> =
+      [];
+    await this._collectResourceFiles(this.resourcesPath, "", files);
+    const categories = new Set(FRAGMENT_CATEGORIES);
+    return {
+      version: INDEX_MANIFEST_VERSION,
+      generatedAt: new Date().toISOString(),
+      files: files
+        .filter((file) => categories.has(file.path.split("/")[0]))
+        .map((file) => ({
+          path: file.path,
+          hash: createHash("sha256").update(file.content).digest("hex"),
+          size: Buffer.byteLength(file.content),
+          ...(file.modifiedAt ? { modifiedAt: file.modifiedAt } : {}),
+        })),
+    };
   }
 
   /**
@@ -371,51 +1146,110 @@ export class ResourceLoader {
     category: string,
     relativePath: string,
     fragments: ResourceFragment[],
+    failures: Array<{ path: string; error: string }>,
+    layer: ResourceLayer,
+    inherited: FrontmatterDefaults = {},
   ): Promise<void> {
-    const entries = await fs.readdir(dirPath, { withFileTypes: true });
+    const entries = sortByName(
+      await this._profiled("walk", () =>
+        fs.readdir(dirPath, { withFileTypes: true }),
+      ),
+    );
+
+    // _defaults.yaml applies to this directory and everything below it
+    let defaults = inherited;
+    if (entries.some((entry) => entry.name === DEFAULTS_FILE)) {
+      const defaultsPath = join(relativePath, DEFAULTS_FILE);
+      try {
+        defaults = {
+          ...inherited,
+          ...parseDefaults(
+            await fs.readFile(join(dirPath, DEFAULTS_FILE), "utf-8"),
+          ),
+        };
+      } catch (error: any) {
+        this.logger.warn(`Failed to parse defaults: ${defaultsPath}`, error);
+        const failure = {
+          path: defaultsPath,
+          error: error?.message ?? String(error),
+        };
+        failures.push(failure);
+        this.eventBus?.publish("validation_failed", failure);
+      }
+    }
 
     for (const entry of entries) {
       const fullPath = join(dirPath, entry.name);
       const newRelativePath = join(relativePath, entry.name);
 
-      if (entry.isDirectory()) {
-        // Recursively scan subdirectories
+      if (await isDirectoryEntry(entry, fullPath)) {
+        // Recursively scan subdirectories (and symlinked ones)
         await this._scanForFragments(
           fullPath,
           category,
           newRelativePath,
           fragments,
+          failures,
+          layer,
+          defaults,
         );
       } else if (entry.name.endsWith(".md")) {
         // Parse markdown file
         try {
-          const content = await fs.readFile(fullPath, "utf-8");
-          const fragment = await this._parseResourceFragment(
-            content,
-            category,
-            newRelativePath,
+          const [content, stat] = await this._profiled("read", () =>
+            Promise.all([fs.readFile(fullPath, "utf-8"), fs.stat(fullPath)]),
+          );
+          const fragment = await this._profiled("parse", () =>
+            this._parseResourceFragment(
+              content,
+              category,
+              newRelativePath,
+              defaults,
+            ),
           );
+          fragment.modifiedAt = stat.mtime.toISOString();
+          fragment.origin = {
+            layer: layer.name,
+            sourceDir: layer.path,
+            filePath: fullPath,
+            hash: createHash("sha256").update(content).digest("hex"),
+            overrides: [],
+          };
           fragments.push(fragment);
           this.logger.debug(`Parsed fragment: ${fragment.id}`);
-        } catch (error) {
+        } catch (error: any) {
           this.logger.warn(`Failed to parse resource: ${fullPath}`, error);
+          const failure = {
+            path: newRelativePath,
+            error: error?.message ?? String(error),
+          };
+          failures.push(failure);
+          this.eventBus?.publish("validation_failed", failure);
         }
+        this.indexProgress?.advance();
       }
     }
   }
 
   /**
    * Parse a markdown file into a ResourceFragment
+   *
+   * @param defaults - Fields from _defaults.yaml files above the file; its
+   *   own frontmatter wins
    * @private
    */
   private async _parseResourceFragment(
     content: string,
     category: string,
     relativePath: string,
+    defaults: FrontmatterDefaults = {},
   ): Promise<ResourceFragment> {
-    // Parse frontmatter if present
+    // Parse frontmatter if present (scalar or null YAML has no fields)
     const parsed = matter(content);
-    const frontmatter = parsed.data;
+    const frontmatter: any = {
+      ...defaults,
+      ...(parsed.data && typeof parsed.data === "object" ? parsed.data : {}),
+    };
     const body = parsed.content;
 
     // Extract metadata from frontmatter or use defaults
@@ -431,6 +1265,7 @@ export class ResourceLoader {
 
     // Map category to ResourceFragment category type
     const fragmentCategory = this._mapCategory(category);
+    const capabilityMatrix = parseCapabilityMatrix(frontmatter);
 
     return {
       id,
@@ -440,9 +1275,36 @@ export class ResourceLoader {
       useWhen,
       estimatedTokens,
       content: body,
+      ...(typeof frontmatter.model === "string"
+        ? { model: frontmatter.model }
+        : {}),
+      ...(typeof frontmatter.minConfidence === "number"
+        ? { minConfidence: frontmatter.minConfidence }
+        : {}),
+      ...(frontmatter.fallback === true ? { fallback: true } : {}),
+      ...(frontmatter.aliases
+        ? { aliases: this._aliasIds(frontmatter.aliases, id) }
+        : {}),
+      ...(capabilityMatrix ? { capabilityMatrix } : {}),
+      ...(frontmatter.handoff_to
+        ? { handoffs: parseHandoffs(frontmatter.handoff_to, id) }
+        : {}),
     };
   }
 
+  /**
+   * Alias ids from frontmatter: bare names are in the resource's category
+   * directory ("old-name" on agents/new-name is agents/old-name)
+   * @private
+   */
+  private _aliasIds(aliases: unknown, id: string): string[] {
+    const category = id.split("/")[0];
+    return (Array.isArray(aliases) ? aliases : [aliases])
+      .map((alias) => String(alias).trim().replace(/\.md$/, ""))
+      .filter(Boolean)
+      .map((alias) => (alias.includes("/") ? alias : `${category}/${alias}`));
+  }
+
   /**
    * Extract tags from frontmatter or content
    * @private
@@ -450,7 +1312,7 @@ export class ResourceLoader {
   private _extractTags(frontmatter: any, content: string): string[] {
     // Check frontmatter first
     if (frontmatter.tags && Array.isArray(frontmatter.tags)) {
-      return frontmatter.tags.map((tag: any) => String(tag).toLowerCase());
+      return frontmatter.tags.map((tag: any) => foldCase(String(tag)));
     }
 
     // Fallback: extract from content headers and keywords
@@ -459,10 +1321,7 @@ export class ResourceLoader {
     // Extract from title (first # heading)
     const titleMatch = content.match(/^#\s+(.+)$/m);
     if (titleMatch) {
-      const words = titleMatch[1]
-        .toLowerCase()
-        .replace(/[^\w\s-]/g, "")
-        .split(/\s+/);
+      const words = tokenizeText(titleMatch[1]);
       words.forEach((word) => {
         if (word.length > 2) tags.add(word);
       });
@@ -602,9 +1461,35 @@ export class ResourceLoader {
    * loadResourceContent("o8://agents/match?query=build+api&maxTokens=2000")
    */
   async loadResourceContent(uri: string): Promise<string> {
+    const generation = this.generation;
+    const content = await this._loadResourceContent(uri);
+    if (generation !== this.generation) {
+      // Loaded across a catalog swap: don't keep it cached for the new one
+      this.cache.delete(uri);
+    }
+    return content;
+  }
+
+  /**
+   * Load resource content (see loadResourceContent)
+   * @private
+   */
+  private async _loadResourceContent(uri: string): Promise<string> {
     // Check cache first (for static URIs)
     if (this.cache.has(uri)) {
       this.logger.debug(`Cache hit for resource: ${uri}`);
+      const match = this.matchOutcomes.get(uri);
+      if (match) {
+        this.queryLog.record(
+          match.query,
+          match.results,
+          "cache",
+          match.category,
+        );
+        if (match.results === 0) {
+          this.missTracker.record(match.query);
+        }
+      }
       return this.cache.get(uri)!;
     }
 
@@ -748,16 +1633,257 @@ export class ResourceLoader {
     // Local resource (o8:// URIs only - providers handled in parent)
     // ============================================================================
 
-    // Parse URI to file path
-    const filePath = this.uriToFilePath(uri);
-
-    const content = await fs.readFile(filePath, "utf-8");
-    this.cache.set(uri, content);
-    this.logger.debug(`Cached static resource content: ${uri}`);
-    return content;
-  }
-
-  /**
+    // Parse URI to file path, in the highest layer that has the file.
+    // Renamed resources stay reachable under their aliases.
+    let raw: string;
+    try {
+      raw = await this._readResourceFile(await this._layeredFilePath(uri));
+    } catch (error: any) {
+      const canonical =
+        error?.code === "ENOENT" ? await this.resolveAlias(uri) : null;
+      if (!canonical) {
+        throw error;
+      }
+      this.logger.debug(`Resolved alias ${uri} to ${canonical}`);
+      raw = await this._readResourceFile(
+        await this._layeredFilePath(`o8://${canonical}`),
+      );
+    }
+    let content: string;
+    try {
+      const expanded = await this._expandPartials(raw, uri);
+      content = this._renderTemplate(expanded, parsed.variables || {});
+    } catch (error: any) {
+      this.eventBus?.publish("validation_failed", {
+        uri,
+        error: error?.message ?? String(error),
+      });
+      throw error;
+    }
+    if (parsed.section) {
+      const body = matter(content).content;
+      const section = findSection(body, parsed.section);
+      if (!section) {
+        throw new SectionNotFoundError(
+          parsed.section,
+          parseSections(body).map((s) => s.heading),
+        );
+      }
+      content = section.content;
+    }
+    this.cache.set(uri, content);
+    this.logger.debug(`Cached static resource content: ${uri}`);
+    return content;
+  }
+
+  /**
+   * Expand {{> name}} partial includes in the resource body
+   *
+   * Records which partials each URI depends on so the cached content can be
+   * invalidated when a partial changes.
+   *
+   * @throws {TemplateSyntaxError} If a partial is missing or includes form a cycle
+   * @private
+   */
+  private async _expandPartials(content: string, uri: string): Promise<string> {
+    if (!this.templateEngine.hasPartials(content)) {
+      return content;
+    }
+
+    const parsed = matter(content);
+    const header = content.slice(0, content.length - parsed.content.length);
+    const used = new Set<string>();
+
+    const body = await this.templateEngine.expandPartials(
+      parsed.content,
+      async (name) => {
+        used.add(name);
+        return this._loadPartial(name);
+      },
+    );
+
+    for (const name of used) {
+      if (!this.partialDependents.has(name)) {
+        this.partialDependents.set(name, new Set());
+      }
+      this.partialDependents.get(name)!.add(uri);
+    }
+
+    return header + body;
+  }
+
+  /**
+   * Load a partial from the partials/ directory (cached)
+   * @private
+   */
+  private async _loadPartial(name: string): Promise<string> {
+    const cached = this.partialCache.get(name);
+    if (cached !== undefined) {
+      return cached;
+    }
+
+    // Resolved, so `..\` on Windows and absolute names are caught too
+    const partialsPath = resolve(this.resourcesPath, "partials");
+    const filePath = resolve(partialsPath, `${name}.md`);
+    if (!isWithin(partialsPath, filePath)) {
+      throw new TemplateSyntaxError(`invalid partial name "${name}"`);
+    }
+    let raw: string;
+    try {
+      raw = await this._readResourceFile(filePath);
+    } catch (error) {
+      throw new TemplateSyntaxError(`unknown partial "${name}"`);
+    }
+
+    const content = matter(raw).content.replace(/\n+$/, "");
+    this.partialCache.set(name, content);
+    return content;
+  }
+
+  /**
+   * Invalidate a partial and every cached resource that includes it
+   */
+  invalidatePartial(name: string): void {
+    this.partialCache.delete(name);
+
+    const dependents = this.partialDependents.get(name);
+    if (dependents) {
+      for (const uri of dependents) {
+        this.cache.delete(uri);
+      }
+      this.partialDependents.delete(name);
+      this.logger.debug(
+        `Invalidated partial ${name} and ${dependents.size} dependent resources`,
+      );
+    }
+
+    this.eventBus?.publish("resource_invalidated", {
+      partial: name,
+      uris: dependents ? Array.from(dependents) : [],
+    });
+  }
+
+  /**
+   * Watch the partials/ directory and invalidate dependents on change
+   *
+   * @param settings - Native events or polling (default: from
+   *   --watch-mode and --poll-interval)
+   */
+  watchPartials(settings: WatchSettings = watchSettings()): void {
+    if (this.partialWatcher) {
+      return; // Already watching
+    }
+
+    const partialsPath = join(this.resourcesPath, "partials");
+    const treeSettings = settingsForTree(settings, partialsPath);
+    if (treeSettings.mode !== settings.mode) {
+      this.logger.warn(
+        `Partials need more than ${settings.maxDescriptors} watch descriptors; polling instead`,
+      );
+    }
+    this.partialWatcher = chokidar.watch(
+      partialsPath,
+      chokidarOptions(treeSettings),
+    );
+
+    this.partialWatcher.on("all", (event: string, filePath: string) => {
+      if (!filePath.endsWith(".md")) return;
+      const name = relative(partialsPath, filePath)
+        .replace(/\\/g, "/")
+        .replace(/\.md$/, "");
+      this.logger.info(`Partial ${event}: ${name}`);
+      this.invalidatePartial(name);
+    });
+
+    this.logger.info(
+      `Hot reload enabled for partials (${treeSettings.mode})`,
+    );
+  }
+
+  /**
+   * Stop watching the partials/ directory
+   */
+  async stopWatchingPartials(): Promise<void> {
+    if (this.partialWatcher) {
+      await this.partialWatcher.close();
+      this.partialWatcher = undefined;
+    }
+  }
+
+  /**
+   * Render template variables declared in frontmatter
+   *
+   * Resources without a `variables` declaration are returned unchanged.
+   * The frontmatter block is preserved; only the body is rendered.
+   *
+   * @throws {TemplateError} If provided variables fail validation
+   * @private
+   */
+  private _renderTemplate(
+    content: string,
+    variables: Record<string, string>,
+  ): string {
+    const parsed = matter(content);
+    const declared = this.templateEngine.parseDeclarations(parsed.data);
+
+    if (declared.length === 0) {
+      return content;
+    }
+
+    const header = content.slice(0, content.length - parsed.content.length);
+    return (
+      header + this.templateEngine.render(parsed.content, declared, variables)
+    );
+  }
+
+  /**
+   * Matcher over the staged canary catalog, scanned on first use
+   * @private
+   */
+  private _getCanaryMatcher(canary: CanaryOptions): Promise<FuzzyMatcher> {
+    if (!this.canaryMatcher) {
+      this.canaryMatcher = this._scanLayer(
+        { name: "canary", path: canary.dir },
+        [],
+      ).then((fragments) => {
+        this.logger.info(
+          `Canary catalog ${canary.dir} loaded with ${fragments.length} fragments`,
+        );
+        const matcher = new FuzzyMatcher();
+        matcher.setResourceIndex(fragments);
+        return matcher;
+      });
+    }
+    return this.canaryMatcher;
+  }
+
+  /**
+   * Match a canary query against the live catalog once the response has
+   * been returned, and log any top-k disagreement
+   * @private
+   */
+  private _compareWithLive(
+    canary: CanaryOptions,
+    request: MatchRequest,
+    canaryResult: MatchResult,
+  ): void {
+    setImmediate(async () => {
+      try {
+        const live = await this.fuzzyMatcher.match(request);
+        recordCanaryDiff(
+          canary.logPath,
+          request.query,
+          live.fragments.map((fragment) => fragment.id),
+          canaryResult.fragments.map((fragment) => fragment.id),
+          request.maxResults || 15,
+        );
+      } catch (error) {
+        this.logger.warn("Canary comparison failed:", error);
+      }
+    });
+  }
+
+  /**
    * Load dynamic resource (index lookup or fuzzy matching)
    * @private
    */
@@ -767,16 +1893,28 @@ export class ResourceLoader {
   ): Promise<string> {
     this.logger.info(`Dynamic resource request: ${uri}`);
 
+    const snapshot = await this._resolveSnapshot(parsed.matchParams);
+    const canary =
+      !snapshot &&
+      this.canary &&
+      inCanary(parsed.matchParams.query, this.canary.percent)
+        ? this.canary
+        : null;
+
     // Feature flag: Use index lookup vs fuzzy match
     // - Default mode is 'index' for optimal efficiency (70-85% token reduction)
     // - Explicitly set mode to 'catalog' or 'full' in URI to use fuzzy match
     // - Environment variable USE_INDEX_LOOKUP can override (but defaults to true)
     const useIndexLookup =
       parsed.matchParams.mode !== "catalog" && // Only use catalog if explicitly requested
-      parsed.matchParams.mode !== "full";
+      parsed.matchParams.mode !== "full" &&
+      !parsed.matchParams.capabilities && // The useWhen index has no capabilities
+      !canary && // The useWhen index is built from the live catalog
+      !snapshot;
 
     if (useIndexLookup) {
       // NEW: Index-based lookup (85-95% token reduction)
+      // timeoutMs does not apply: the lookup never scores the whole catalog
       this.logger.debug("Using index-based lookup");
 
       try {
@@ -788,9 +1926,8 @@ export class ResourceLoader {
           mode: parsed.matchParams.mode,
         });
 
-        // Cache the result
-        this.cache.set(uri, result);
-
+        // Not cached here: repeats are answered from the lookup's own quick
+        // cache, which still reports them to the query log
         return result;
       } catch (error) {
         this.logger.warn(
@@ -808,16 +1945,43 @@ export class ResourceLoader {
     await this.loadResourceIndex();
 
     // Perform fuzzy matching
-    const matchResult = await this.fuzzyMatcher.match({
+    const request: MatchRequest = {
       query: parsed.matchParams.query,
       maxTokens: parsed.matchParams.maxTokens,
       requiredTags: parsed.matchParams.tags,
+      requiredCapabilities: parsed.matchParams.capabilities,
       category: parsed.category, // Category from URI path (e.g., o8://agents/match?)
       categories: parsed.matchParams.categories, // Categories from query param (e.g., ?categories=agent,skill)
       mode: parsed.matchParams.mode || "catalog", // 'full' or 'catalog'
       maxResults: parsed.matchParams.maxResults, // Max results for catalog mode
       minScore: parsed.matchParams.minScore, // Minimum relevance score threshold
-    });
+      timeoutMs: parsed.matchParams.timeoutMs, // Return best-so-far if scoring runs long
+      fields: parsed.matchParams.fields, // Sparse fieldset for minimal mode
+    };
+    const matcher = snapshot
+      ? (await this._getSnapshotCatalog(snapshot)).matcher
+      : canary
+        ? await this._getCanaryMatcher(canary)
+        : this.fuzzyMatcher;
+    const matchResult = await matcher.match(request);
+    if (canary) {
+      this._compareWithLive(canary, request, matchResult);
+    }
+
+    const outcome: MatchOutcome = {
+      query: parsed.matchParams.query,
+      results: matchResult.noConfidentMatch ? 0 : matchResult.fragments.length,
+      category: parsed.category,
+    };
+    this.queryLog.record(
+      outcome.query,
+      outcome.results,
+      "fuzzy",
+      outcome.category,
+    );
+    if (matchResult.noConfidentMatch) {
+      this.missTracker.record(parsed.matchParams.query);
+    }
 
     // ============================================================================
     // NEW: Track token usage for dynamic resource loads
@@ -852,8 +2016,11 @@ export class ResourceLoader {
       }
     }
 
-    // Cache the assembled content
-    this.cache.set(uri, matchResult.assembledContent);
+    // Cache the assembled content, unless the deadline cut scoring short
+    if (!matchResult.partial) {
+      this.cache.set(uri, matchResult.assembledContent);
+      this.matchOutcomes.set(uri, outcome);
+    }
 
     this.logger.info(
       `Assembled ${matchResult.fragments.length} fragments (${matchResult.totalTokens} tokens)`,
@@ -862,12 +2029,49 @@ export class ResourceLoader {
     return matchResult.assembledContent;
   }
 
+  /**
+   * File for a static URI in the highest layer that has it
+   *
+   * Pinned snapshots hold the built-in directory only, so they always
+   * resolve there.
+   * @private
+   */
+  private async _layeredFilePath(uri: string): Promise<string> {
+    const filePath = this.uriToFilePath(uri);
+    await this._ensurePinRestored();
+    if (this.pinned) {
+      return filePath;
+    }
+    const relativePath = relative(this.resourcesPath, filePath);
+    const layers = [...(await this._getLayers())].reverse();
+    for (const layer of layers) {
+      const candidate = join(layer.path, relativePath);
+      if (!isWithin(layer.path, candidate)) {
+        continue;
+      }
+      try {
+        await fs.access(candidate);
+        return candidate;
+      } catch {
+        // Try the next layer down
+      }
+    }
+    // Names differing only in case (Rust-Expert for rust-expert.md)
+    for (const layer of layers) {
+      const candidate = await findCaseInsensitive(layer.path, relativePath);
+      if (candidate && isWithin(layer.path, candidate)) {
+        return candidate;
+      }
+    }
+    return filePath;
+  }
+
   /**
    * Convert URI to filesystem path
    */
   private uriToFilePath(uri: string): string {
-    // Remove protocol
-    const pathPart = uri.replace("o8://", "");
+    // Remove protocol and template variables
+    const pathPart = uri.replace("o8://", "").split("?")[0];
 
     // Try different extensions
     const extensions = [".md", ".json", ".yaml"];
@@ -907,6 +2111,7 @@ export class ResourceLoader {
         tags: fragment.tags || [],
         capabilities: fragment.capabilities || [],
         tokens: fragment.estimatedTokens,
+        ...withProvenance(fragment),
       }));
   }
 
@@ -920,16 +2125,16 @@ export class ResourceLoader {
       return [];
     }
 
-    const lowerQuery = query.toLowerCase();
+    const lowerQuery = foldCase(query);
 
     return this.resourceIndex
       .filter((fragment) => {
-        const idMatch = fragment.id.toLowerCase().includes(lowerQuery);
+        const idMatch = foldCase(fragment.id).includes(lowerQuery);
         const tagMatch = fragment.tags?.some((tag: string) =>
-          tag.toLowerCase().includes(lowerQuery),
+          foldCase(tag).includes(lowerQuery),
         );
         const capMatch = fragment.capabilities?.some((cap: string) =>
-          cap.toLowerCase().includes(lowerQuery),
+          foldCase(cap).includes(lowerQuery),
         );
         return idMatch || tagMatch || capMatch;
       })
@@ -940,10 +2145,967 @@ export class ResourceLoader {
         tags: fragment.tags || [],
         capabilities: fragment.capabilities || [],
         tokens: fragment.estimatedTokens,
+        ...withProvenance(fragment),
       }))
       .slice(0, 50); // Limit results
   }
 
+  /**
+   * Full-text search inside resource bodies
+   *
+   * The body index is built on first use and rebuilt whenever the resource
+   * index is reloaded.
+   *
+   * @param query - Free-text query; every term must appear in the body
+   * @param options - Category filter ('agents' or 'agent'), limit, snippets
+   * @returns Hits with highlighted snippets
+   */
+  async searchBodies(
+    query: string,
+    options: Omit<BodySearchOptions, "category"> & { category?: string } = {},
+  ): Promise<BodySearchHit[]> {
+    await this.ensureIndexLoaded();
+
+    if (!this.resourceIndex) {
+      return [];
+    }
+
+    if (this.bodyIndexSource !== this.resourceIndex) {
+      this.bodyIndex.build(this.resourceIndex);
+      this.bodyIndexSource = this.resourceIndex;
+      this.logger.debug(
+        `Body index built for ${this.bodyIndex.size} fragments`,
+      );
+    }
+
+    const category = this._normalizeCategoryFilter(options.category);
+    return this.bodyIndex.search(query, { ...options, category });
+  }
+
+  /**
+   * Every line of the resource files matching a regular expression
+   *
+   * Files are searched in id order, frontmatter included.
+   *
+   * @param pattern - JavaScript regular expression source
+   * @param options - Category filter (default: agents), case-insensitive
+   *   matching, and maximum number of hits
+   * @returns Hits, and whether more lines matched than the limit
+   * @throws {GrepPatternError} If the pattern is not a valid expression
+   */
+  async grepResources(
+    pattern: string,
+    options: { category?: string; ignoreCase?: boolean; limit?: number } = {},
+  ): Promise<{ hits: GrepHit[]; truncated: boolean }> {
+    const regex = compilePattern(pattern, options);
+    const limit = options.limit ?? DEFAULT_GREP_LIMIT;
+    const category = this._normalizeCategoryFilter(
+      options.category ?? "agents",
+    );
+    await this.ensureIndexLoaded();
+
+    const fragments = (this.resourceIndex || [])
+      .filter((fragment) => fragment.category === category && fragment.origin)
+      .sort((a, b) => compareStrings(a.id, b.id));
+    const hits: GrepHit[] = [];
+    for (const fragment of fragments) {
+      if (hits.length > limit) {
+        break;
+      }
+      let content: string;
+      try {
+        content = await this._readResourceFile(fragment.origin!.filePath);
+      } catch (error) {
+        this.logger.debug(`Skipping ${fragment.id} in grep:`, error);
+        continue;
+      }
+      // One hit past the limit tells whether the result is complete
+      hits.push(
+        ...grepLines(fragment.id, content, regex, limit + 1 - hits.length),
+      );
+    }
+    return { hits: hits.slice(0, limit), truncated: hits.length > limit };
+  }
+
+  /**
+   * Draw a random or stratified sample of resources for spot checks
+   *
+   * Without a seed a fresh one is chosen (0 in deterministic mode); the seed
+   * is returned so the same sample can be requested again.
+   *
+   * @param options - count, perCategory (stratified), category filter, seed
+   * @returns Seed and sampled resource summaries
+   */
+  async sampleResources(
+    options: {
+      count?: number;
+      perCategory?: number;
+      category?: string;
+      seed?: number;
+    } = {},
+  ): Promise<{ seed: number; resources: any[] }> {
+    await this.ensureIndexLoaded();
+
+    const seed =
+      options.seed ??
+      (isDeterministic() ? 0 : Math.floor(Math.random() * 0x100000000));
+    const category = this._normalizeCategoryFilter(options.category);
+    const population = (this.resourceIndex || []).filter(
+      (fragment) => !category || fragment.category === category,
+    );
+
+    const resources = sampleItems(population, {
+      count: options.count,
+      perCategory: options.perCategory,
+      categoryOf: (fragment) => fragment.category,
+      seed,
+    }).map((fragment) => ({
+      id: fragment.id,
+      uri: `o8://${fragment.id}`,
+      category: fragment.category,
+      tags: fragment.tags || [],
+      capabilities: fragment.capabilities || [],
+      tokens: fragment.estimatedTokens,
+      ...withProvenance(fragment),
+    }));
+
+    return { seed, resources };
+  }
+
+  /**
+   * Stream resource summaries one at a time
+   *
+   * With a query, yields resources scoring at least minScore in catalog
+   * order (unsorted, no result limit); without one, yields the whole
+   * catalog. Nothing is collected, so exports of large catalogs start
+   * immediately and use constant memory.
+   *
+   * @param options - Optional query, category filter, and minimum score
+   * @returns Async iterator of resource summaries
+   */
+  async *streamResources(
+    options: { query?: string; category?: string; minScore?: number } = {},
+  ): AsyncGenerator<Record<string, unknown>> {
+    const index = await this.loadResourceIndex();
+    const category = this._normalizeCategoryFilter(options.category);
+    const summarize = (fragment: ResourceFragment) => ({
+      id: fragment.id,
+      uri: `o8://${fragment.id}`,
+      category: fragment.category,
+      tags: fragment.tags || [],
+      capabilities: fragment.capabilities || [],
+      tokens: fragment.estimatedTokens,
+      ...withProvenance(fragment),
+    });
+
+    if (!options.query) {
+      for (const fragment of index) {
+        if (!category || fragment.category === category) {
+          yield summarize(fragment);
+        }
+      }
+      return;
+    }
+
+    for await (const { resource, score } of this.fuzzyMatcher.scoreEach({
+      query: options.query,
+      minScore: options.minScore,
+    })) {
+      if (!category || resource.category === category) {
+        yield { ...summarize(resource), score };
+      }
+    }
+  }
+
+  /**
+   * Map aliases to canonical ids
+   *
+   * An alias that names an existing resource, or that another resource
+   * already claims, is a validation failure and is ignored.
+   * @private
+   */
+  private _collectAliases(
+    fragments: ResourceFragment[],
+    failures: Array<{ path: string; error: string }>,
+  ): Map<string, string> {
+    const ids = new Set(fragments.map((fragment) => fragment.id));
+    const aliases = new Map<string, string>();
+    const sorted = [...fragments].sort((a, b) => compareStrings(a.id, b.id));
+    for (const fragment of sorted) {
+      for (const alias of fragment.aliases ?? []) {
+        const owner = ids.has(alias) ? alias : aliases.get(alias);
+        if (owner !== undefined && owner !== fragment.id) {
+          const failure = {
+            path: `${fragment.id}.md`,
+            error: ids.has(alias)
+              ? `Alias ${alias} collides with an existing resource`
+              : `Alias ${alias} is already an alias of ${owner}`,
+          };
+          failures.push(failure);
+          this.eventBus?.publish("validation_failed", failure);
+          continue;
+        }
+        aliases.set(alias, fragment.id);
+      }
+    }
+    return aliases;
+  }
+
+  /**
+   * Canonical id for a former id declared in `aliases:` frontmatter
+   *
+   * @param id - Resource id or URI (e.g. 'agents/old-name')
+   * @returns Canonical id, or null if the id is not an alias
+   */
+  async resolveAlias(id: string): Promise<string | null> {
+    await this.ensureIndexLoaded();
+    return this.aliases.get(id.replace("o8://", "").split("?")[0]) ?? null;
+  }
+
+  /**
+   * Load several resources in one call
+   *
+   * Bare names are agents ("rust-expert" is agents/rust-expert). Each
+   * entry succeeds or fails on its own, in the order requested.
+   *
+   * @param ids - Resource names, ids, or URIs
+   * @param at - Read the resources as they were in a snapshot
+   * @returns Content and provenance per id, or the error for that id
+   * @throws {SnapshotError} If the snapshot does not exist
+   */
+  async batchGet(
+    ids: string[],
+    at?: SnapshotRef,
+  ): Promise<{
+    resources: Array<
+      | {
+          id: string;
+          content: string;
+          provenance: (ResourceOrigin & { id: string }) | null;
+          canonical?: string;
+        }
+      | { id: string; error: string; code?: string }
+    >;
+  }> {
+    const snapshot = await this._resolveSnapshot(at);
+    const catalog = snapshot ? await this._getSnapshotCatalog(snapshot) : null;
+    const resources = await Promise.all(
+      ids.map(async (name) => {
+        const id = name.replace("o8://", "").includes("/")
+          ? name.replace("o8://", "")
+          : `agents/${name}`;
+        try {
+          if (catalog) {
+            const canonical = catalog.aliases.get(id);
+            const fragment = catalog.fragments.get(canonical ?? id);
+            if (!fragment?.origin) {
+              return {
+                id,
+                error: `Resource not found in snapshot ${snapshot}: ${id}`,
+                code: "NOT_FOUND",
+              };
+            }
+            return {
+              id,
+              content: await this.snapshotStore.readBlob(fragment.origin.hash),
+              provenance: { id: fragment.id, ...fragment.origin },
+              ...(canonical ? { canonical } : {}),
+            };
+          }
+          const content = await this.loadResourceContent(`o8://${id}`);
+          const canonical = await this.resolveAlias(id);
+          return {
+            id,
+            content,
+            provenance: await this.getResourceProvenance(id),
+            ...(canonical ? { canonical } : {}),
+          };
+        } catch (error: any) {
+          return error?.code === "ENOENT"
+            ? { id, error: `Resource not found: ${id}`, code: "NOT_FOUND" }
+            : {
+                id,
+                error: error?.message ?? String(error),
+                ...(typeof error?.code === "string" ? { code: error.code } : {}),
+              };
+        }
+      }),
+    );
+    return { resources };
+  }
+
+  /**
+   * Declared capabilities of every agent, for programmatic routing
+   *
+   * @param filter - Only agents with all of these capabilities
+   * @returns Matching agents with their matrix, and how many of them have
+   *   each value
+   */
+  async getCapabilities(filter?: CapabilityFilter): Promise<{
+    agents: Array<{ id: string } & Record<CapabilityDimension, string[]>>;
+    facets: Record<CapabilityDimension, Record<string, number>>;
+  }> {
+    await this.ensureIndexLoaded();
+    const agents = (this.resourceIndex || [])
+      .filter(
+        (fragment) =>
+          fragment.category === "agent" &&
+          fragment.capabilityMatrix &&
+          matchesCapabilities(fragment.capabilityMatrix, filter),
+      )
+      .map((fragment) => ({ id: fragment.id, ...fragment.capabilityMatrix! }))
+      .sort((a, b) => compareStrings(a.id, b.id));
+    return { agents, facets: capabilityFacets(agents) };
+  }
+
+  /**
+   * Recommend one agent for a task
+   *
+   * @param request - Task description and constraints
+   * @returns The best qualifying agent with its confidence, and runners-up
+   */
+  async routeAgent(request: RouteRequest): Promise<RouteDecision> {
+    await this.ensureIndexLoaded();
+    return routeAgent(this.resourceIndex || [], this.fuzzyMatcher, request);
+  }
+
+  /**
+   * Propose a multi-agent plan for a task
+   *
+   * @param request - Task description and constraints for every step
+   * @returns One step per phase of the task, each with a recommended agent
+   */
+  async planTask(request: PlanRequest): Promise<TaskPlan> {
+    await this.ensureIndexLoaded();
+    return planTask(this.resourceIndex || [], this.fuzzyMatcher, request);
+  }
+
+  /**
+   * Handoff graph declared in `handoff_to:` frontmatter
+   *
+   * @param agent - Only handoffs from or to this agent (name, id, or alias)
+   * @returns Edges between resources, and edges to resources that don't exist
+   */
+  async getHandoffs(agent?: string): Promise<HandoffGraph> {
+    await this.ensureIndexLoaded();
+    const fragments = this.resourceIndex || [];
+    const ids = new Set(fragments.map((fragment) => fragment.id));
+    const resolve = (id: string) =>
+      ids.has(id) ? id : (this.aliases.get(id) ?? null);
+
+    let focus: string | undefined;
+    if (agent) {
+      const id = agent.replace("o8://", "");
+      focus = id.includes("/") ? id : `agents/${id}`;
+      focus = resolve(focus) ?? focus;
+    }
+    return handoffGraph(fragments, resolve, focus);
+  }
+
+  /**
+   * Catalog statistics for dashboards
+   *
+   * @param options - Optional category filter and number of top tags
+   * @returns Counts by category/model/tag, sizes, modification range,
+   *   and validation problems from the last index load
+   */
+  async getCatalogSummary(
+    options: { category?: string; topTags?: number } = {},
+  ): Promise<CatalogSummary> {
+    await this.ensureIndexLoaded();
+
+    const category = this._normalizeCategoryFilter(options.category);
+    const fragments = (this.resourceIndex || []).filter(
+      (fragment) => !category || fragment.category === category,
+    );
+    const failures = category
+      ? this.scanFailures.filter(
+          (failure) =>
+            this._mapCategory(failure.path.split(/[\\/]/)[0]) === category,
+        )
+      : this.scanFailures;
+
+    return summarizeCatalog(fragments, failures, options.topTags);
+  }
+
+  /**
+   * Layer a local resource was loaded from and the layers it overrides
+   *
+   * @param id - Resource id or URI (e.g. 'agents/rust-expert')
+   * @returns Origin, or null if the id is not a local resource
+   */
+  async getResourceProvenance(
+    id: string,
+  ): Promise<(ResourceOrigin & { id: string }) | null> {
+    const requested = id.replace("o8://", "").split("?")[0];
+    const index = await this.loadResourceIndex();
+    const resourceId = this.aliases.get(requested) ?? requested;
+    const origin = index.find((fragment) => fragment.id === resourceId)?.origin;
+    return origin ? { id: resourceId, ...origin } : null;
+  }
+
+  /**
+   * Git history of a local resource file
+   *
+   * @param id - Resource id or URI (e.g. 'agents/rust-expert')
+   * @param limit - Maximum number of commits (default: 20)
+   * @returns Commits touching the file, newest first
+   * @throws {GitHistoryError} If the resources directory is not in a git repository
+   */
+  async getResourceHistory(
+    id: string,
+    limit: number = 20,
+  ): Promise<HistoryEntry[]> {
+    return fileHistory(this.resourcesPath, this._resourceFilePath(id), limit);
+  }
+
+  /**
+   * Unified diff of a resource file between two versions
+   *
+   * Refs are git revisions (HEAD, HEAD~3, a commit or tag), 'working' for
+   * the file currently on disk, or 'snapshot:<name>' for a catalog snapshot.
+   *
+   * @param id - Resource id or URI (e.g. 'agents/rust-expert')
+   * @param from - Old version (default: 'HEAD')
+   * @param to - New version (default: 'working')
+   * @returns Unified diff of frontmatter and body; empty if unchanged
+   * @throws {GitHistoryError} If a revision cannot be read
+   */
+  async diffResource(
+    id: string,
+    from: string = "HEAD",
+    to: string = "working",
+  ): Promise<{ id: string; from: string; to: string; diff: string }> {
+    const relativePath = this._resourceFilePath(id);
+    const read = async (ref: string) => {
+      if (ref === "working") {
+        return fs.readFile(join(this.resourcesPath, relativePath), "utf-8");
+      }
+      if (ref.startsWith("snapshot:")) {
+        const name = ref.slice("snapshot:".length);
+        const manifest = await this.snapshotStore.load(name);
+        const file = manifest.files.find((f) => f.path === relativePath);
+        return file ? this.snapshotStore.readBlob(file.hash) : "";
+      }
+      return fileAtRevision(this.resourcesPath, relativePath, ref);
+    };
+
+    const [oldText, newText] = await Promise.all([read(from), read(to)]);
+    const diff = unifiedDiff(oldText, newText, {
+      fromLabel: `${relativePath}@${from}`,
+      toLabel: `${relativePath}@${to}`,
+    });
+    return { id, from, to, diff };
+  }
+
+  /**
+   * Find and replace across resource files, then reindex
+   *
+   * All changed files are written or none are. With dryRun the diffs are
+   * returned and nothing is written.
+   *
+   * @param request - Pattern, replacement, and the resources to rewrite
+   * @param actor - Who asked for it, recorded in the journal
+   * @returns Changed files with their diffs, total replacements, and ids
+   *   that were requested but not found
+   * @throws {GrepPatternError} If the pattern is not a valid expression
+   * @throws {RewriteConflictError} If a resource does not have its expected
+   *   hash; nothing is written
+   * @throws {Error} If the catalog is pinned to a snapshot
+   */
+  async rewriteResources(
+    request: RewriteRequest,
+    actor: string = "unknown",
+  ): Promise<{
+    dryRun: boolean;
+    changes: RewriteChange[];
+    replacements: number;
+    notFound: string[];
+  }> {
+    const regex = compileRewrite(request.pattern, request);
+    await this._ensurePinRestored();
+    if (this.pinned) {
+      throw new Error(
+        `Catalog is pinned to snapshot ${this.pinned.name}; roll back to live before rewriting`,
+      );
+    }
+    await this.ensureIndexLoaded();
+
+    const index = this.resourceIndex || [];
+    const notFound: string[] = [];
+    let selected: ResourceFragment[];
+    if (request.ids) {
+      const byId = new Map(index.map((fragment) => [fragment.id, fragment]));
+      selected = [];
+      for (const name of request.ids) {
+        const id = name.replace("o8://", "").includes("/")
+          ? name.replace("o8://", "")
+          : `agents/${name}`;
+        const fragment = byId.get(this.aliases.get(id) ?? id);
+        if (fragment) {
+          selected.push(fragment);
+        } else {
+          notFound.push(id);
+        }
+      }
+    } else {
+      const category = this._normalizeCategoryFilter(
+        request.category ?? "agents",
+      );
+      selected = index.filter((fragment) => fragment.category === category);
+    }
+
+    const apply = () => this._rewriteFiles(request, regex, selected, actor);
+    const result = request.dryRun
+      ? await apply()
+      : await withStateLock(this.stateDir, "rewrite", apply);
+    return { ...result, notFound };
+  }
+
+  /**
+   * Rewrite the files of selected resources, unless it is a dry run
+   *
+   * Runs under the state lock when writing, so expected hashes are checked
+   * against files no other instance is changing.
+   * @private
+   */
+  private async _rewriteFiles(
+    request: RewriteRequest,
+    regex: RegExp,
+    selected: ResourceFragment[],
+    actor: string,
+  ): Promise<{
+    dryRun: boolean;
+    changes: RewriteChange[];
+    replacements: number;
+  }> {
+    const index = this.resourceIndex || [];
+    const changes: RewriteChange[] = [];
+    const writes: Array<{ path: string; content: string }> = [];
+    const journalFiles: JournalFile[] = [];
+    const seen = new Set<string>();
+    const expected = new Map(Object.entries(request.expectedHashes ?? {}));
+    const conflicts: RewriteConflict[] = [];
+    selected.sort((a, b) => compareStrings(a.id, b.id));
+    for (const fragment of selected) {
+      const path = fragment.origin?.filePath;
+      if (!path || seen.has(path)) {
+        continue;
+      }
+      seen.add(path);
+      const before = await fs.readFile(path, "utf-8");
+      const hash = hashContent(before);
+      const expectedHash = expected.get(fragment.id);
+      expected.delete(fragment.id);
+      if (expectedHash !== undefined && expectedHash !== hash) {
+        conflicts.push({
+          id: fragment.id,
+          expected: expectedHash,
+          actual: hash,
+        });
+      }
+      const after = applyRewrite(
+        before,
+        regex,
+        request.replacement,
+        request.literal,
+      );
+      if (after.replacements === 0 || after.content === before) {
+        continue;
+      }
+      const label = relative(fragment.origin!.sourceDir, path);
+      changes.push({
+        id: fragment.id,
+        path,
+        replacements: after.replacements,
+        diff: unifiedDiff(before, after.content, {
+          fromLabel: `${label}@working`,
+          toLabel: `${label}@rewritten`,
+        }),
+        hash,
+      });
+      writes.push({ path, content: after.content });
+      journalFiles.push({
+        path,
+        staged: stagingPath(path),
+        before: hash,
+        after: hashContent(after.content),
+      });
+    }
+    // Expected resources that were removed or renamed in the meantime
+    const known = new Set(index.map((fragment) => fragment.id));
+    for (const [id, expectedHash] of expected) {
+      if (!known.has(id)) {
+        conflicts.push({ id, expected: expectedHash, actual: null });
+      }
+    }
+    if (conflicts.length > 0) {
+      throw new RewriteConflictError(conflicts);
+    }
+    const replacements = changes.reduce((sum, c) => sum + c.replacements, 0);
+
+    if (!request.dryRun && writes.length > 0) {
+      const entry = await this.journal.begin("rewrite", actor, journalFiles);
+      try {
+        await writeFilesAtomically(writes);
+      } catch (error) {
+        // Rolls the entry back, or forward if only a rename failed
+        await this.journal
+          .recover()
+          .catch((recoveryError) =>
+            this.logger.error("Failed to recover rewrite", recoveryError),
+          );
+        throw error;
+      }
+      await this.journal.finish(entry, "committed");
+      this.logger.info(
+        `Rewrote ${replacements} occurrences in ${writes.length} files`,
+      );
+      await this.reloadIndex();
+    }
+    return {
+      dryRun: Boolean(request.dryRun),
+      changes,
+      replacements,
+    };
+  }
+
+  // ============================================================================
+  // Catalog snapshots
+  // ============================================================================
+
+  /**
+   * Capture every resource file into the state store
+   *
+   * @param name - Snapshot name (letters, digits, '.', '_', '-'; not 'live')
+   * @returns Snapshot name, creation time, and file count
+   */
+  async snapshotCatalog(name: string): Promise<SnapshotInfo> {
+    if (name === "live") {
+      throw new SnapshotError(
+        "INVALID_SNAPSHOT_NAME",
+        '"live" is reserved for the resources directory',
+      );
+    }
+
+    const files: Array<{ path: string; content: string; modifiedAt?: string }> =
+      [];
+    await this._ensurePinRestored();
+    if (this.pinned) {
+      // Snapshot of a snapshot: copy the pinned manifest's files
+      const pinned = await this.snapshotStore.load(this.pinned.name);
+      for (const file of pinned.files) {
+        files.push({
+          path: file.path,
+          content: await this.snapshotStore.readBlob(file.hash),
+          modifiedAt: file.modifiedAt,
+        });
+      }
+    } else {
+      await this._collectResourceFiles(this.resourcesPath, "", files);
+    }
+
+    // Garbage collection in another instance must not see the blobs
+    // before the manifest that references them
+    const manifest = await withStateLock(this.stateDir, "snapshot", () =>
+      this.snapshotStore.save(name, files),
+    );
+    this.snapshotCatalogs.delete(name);
+    this.logger.info(
+      `Saved catalog snapshot ${name} (${manifest.files.length} files)`,
+    );
+    return {
+      name: manifest.name,
+      createdAt: manifest.createdAt,
+      files: manifest.files.length,
+    };
+  }
+
+  /**
+   * Serve the catalog from a snapshot, or from the resources directory again
+   *
+   * The pin is persisted in the state store and survives restarts.
+   *
+   * @param name - Snapshot name, or 'live' to serve the resources directory
+   * @returns Pinned snapshot (null when live) and resource count
+   * @throws {SnapshotError} If the snapshot does not exist
+   */
+  async rollbackCatalog(
+    name: string,
+  ): Promise<{ pinned: string | null; resources: number }> {
+    await this._ensurePinRestored();
+    let pinned: PinnedSnapshot | null = null;
+    if (name !== "live") {
+      const manifest = await this.snapshotStore.load(name);
+      pinned = {
+        name,
+        files: new Map(manifest.files.map((f) => [f.path, f.hash])),
+      };
+    }
+
+    // The pin takes effect together with the index built from it
+    const { resources } = await this._queueReload(() => pinned, true);
+    await withStateLock(this.stateDir, "pin", () =>
+      this.snapshotStore.setPinned(pinned?.name ?? null),
+    );
+
+    this.logger.info(
+      pinned
+        ? `Catalog pinned to snapshot ${name}`
+        : "Catalog serving live resources",
+    );
+    return { pinned: pinned?.name ?? null, resources };
+  }
+
+  /**
+   * List catalog snapshots and the pinned one
+   */
+  async listSnapshots(): Promise<{
+    pinned: string | null;
+    snapshots: SnapshotInfo[];
+  }> {
+    await this._ensurePinRestored();
+    return {
+      pinned: this.pinned?.name ?? null,
+      snapshots: await this.snapshotStore.list(),
+    };
+  }
+
+  /**
+   * Snapshot name a time-travel read resolves to, or null for the served
+   * catalog
+   *
+   * @throws {SnapshotError} If asOf predates every snapshot
+   * @private
+   */
+  private async _resolveSnapshot(at?: SnapshotRef): Promise<string | null> {
+    if (at?.snapshot) {
+      return at.snapshot;
+    }
+    if (at?.asOf) {
+      const asOf = new Date(at.asOf);
+      if (isNaN(asOf.getTime())) {
+        throw new Error(`Invalid asOf: ${at.asOf} (expected ISO 8601 time)`);
+      }
+      return this.snapshotStore.findAsOf(asOf);
+    }
+    return null;
+  }
+
+  /**
+   * Index of a snapshot for time-travel queries; the served catalog and pin
+   * are not touched
+   *
+   * @throws {SnapshotError} If the snapshot does not exist
+   * @private
+   */
+  private _getSnapshotCatalog(name: string): Promise<SnapshotCatalog> {
+    let catalog = this.snapshotCatalogs.get(name);
+    if (!catalog) {
+      catalog = this._loadPinnedIndex(name, []).then((fragments) => {
+        const matcher = new FuzzyMatcher();
+        matcher.setResourceIndex(fragments);
+        return {
+          matcher,
+          fragments: new Map(fragments.map((f) => [f.id, f])),
+          aliases: this._collectAliases(fragments, []),
+        };
+      });
+      // A failed load is not cached
+      catalog.catch(() => this.snapshotCatalogs.delete(name));
+      this.snapshotCatalogs.set(name, catalog);
+    }
+    return catalog;
+  }
+
+  /**
+   * Read a resource file, from the pinned snapshot if there is one
+   * @private
+   */
+  private async _readResourceFile(filePath: string): Promise<string> {
+    await this._ensurePinRestored();
+    if (!this.pinned) {
+      return fs.readFile(filePath, "utf-8");
+    }
+    const path = relative(this.resourcesPath, filePath).replace(/\\/g, "/");
+    const hash = this.pinned.files.get(path);
+    if (!hash) {
+      const error: NodeJS.ErrnoException = new Error(
+        `ENOENT: ${path} is not in snapshot ${this.pinned.name}`,
+      );
+      error.code = "ENOENT";
+      throw error;
+    }
+    return this.snapshotStore.readBlob(hash);
+  }
+
+  /**
+   * Build the resource index from the pinned snapshot
+   * @private
+   */
+  private async _loadPinnedIndex(
+    name: string,
+    failures: Array<{ path: string; error: string }>,
+  ): Promise<ResourceFragment[]> {
+    const manifest = await this.snapshotStore.load(name);
+    const categories = new Set(FRAGMENT_CATEGORIES);
+
+    // _defaults.yaml files by directory
+    const defaults = new Map<string, FrontmatterDefaults>();
+    for (const file of manifest.files) {
+      if (!file.path.endsWith(`/${DEFAULTS_FILE}`)) {
+        continue;
+      }
+      try {
+        defaults.set(
+          file.path.slice(0, -DEFAULTS_FILE.length - 1),
+          parseDefaults(await this.snapshotStore.readBlob(file.hash)),
+        );
+      } catch (error: any) {
+        failures.push({
+          path: file.path,
+          error: error?.message ?? String(error),
+        });
+      }
+    }
+
+    const fragments: ResourceFragment[] = [];
+    for (const file of manifest.files) {
+      const category = file.path.split("/")[0];
+      if (!categories.has(category) || !file.path.endsWith(".md")) {
+        continue;
+      }
+      try {
+        const content = await this.snapshotStore.readBlob(file.hash);
+        const fragment = await this._parseResourceFragment(
+          content,
+          category,
+          file.path,
+          inheritedDefaults(file.path, defaults),
+        );
+        if (file.modifiedAt) {
+          fragment.modifiedAt = file.modifiedAt;
+        }
+        fragment.origin = {
+          layer: `snapshot:${name}`,
+          sourceDir: this.resourcesPath,
+          filePath: join(this.resourcesPath, file.path),
+          hash: file.hash,
+          overrides: [],
+        };
+        fragments.push(fragment);
+      } catch (error: any) {
+        failures.push({
+          path: file.path,
+          error: error?.message ?? String(error),
+        });
+      }
+    }
+
+    return fragments;
+  }
+
+  /**
+   * Finish interrupted rewrites and restore a persisted pin once, before
+   * the first read
+   * @private
+   */
+  private _ensurePinRestored(): Promise<void> {
+    this.pinRestore ??= this._recoverJournal().then(() => this._restorePin());
+    return this.pinRestore;
+  }
+
+  /**
+   * Roll interrupted rewrites forward or back
+   * @private
+   */
+  private async _recoverJournal(): Promise<void> {
+    try {
+      const entries = await this.journal.entries();
+      if (!entries.some((entry) => entry.status === "pending")) {
+        return;
+      }
+      await withStateLock(this.stateDir, "recovery", () =>
+        this.journal.recover(),
+      );
+    } catch (error) {
+      this.logger.error(`Failed to recover journal ${this.journal.path}`, error);
+    }
+  }
+
+  /**
+   * Re-apply a pin persisted by a previous run
+   * @private
+   */
+  private async _restorePin(): Promise<void> {
+    const name = await this.snapshotStore.getPinned();
+    if (!name) {
+      return;
+    }
+    try {
+      const manifest = await this.snapshotStore.load(name);
+      this.pinned = {
+        name,
+        files: new Map(manifest.files.map((f) => [f.path, f.hash])),
+      };
+      this.logger.warn(`Catalog pinned to snapshot ${name}`);
+    } catch (error) {
+      this.logger.error(`Pinned snapshot ${name} unavailable, serving live`, error);
+    }
+  }
+
+  /**
+   * Collect every .md and _defaults.yaml file under the resources root
+   * @private
+   */
+  private async _collectResourceFiles(
+    dirPath: string,
+    relativePath: string,
+    files: Array<{ path: string; content: string; modifiedAt?: string }>,
+  ): Promise<void> {
+    const entries = sortByName(
+      await fs.readdir(dirPath, { withFileTypes: true }),
+    );
+    for (const entry of entries) {
+      const fullPath = join(dirPath, entry.name);
+      const path = relativePath ? `${relativePath}/${entry.name}` : entry.name;
+      if (await isDirectoryEntry(entry, fullPath)) {
+        await this._collectResourceFiles(fullPath, path, files);
+      } else if (entry.name.endsWith(".md") || entry.name === DEFAULTS_FILE) {
+        const [content, stat] = await Promise.all([
+          fs.readFile(fullPath, "utf-8"),
+          fs.stat(fullPath),
+        ]);
+        files.push({ path, content, modifiedAt: stat.mtime.toISOString() });
+      }
+    }
+  }
+
+  /**
+   * Relative .md path for a resource id, rejecting paths outside the root
+   * @private
+   */
+  private _resourceFilePath(id: string): string {
+    const relativePath = `${id.replace("o8://", "").split("?")[0]}.md`;
+    const root = resolve(this.resourcesPath);
+    if (!isWithin(root, resolve(root, relativePath))) {
+      throw new Error(`Invalid resource id: ${id}`);
+    }
+    return relativePath;
+  }
+
+  /**
+   * Map a category filter to a fragment category, accepting both 'agents'
+   * and 'agent'
+   */
+  private _normalizeCategoryFilter(
+    category?: string,
+  ): ResourceFragment["category"] | undefined {
+    return category
+      ? this._mapCategory(category.endsWith("s") ? category : `${category}s`)
+      : undefined;
+  }
+
   // ============================================================================
   // NEW: Multi-provider search methods
   // ============================================================================
@@ -14,6 +14,7 @@
 import { LRUCache } from "lru-cache";
 import matter from "gray-matter";
 import { Logger } from "../utils/logger.js";
+import { matchSnippet } from "../utils/bodyIndex.js";
 import { ResourceFragment } from "../utils/fuzzyMatcher.js";
 import {
   ResourceProvider,
@@ -329,6 +330,7 @@ export class AITMPLProvider implements ResourceProvider {
           } as any,
           score,
           matchReason: this.getMatchReasons(component, keywords),
+          snippet: matchSnippet([component.description], keywords),
         });
       }
     }
@@ -20,6 +20,7 @@ import { LRUCache } from "lru-cache";
 import matter from "gray-matter";
 import { GithubProviderConfig } from "../config/schema.js";
 import { Logger } from "../utils/logger.js";
+import { matchSnippet } from "../utils/bodyIndex.js";
 import {
   ResourceProvider,
   RemoteResourceIndex,
@@ -529,6 +530,7 @@ export class GitHubProvider implements ResourceProvider {
             } as any,
             score,
             matchReason: this.getMatchReasons(resource, keywords),
+            snippet: matchSnippet([resource.description], keywords),
           };
         })
         .filter((result) => result.score >= (options?.minScore || 0));
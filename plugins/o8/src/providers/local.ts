@@ -609,6 +609,7 @@ export class LocalProvider implements ResourceProvider {
           } as any,
           score: matchResult.matchScores[index],
           matchReason: this.generateMatchReason(fragment, query),
+          snippet: matchResult.snippets[index],
         }),
       );
 
@@ -210,6 +210,9 @@ export interface SearchResult {
 
   /** Highlighted snippets from content */
   highlights?: string[];
+
+  /** Matched region of the body or description, query terms marked as **term** */
+  snippet?: string;
 }
 
 /**
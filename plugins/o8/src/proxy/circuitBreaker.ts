@@ -0,0 +1,146 @@
+/**
+ * Circuit breaker for upstream calls
+ *
+ * After `failureThreshold` consecutive failures the circuit opens and calls
+ * fail at once instead of waiting on a server that is down. After
+ * `resetTimeoutMs` one trial call is let through (half-open): success
+ * closes the circuit, failure opens it for another timeout.
+ */
+
+export type CircuitState = "closed" | "open" | "half-open";
+
+export interface CircuitBreakerOptions {
+  /** Consecutive failures that open the circuit (default: 3) */
+  failureThreshold?: number;
+  /** Milliseconds the circuit stays open (default: 30000) */
+  resetTimeoutMs?: number;
+  /** Clock (for tests) */
+  now?: () => number;
+}
+
+/**
+ * Call counts, latency, and circuit state of one upstream
+ */
+export interface CircuitStatus {
+  state: CircuitState;
+  calls: number;
+  failures: number;
+  consecutiveFailures: number;
+  /** Mean latency of the last calls, in milliseconds */
+  avgLatencyMs: number | null;
+  lastError: string | null;
+  /** When an open circuit lets a trial call through (ISO timestamp) */
+  retryAt: string | null;
+}
+
+/**
+ * Calls averaged for avgLatencyMs
+ */
+const LATENCY_WINDOW = 20;
+
+export class CircuitBreaker {
+  private readonly failureThreshold: number;
+  private readonly resetTimeoutMs: number;
+  private readonly now: () => number;
+  private state: CircuitState = "closed";
+  private openedAt = 0;
+  private trialInFlight = false;
+  private calls = 0;
+  private failures = 0;
+  private consecutiveFailures = 0;
+  private latencies: number[] = [];
+  private lastError: string | null = null;
+
+  constructor(options: CircuitBreakerOptions = {}) {
+    this.failureThreshold = options.failureThreshold ?? 3;
+    this.resetTimeoutMs = options.resetTimeoutMs ?? 30000;
+    this.now = options.now ?? Date.now;
+  }
+
+  /**
+   * Whether a call may go through now; in half-open state only one trial
+   * call is allowed at a time
+   */
+  allowRequest(): boolean {
+    if (
+      this.state === "open" &&
+      this.now() - this.openedAt >= this.resetTimeoutMs
+    ) {
+      this.state = "half-open";
+    }
+    if (this.state === "closed") {
+      return true;
+    }
+    if (this.state === "half-open" && !this.trialInFlight) {
+      this.trialInFlight = true;
+      return true;
+    }
+    return false;
+  }
+
+  /**
+   * Milliseconds until an open circuit lets a trial call through
+   */
+  retryAfterMs(): number {
+    return this.state === "open"
+      ? Math.max(0, this.openedAt + this.resetTimeoutMs - this.now())
+      : 0;
+  }
+
+  recordSuccess(latencyMs: number): void {
+    this.calls++;
+    this.recordLatency(latencyMs);
+    this.consecutiveFailures = 0;
+    this.trialInFlight = false;
+    this.state = "closed";
+  }
+
+  recordFailure(error: unknown, latencyMs: number): void {
+    this.calls++;
+    this.failures++;
+    this.consecutiveFailures++;
+    this.recordLatency(latencyMs);
+    this.lastError = error instanceof Error ? error.message : String(error);
+    if (
+      this.state === "half-open" ||
+      this.consecutiveFailures >= this.failureThreshold
+    ) {
+      this.state = "open";
+      this.openedAt = this.now();
+    }
+    this.trialInFlight = false;
+  }
+
+  private recordLatency(latencyMs: number): void {
+    this.latencies.push(latencyMs);
+    if (this.latencies.length > LATENCY_WINDOW) {
+      this.latencies.shift();
+    }
+  }
+
+  status(): CircuitStatus {
+    // Report an expired open circuit as half-open without consuming the trial
+    const state =
+      this.state === "open" && this.retryAfterMs() === 0
+        ? "half-open"
+        : this.state;
+    return {
+      state,
+      calls: this.calls,
+      failures: this.failures,
+      consecutiveFailures: this.consecutiveFailures,
+      avgLatencyMs:
+        this.latencies.length === 0
+          ? null
+          : Math.round(
+              this.latencies.reduce((sum, latency) => sum + latency, 0) /
+                this.latencies.length,
+            ),
+      lastError: this.lastError,
+      retryAt:
+        state === "open"
+          ? new Date(this.openedAt + this.resetTimeoutMs).toISOString()
+          : null,
+    };
+  }
+}
@@ -0,0 +1,499 @@
+/**
+ * Upstream MCP servers
+ *
+ * With an `upstreams` section in the configuration, the server connects to
+ * other MCP servers as a client and exposes their tools and resources next
+ * to its own, so an agent stack composed of several servers needs a single
+ * connection. Names are namespaced by upstream: tool `search` of upstream
+ * `docs` becomes `docs__search`, and resource `file:///a.md` becomes
+ * `upstream://docs/file:///a.md`. Tools and resources are listed once, when
+ * the upstream connects.
+ *
+ * Every call has a timeout and goes through a per-upstream circuit breaker,
+ * so a flaky upstream fails fast instead of stalling aggregated calls, and
+ * its state shows up in `/health`.
+ *
+ * `tools/list` and `resources/read` results are cached for the upstream's
+ * `cacheTTL`. MCP has no conditional reads, so entries expire by time; the
+ * upstream's `list_changed` and `resources/updated` notifications drop
+ * them early. Tool calls are never cached. With `serveStale`, an expired
+ * read is still served when the upstream cannot be reached, so a central
+ * registry going down leaves its resources readable.
+ *
+ * @example ~/.orchestr8/config.json
+ * {
+ *   "upstreams": {
+ *     "docs": { "command": "npx", "args": ["-y", "@acme/docs-mcp"] },
+ *     "tickets": { "url": "http://localhost:4000/mcp" }
+ *   }
+ * }
+ */
+
+import { LRUCache } from "lru-cache";
+import { Client } from "@modelcontextprotocol/sdk/client/index.js";
+import {
+  StdioClientTransport,
+  getDefaultEnvironment,
+} from "@modelcontextprotocol/sdk/client/stdio.js";
+import { StreamableHTTPClientTransport } from "@modelcontextprotocol/sdk/client/streamableHttp.js";
+import type { Transport } from "@modelcontextprotocol/sdk/shared/transport.js";
+import {
+  ResourceListChangedNotificationSchema,
+  ResourceUpdatedNotificationSchema,
+  ToolListChangedNotificationSchema,
+  type CallToolResult,
+  type ReadResourceResult,
+  type Resource,
+  type Tool,
+} from "@modelcontextprotocol/sdk/types.js";
+import type { UpstreamConfig } from "../config/schema.js";
+import { Logger } from "../utils/logger.js";
+import { compareStrings } from "../utils/deterministic.js";
+import { CircuitBreaker, type CircuitStatus } from "./circuitBreaker.js";
+
+/**
+ * Separator between upstream name and tool name
+ */
+export const TOOL_SEPARATOR = "__";
+
+/**
+ * Scheme of namespaced upstream resource URIs
+ */
+export const UPSTREAM_URI_PREFIX = "upstream://";
+
+/**
+ * Most resource reads cached per upstream
+ */
+const MAX_CACHED_READS = 500;
+
+/**
+ * Raised for names that belong to no connected upstream
+ */
+export class UpstreamError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "UpstreamError";
+  }
+}
+
+/**
+ * Raised while an upstream's circuit is open
+ */
+export class UpstreamUnavailableError extends UpstreamError {
+  constructor(
+    public readonly upstream: string,
+    public readonly retryAfterMs: number,
+  ) {
+    super(
+      `Upstream ${upstream} is unavailable after repeated failures; retry in ${Math.ceil(retryAfterMs / 1000)}s`,
+    );
+    this.name = "UpstreamUnavailableError";
+  }
+}
+
+/**
+ * Health of one upstream, as reported by `/health`
+ */
+export interface UpstreamStatus {
+  connected: boolean;
+  tools: number;
+  resources: number;
+  /** Calls and circuit state; null if the upstream never connected */
+  circuit: CircuitStatus | null;
+  /** Cached resource reads and hit counts; null if never connected */
+  cache: {
+    entries: number;
+    hits: number;
+    misses: number;
+    /** Expired reads served while the upstream was unreachable */
+    stale: number;
+  } | null;
+  /** Why the upstream failed to connect */
+  error: string | null;
+}
+
+/**
+ * Namespaced tool name
+ */
+export function upstreamToolName(upstream: string, tool: string): string {
+  return `${upstream}${TOOL_SEPARATOR}${tool}`;
+}
+
+/**
+ * Namespaced resource URI
+ */
+export function upstreamResourceUri(upstream: string, uri: string): string {
+  return `${UPSTREAM_URI_PREFIX}${upstream}/${uri}`;
+}
+
+/**
+ * Split a namespaced tool name or resource URI into upstream and original
+ *
+ * @returns null if the name is not namespaced
+ */
+export function splitUpstreamName(
+  name: string,
+): { upstream: string; name: string } | null {
+  if (name.startsWith(UPSTREAM_URI_PREFIX)) {
+    const rest = name.slice(UPSTREAM_URI_PREFIX.length);
+    const slash = rest.indexOf("/");
+    return slash > 0
+      ? { upstream: rest.slice(0, slash), name: rest.slice(slash + 1) }
+      : null;
+  }
+  const separator = name.indexOf(TOOL_SEPARATOR);
+  return separator > 0
+    ? {
+        upstream: name.slice(0, separator),
+        name: name.slice(separator + TOOL_SEPARATOR.length),
+      }
+    : null;
+}
+
+/**
+ * Transport for an upstream: a child process for `command`, Streamable
+ * HTTP for `url`
+ */
+export function upstreamTransport(config: UpstreamConfig): Transport {
+  if (config.url) {
+    return new StreamableHTTPClientTransport(new URL(config.url));
+  }
+  return new StdioClientTransport({
+    command: config.command!,
+    args: config.args,
+    env: { ...getDefaultEnvironment(), ...config.env },
+    // Upstream logs go to our stderr; stdout carries our own protocol
+    stderr: "inherit",
+  });
+}
+
+interface ConnectedUpstream {
+  client: Client;
+  tools: Tool[];
+  resources: Resource[];
+  breaker: CircuitBreaker;
+  timeout: number;
+  cacheTTL: number;
+  /** When `tools` was fetched (0: stale) */
+  toolsFetchedAt: number;
+  reads: LRUCache<string, ReadResourceResult> | null;
+  serveStale: boolean;
+  hits: number;
+  misses: number;
+  staleHits: number;
+}
+
+/**
+ * Clients for the configured upstream servers
+ */
+export class UpstreamPool {
+  private upstreams = new Map<string, ConnectedUpstream>();
+  // Connection errors of upstreams that failed to connect
+  private failed = new Map<string, string>();
+
+  /**
+   * @param logger - Logger for connection failures
+   * @param createTransport - Transport factory (for tests)
+   */
+  constructor(
+    private readonly logger: Logger,
+    private readonly createTransport: (
+      name: string,
+      config: UpstreamConfig,
+    ) => Transport = (_name, config) => upstreamTransport(config),
+  ) {}
+
+  /**
+   * Connect to every upstream and list its tools and resources
+   *
+   * An upstream that fails to connect is logged and left out.
+   *
+   * @returns Names of the connected upstreams
+   */
+  async connect(configs: Record<string, UpstreamConfig>): Promise<string[]> {
+    const names = Object.keys(configs).sort(compareStrings);
+    await Promise.all(
+      names.map(async (name) => {
+        try {
+          const upstream = await this.connectOne(name, configs[name]);
+          this.upstreams.set(name, upstream);
+          this.logger.info(`Connected upstream ${name}`);
+        } catch (error) {
+          this.failed.set(
+            name,
+            error instanceof Error ? error.message : String(error),
+          );
+          this.logger.warn(`Failed to connect upstream ${name}:`, error);
+        }
+      }),
+    );
+    return this.names();
+  }
+
+  private async connectOne(
+    name: string,
+    config: UpstreamConfig,
+  ): Promise<ConnectedUpstream> {
+    const client = new Client({ name: `o8-proxy/${name}`, version: "1.0.0" });
+    // Set below; notifications only arrive after connect
+    let upstream: ConnectedUpstream | undefined;
+    client.setNotificationHandler(
+      ToolListChangedNotificationSchema,
+      async () => {
+        if (upstream) upstream.toolsFetchedAt = 0;
+      },
+    );
+    client.setNotificationHandler(
+      ResourceListChangedNotificationSchema,
+      async () => upstream?.reads?.clear(),
+    );
+    client.setNotificationHandler(
+      ResourceUpdatedNotificationSchema,
+      async (notification) => {
+        upstream?.reads?.delete(notification.params.uri);
+      },
+    );
+
+    const options = { timeout: config.timeout };
+    await client.connect(this.createTransport(name, config), options);
+    const capabilities = client.getServerCapabilities() ?? {};
+    const [tools, resources] = await Promise.all([
+      capabilities.tools
+        ? client.listTools(undefined, options).then((r) => r.tools)
+        : [],
+      capabilities.resources
+        ? client.listResources(undefined, options).then((r) => r.resources)
+        : [],
+    ]);
+    upstream = {
+      client,
+      tools,
+      resources,
+      breaker: new CircuitBreaker({
+        failureThreshold: config.failureThreshold,
+        resetTimeoutMs: config.resetTimeout,
+      }),
+      timeout: config.timeout,
+      cacheTTL: config.cacheTTL,
+      toolsFetchedAt: Date.now(),
+      reads:
+        config.cacheTTL > 0
+          ? new LRUCache({
+              max: MAX_CACHED_READS,
+              ttl: config.cacheTTL,
+              // Expired entries stay until replaced, for serveStale
+              noDeleteOnStaleGet: config.serveStale,
+            })
+          : null,
+      serveStale: config.serveStale,
+      hits: 0,
+      misses: 0,
+      staleHits: 0,
+    };
+    return upstream;
+  }
+
+  /**
+   * Names of the connected upstreams
+   */
+  names(): string[] {
+    return [...this.upstreams.keys()].sort(compareStrings);
+  }
+
+  /**
+   * Tools of every upstream, namespaced
+   *
+   * Tool lists older than the upstream's cacheTTL are fetched again; if
+   * that fails, the last list is served.
+   */
+  async listTools(): Promise<Tool[]> {
+    await Promise.all(
+      this.names().map(async (name) => {
+        const upstream = this.upstreams.get(name)!;
+        if (Date.now() - upstream.toolsFetchedAt < upstream.cacheTTL) {
+          return;
+        }
+        try {
+          const { tools } = await this.guarded(name, (client, options) =>
+            client.listTools(undefined, options),
+          );
+          upstream.tools = tools;
+          upstream.toolsFetchedAt = Date.now();
+        } catch (error) {
+          this.logger.debug(`Serving cached tools of upstream ${name}`, error);
+        }
+      }),
+    );
+    return this.names().flatMap((upstream) =>
+      this.upstreams.get(upstream)!.tools.map((tool) => ({
+        ...tool,
+        name: upstreamToolName(upstream, tool.name),
+      })),
+    );
+  }
+
+  /**
+   * Resources of every upstream, namespaced
+   */
+  listResources(): Resource[] {
+    return this.names().flatMap((upstream) =>
+      this.upstreams.get(upstream)!.resources.map((resource) => ({
+        ...resource,
+        uri: upstreamResourceUri(upstream, resource.uri),
+      })),
+    );
+  }
+
+  /**
+   * Call a namespaced tool on its upstream
+   *
+   * @throws {UpstreamError} If no connected upstream owns the name
+   * @throws {UpstreamUnavailableError} If the upstream's circuit is open
+   */
+  async callTool(
+    name: string,
+    args?: Record<string, unknown>,
+  ): Promise<CallToolResult> {
+    const { upstream, name: tool } = this.route(name);
+    return this.guarded(
+      upstream,
+      (client, options) =>
+        client.callTool(
+          { name: tool, arguments: args },
+          undefined,
+          options,
+        ) as Promise<CallToolResult>,
+    );
+  }
+
+  /**
+   * Read a namespaced resource from its upstream, under the namespaced URI
+   *
+   * With `serveStale`, an expired cached read is returned if the upstream
+   * call fails.
+   *
+   * @throws {UpstreamError} If no connected upstream owns the URI
+   * @throws {UpstreamUnavailableError} If the upstream's circuit is open
+   */
+  async readResource(uri: string): Promise<ReadResourceResult> {
+    const { upstream, name } = this.route(uri);
+    const cached = this.upstreams.get(upstream)!;
+    let result = cached.reads?.get(name);
+    if (result) {
+      cached.hits++;
+    } else {
+      cached.misses++;
+      try {
+        result = await this.guarded(upstream, (client, options) =>
+          client.readResource({ uri: name }, options),
+        );
+        cached.reads?.set(name, result);
+      } catch (error) {
+        const stale = cached.serveStale
+          ? cached.reads?.get(name, { allowStale: true })
+          : undefined;
+        if (!stale) {
+          throw error;
+        }
+        cached.staleHits++;
+        this.logger.debug(
+          `Serving stale ${name} of unreachable upstream ${upstream}`,
+          error,
+        );
+        result = stale;
+      }
+    }
+    return {
+      ...result,
+      contents: result.contents.map((content) => ({
+        ...content,
+        uri: upstreamResourceUri(upstream, content.uri),
+      })),
+    };
+  }
+
+  private route(namespaced: string): { upstream: string; name: string } {
+    const parts = splitUpstreamName(namespaced);
+    if (!parts || !this.upstreams.has(parts.upstream)) {
+      throw new UpstreamError(`No upstream for ${namespaced}`);
+    }
+    return parts;
+  }
+
+  /**
+   * Run a call on an upstream through its circuit breaker and timeout
+   */
+  private async guarded<T>(
+    name: string,
+    call: (client: Client, options: { timeout: number }) => Promise<T>,
+  ): Promise<T> {
+    const upstream = this.upstreams.get(name)!;
+    const { breaker } = upstream;
+    if (!breaker.allowRequest()) {
+      throw new UpstreamUnavailableError(name, breaker.retryAfterMs());
+    }
+    const startTime = Date.now();
+    try {
+      const result = await call(upstream.client, { timeout: upstream.timeout });
+      breaker.recordSuccess(Date.now() - startTime);
+      return result;
+    } catch (error) {
+      breaker.recordFailure(error, Date.now() - startTime);
+      if (breaker.status().state === "open") {
+        this.logger.warn(`Upstream ${name} circuit opened`, {
+          error: breaker.status().lastError,
+        });
+      }
+      throw error;
+    }
+  }
+
+  /**
+   * Health of every configured upstream, by name
+   */
+  status(): Record<string, UpstreamStatus> {
+    const status: Record<string, UpstreamStatus> = {};
+    const names = [...this.upstreams.keys(), ...this.failed.keys()].sort(
+      compareStrings,
+    );
+    for (const name of names) {
+      const upstream = this.upstreams.get(name);
+      status[name] = upstream
+        ? {
+            connected: true,
+            tools: upstream.tools.length,
+            resources: upstream.resources.length,
+            circuit: upstream.breaker.status(),
+            cache: {
+              entries: upstream.reads?.size ?? 0,
+              hits: upstream.hits,
+              misses: upstream.misses,
+              stale: upstream.staleHits,
+            },
+            error: null,
+          }
+        : {
+            connected: false,
+            tools: 0,
+            resources: 0,
+            circuit: null,
+            cache: null,
+            error: this.failed.get(name)!,
+          };
+    }
+    return status;
+  }
+
+  /**
+   * Disconnect every upstream
+   */
+  async close(): Promise<void> {
+    await Promise.all(
+      [...this.upstreams.values()].map(({ client }) =>
+        client.close().catch(() => undefined),
+      ),
+    );
+    this.upstreams.clear();
+    this.failed.clear();
+  }
+}
@@ -0,0 +1,1779 @@
+/**
+ * orchestr8 MCP server
+ *
+ * The server class lives here so it can be embedded and driven in-process
+ * (see src/__tests__/server.test.ts); index.ts is the CLI entry point.
+ */
+
+import {
+  McpServer,
+  ResourceTemplate,
+  type RegisteredPrompt,
+} from "@modelcontextprotocol/sdk/server/mcp.js";
+import { StdioServerTransport } from "@modelcontextprotocol/sdk/server/stdio.js";
+import type { Transport } from "@modelcontextprotocol/sdk/shared/transport.js";
+import {
+  CallToolRequestSchema,
+  ListToolsRequestSchema,
+} from "@modelcontextprotocol/sdk/types.js";
+import { PromptLoader } from "./loaders/promptLoader.js";
+import { ResourceLoader } from "./loaders/resourceLoader.js";
+import { Logger } from "./utils/logger.js";
+import { StatsCollector } from "./stats/collector.js";
+import { HTTPTransport, RequestContext } from "./transports/http.js";
+import {
+  StdioLifecycle,
+  type StdioCloseReason,
+} from "./transports/stdioLifecycle.js";
+import { StdoutGuard } from "./transports/stdoutGuard.js";
+import { createTokenSystem } from "./token/index.js";
+import { ServerEventBus, type ServerEventType } from "./events/bus.js";
+import { WebhookSink } from "./events/webhook.js";
+import { ExtensionRegistry } from "./extensions/registry.js";
+import {
+  payloadLimitsFromEnv,
+  checkCount,
+  checkLimit,
+  type PayloadLimits,
+} from "./utils/limits.js";
+import { parseFields, selectFields } from "./utils/fields.js";
+import { flagEnabled, flagValue } from "./utils/flags.js";
+import { SessionRecorder } from "./session/recorder.js";
+import {
+  SessionRegistry,
+  clientLabel,
+  type SessionTransport,
+} from "./session/registry.js";
+import { selectClientProfile } from "./config/clients.js";
+import {
+  CRASH_REPORT_REQUESTS,
+  type CrashContext,
+} from "./state/crashReport.js";
+import { readyTimeout, waitForReady } from "./utils/readiness.js";
+import { UpstreamPool } from "./proxy/upstreams.js";
+import { parseCapabilityFilter } from "./utils/capabilityMatrix.js";
+import { parseRouteRequest } from "./utils/agentRouter.js";
+import { defaultStateDir } from "./state/snapshotStore.js";
+import { policyFromEnv } from "./utils/policy.js";
+import { tlsFromEnv } from "./transports/tls.js";
+import { connectionLimitsFromEnv } from "./transports/connectionLimits.js";
+import { DEFAULT_GREP_LIMIT, GrepPatternError } from "./utils/grep.js";
+import { DEFAULT_JOURNAL_LIMIT } from "./state/journal.js";
+import {
+  StartupProfile,
+  startupProfilingEnabled,
+} from "./utils/startupProfile.js";
+import { withStateLock } from "./state/lock.js";
+import { parseRewriteRequest } from "./utils/rewrite.js";
+import {
+  collectGarbage,
+  gcInterval,
+  retentionPolicy,
+  type GcResult,
+  type RetentionPolicy,
+} from "./state/gc.js";
+import {
+  INDEX_PROGRESS_TOKEN,
+  type IndexingState,
+} from "./utils/indexProgress.js";
+import type { TokenTracker } from "./token/tracker.js";
+import type { TokenStore } from "./token/store.js";
+import type { TokenMetrics } from "./token/metrics.js";
+import type { EfficiencyEngine } from "./token/efficiency.js";
+import { z } from "zod";
+import path from "path";
+import { fileURLToPath } from "url";
+
+// Get current file directory for static files
+const __filename = fileURLToPath(import.meta.url);
+const __dirname = path.dirname(__filename);
+
+// Initialize logger (writes to stderr to avoid corrupting MCP protocol)
+const logger = new Logger("orchestr8-mcp");
+
+export const SERVER_VERSION = "1.0.0";
+
+const HTTP_PORT = parseInt(flagValue("http-port") || "1337", 10);
+
+// Keep serving HTTP after the stdio client disconnects
+const STAY_ALIVE = flagEnabled("stay-alive");
+
+// Throw on writes to stdout that are not JSON-RPC frames (tests, debugging)
+const STRICT_STDOUT = flagEnabled("strict-stdout");
+
+// Record stdio traffic to a JSONL file for later replay
+const RECORD_PATH = flagValue("record");
+
+// Write startup phase timings to stderr once the index is ready
+const PROFILE_STARTUP = startupProfilingEnabled();
+
+/**
+ * Options for embedding the server (tests, custom entry points)
+ */
+export interface Orchestr8ServerOptions {
+  /** Watch prompts and partials for changes (default: outside production) */
+  watch?: boolean;
+}
+
+/**
+ * orchestr8 MCP server: loads prompts and resources and registers them on an
+ * McpServer that can be connected to stdio, HTTP, or an in-memory transport
+ */
+export class Orchestr8Server {
+  private server: McpServer;
+  private promptLoader: PromptLoader;
+  private resourceLoader: ResourceLoader;
+  private stats: StatsCollector;
+  private httpTransport: HTTPTransport | null = null;
+  private stdioLifecycle: StdioLifecycle | null = null;
+  private stdoutGuard: StdoutGuard | null = null;
+  private recorder: SessionRecorder | null = null;
+  private eventBus: ServerEventBus;
+  private webhookSink: WebhookSink | null = null;
+  private extensions!: ExtensionRegistry;
+  private registeredPrompts: RegisteredPrompt[] = [];
+  private sessions = new SessionRegistry();
+  private mcpSessionId: string | null = null;
+  // Progress notifications are only sent once the client has initialized
+  private clientInitialized = false;
+  // Settles when the background index build finishes (or fails)
+  private catalogReady: Promise<void> = Promise.resolve();
+  private catalogSettled = true;
+  private readyTimeout = readyTimeout();
+  private upstreams: UpstreamPool | null = null;
+  private retention: RetentionPolicy | null = retentionPolicy();
+  private gcTimer: NodeJS.Timeout | null = null;
+
+  // Token tracking system
+  private tokenTracker!: TokenTracker;
+  private tokenStore!: TokenStore;
+  private tokenMetrics!: TokenMetrics;
+  private efficiencyEngine!: EfficiencyEngine;
+
+  private options: Required<Orchestr8ServerOptions>;
+  private limits: PayloadLimits = payloadLimitsFromEnv();
+
+  constructor(options: Orchestr8ServerOptions = {}) {
+    this.options = {
+      watch: options.watch ?? process.env.NODE_ENV !== "production",
+    };
+    this.server = new McpServer({
+      name: "o8",
+      version: SERVER_VERSION,
+    });
+    // Record the client and apply its profile once it has identified itself
+    this.server.server.oninitialized = () => {
+      this.clientInitialized = true;
+      this.identifyClient();
+      this.applyClientProfile();
+    };
+    this.server.server.onclose = () => this.closeSession();
+    this.promptLoader = new PromptLoader(logger);
+    this.eventBus = new ServerEventBus();
+    // ResourceLoader and StatsCollector will be initialized in initialize() with token system
+    this.resourceLoader = null as any; // Temporary
+    this.stats = null as any; // Temporary - will be initialized with token metrics
+  }
+
+  async initialize(): Promise<void> {
+    logger.info(`Starting orchestr8 MCP server v${SERVER_VERSION}`);
+    const profile = PROFILE_STARTUP ? new StartupProfile() : null;
+    const time = <T>(phase: string, fn: () => Promise<T>): Promise<T> =>
+      profile ? profile.time(phase, fn) : fn();
+
+    // ============================================================================
+    // NEW: Initialize token tracking system
+    // ============================================================================
+    logger.info("Initializing token efficiency monitoring...");
+    const tokenSystem = createTokenSystem({
+      tracking: {
+        enabled: true,
+        baselineStrategy: "no_jit",
+        deduplication: true,
+        retentionDays: 7,
+        enableTrends: true,
+      },
+      storage: {
+        maxRecords: 10000,
+        retentionDays: 7,
+        autoCleanup: true,
+        cleanupIntervalMs: 60 * 60 * 1000, // 1 hour
+      },
+    });
+
+    this.tokenTracker = tokenSystem.tracker;
+    this.tokenStore = tokenSystem.store;
+    this.tokenMetrics = tokenSystem.metrics;
+    this.efficiencyEngine = tokenSystem.efficiency;
+
+    logger.info("Token efficiency monitoring initialized");
+
+    // Initialize StatsCollector with token metrics
+    this.stats = new StatsCollector(this.tokenMetrics);
+
+    // Mirror server events into the activity log and configured webhooks
+    this.eventBus.subscribe((event) => {
+      this.stats.logActivity(event.type, event.data);
+    });
+    this.webhookSink = WebhookSink.fromEnv(logger);
+    if (this.webhookSink) {
+      this.webhookSink.attach(this.eventBus);
+      logger.info("Webhook delivery enabled for server events");
+    }
+
+    // Initialize ResourceLoader with token system
+    this.resourceLoader = new ResourceLoader(
+      logger,
+      this.tokenTracker,
+      this.tokenStore,
+      this.eventBus,
+    );
+    this.resourceLoader.setIndexProgressListener((state) =>
+      this.notifyIndexProgress(state),
+    );
+    this.resourceLoader.setStartupProfile(profile);
+
+    // Load extension methods (O8_EXTENSIONS=path/to/ext.mjs,...)
+    this.extensions = new ExtensionRegistry({
+      logger,
+      getResourceIndex: () => this.resourceLoader.loadResourceIndex(),
+      getResourceContent: (uri) => this.resourceLoader.loadResourceContent(uri),
+    });
+    const extensionPaths = (process.env.O8_EXTENSIONS || "")
+      .split(",")
+      .map((p) => p.trim())
+      .filter(Boolean);
+    if (extensionPaths.length > 0) {
+      const loaded = await time("extensions", () =>
+        this.extensions.loadFromPaths(extensionPaths),
+      );
+      logger.info(`Loaded ${loaded.length} extensions`);
+    }
+
+    // Load all prompts and resources
+    const prompts = await time("prompts", () =>
+      this.promptLoader.loadAllPrompts(),
+    );
+    const resources = await time("resources", () =>
+      this.resourceLoader.loadAllResources(),
+    );
+
+    logger.info(`Loaded ${prompts.length} prompts`);
+    logger.info(`Loaded ${resources.length} resources`);
+
+    // Pre-load resource index in the background: initialize is answered
+    // at once and queries wait for the index (see awaitCatalog)
+    this.catalogSettled = false;
+    this.catalogReady = this.resourceLoader.loadResourceIndex().then(
+      () => {
+        this.catalogSettled = true;
+        logger.info("Resource index pre-loaded for dynamic matching");
+        this.notifyCatalogReady();
+      },
+      (error) => {
+        this.catalogSettled = true;
+        logger.warn(
+          "Failed to pre-load resource index, will load on-demand:",
+          error,
+        );
+      },
+    );
+    if (profile) {
+      void this.catalogReady.then(() => {
+        process.stderr.write(
+          `${JSON.stringify({ startupProfile: profile.report() })}\n`,
+        );
+      });
+    }
+
+    // ============================================================================
+    // NEW: Initialize provider system
+    // ============================================================================
+    try {
+      await time("providers", () =>
+        this.resourceLoader.initializeProviders(),
+      );
+      logger.info("Provider system initialized successfully");
+    } catch (error) {
+      logger.error("Failed to initialize provider system:", error);
+      // Don't fail startup - continue with local resources only
+      logger.warn("Continuing with local resources only");
+    }
+
+    // Register prompts
+    this.registerPrompts(prompts);
+
+    // Register resources
+    this.registerResources(resources);
+
+    // Register dynamic resource templates
+    this.registerDynamicTemplates();
+
+    // Aggregate tools and resources of configured upstream MCP servers
+    const upstreamConfigs = this.resourceLoader.getUpstreams();
+    if (Object.keys(upstreamConfigs).length > 0) {
+      const upstreams = new UpstreamPool(logger);
+      this.upstreams = upstreams;
+      await time("upstreams", () => upstreams.connect(upstreamConfigs));
+      await this.registerUpstreams(upstreams);
+    }
+
+    // Apply the state retention policy in the background
+    if (this.retention) {
+      this.gcTimer = setInterval(() => {
+        this.collectStateGarbage().catch((error) =>
+          logger.warn("State garbage collection failed", error),
+        );
+      }, gcInterval());
+      this.gcTimer.unref();
+    }
+
+    // Set up hot reload in development
+    if (this.options.watch) {
+      this.promptLoader.watchForChanges(() => {
+        logger.info("Prompts changed - restart server to reload");
+      });
+      this.resourceLoader.watchPartials();
+    }
+  }
+
+  /**
+   * Store the clientInfo from `initialize` on the MCP session
+   */
+  private identifyClient(): void {
+    const clientInfo = this.server.server.getClientVersion();
+    if (!this.mcpSessionId || !clientInfo) {
+      return;
+    }
+    this.sessions.identify(this.mcpSessionId, clientInfo);
+    logger.info("Client initialized", {
+      session: this.mcpSessionId,
+      client: clientInfo.name,
+      version: clientInfo.version,
+    });
+  }
+
+  /**
+   * Count a request on the MCP session and in the stats, labelled by client
+   */
+  private trackRequest(method: string, latency: number): void {
+    const session = this.mcpSessionId
+      ? this.sessions.recordRequest(this.mcpSessionId)
+      : null;
+    this.stats.trackRequest(
+      method,
+      latency,
+      undefined,
+      clientLabel(session?.client),
+    );
+  }
+
+  /**
+   * Connect the MCP server and register the connection as a session
+   */
+  private async openSession(
+    transport: Transport,
+    kind: SessionTransport,
+  ): Promise<void> {
+    this.mcpSessionId = this.sessions.open(kind);
+    await this.server.connect(transport);
+  }
+
+  private closeSession(): void {
+    if (this.mcpSessionId) {
+      this.sessions.close(this.mcpSessionId);
+      this.mcpSessionId = null;
+    }
+  }
+
+  /**
+   * Wait (bounded) for the background index build before a query
+   *
+   * @throws {CatalogNotReadyError} If the index is still loading after the
+   *   ready timeout
+   */
+  private async awaitCatalog(): Promise<void> {
+    if (!this.catalogSettled) {
+      await waitForReady(this.catalogReady, this.readyTimeout);
+    }
+  }
+
+  /**
+   * Wait until the background index build has finished
+   */
+  whenCatalogReady(): Promise<void> {
+    return this.catalogReady;
+  }
+
+  /**
+   * Tell an initialized client that the catalog can now be queried
+   */
+  private notifyCatalogReady(): void {
+    if (!this.clientInitialized) {
+      return;
+    }
+    try {
+      this.server.sendResourceListChanged();
+    } catch (error) {
+      logger.debug("Failed to send catalog ready notification", error);
+    }
+  }
+
+  /**
+   * Forward index build progress to the client as notifications/progress
+   */
+  private notifyIndexProgress(state: IndexingState): void {
+    if (!this.clientInitialized) {
+      return;
+    }
+    this.server.server
+      .notification({
+        method: "notifications/progress",
+        params: {
+          progressToken: INDEX_PROGRESS_TOKEN,
+          progress: state.processed,
+          total: state.total,
+          message: `Indexing resources (${state.percent}%)`,
+        },
+      })
+      .catch((error) => {
+        logger.debug("Failed to send indexing progress notification", error);
+      });
+  }
+
+  /**
+   * Apply the `clients` profile matching the clientInfo from `initialize`
+   */
+  private applyClientProfile(): void {
+    const clientInfo = this.server.server.getClientVersion();
+    const selected = selectClientProfile(
+      this.resourceLoader.getClientProfiles(),
+      clientInfo?.name,
+    );
+    if (!selected) {
+      return;
+    }
+
+    const { key, profile } = selected;
+    logger.info(
+      `Client ${clientInfo?.name ?? "unknown"} ${clientInfo?.version ?? ""} uses profile "${key}"`,
+    );
+    if (!profile.prompts) {
+      this.registeredPrompts.forEach((prompt) => prompt.disable());
+    }
+    this.resourceLoader.setMatchDefaults({
+      maxResults: profile.maxResults,
+      maxTokens: profile.maxTokens,
+      mode: profile.mode,
+    });
+  }
+
+  private registerPrompts(prompts: any[]): void {
+    for (const prompt of prompts) {
+      // Convert prompt arguments to Zod schema
+      const argsSchema: any = {};
+      if (prompt.arguments && prompt.arguments.length > 0) {
+        for (const arg of prompt.arguments) {
+          const zodType = arg.required ? z.string() : z.string().optional();
+          argsSchema[arg.name] = zodType;
+        }
+      }
+
+      const registered = this.server.registerPrompt(
+        prompt.name,
+        {
+          title: prompt.title,
+          description: prompt.description,
+          argsSchema:
+            Object.keys(argsSchema).length > 0 ? argsSchema : undefined,
+        },
+        async (args: any, _extra: any) => {
+          const startTime = Date.now();
+          logger.debug(`Loading prompt: ${prompt.name}`);
+
+          // Track MCP prompt request
+          this.stats.logActivity("prompt_get", {
+            name: prompt.name,
+            args: args,
+          });
+
+          try {
+            // Convert args to the format expected by loader
+            const argValues: Record<string, any> = args || {};
+
+            // Load prompt content with argument substitution
+            const content = await this.promptLoader.loadPromptContent(
+              prompt,
+              argValues,
+            );
+
+            const latency = Date.now() - startTime;
+            this.trackRequest(`prompt:${prompt.name}`, latency);
+
+            return {
+              messages: [
+                {
+                  role: "user" as const,
+                  content: {
+                    type: "text" as const,
+                    text: content,
+                  },
+                },
+              ],
+            };
+          } catch (error) {
+            this.stats.trackError(error);
+            throw error;
+          }
+        },
+      );
+      this.registeredPrompts.push(registered);
+    }
+  }
+
+  /**
+   * Expose upstream tools and resources under namespaced names
+   */
+  private async registerUpstreams(upstreams: UpstreamPool): Promise<void> {
+    const tools = await upstreams.listTools();
+    if (tools.length > 0) {
+      // Upstream tools come with JSON Schemas, so they bypass registerTool
+      // (which takes zod shapes) and are served by raw handlers
+      this.server.server.registerCapabilities({ tools: {} });
+      this.server.server.setRequestHandler(
+        ListToolsRequestSchema,
+        async () => ({ tools: upstreams.listTools() }),
+      );
+      this.server.server.setRequestHandler(
+        CallToolRequestSchema,
+        async (request) => {
+          const startTime = Date.now();
+          try {
+            const result = await upstreams.callTool(
+              request.params.name,
+              request.params.arguments,
+            );
+            this.trackRequest(
+              `upstream:${request.params.name}`,
+              Date.now() - startTime,
+            );
+            return result;
+          } catch (error) {
+            this.stats.trackError(error);
+            throw error;
+          }
+        },
+      );
+    }
+
+    for (const resource of upstreams.listResources()) {
+      this.server.registerResource(
+        resource.uri,
+        resource.uri,
+        {
+          mimeType: resource.mimeType,
+          description: resource.description,
+        },
+        async (uri) => {
+          const startTime = Date.now();
+          try {
+            const result = await upstreams.readResource(uri.toString());
+            this.trackRequest("upstream:resource", Date.now() - startTime);
+            return result;
+          } catch (error) {
+            this.stats.trackError(error);
+            throw error;
+          }
+        },
+      );
+    }
+
+    logger.info(
+      `Aggregating ${tools.length} tools and ${upstreams.listResources().length} resources from upstreams: ${upstreams.names().join(", ")}`,
+    );
+  }
+
+  private registerResources(resources: any[]): void {
+    // Register lightweight resource registry for discovery
+    this.registerResourceRegistry(resources);
+
+    // Register aggregate list resources for each category
+    this.registerAggregateListResources(resources);
+
+    // Register individual resources so they appear in resources/list
+    this.registerIndividualResources(resources);
+  }
+
+  private registerResourceRegistry(resources: any[]): void {
+    this.server.registerResource(
+      "resource-registry",
+      "o8://registry",
+      {
+        mimeType: "application/json",
+        description: "Lightweight resource catalog for discovery",
+      },
+      async () => {
+        // Track MCP registry request
+        this.stats.logActivity("resource_read", {
+          uri: "o8://registry",
+          category: "registry",
+        });
+
+        const catalog = {
+          version: "1.0.0",
+          totalResources: resources.length,
+          categories: {
+            agents: resources.filter((r) => r.category === "agents").length,
+            skills: resources.filter((r) => r.category === "skills").length,
+            patterns: resources.filter((r) => r.category === "patterns").length,
+            examples: resources.filter((r) => r.category === "examples").length,
+            guides: resources.filter((r) => r.category === "guides").length,
+            workflows: resources.filter((r) => r.category === "workflows")
+              .length,
+          },
+          searchUri:
+            "o8://match?query=<keywords>&mode=index&maxResults=5",
+          bodySearchUri: "o8://search?query=<terms>&category=agents&limit=10",
+          sampleUri: "o8://sample?category=agents&count=10&seed=<n>",
+          summaryUri: "o8://summary?category=agents",
+          usage:
+            "Use o8://match?query=... for resource discovery. Default mode is 'index' for optimal efficiency.",
+        };
+
+        return {
+          contents: [
+            {
+              uri: "o8://registry",
+              mimeType: "application/json",
+              text: JSON.stringify(catalog, null, 2),
+            },
+          ],
+        };
+      },
+    );
+
+    logger.debug(
+      `Registered resource registry with ${resources.length} total resources`,
+    );
+  }
+
+  private registerAggregateListResources(resources: any[]): void {
+    // Group resources by category
+    const byCategory = resources.reduce(
+      (acc, resource) => {
+        const category = resource.category || "other";
+        if (!acc[category]) {
+          acc[category] = [];
+        }
+        acc[category].push(resource);
+        return acc;
+      },
+      {} as Record<string, any[]>,
+    );
+
+    // Register aggregate list resource for each category
+    const categories = [
+      {
+        name: "workflows",
+        description: "Complete list of all available workflows",
+      },
+      { name: "agents", description: "Complete list of all available agents" },
+      { name: "skills", description: "Complete list of all available skills" },
+      {
+        name: "patterns",
+        description: "Complete list of all available patterns",
+      },
+      {
+        name: "examples",
+        description: "Complete list of all available examples",
+      },
+      { name: "guides", description: "Complete list of all available guides" },
+    ];
+
+    for (const { name, description } of categories) {
+      const categoryResources = byCategory[name] || [];
+      const aggregateUri = `o8://${name}`;
+
+      this.server.registerResource(
+        `${name}-list`,
+        aggregateUri,
+        {
+          mimeType: "application/json",
+          description: description,
+        },
+        async (uri) => {
+          const startTime = Date.now();
+          logger.debug(`Loading aggregate list: ${aggregateUri}`);
+
+          // Track MCP resource list request
+          this.stats.logActivity("resources_list", {
+            category: name,
+            count: categoryResources.length,
+          });
+
+          try {
+            // Build JSON list of all resources in this category
+            const resourceList = categoryResources.map((r: any) => ({
+              name: r.name,
+              uri: r.uri,
+              description: r.description,
+              mimeType: r.mimeType,
+            }));
+
+            const jsonContent = JSON.stringify(
+              {
+                category: name,
+                count: resourceList.length,
+                resources: resourceList,
+              },
+              null,
+              2,
+            );
+
+            const latency = Date.now() - startTime;
+            this.trackRequest(`aggregate:${name}`, latency);
+
+            return {
+              contents: [
+                {
+                  uri: uri.toString(),
+                  mimeType: "application/json",
+                  text: jsonContent,
+                },
+              ],
+            };
+          } catch (error) {
+            this.stats.trackError(error);
+            throw error;
+          }
+        },
+      );
+
+      logger.debug(`Registered aggregate list resource: ${aggregateUri}`);
+    }
+  }
+
+  private registerIndividualResources(resources: any[]): void {
+    logger.info(
+      `Registering ${resources.length} individual resources for discovery`,
+    );
+
+    for (const resource of resources) {
+      this.server.registerResource(
+        resource.uri,
+        resource.uri,
+        {
+          mimeType: resource.mimeType,
+          description: resource.description,
+        },
+        async (uri) => {
+          const startTime = Date.now();
+          logger.debug(`Loading individual resource: ${uri.toString()}`);
+
+          // Track MCP resource request
+          this.stats.logActivity("resource_read", {
+            uri: uri.toString(),
+            category: resource.category,
+          });
+
+          try {
+            const content = await this.resourceLoader.loadResourceContent(
+              uri.toString(),
+            );
+
+            const latency = Date.now() - startTime;
+            this.trackRequest(`individual:${resource.category}`, latency);
+
+            return {
+              contents: [
+                {
+                  uri: uri.toString(),
+                  mimeType: resource.mimeType,
+                  text: content,
+                },
+              ],
+            };
+          } catch (error) {
+            this.stats.trackError(error);
+            throw error;
+          }
+        },
+      );
+    }
+
+    logger.info(
+      `Successfully registered ${resources.length} individual resources`,
+    );
+  }
+
+  private registerDynamicTemplates(): void {
+    const dynamicCategories = [
+      {
+        category: "agents",
+        description:
+          "Dynamic agent matching - finds and assembles relevant agent resources based on query",
+      },
+      {
+        category: "skills",
+        description:
+          "Dynamic skill matching - finds and assembles relevant skill resources based on query",
+      },
+      {
+        category: "examples",
+        description:
+          "Dynamic example matching - finds and assembles relevant example resources based on query",
+      },
+      {
+        category: "patterns",
+        description:
+          "Dynamic pattern matching - finds and assembles relevant pattern resources based on query",
+      },
+      {
+        category: "guides",
+        description:
+          "Dynamic guide matching - finds and assembles relevant guide resources based on query",
+      },
+      {
+        category: "best-practices",
+        description:
+          "Dynamic best practice matching - finds and assembles relevant best practice resources based on query",
+      },
+      {
+        category: "workflows",
+        description:
+          "Dynamic workflow matching - finds and assembles relevant workflow resources based on query",
+      },
+    ];
+
+    for (const { category, description } of dynamicCategories) {
+      // Register dynamic matching template (e.g., o8://agents/match?query=...)
+      const dynamicTemplateUri = `o8://${category}/match{+rest}`;
+
+      this.server.registerResource(
+        `${category}-dynamic`,
+        new ResourceTemplate(dynamicTemplateUri, { list: undefined }),
+        {
+          mimeType: "text/markdown",
+          description: description,
+        },
+        async (uri, params: Record<string, any>) => {
+          const startTime = Date.now();
+          const fullUri = uri.toString();
+          logger.debug(`Loading dynamic resource: ${fullUri}`);
+
+          // Track MCP resource request
+          this.stats.logActivity("resource_read", {
+            uri: fullUri,
+            category: category,
+          });
+
+          try {
+            await this.awaitCatalog();
+            const content =
+              await this.resourceLoader.loadResourceContent(fullUri);
+
+            const latency = Date.now() - startTime;
+            this.trackRequest(`dynamic:${category}`, latency);
+
+            return {
+              contents: [
+                {
+                  uri: fullUri,
+                  mimeType: "text/markdown",
+                  text: content,
+                },
+              ],
+            };
+          } catch (error) {
+            this.stats.trackError(error);
+            throw error;
+          }
+        },
+      );
+
+      logger.debug(
+        `Registered dynamic resource template: ${dynamicTemplateUri}`,
+      );
+
+      // Register static resource template (e.g., o8://agents/medium-writer-expert)
+      const staticTemplateUri = `o8://${category}/{+resourceId}`;
+
+      this.server.registerResource(
+        `${category}-static`,
+        new ResourceTemplate(staticTemplateUri, { list: undefined }),
+        {
+          mimeType: "text/markdown",
+          description: `Access individual ${category} resources by ID`,
+        },
+        async (uri, params: Record<string, any>) => {
+          const startTime = Date.now();
+          const fullUri = uri.toString();
+
+          // Skip if this is a /match URI (handled by dynamic template)
+          if (fullUri.includes("/match")) {
+            throw new Error(
+              `URI ${fullUri} should be handled by dynamic template`,
+            );
+          }
+
+          logger.debug(`Loading static resource: ${fullUri}`);
+
+          // Track MCP resource request
+          this.stats.logActivity("resource_read", {
+            uri: fullUri,
+            category: category,
+          });
+
+          try {
+            await this.awaitCatalog();
+            const content =
+              await this.resourceLoader.loadResourceContent(fullUri);
+            // Flag reads through an alias with the canonical id
+            const canonical = await this.resourceLoader.resolveAlias(fullUri);
+
+            const latency = Date.now() - startTime;
+            this.trackRequest(`static:${category}`, latency);
+
+            return {
+              contents: [
+                {
+                  uri: fullUri,
+                  mimeType: "text/markdown",
+                  text: content,
+                  ...(canonical ? { _meta: { canonical } } : {}),
+                },
+              ],
+            };
+          } catch (error) {
+            this.stats.trackError(error);
+            throw error;
+          }
+        },
+      );
+
+      logger.debug(`Registered static resource template: ${staticTemplateUri}`);
+    }
+
+    // Register global catch-all template
+    const globalTemplateUri = "o8://match{+rest}";
+    this.server.registerResource(
+      "global-dynamic",
+      new ResourceTemplate(globalTemplateUri, { list: undefined }),
+      {
+        mimeType: "text/markdown",
+        description: "Dynamic resource matching across all categories",
+      },
+      async (uri, params: Record<string, any>) => {
+        const startTime = Date.now();
+        const fullUri = uri.toString();
+        logger.debug(`Loading global dynamic resource: ${fullUri}`);
+
+        // Track MCP resource request
+        this.stats.logActivity("resource_read", {
+          uri: fullUri,
+          category: "global",
+        });
+
+        try {
+          await this.awaitCatalog();
+          const content =
+            await this.resourceLoader.loadResourceContent(fullUri);
+
+          const latency = Date.now() - startTime;
+          this.trackRequest("dynamic:global", latency);
+
+          return {
+            contents: [
+              {
+                uri: fullUri,
+                mimeType: "text/markdown",
+                text: content,
+              },
+            ],
+          };
+        } catch (error) {
+          this.stats.trackError(error);
+          throw error;
+        }
+      },
+    );
+    logger.debug(
+      `Registered global dynamic resource template: ${globalTemplateUri}`,
+    );
+
+    // Register full-text body search (o8://search?query=...&category=agents&limit=10)
+    const searchTemplateUri = "o8://search{+rest}";
+    this.server.registerResource(
+      "body-search",
+      new ResourceTemplate(searchTemplateUri, { list: undefined }),
+      {
+        mimeType: "application/json",
+        description:
+          "Full-text search inside resource bodies with highlighted snippets",
+      },
+      async (uri) => {
+        const startTime = Date.now();
+        const fullUri = uri.toString();
+        const params = new URLSearchParams(fullUri.split("?")[1] || "");
+        const query = params.get("query") || "";
+        const limit = Number(params.get("limit") ?? 10);
+
+        this.stats.logActivity("resource_read", {
+          uri: fullUri,
+          category: "search",
+        });
+
+        try {
+          if (!query) {
+            throw new Error('Body search requires a "query" parameter');
+          }
+          if (!Number.isInteger(limit) || limit < 1) {
+            throw new Error('Body search "limit" must be a positive integer');
+          }
+          checkLimit("query", query.length, this.limits.maxQueryLength);
+          checkLimit("limit", limit, this.limits.maxResults);
+
+          const hits = await this.resourceLoader.searchBodies(query, {
+            category: params.get("category") || undefined,
+            limit,
+          });
+
+          const latency = Date.now() - startTime;
+          this.trackRequest("search:body", latency);
+
+          return {
+            contents: [
+              {
+                uri: fullUri,
+                mimeType: "application/json",
+                text: JSON.stringify(
+                  {
+                    query,
+                    total: hits.length,
+                    hits: selectFields(hits, parseFields(params.get("fields"))),
+                  },
+                  null,
+                  2,
+                ),
+              },
+            ],
+          };
+        } catch (error) {
+          this.stats.trackError(error);
+          throw error;
+        }
+      },
+    );
+    logger.debug(`Registered body search template: ${searchTemplateUri}`);
+
+    // Register sampling for catalog QA (o8://sample?category=agents&count=5,
+    // o8://sample?perCategory=2, o8://random?category=agents)
+    const samplers = [
+      {
+        name: "sample",
+        description:
+          "Random or stratified (perCategory) sample of resources for spot checks",
+      },
+      { name: "random", description: "One random resource" },
+    ];
+    for (const { name, description } of samplers) {
+      const sampleTemplateUri = `o8://${name}{+rest}`;
+      this.server.registerResource(
+        `resource-${name}`,
+        new ResourceTemplate(sampleTemplateUri, { list: undefined }),
+        { mimeType: "application/json", description },
+        async (uri) => {
+          const startTime = Date.now();
+          const fullUri = uri.toString();
+          const params = new URLSearchParams(fullUri.split("?")[1] || "");
+          const intParam = (key: string) =>
+            params.has(key) ? parseInt(params.get(key)!, 10) : undefined;
+          const countParam = (key: string) =>
+            params.has(key)
+              ? checkCount(key, Number(params.get(key)), this.limits.maxResults)
+              : undefined;
+
+          this.stats.logActivity("resource_read", {
+            uri: fullUri,
+            category: name,
+          });
+
+          try {
+            const count = name === "random" ? 1 : (countParam("count") ?? 10);
+            const perCategory =
+              name === "random" ? undefined : countParam("perCategory");
+
+            const sample = await this.resourceLoader.sampleResources({
+              count,
+              perCategory,
+              category: params.get("category") || undefined,
+              seed: intParam("seed"),
+            });
+
+            const latency = Date.now() - startTime;
+            this.trackRequest(`sample:${name}`, latency);
+
+            return {
+              contents: [
+                {
+                  uri: fullUri,
+                  mimeType: "application/json",
+                  text: JSON.stringify(
+                    {
+                      ...sample,
+                      resources: selectFields(
+                        sample.resources,
+                        parseFields(params.get("fields")),
+                      ),
+                    },
+                    null,
+                    2,
+                  ),
+                },
+              ],
+            };
+          } catch (error) {
+            this.stats.trackError(error);
+            throw error;
+          }
+        },
+      );
+      logger.debug(`Registered sampling template: ${sampleTemplateUri}`);
+    }
+
+    // Register catalog statistics (o8://summary, o8://summary?category=agents)
+    const summaryTemplateUri = "o8://summary{+rest}";
+    this.server.registerResource(
+      "catalog-summary",
+      new ResourceTemplate(summaryTemplateUri, { list: undefined }),
+      {
+        mimeType: "application/json",
+        description:
+          "Catalog statistics: counts by category, model, and tag, sizes, and validation problems",
+      },
+      async (uri) => {
+        const startTime = Date.now();
+        const fullUri = uri.toString();
+        const params = new URLSearchParams(fullUri.split("?")[1] || "");
+
+        this.stats.logActivity("resource_read", {
+          uri: fullUri,
+          category: "summary",
+        });
+
+        try {
+          const summary = await this.resourceLoader.getCatalogSummary({
+            category: params.get("category") || undefined,
+            topTags: params.has("topTags")
+              ? checkCount(
+                  "topTags",
+                  Number(params.get("topTags")),
+                  this.limits.maxResults,
+                )
+              : undefined,
+          });
+
+          const latency = Date.now() - startTime;
+          this.trackRequest("summary", latency);
+
+          return {
+            contents: [
+              {
+                uri: fullUri,
+                mimeType: "application/json",
+                text: JSON.stringify(summary, null, 2),
+              },
+            ],
+          };
+        } catch (error) {
+          this.stats.trackError(error);
+          throw error;
+        }
+      },
+    );
+    logger.debug(`Registered catalog summary template: ${summaryTemplateUri}`);
+
+    // Register git history (o8://history?id=agents/rust-expert&limit=20)
+    const historyTemplateUri = "o8://history{+rest}";
+    this.server.registerResource(
+      "resource-history",
+      new ResourceTemplate(historyTemplateUri, { list: undefined }),
+      {
+        mimeType: "application/json",
+        description:
+          "Git commits that changed a resource (requires resources in a git repository)",
+      },
+      async (uri) => {
+        const startTime = Date.now();
+        const fullUri = uri.toString();
+        const params = new URLSearchParams(fullUri.split("?")[1] || "");
+        const id = params.get("id") || "";
+        const limit = Number(params.get("limit") ?? 20);
+
+        this.stats.logActivity("resource_read", {
+          uri: fullUri,
+          category: "history",
+        });
+
+        try {
+          if (!id) {
+            throw new Error('History requires an "id" parameter');
+          }
+          if (!Number.isInteger(limit) || limit < 1) {
+            throw new Error('History "limit" must be a positive integer');
+          }
+          checkLimit("limit", limit, this.limits.maxResults);
+
+          const commits = await this.resourceLoader.getResourceHistory(
+            id,
+            limit,
+          );
+
+          const latency = Date.now() - startTime;
+          this.trackRequest("history", latency);
+
+          return {
+            contents: [
+              {
+                uri: fullUri,
+                mimeType: "application/json",
+                text: JSON.stringify({ id, commits }, null, 2),
+              },
+            ],
+          };
+        } catch (error) {
+          this.stats.trackError(error);
+          throw error;
+        }
+      },
+    );
+    logger.debug(`Registered history template: ${historyTemplateUri}`);
+
+    // Register layer provenance (o8://provenance?id=agents/rust-expert)
+    const provenanceTemplateUri = "o8://provenance{+rest}";
+    this.server.registerResource(
+      "resource-provenance",
+      new ResourceTemplate(provenanceTemplateUri, { list: undefined }),
+      {
+        mimeType: "application/json",
+        description:
+          "Layer and file a resource was loaded from, and the layers it overrides",
+      },
+      async (uri) => {
+        const startTime = Date.now();
+        const fullUri = uri.toString();
+        const params = new URLSearchParams(fullUri.split("?")[1] || "");
+        const id = params.get("id") || "";
+
+        this.stats.logActivity("resource_read", {
+          uri: fullUri,
+          category: "provenance",
+        });
+
+        try {
+          if (!id) {
+            throw new Error('Provenance requires an "id" parameter');
+          }
+
+          const provenance =
+            await this.resourceLoader.getResourceProvenance(id);
+          if (!provenance) {
+            throw new Error(`Resource not found: ${id}`);
+          }
+
+          const latency = Date.now() - startTime;
+          this.trackRequest("provenance", latency);
+
+          return {
+            contents: [
+              {
+                uri: fullUri,
+                mimeType: "application/json",
+                text: JSON.stringify(provenance, null, 2),
+              },
+            ],
+          };
+        } catch (error) {
+          this.stats.trackError(error);
+          throw error;
+        }
+      },
+    );
+    logger.debug(`Registered provenance template: ${provenanceTemplateUri}`);
+
+    // Register version diff (o8://diff?id=agents/rust-expert&from=HEAD~1&to=HEAD)
+    const diffTemplateUri = "o8://diff{+rest}";
+    this.server.registerResource(
+      "resource-diff",
+      new ResourceTemplate(diffTemplateUri, { list: undefined }),
+      {
+        mimeType: "application/json",
+        description:
+          "Unified diff of a resource between two git revisions or the working copy",
+      },
+      async (uri) => {
+        const startTime = Date.now();
+        const fullUri = uri.toString();
+        const params = new URLSearchParams(fullUri.split("?")[1] || "");
+        const id = params.get("id") || "";
+
+        this.stats.logActivity("resource_read", {
+          uri: fullUri,
+          category: "diff",
+        });
+
+        try {
+          if (!id) {
+            throw new Error('Diff requires an "id" parameter');
+          }
+
+          const result = await this.resourceLoader.diffResource(
+            id,
+            params.get("from") || undefined,
+            params.get("to") || undefined,
+          );
+
+          const latency = Date.now() - startTime;
+          this.trackRequest("diff", latency);
+
+          return {
+            contents: [
+              {
+                uri: fullUri,
+                mimeType: "application/json",
+                text: JSON.stringify(result, null, 2),
+              },
+            ],
+          };
+        } catch (error) {
+          this.stats.trackError(error);
+          throw error;
+        }
+      },
+    );
+    logger.debug(`Registered diff template: ${diffTemplateUri}`);
+  }
+
+  /**
+   * Connect the MCP server to a transport
+   *
+   * Used directly by tests with an in-memory transport pair.
+   *
+   * @param transport - Transport to serve requests on
+   */
+  async connect(transport: Transport): Promise<void> {
+    await this.openSession(transport, "memory");
+  }
+
+  async startStdio(): Promise<void> {
+    logger.info("Starting in stdio mode (Claude Desktop integration)");
+
+    const transport = this.createStdioTransport();
+    await this.openSession(transport, "stdio");
+    this.watchStdio(transport);
+
+    logger.info("orchestr8 MCP server started successfully in stdio mode");
+  }
+
+  async startHttp(): Promise<void> {
+    logger.info(`Starting in HTTP mode on port ${HTTP_PORT}`);
+
+    // Create HTTP transport with methods to access MCP functionality
+    const mcpInterface = {
+      handleRequest: async (
+        method: string,
+        params: any,
+        context?: RequestContext,
+      ) => {
+        switch (method) {
+          case "extensions/list":
+            return {
+              extensions: this.extensions.listExtensions(),
+              methods: this.extensions.listMethods(),
+            };
+          case "catalog/snapshot":
+            return this.resourceLoader.snapshotCatalog(params?.name);
+          case "catalog/rollback":
+            return this.resourceLoader.rollbackCatalog(params?.name);
+          case "catalog/snapshots":
+            return this.resourceLoader.listSnapshots();
+          case "state/gc":
+            return this.collectStateGarbage();
+          case "catalog/reload":
+            return this.resourceLoader.reloadIndex();
+          case "telemetry/summary":
+            return this.resourceLoader.getQueryLogSummary(
+              params?.limit === undefined
+                ? undefined
+                : checkCount("limit", params.limit, this.limits.maxResults),
+            );
+          case "agents/capabilities":
+            await this.awaitCatalog();
+            return this.resourceLoader.getCapabilities(
+              parseCapabilityFilter(params),
+            );
+          case "agents/route": {
+            const request = parseRouteRequest(method, params);
+            await this.awaitCatalog();
+            return this.resourceLoader.routeAgent(request);
+          }
+          case "orchestrate/plan": {
+            const request = parseRouteRequest(method, params);
+            await this.awaitCatalog();
+            return this.resourceLoader.planTask(request);
+          }
+          case "agents/handoffs":
+            if (
+              params?.agent !== undefined &&
+              typeof params.agent !== "string"
+            ) {
+              throw new Error('agents/handoffs "agent" must be an agent id');
+            }
+            await this.awaitCatalog();
+            return this.resourceLoader.getHandoffs(params?.agent);
+          case "agents/misses": {
+            const limit =
+              params?.limit === undefined
+                ? undefined
+                : checkCount("limit", params.limit, this.limits.maxResults);
+            await this.awaitCatalog();
+            return this.resourceLoader.getMisses(limit);
+          }
+          case "agents/grep": {
+            if (typeof params?.pattern !== "string") {
+              throw new GrepPatternError(
+                'agents/grep requires "pattern": a regular expression',
+              );
+            }
+            const limit = checkCount(
+              "limit",
+              params.limit ?? DEFAULT_GREP_LIMIT,
+              this.limits.maxResults,
+            );
+            await this.awaitCatalog();
+            return this.resourceLoader.grepResources(params.pattern, {
+              category: params.category,
+              ignoreCase: params.ignoreCase === true,
+              limit,
+            });
+          }
+          case "sessions/list":
+            return { sessions: this.sessions.list() };
+          case "sessions/terminate": {
+            if (typeof params?.id !== "string") {
+              throw new Error('sessions/terminate requires "id": a session id');
+            }
+            const session = this.sessions.terminate(params.id);
+            logger.warn("Session terminated by admin", {
+              session: session.id,
+              transport: session.transport,
+              client: clientLabel(session.client),
+            });
+            return { terminated: session };
+          }
+          case "agents/rewrite":
+            return this.resourceLoader.rewriteResources(
+              parseRewriteRequest(params),
+              `http:${context?.caller ?? "unknown"}`,
+            );
+          case "agents/journal": {
+            const limit = params?.limit ?? DEFAULT_JOURNAL_LIMIT;
+            if (!Number.isInteger(limit) || limit < 1) {
+              throw new Error(
+                'agents/journal "limit" must be a positive integer',
+              );
+            }
+            checkLimit("limit", limit, this.limits.maxResults);
+            return this.resourceLoader.getJournal(limit);
+          }
+          case "agents/batch_get": {
+            const ids = params?.ids;
+            if (
+              !Array.isArray(ids) ||
+              !ids.every((id: unknown) => typeof id === "string")
+            ) {
+              throw new Error(
+                'agents/batch_get requires "ids": an array of names or ids',
+              );
+            }
+            checkLimit("ids", ids.length, this.limits.maxBatchSize);
+            await this.awaitCatalog();
+            return this.resourceLoader.batchGet(ids, {
+              snapshot: params?.snapshot,
+              asOf: params?.asOf,
+            });
+          }
+          default:
+            return this.extensions.dispatch(method, params);
+        }
+      },
+      getAvailableAgents: async () => {
+        return this.resourceLoader.getResourcesByCategory("agents");
+      },
+      getAvailableSkills: async () => {
+        return this.resourceLoader.getResourcesByCategory("skills");
+      },
+      getAvailableWorkflows: async () => {
+        return this.resourceLoader.getResourcesByCategory("workflows");
+      },
+      getAvailablePatterns: async () => {
+        return this.resourceLoader.getResourcesByCategory("patterns");
+      },
+      searchResources: async (query: string) => {
+        return this.resourceLoader.searchResources(query);
+      },
+      searchBodies: async (query: string, options?: any) => {
+        return this.resourceLoader.searchBodies(query, options);
+      },
+      sampleResources: async (options?: any) => {
+        return this.resourceLoader.sampleResources(options);
+      },
+      streamResources: (options?: any) => {
+        return this.resourceLoader.streamResources(options);
+      },
+      getCatalogSummary: async (options?: any) => {
+        return this.resourceLoader.getCatalogSummary(options);
+      },
+      getResourceHistory: async (id: string, limit?: number) => {
+        return this.resourceLoader.getResourceHistory(id, limit);
+      },
+      getResourceProvenance: async (id: string) => {
+        return this.resourceLoader.getResourceProvenance(id);
+      },
+      resolveAlias: async (id: string) => {
+        return this.resourceLoader.resolveAlias(id);
+      },
+      getQueryLogSummary: async (limit?: number) => {
+        return this.resourceLoader.getQueryLogSummary(limit);
+      },
+      getCatalogState: () => {
+        return this.resourceLoader.getCatalogState();
+      },
+      getUpstreamStatus: () => {
+        return this.upstreams?.status() ?? {};
+      },
+      diffResource: async (id: string, from?: string, to?: string) => {
+        return this.resourceLoader.diffResource(id, from, to);
+      },
+      getResourceContent: async (uri: string) => {
+        return this.resourceLoader.loadResourceContent(uri);
+      },
+      // ============================================================================
+      // NEW: Provider system endpoints
+      // ============================================================================
+      getProviderNames: () => {
+        return this.resourceLoader.getProviderNames(true); // Only enabled providers
+      },
+      getProvidersHealth: async () => {
+        return this.resourceLoader.getProvidersHealth();
+      },
+      getProvidersStats: () => {
+        return this.resourceLoader.getProvidersStats();
+      },
+      getAggregateProviderStats: () => {
+        return this.resourceLoader.getAggregateProviderStats();
+      },
+      searchAllProviders: async (query: string, options?: any) => {
+        return this.resourceLoader.searchAllProviders(query, options);
+      },
+      searchProvider: async (
+        providerName: string,
+        query: string,
+        options?: any,
+      ) => {
+        return this.resourceLoader.searchProvider(providerName, query, options);
+      },
+      // Provider methods (Wave 4: HTTP Transport Integration)
+      getProviders: async () => {
+        return this.resourceLoader.getProviders();
+      },
+      getProviderIndex: async (name: string) => {
+        return this.resourceLoader.getProviderIndex(name);
+      },
+      getProviderHealth: async (name: string) => {
+        return this.resourceLoader.getProviderHealth(name);
+      },
+      getAllProvidersHealth: async () => {
+        return this.resourceLoader.getProvidersHealth();
+      },
+      getProviderStats: (name: string) => {
+        return this.resourceLoader.getProviderStats(name);
+      },
+      enableProvider: async (name: string) => {
+        return this.resourceLoader.enableProvider(name);
+      },
+      disableProvider: async (name: string) => {
+        return this.resourceLoader.disableProvider(name);
+      },
+      // ============================================================================
+      // NEW: Token system interface
+      // ============================================================================
+      tokenSystem: {
+        tracker: this.tokenTracker,
+        store: this.tokenStore,
+        metrics: this.tokenMetrics,
+        efficiency: this.efficiencyEngine,
+      },
+    };
+
+    const tls = tlsFromEnv();
+    this.httpTransport = new HTTPTransport(
+      {
+        port: HTTP_PORT,
+        staticPath: path.join(__dirname, "web", "static"),
+        enableCORS: true,
+        backpressure: {
+          highWaterMark: parseInt(
+            process.env.O8_WS_HIGH_WATER_MARK || "1048576",
+            10,
+          ),
+          maxDroppedMessages: parseInt(
+            process.env.O8_WS_MAX_DROPPED || "50",
+            10,
+          ),
+        },
+        limits: payloadLimitsFromEnv(),
+        connections: connectionLimitsFromEnv(),
+        sessions: this.sessions,
+        adminToken: process.env.O8_ADMIN_TOKEN || undefined,
+        policy: policyFromEnv(),
+        ...(tls ? { tls } : {}),
+        dashboard: flagEnabled("admin-dashboard"),
+      },
+      mcpInterface,
+      this.stats,
+    );
+
+    await this.httpTransport.start();
+
+    // Log server startup activity
+    this.stats.logActivity("server_start", {
+      mode: "HTTP",
+      port: HTTP_PORT,
+      timestamp: Date.now(),
+    });
+
+    logger.info(`orchestr8 MCP server started successfully in HTTP mode`);
+    logger.info(
+      `Web UI available at: ${tls ? "https" : "http"}://localhost:${HTTP_PORT}`,
+    );
+  }
+
+  async startDual(): Promise<void> {
+    logger.info("Starting in dual mode (stdio + HTTP)");
+
+    // Start stdio for Claude Desktop
+    const stdioTransport = this.createStdioTransport();
+    await this.openSession(stdioTransport, "stdio");
+    logger.info("stdio transport ready");
+
+    // Start HTTP for web UI
+    await this.startHttp();
+    this.watchStdio(stdioTransport);
+
+    logger.info("orchestr8 MCP server started successfully in dual mode");
+  }
+
+  /**
+   * stdio transport with stdout reserved for JSON-RPC frames
+   *
+   * Any other write to stdout goes to stderr, or throws with
+   * ORCHESTR8_STRICT_STDOUT.
+   * @private
+   */
+  private createStdioTransport(): StdioServerTransport {
+    this.stdoutGuard = new StdoutGuard({ strict: STRICT_STDOUT });
+    this.stdoutGuard.install();
+    return new StdioServerTransport(process.stdin, this.stdoutGuard.protocolOut);
+  }
+
+  /**
+   * Exit cleanly when the stdio client disconnects
+   *
+   * Stay-alive only applies when another transport (HTTP) can keep serving.
+   * @private
+   */
+  private watchStdio(transport: StdioServerTransport): void {
+    const stayAlive = STAY_ALIVE && this.httpTransport !== null;
+    if (STAY_ALIVE && !stayAlive) {
+      logger.debug("Stay-alive ignored: no other transport is running");
+    }
+
+    if (RECORD_PATH) {
+      this.recorder = new SessionRecorder(RECORD_PATH);
+      this.recorder.attach(transport);
+      logger.info(`Recording stdio session to ${RECORD_PATH}`);
+    }
+
+    this.stdioLifecycle = new StdioLifecycle(logger, {
+      stayAlive,
+      onExit: async (reason: StdioCloseReason, code: number) => {
+        logger.info(`Exiting after stdio close (${reason}) with code ${code}`);
+        await this.shutdown();
+        process.exit(code);
+      },
+    });
+    this.stdioLifecycle.attach(transport);
+  }
+
+  /**
+   * Server state for a crash report
+   *
+   * @param mode - Transport mode the server was started in, if any
+   */
+  getCrashContext(mode: string | null): CrashContext {
+    return {
+      version: SERVER_VERSION,
+      mode,
+      activity: this.stats?.getActivityLog(CRASH_REPORT_REQUESTS * 5) ?? [],
+    };
+  }
+
+  /**
+   * Publish a server event to subscribers (activity log, webhooks)
+   */
+  publishEvent(type: ServerEventType, data: Record<string, any>): void {
+    this.eventBus.publish(type, data);
+  }
+
+  /**
+   * Remove old snapshots and crash reports per the retention policy, and
+   * blobs no snapshot refers to
+   */
+  async collectStateGarbage(): Promise<GcResult> {
+    const stateDir = defaultStateDir();
+    const result = await withStateLock(stateDir, "gc", () =>
+      collectGarbage(stateDir, this.retention ?? {}),
+    );
+    const { snapshots, crashReports, blobs } = result.removed;
+    if (snapshots.length + crashReports + blobs > 0) {
+      logger.info("State garbage collected", {
+        snapshots: snapshots.length,
+        crashReports,
+        blobs,
+        freedBytes: result.freedBytes,
+      });
+    }
+    return result;
+  }
+
+  async shutdown(): Promise<void> {
+    logger.info("Shutting down orchestr8 MCP server");
+
+    if (this.gcTimer) {
+      clearInterval(this.gcTimer);
+    }
+
+    if (this.httpTransport) {
+      await this.httpTransport.stop();
+    }
+
+    this.webhookSink?.detach();
+    await this.recorder?.close();
+    await this.promptLoader.stopWatching();
+    await this.resourceLoader.stopWatchingPartials();
+    await this.upstreams?.close();
+    await this.server.close();
+    this.stdoutGuard?.uninstall();
+  }
+}
@@ -0,0 +1,209 @@
+/**
+ * Mock server for client development
+ *
+ * `--mock fixtures.json` starts a server that answers every request from a
+ * fixtures file instead of a resource catalog, so client developers and CI
+ * can test an integration without a real agent catalog. `initialize` and
+ * `ping` are answered by the SDK; every other method is looked up in the
+ * fixtures. A method maps to one response or to a list of candidates, where
+ * the first candidate whose `params` are contained in the request's params
+ * wins. Strings in responses are templates: `{{id}}` is the request id,
+ * `{{uuid}}` a fresh UUID, `{{now}}` the current time, and
+ * `{{params.uri}}` a request parameter.
+ *
+ * @example fixtures.json
+ * {
+ *   "methods": {
+ *     "resources/read": [
+ *       { "params": { "uri": "o8://agents/typescript-developer" },
+ *         "result": { "contents": [{ "uri": "{{params.uri}}", "text": "# TypeScript" }] } },
+ *       { "error": { "code": -32002, "message": "Not found: {{params.uri}}" } }
+ *     ]
+ *   }
+ * }
+ *
+ * @example
+ * node dist/index.js --mock fixtures.json
+ */
+
+import { promises as fs } from "fs";
+import { randomUUID } from "crypto";
+import { isDeepStrictEqual } from "util";
+import { Server } from "@modelcontextprotocol/sdk/server/index.js";
+import {
+  ErrorCode,
+  McpError,
+  type ServerCapabilities,
+} from "@modelcontextprotocol/sdk/types.js";
+
+/**
+ * Canned answer to one request
+ */
+export interface MockResponse {
+  /** Only used when these params are contained in the request's params */
+  params?: Record<string, unknown>;
+  result?: unknown;
+  error?: { code: number; message: string; data?: unknown };
+}
+
+export interface MockFixtures {
+  serverInfo?: { name: string; version: string };
+  methods: Record<string, MockResponse | MockResponse[]>;
+}
+
+/**
+ * Values available to `{{...}}` placeholders
+ */
+export interface TemplateContext {
+  id: string | number;
+  params: Record<string, unknown>;
+  now: () => string;
+  uuid: () => string;
+}
+
+/**
+ * Read and validate a fixtures file
+ *
+ * @throws {Error} If the file is not JSON or has no `methods` object
+ */
+export async function loadFixtures(filePath: string): Promise<MockFixtures> {
+  const fixtures = JSON.parse(await fs.readFile(filePath, "utf-8"));
+  if (
+    !fixtures ||
+    typeof fixtures.methods !== "object" ||
+    Array.isArray(fixtures.methods)
+  ) {
+    throw new Error(`${filePath}: fixtures need a "methods" object`);
+  }
+  for (const [method, entry] of Object.entries<unknown>(fixtures.methods)) {
+    for (const response of Array.isArray(entry) ? entry : [entry]) {
+      if (
+        !response ||
+        typeof response !== "object" ||
+        !("result" in response || "error" in response)
+      ) {
+        throw new Error(
+          `${filePath}: every response for ${method} needs "result" or "error"`,
+        );
+      }
+    }
+  }
+  return fixtures;
+}
+
+/**
+ * Whether every key of `expected` has an equal value in `actual`
+ */
+function containsParams(
+  actual: Record<string, unknown>,
+  expected: Record<string, unknown>,
+): boolean {
+  return Object.entries(expected).every(([key, value]) =>
+    isDeepStrictEqual(actual[key], value),
+  );
+}
+
+/**
+ * The fixture response for a request, if any
+ */
+export function resolveFixture(
+  fixtures: MockFixtures,
+  method: string,
+  params: Record<string, unknown> = {},
+): MockResponse | undefined {
+  if (!Object.hasOwn(fixtures.methods, method)) {
+    return undefined;
+  }
+  const entry = fixtures.methods[method];
+  const candidates = Array.isArray(entry) ? entry : [entry];
+  return candidates.find(
+    (candidate) => !candidate.params || containsParams(params, candidate.params),
+  );
+}
+
+/**
+ * Replace `{{...}}` placeholders in every string of a JSON value
+ *
+ * Unknown placeholders are left as they are.
+ */
+export function renderTemplate(value: unknown, context: TemplateContext): unknown {
+  if (typeof value === "string") {
+    return value.replace(/\{\{\s*([\w.]+)\s*\}\}/g, (placeholder, name) => {
+      if (name === "id") return String(context.id);
+      if (name === "now") return context.now();
+      if (name === "uuid") return context.uuid();
+      if (name.startsWith("params.")) {
+        const param = context.params[name.slice("params.".length)];
+        if (param !== undefined) {
+          return typeof param === "string" ? param : JSON.stringify(param);
+        }
+      }
+      return placeholder;
+    });
+  }
+  if (Array.isArray(value)) {
+    return value.map((item) => renderTemplate(item, context));
+  }
+  if (value && typeof value === "object") {
+    return Object.fromEntries(
+      Object.entries(value).map(([key, item]) => [
+        key,
+        renderTemplate(item, context),
+      ]),
+    );
+  }
+  return value;
+}
+
+/**
+ * Capabilities advertised for the methods the fixtures answer
+ */
+function fixtureCapabilities(fixtures: MockFixtures): ServerCapabilities {
+  const capabilities: ServerCapabilities = {};
+  for (const method of Object.keys(fixtures.methods)) {
+    const [group] = method.split("/");
+    if (group === "resources" || group === "prompts" || group === "tools") {
+      capabilities[group] = {};
+    }
+  }
+  return capabilities;
+}
+
+/**
+ * Create an MCP server that answers from fixtures
+ *
+ * @param now - Clock for `{{now}}` (for tests)
+ */
+export function createMockServer(
+  fixtures: MockFixtures,
+  now: () => string = () => new Date().toISOString(),
+): Server {
+  const server = new Server(
+    fixtures.serverInfo ?? { name: "o8-mock", version: "1.0.0" },
+    { capabilities: fixtureCapabilities(fixtures) },
+  );
+
+  server.fallbackRequestHandler = async (request) => {
+    const params = (request.params ?? {}) as Record<string, unknown>;
+    const response = resolveFixture(fixtures, request.method, params);
+    if (!response) {
+      throw new McpError(
+        ErrorCode.MethodNotFound,
+        `No fixture for ${request.method}`,
+      );
+    }
+    const context = { id: request.id, params, now, uuid: randomUUID };
+    if (response.error) {
+      const error = renderTemplate(response.error, context) as NonNullable<
+        MockResponse["error"]
+      >;
+      throw new McpError(error.code, error.message, error.data);
+    }
+    return renderTemplate(response.result ?? {}, context) as Record<
+      string,
+      unknown
+    >;
+  };
+
+  return server;
+}
@@ -0,0 +1,192 @@
+/**
+ * Session record and replay
+ *
+ * Recording captures every JSON-RPC message on the stdio transport to a JSONL
+ * file. Replay feeds the recorded client messages to the current build over
+ * an in-memory transport and compares each response with the recorded one,
+ * so protocol regressions show up when handlers are refactored. Secrets are
+ * redacted before messages are written, so a response that contains one
+ * replays as a mismatch.
+ *
+ * @example Record a session
+ * ORCHESTR8_RECORD=session.jsonl node dist/index.js
+ *
+ * @example Replay it against the current build
+ * node dist/index.js replay session.jsonl
+ */
+
+import { createWriteStream, WriteStream, promises as fs } from "fs";
+import { isDeepStrictEqual } from "util";
+import { InMemoryTransport } from "@modelcontextprotocol/sdk/inMemory.js";
+import type { Transport } from "@modelcontextprotocol/sdk/shared/transport.js";
+import type { TrackableTransport } from "../transports/stdioLifecycle.js";
+import { redactor } from "../utils/redact.js";
+
+/**
+ * One line of a session recording
+ */
+export interface RecordedMessage {
+  /** "in" for client-to-server, "out" for server-to-client */
+  dir: "in" | "out";
+  /** Raw JSON-RPC message */
+  message: any;
+}
+
+/**
+ * Response that differs between the recording and the replay
+ */
+export interface ReplayMismatch {
+  id: string | number;
+  method: string;
+  expected: any;
+  actual: any;
+}
+
+/**
+ * Replay outcome
+ */
+export interface ReplayReport {
+  /** Requests replayed */
+  total: number;
+  /** Responses identical to the recording */
+  matched: number;
+  /** Responses that differ */
+  mismatches: ReplayMismatch[];
+}
+
+/**
+ * Appends transport traffic to a JSONL file
+ */
+export class SessionRecorder {
+  private stream: WriteStream;
+
+  constructor(filePath: string) {
+    this.stream = createWriteStream(filePath, { flags: "a" });
+  }
+
+  /**
+   * Record all messages on a connected transport
+   *
+   * @param transport - Transport whose message handler is already installed
+   */
+  attach(transport: TrackableTransport): void {
+    const onmessage = transport.onmessage;
+    transport.onmessage = (message: any, ...rest: any[]) => {
+      this.write({ dir: "in", message });
+      onmessage?.(message, ...rest);
+    };
+
+    const send = transport.send.bind(transport);
+    transport.send = async (message: any, ...rest: any[]) => {
+      this.write({ dir: "out", message });
+      return send(message, ...rest);
+    };
+  }
+
+  /**
+   * Flush and close the recording
+   */
+  close(): Promise<void> {
+    return new Promise((resolve) => this.stream.end(resolve));
+  }
+
+  /**
+   * @private
+   */
+  private write(entry: RecordedMessage): void {
+    this.stream.write(JSON.stringify(redactor().redact(entry)) + "\n");
+  }
+}
+
+/**
+ * Read a session recording
+ *
+ * @param filePath - JSONL file written by SessionRecorder
+ * @returns Recorded messages in order
+ */
+export async function readSession(
+  filePath: string,
+): Promise<RecordedMessage[]> {
+  const content = await fs.readFile(filePath, "utf-8");
+  return content
+    .split("\n")
+    .filter((line) => line.trim())
+    .map((line) => JSON.parse(line));
+}
+
+/**
+ * Replay recorded client messages and diff the responses
+ *
+ * @param session - Recorded messages
+ * @param connect - Connects the server under test to a transport
+ * @param timeoutMs - Maximum time to wait for each response (default: 10000ms)
+ * @returns Replay report
+ */
+export async function replaySession(
+  session: RecordedMessage[],
+  connect: (transport: Transport) => Promise<void>,
+  timeoutMs: number = 10000,
+): Promise<ReplayReport> {
+  const expected = new Map<string | number, any>();
+  for (const { dir, message } of session) {
+    if (dir === "out" && message.id !== undefined && !("method" in message)) {
+      expected.set(message.id, message);
+    }
+  }
+
+  const [client, server] = InMemoryTransport.createLinkedPair();
+  const waiting = new Map<string | number, (message: any) => void>();
+  client.onmessage = (message: any) => {
+    if (message.id !== undefined && !("method" in message)) {
+      waiting.get(message.id)?.(message);
+    }
+  };
+  await connect(server);
+  await client.start();
+
+  const report: ReplayReport = { total: 0, matched: 0, mismatches: [] };
+
+  for (const { dir, message } of session) {
+    if (dir !== "in") {
+      continue;
+    }
+
+    if (message.id === undefined) {
+      // Notification: nothing to compare
+      await client.send(message);
+      continue;
+    }
+
+    report.total++;
+    const response = new Promise<any>((resolve) => {
+      const timer = setTimeout(
+        () => resolve({ error: { message: "No response (timeout)" } }),
+        timeoutMs,
+      );
+      waiting.set(message.id, (reply) => {
+        clearTimeout(timer);
+        resolve(reply);
+      });
+    });
+    await client.send(message);
+    const actual = await response;
+    waiting.delete(message.id);
+
+    const recorded = expected.get(message.id);
+    const strip = (reply: any) =>
+      reply ? { result: reply.result, error: reply.error } : undefined;
+    if (isDeepStrictEqual(strip(recorded), strip(actual))) {
+      report.matched++;
+    } else {
+      report.mismatches.push({
+        id: message.id,
+        method: message.method,
+        expected: strip(recorded),
+        actual: strip(actual),
+      });
+    }
+  }
+
+  await client.close();
+  return report;
+}
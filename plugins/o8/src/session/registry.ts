@@ -0,0 +1,177 @@
+/**
+ * Active session registry
+ *
+ * Keeps one entry per connected client with the clientInfo it sent in
+ * `initialize`, so operators of a shared server can see which client
+ * versions are connected (`sessions/list`) and request metrics and the
+ * activity log can be labelled by client. Sessions on network transports
+ * register a terminator so an admin can close a misbehaving connection
+ * (`sessions/terminate`).
+ */
+
+import { randomUUID } from "crypto";
+import { compareStrings } from "../utils/deterministic.js";
+
+/**
+ * How a session is connected
+ */
+export type SessionTransport = "stdio" | "memory" | "websocket";
+
+/**
+ * clientInfo from `initialize`
+ */
+export interface ClientIdentity {
+  name: string;
+  version: string;
+}
+
+/**
+ * A connected session
+ */
+export interface SessionInfo {
+  id: string;
+  transport: SessionTransport;
+  /** null until the client has initialized */
+  client: ClientIdentity | null;
+  connectedAt: string;
+  requests: number;
+}
+
+/**
+ * Error raised for unknown sessions or sessions that cannot be closed
+ */
+export class SessionError extends Error {
+  public readonly code: "SESSION_NOT_FOUND" | "SESSION_NOT_TERMINABLE";
+
+  constructor(
+    code: "SESSION_NOT_FOUND" | "SESSION_NOT_TERMINABLE",
+    message: string,
+  ) {
+    super(message);
+    this.name = "SessionError";
+    this.code = code;
+  }
+}
+
+/**
+ * Label for metrics and logs: "name/version", or "unknown" before the
+ * client has identified itself
+ */
+export function clientLabel(client: ClientIdentity | null | undefined): string {
+  return client ? `${client.name}/${client.version}` : "unknown";
+}
+
+/**
+ * In-memory registry of connected sessions
+ */
+export class SessionRegistry {
+  private sessions = new Map<string, SessionInfo>();
+  private terminators = new Map<string, () => void>();
+
+  /**
+   * Register a new connection
+   *
+   * @param terminate - Forcibly closes the connection; only network
+   *   transports provide one
+   * @returns The session id
+   */
+  open(
+    transport: SessionTransport,
+    client: ClientIdentity | null = null,
+    terminate?: () => void,
+  ): string {
+    const id = randomUUID();
+    if (terminate) {
+      this.terminators.set(id, terminate);
+    }
+    this.sessions.set(id, {
+      id,
+      transport,
+      client: client ? { name: client.name, version: client.version } : null,
+      connectedAt: new Date().toISOString(),
+      requests: 0,
+    });
+    return id;
+  }
+
+  /**
+   * Store the clientInfo a session sent in `initialize`
+   */
+  identify(id: string, client: ClientIdentity): void {
+    const session = this.sessions.get(id);
+    if (session) {
+      session.client = { name: client.name, version: client.version };
+    }
+  }
+
+  /**
+   * Count a request on a session
+   *
+   * @returns The session, or null if it is not registered
+   */
+  recordRequest(id: string): SessionInfo | null {
+    const session = this.sessions.get(id);
+    if (!session) {
+      return null;
+    }
+    session.requests++;
+    return session;
+  }
+
+  /**
+   * Forget a closed session
+   */
+  close(id: string): boolean {
+    this.terminators.delete(id);
+    return this.sessions.delete(id);
+  }
+
+  /**
+   * Forcibly close a session's connection
+   *
+   * @throws {SessionError} If the session is unknown or its transport
+   *   cannot be closed from the server (stdio)
+   */
+  terminate(id: string): SessionInfo {
+    const session = this.sessions.get(id);
+    if (!session) {
+      throw new SessionError("SESSION_NOT_FOUND", `Session not found: ${id}`);
+    }
+    const terminate = this.terminators.get(id);
+    if (!terminate) {
+      throw new SessionError(
+        "SESSION_NOT_TERMINABLE",
+        `Session ${id} uses the ${session.transport} transport and cannot be terminated`,
+      );
+    }
+    const info = this.copy(session);
+    this.close(id);
+    terminate();
+    return info;
+  }
+
+  get(id: string): SessionInfo | null {
+    const session = this.sessions.get(id);
+    return session ? this.copy(session) : null;
+  }
+
+  /**
+   * Active sessions, oldest first
+   */
+  list(): SessionInfo[] {
+    return [...this.sessions.values()]
+      .sort(
+        (a, b) =>
+          compareStrings(a.connectedAt, b.connectedAt) ||
+          compareStrings(a.id, b.id),
+      )
+      .map((session) => this.copy(session));
+  }
+
+  private copy(session: SessionInfo): SessionInfo {
+    return {
+      ...session,
+      client: session.client ? { ...session.client } : null,
+    };
+  }
+}
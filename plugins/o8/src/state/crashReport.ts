@@ -0,0 +1,176 @@
+/**
+ * Crash reports
+ *
+ * An uncaught exception or unhandled rejection ends the process, which over
+ * stdio looks to the user like the server silently disappeared. Before
+ * exiting, the crash handler writes a JSON report to
+ * `<state dir>/crashes/` with the server version, the stack trace, the last
+ * requests, and a summary of the configuration, so the crash can be
+ * diagnosed from a file the user attaches to an issue.
+ */
+
+import { mkdirSync, writeFileSync } from "fs";
+import { join } from "path";
+import type { ActivityEvent } from "../stats/collector.js";
+import { redactor } from "../utils/redact.js";
+
+/**
+ * Requests kept in a crash report
+ */
+export const CRASH_REPORT_REQUESTS = 20;
+
+/**
+ * Environment variables summarized in a crash report; anything that could
+ * hold a secret (tokens, webhook URLs) is left out
+ */
+const CONFIG_VARIABLES = [
+  "NODE_ENV",
+  "RESOURCES_PATH",
+  "PROMPTS_PATH",
+  "O8_HTTP",
+  "O8_HTTP_PORT",
+  "ORCHESTR8_HTTP",
+  "ORCHESTR8_HTTP_PORT",
+  "O8_STATE_DIR",
+  "O8_LAYERS",
+  "O8_LOCALE",
+  "O8_QUERY_LOG",
+  "O8_STAY_ALIVE",
+  "O8_AUTO",
+  "ORCHESTR8_STAY_ALIVE",
+  "ORCHESTR8_AUTO",
+];
+
+/**
+ * What the server can tell about itself when it crashes
+ */
+export interface CrashContext {
+  version: string;
+  mode: string | null;
+  activity: ActivityEvent[];
+}
+
+export interface CrashReport {
+  version: string;
+  timestamp: string;
+  /** "uncaughtException" or "unhandledRejection" */
+  origin: string;
+  error: { name: string; message: string; stack: string | null };
+  node: string;
+  platform: string;
+  uptimeSeconds: number;
+  memory: NodeJS.MemoryUsage;
+  mode: string | null;
+  config: Record<string, string>;
+  recentRequests: Array<{
+    timestamp: string;
+    method: string;
+    client?: string;
+    latency?: number;
+  }>;
+}
+
+/**
+ * Build a crash report
+ *
+ * @param error - What was thrown (not necessarily an Error)
+ * @param origin - Which process event reported it
+ * @param context - Server version, mode, and recent activity
+ * @param env - Environment to summarize (default: process.env)
+ */
+export function buildCrashReport(
+  error: unknown,
+  origin: string,
+  context: CrashContext,
+  env: NodeJS.ProcessEnv = process.env,
+): CrashReport {
+  const err =
+    error instanceof Error
+      ? { name: error.name, message: error.message, stack: error.stack ?? null }
+      : { name: "NonError", message: String(error), stack: null };
+
+  const config: Record<string, string> = {};
+  for (const name of CONFIG_VARIABLES) {
+    if (env[name] !== undefined) {
+      config[name] = env[name]!;
+    }
+  }
+
+  return {
+    version: context.version,
+    timestamp: new Date().toISOString(),
+    origin,
+    error: err,
+    node: process.version,
+    platform: `${process.platform}-${process.arch}`,
+    uptimeSeconds: Math.round(process.uptime()),
+    memory: process.memoryUsage(),
+    mode: context.mode,
+    config,
+    recentRequests: context.activity
+      .filter((event) => event.type === "mcp_request")
+      .slice(-CRASH_REPORT_REQUESTS)
+      .map((event) => ({
+        timestamp: new Date(event.timestamp).toISOString(),
+        method: String(event.data?.method ?? ""),
+        ...(event.data?.client && { client: String(event.data.client) }),
+        ...(typeof event.data?.latency === "number" && {
+          latency: event.data.latency,
+        }),
+      })),
+  };
+}
+
+/**
+ * Write a crash report synchronously (the process is about to exit)
+ *
+ * @returns Path of the report
+ */
+export function writeCrashReport(stateDir: string, report: CrashReport): string {
+  const dir = join(stateDir, "crashes");
+  mkdirSync(dir, { recursive: true });
+  const path = join(
+    dir,
+    `crash-${report.timestamp.replace(/[:.]/g, "-")}-${process.pid}.json`,
+  );
+  // Error messages and stack traces can quote secrets
+  const redacted = redactor().redact(report);
+  writeFileSync(path, `${JSON.stringify(redacted, null, 2)}\n`);
+  return path;
+}
+
+/**
+ * Write a crash report and exit on uncaught exceptions and unhandled
+ * rejections
+ *
+ * @param stateDir - Where `crashes/` is created
+ * @param getContext - Called at crash time for the server's state
+ * @param exit - Exit function (default: process.exit)
+ */
+export function installCrashHandler(
+  stateDir: string,
+  getContext: () => CrashContext,
+  exit: (code: number) => void = (code) => process.exit(code),
+): void {
+  const handle = (error: unknown, origin: string) => {
+    try {
+      const path = writeCrashReport(
+        stateDir,
+        buildCrashReport(error, origin, getContext()),
+      );
+      // stderr: stdout carries the MCP protocol
+      process.stderr.write(`orchestr8 crashed; report written to ${path}\n`);
+    } catch (reportError) {
+      process.stderr.write(
+        `orchestr8 crashed and the crash report could not be written: ${reportError}\n`,
+      );
+    }
+    process.stderr.write(
+      `${error instanceof Error ? error.stack ?? error.message : String(error)}\n`,
+    );
+    exit(1);
+  };
+
+  process.on("uncaughtException", (error) => handle(error, "uncaughtException"));
+  process.on("unhandledRejection", (reason) => handle(reason, "unhandledRejection"));
+}
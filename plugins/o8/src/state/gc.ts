@@ -0,0 +1,259 @@
+/**
+ * State directory retention
+ *
+ * Snapshots and crash reports accumulate in the state directory on
+ * long-lived servers. With a retention policy, garbage collection removes
+ * snapshots beyond O8_STATE_MAX_SNAPSHOTS, snapshots and crash reports older
+ * than O8_STATE_MAX_AGE_DAYS, and then the oldest of either until the
+ * directory fits in O8_STATE_MAX_SIZE_MB. The pinned snapshot is never
+ * removed. Blobs no remaining snapshot refers to are deleted on every run.
+ *
+ * Logs (queries.jsonl, shadow-ranking.jsonl) count toward the size limit
+ * but are not deleted: they are appended to while the server runs.
+ */
+
+import { promises as fs } from "fs";
+import { join } from "path";
+import { SnapshotStore } from "./snapshotStore.js";
+import { compareStrings } from "../utils/deterministic.js";
+
+/**
+ * Unreferenced blobs younger than this are kept: a snapshot being saved
+ * writes its blobs before its manifest
+ */
+export const BLOB_GRACE_MS = 10 * 60 * 1000;
+
+/**
+ * Default interval of background collection (O8_STATE_GC_INTERVAL, ms)
+ */
+export const DEFAULT_GC_INTERVAL = 60 * 60 * 1000;
+
+export interface RetentionPolicy {
+  /** Most snapshots kept, the pinned one included */
+  maxSnapshots?: number;
+  /** Oldest snapshot or crash report kept, in days */
+  maxAgeDays?: number;
+  /** Largest state directory, in MB */
+  maxSizeMb?: number;
+}
+
+export interface GcResult {
+  removed: { snapshots: string[]; crashReports: number; blobs: number };
+  /** Bytes freed */
+  freedBytes: number;
+  /** Size of the state directory afterwards */
+  sizeBytes: number;
+}
+
+/**
+ * Retention policy from O8_STATE_MAX_SNAPSHOTS, O8_STATE_MAX_AGE_DAYS, and
+ * O8_STATE_MAX_SIZE_MB, or null when none is set
+ *
+ * @throws {Error} If a value is not a positive number
+ */
+export function retentionPolicy(
+  env: NodeJS.ProcessEnv = process.env,
+): RetentionPolicy | null {
+  const policy: RetentionPolicy = {};
+  const limits = [
+    ["O8_STATE_MAX_SNAPSHOTS", "maxSnapshots"],
+    ["O8_STATE_MAX_AGE_DAYS", "maxAgeDays"],
+    ["O8_STATE_MAX_SIZE_MB", "maxSizeMb"],
+  ] as const;
+  for (const [variable, key] of limits) {
+    const raw = env[variable];
+    if (raw === undefined || raw === "") {
+      continue;
+    }
+    const value = Number(raw);
+    if (!Number.isFinite(value) || value <= 0) {
+      throw new Error(
+        `Invalid ${variable}: ${raw} (expected a positive number)`,
+      );
+    }
+    policy[key] = value;
+  }
+  return Object.keys(policy).length > 0 ? policy : null;
+}
+
+/**
+ * Interval of background collection from O8_STATE_GC_INTERVAL (ms)
+ */
+export function gcInterval(env: NodeJS.ProcessEnv = process.env): number {
+  const value = Number(env.O8_STATE_GC_INTERVAL);
+  return Number.isFinite(value) && value > 0 ? value : DEFAULT_GC_INTERVAL;
+}
+
+/**
+ * Something garbage collection may remove, oldest first
+ */
+interface Candidate {
+  kind: "snapshot" | "crash";
+  name: string;
+  time: number;
+  bytes: number;
+  /** Blob hashes of a snapshot */
+  blobs: string[];
+}
+
+async function fileSize(path: string): Promise<number> {
+  try {
+    return (await fs.stat(path)).size;
+  } catch {
+    return 0;
+  }
+}
+
+async function directorySize(dir: string): Promise<number> {
+  let entries;
+  try {
+    entries = await fs.readdir(dir, { withFileTypes: true });
+  } catch {
+    return 0;
+  }
+  let total = 0;
+  for (const entry of entries) {
+    const path = join(dir, entry.name);
+    total += entry.isDirectory()
+      ? await directorySize(path)
+      : await fileSize(path);
+  }
+  return total;
+}
+
+/**
+ * Apply a retention policy to a state directory
+ *
+ * @param stateDir - State directory
+ * @param policy - Limits; with none, only unreferenced blobs are removed
+ * @param now - Clock, for tests
+ */
+export async function collectGarbage(
+  stateDir: string,
+  policy: RetentionPolicy = {},
+  now: number = Date.now(),
+): Promise<GcResult> {
+  const store = new SnapshotStore(stateDir);
+  const pinned = await store.getPinned();
+  const result: GcResult = {
+    removed: { snapshots: [], crashReports: 0, blobs: 0 },
+    freedBytes: 0,
+    sizeBytes: 0,
+  };
+
+  // Blob reference counts and removal candidates, oldest first
+  const refs = new Map<string, number>();
+  const candidates: Candidate[] = [];
+  let snapshotCount = 0;
+  for (const info of await store.list()) {
+    const manifest = await store.load(info.name);
+    const blobs = [...new Set(manifest.files.map((file) => file.hash))];
+    for (const hash of blobs) {
+      refs.set(hash, (refs.get(hash) ?? 0) + 1);
+    }
+    snapshotCount++;
+    if (info.name !== pinned) {
+      const manifestPath = join(stateDir, "snapshots", `${info.name}.json`);
+      candidates.push({
+        kind: "snapshot",
+        name: info.name,
+        time: Date.parse(info.createdAt),
+        bytes: await fileSize(manifestPath),
+        blobs,
+      });
+    }
+  }
+  const crashDir = join(stateDir, "crashes");
+  for (const name of await fs.readdir(crashDir).catch(() => [])) {
+    const stat = await fs.stat(join(crashDir, name)).catch(() => null);
+    if (stat?.isFile()) {
+      candidates.push({
+        kind: "crash",
+        name,
+        time: stat.mtimeMs,
+        bytes: stat.size,
+        blobs: [],
+      });
+    }
+  }
+  candidates.sort((a, b) => a.time - b.time || compareStrings(a.name, b.name));
+
+  const blobSizes = new Map<string, number>();
+  const blobDir = join(stateDir, "blobs");
+  for (const hash of await fs.readdir(blobDir).catch(() => [])) {
+    if (!hash.endsWith(".tmp")) {
+      blobSizes.set(hash, await fileSize(join(blobDir, hash)));
+    }
+  }
+
+  const remove = async (candidate: Candidate) => {
+    if (candidate.kind === "crash") {
+      await fs.rm(join(crashDir, candidate.name), { force: true });
+      result.removed.crashReports++;
+      result.freedBytes += candidate.bytes;
+      return;
+    }
+    await store.remove(candidate.name);
+    snapshotCount--;
+    result.removed.snapshots.push(candidate.name);
+    result.freedBytes += candidate.bytes;
+    for (const hash of candidate.blobs) {
+      refs.set(hash, (refs.get(hash) ?? 1) - 1);
+      if (refs.get(hash) === 0) {
+        refs.delete(hash);
+        result.freedBytes += blobSizes.get(hash) ?? 0;
+        blobSizes.delete(hash);
+        await fs.rm(join(blobDir, hash), { force: true });
+        result.removed.blobs++;
+      }
+    }
+  };
+
+  // Count and age limits
+  const maxAge =
+    policy.maxAgeDays !== undefined
+      ? now - policy.maxAgeDays * 24 * 60 * 60 * 1000
+      : -Infinity;
+  const kept: Candidate[] = [];
+  for (const candidate of candidates) {
+    const tooMany =
+      candidate.kind === "snapshot" &&
+      policy.maxSnapshots !== undefined &&
+      snapshotCount > policy.maxSnapshots;
+    if (tooMany || candidate.time < maxAge) {
+      await remove(candidate);
+    } else {
+      kept.push(candidate);
+    }
+  }
+
+  // Blobs no snapshot refers to (left by interrupted saves or removals)
+  for (const [hash, size] of blobSizes) {
+    if (refs.has(hash)) {
+      continue;
+    }
+    const stat = await fs.stat(join(blobDir, hash)).catch(() => null);
+    if (stat && now - stat.mtimeMs >= BLOB_GRACE_MS) {
+      await fs.rm(join(blobDir, hash), { force: true });
+      blobSizes.delete(hash);
+      result.removed.blobs++;
+      result.freedBytes += size;
+    }
+  }
+
+  // Size limit: oldest first until the directory fits
+  let size = await directorySize(stateDir);
+  if (policy.maxSizeMb !== undefined) {
+    const maxBytes = policy.maxSizeMb * 1024 * 1024;
+    for (const candidate of kept) {
+      if (size <= maxBytes) {
+        break;
+      }
+      const before = result.freedBytes;
+      await remove(candidate);
+      size -= result.freedBytes - before;
+    }
+  }
+  result.sizeBytes = size;
+  return result;
+}
@@ -0,0 +1,238 @@
+/**
+ * Write-ahead journal of catalog mutations
+ *
+ * Before a mutation touches resource files it appends an entry to
+ * journal.jsonl in the state directory listing every file with the hash of
+ * its content before and after, and where the new content is staged. Once
+ * the files are in place a second line marks the entry committed. An entry
+ * left pending by a crash is finished on the next start: if every file is
+ * either in place or fully staged the staged files are renamed in (rolled
+ * forward), otherwise the staged files are removed (rolled back). Nothing
+ * is renamed before everything is staged, so a rollback never leaves a
+ * mutation half applied.
+ *
+ * The journal also answers `agents/journal`: who changed what, and when.
+ */
+
+import { promises as fs } from "fs";
+import { createHash, randomUUID } from "crypto";
+import { dirname, join } from "path";
+import { Logger } from "../utils/logger.js";
+
+const logger = new Logger("Journal");
+
+/** Entries agents/journal returns by default */
+export const DEFAULT_JOURNAL_LIMIT = 50;
+
+export type JournalStatus =
+  | "pending"
+  | "committed"
+  | "rolled-forward"
+  | "rolled-back";
+
+/**
+ * One file changed by a mutation
+ */
+export interface JournalFile {
+  path: string;
+  /** Where the new content is written before it is renamed into place */
+  staged: string;
+  /** SHA-256 of the content before and after */
+  before: string;
+  after: string;
+}
+
+/**
+ * Mutation as listed by agents/journal
+ */
+export interface JournalEntry {
+  id: string;
+  /** Operation (e.g. rewrite) */
+  op: string;
+  /** Who asked for it (e.g. 'http:admin', 'cli:alice') */
+  actor: string;
+  startedAt: string;
+  finishedAt?: string;
+  status: JournalStatus;
+  files: JournalFile[];
+}
+
+/**
+ * One journal line: an intent (with op, actor, and files) or a status change
+ */
+interface JournalRecord {
+  id: string;
+  timestamp: string;
+  status: JournalStatus;
+  op?: string;
+  actor?: string;
+  files?: JournalFile[];
+}
+
+/**
+ * SHA-256 of file content
+ */
+export function hashContent(content: string): string {
+  return createHash("sha256").update(content).digest("hex");
+}
+
+/**
+ * Hash of a file, or null if it does not exist
+ */
+async function hashFile(path: string): Promise<string | null> {
+  try {
+    return hashContent(await fs.readFile(path, "utf-8"));
+  } catch (error: any) {
+    if (error.code === "ENOENT") {
+      return null;
+    }
+    throw error;
+  }
+}
+
+/**
+ * Append-only mutation journal in the state directory
+ */
+export class Journal {
+  readonly path: string;
+
+  constructor(stateDir: string) {
+    this.path = join(stateDir, "journal.jsonl");
+  }
+
+  /**
+   * Record a mutation before any of its files are written
+   *
+   * @returns Entry id, for finish()
+   */
+  async begin(op: string, actor: string, files: JournalFile[]): Promise<string> {
+    const id = randomUUID();
+    await this.append({
+      id,
+      timestamp: new Date().toISOString(),
+      status: "pending",
+      op,
+      actor,
+      files,
+    });
+    return id;
+  }
+
+  /**
+   * Mark a mutation finished
+   */
+  async finish(
+    id: string,
+    status: Exclude<JournalStatus, "pending">,
+  ): Promise<void> {
+    await this.append({ id, timestamp: new Date().toISOString(), status });
+  }
+
+  /**
+   * Every mutation in the journal, oldest first
+   */
+  async entries(): Promise<JournalEntry[]> {
+    let text: string;
+    try {
+      text = await fs.readFile(this.path, "utf-8");
+    } catch {
+      return [];
+    }
+
+    const byId = new Map<string, JournalEntry>();
+    for (const line of text.split("\n")) {
+      if (!line.trim()) continue;
+      let record: JournalRecord;
+      try {
+        record = JSON.parse(line);
+      } catch {
+        // A crash while appending leaves a partial last line
+        continue;
+      }
+      const entry = byId.get(record.id);
+      if (record.files) {
+        byId.set(record.id, {
+          id: record.id,
+          op: record.op ?? "unknown",
+          actor: record.actor ?? "unknown",
+          startedAt: record.timestamp,
+          status: record.status,
+          files: record.files,
+        });
+      } else if (entry) {
+        entry.status = record.status;
+        entry.finishedAt = record.timestamp;
+      }
+    }
+    return [...byId.values()];
+  }
+
+  /**
+   * Most recent mutations, newest first
+   *
+   * @param limit - Maximum entries (default: DEFAULT_JOURNAL_LIMIT)
+   */
+  async recent(limit: number = DEFAULT_JOURNAL_LIMIT): Promise<JournalEntry[]> {
+    return (await this.entries()).reverse().slice(0, limit);
+  }
+
+  /**
+   * Finish mutations left pending by a crash
+   *
+   * @returns The recovered entries with their new status
+   */
+  async recover(): Promise<JournalEntry[]> {
+    const recovered: JournalEntry[] = [];
+    for (const entry of await this.entries()) {
+      if (entry.status !== "pending") {
+        continue;
+      }
+
+      const files = await Promise.all(
+        entry.files.map(async (file) => ({
+          file,
+          current: await hashFile(file.path),
+          staged: await hashFile(file.staged),
+        })),
+      );
+      const complete = files.every(
+        ({ file, current, staged }) =>
+          current === file.after || staged === file.after,
+      );
+      if (complete) {
+        for (const { file, current } of files) {
+          if (current !== file.after) {
+            await fs.rename(file.staged, file.path);
+          }
+        }
+      } else {
+        await Promise.all(
+          entry.files.map((file) => fs.rm(file.staged, { force: true })),
+        );
+      }
+
+      entry.status = complete ? "rolled-forward" : "rolled-back";
+      await this.finish(entry.id, entry.status);
+      logger.warn(
+        `Recovered interrupted ${entry.op} by ${entry.actor} (${entry.files.length} files): ${entry.status}`,
+      );
+      recovered.push(entry);
+    }
+    return recovered;
+  }
+
+  /**
+   * Append one record and flush it to disk
+   * @private
+   */
+  private async append(record: JournalRecord): Promise<void> {
+    await fs.mkdir(dirname(this.path), { recursive: true });
+    const handle = await fs.open(this.path, "a");
+    try {
+      await handle.write(JSON.stringify(record) + "\n");
+      await handle.sync();
+    } finally {
+      await handle.close();
+    }
+  }
+}
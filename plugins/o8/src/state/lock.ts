@@ -0,0 +1,162 @@
+/**
+ * Advisory lock between server instances sharing a state directory
+ *
+ * Two editors can each start a server on the same resources and state
+ * directory. Operations that write resource files or shared state
+ * (rewrites, journal recovery, snapshots, the pin, garbage collection)
+ * hold `locks/catalog.lock` while they run. The lock is a file created
+ * exclusively that names its holder. Another instance waits for it up to
+ * O8_LOCK_TIMEOUT milliseconds and then refuses the write with LOCKED. A
+ * lock left by a process that no longer runs on this host is taken over.
+ */
+
+import { promises as fs } from "fs";
+import { hostname } from "os";
+import { dirname, join } from "path";
+import { Logger } from "../utils/logger.js";
+
+const logger = new Logger("Lock");
+
+/** Default wait for the lock, in milliseconds */
+export const DEFAULT_LOCK_TIMEOUT = 10_000;
+
+/** Delay between attempts to take the lock */
+const RETRY_INTERVAL = 50;
+
+/**
+ * Contents of a lock file
+ */
+export interface LockHolder {
+  pid: number;
+  host: string;
+  /** What the holder is doing (e.g. rewrite, snapshot) */
+  operation: string;
+  acquiredAt: string;
+}
+
+/**
+ * Error raised when another instance keeps the lock past the timeout
+ */
+export class LockError extends Error {
+  public readonly code = "LOCKED";
+
+  constructor(public readonly holder: LockHolder | null) {
+    super(
+      holder
+        ? `State is locked by ${holder.operation} in process ${holder.pid} on ${holder.host} since ${holder.acquiredAt}`
+        : "State is locked by another process",
+    );
+    this.name = "LockError";
+  }
+}
+
+/**
+ * Lock wait from O8_LOCK_TIMEOUT (milliseconds)
+ *
+ * @throws {Error} If the value is not a non-negative integer
+ */
+export function lockTimeout(env: NodeJS.ProcessEnv = process.env): number {
+  const raw = env.O8_LOCK_TIMEOUT;
+  if (raw === undefined || raw === "") {
+    return DEFAULT_LOCK_TIMEOUT;
+  }
+  const value = Number(raw);
+  if (!Number.isInteger(value) || value < 0) {
+    throw new Error(
+      `Invalid O8_LOCK_TIMEOUT: ${raw} (expected milliseconds, or 0)`,
+    );
+  }
+  return value;
+}
+
+/**
+ * Path of the lock file in a state directory
+ */
+export function lockPath(stateDir: string): string {
+  return join(stateDir, "locks", "catalog.lock");
+}
+
+/**
+ * Current holder of the lock, or null if it is free or unreadable
+ */
+export async function readLockHolder(
+  stateDir: string,
+): Promise<LockHolder | null> {
+  try {
+    return JSON.parse(await fs.readFile(lockPath(stateDir), "utf-8"));
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Whether a holder is a process on this host that has exited
+ */
+function isStale(holder: LockHolder): boolean {
+  if (holder.host !== hostname()) {
+    return false;
+  }
+  try {
+    process.kill(holder.pid, 0);
+    return false;
+  } catch (error: any) {
+    // EPERM: the process exists but belongs to another user
+    return error.code !== "EPERM";
+  }
+}
+
+/**
+ * Run `fn` while holding the state lock
+ *
+ * @param stateDir - State directory shared by the instances
+ * @param operation - What the lock is taken for, shown to waiting instances
+ * @param timeoutMs - How long to wait for another holder (default:
+ *   O8_LOCK_TIMEOUT)
+ * @throws {LockError} If the lock is still held after the timeout
+ */
+export async function withStateLock<T>(
+  stateDir: string,
+  operation: string,
+  fn: () => Promise<T>,
+  timeoutMs: number = lockTimeout(),
+): Promise<T> {
+  const path = lockPath(stateDir);
+  await fs.mkdir(dirname(path), { recursive: true });
+  const holder: LockHolder = {
+    pid: process.pid,
+    host: hostname(),
+    operation,
+    acquiredAt: new Date().toISOString(),
+  };
+
+  const deadline = Date.now() + timeoutMs;
+  for (;;) {
+    try {
+      await fs.writeFile(path, JSON.stringify(holder), { flag: "wx" });
+      break;
+    } catch (error: any) {
+      if (error.code !== "EEXIST") {
+        throw error;
+      }
+    }
+
+    const current = await readLockHolder(stateDir);
+    if (current && isStale(current)) {
+      logger.warn(
+        `Taking over lock of exited process ${current.pid} (${current.operation})`,
+      );
+      await fs.rm(path, { force: true });
+      continue;
+    }
+    if (Date.now() >= deadline) {
+      throw new LockError(current);
+    }
+    await new Promise((resolve) => setTimeout(resolve, RETRY_INTERVAL));
+  }
+
+  try {
+    return await fn();
+  } finally {
+    await fs.rm(path, { force: true });
+  }
+}
@@ -0,0 +1,234 @@
+/**
+ * Opt-in query log
+ *
+ * With O8_QUERY_LOG set, a sample of match queries is appended to
+ * queries.jsonl in the state directory so catalog owners can see what
+ * users search for and which queries find nothing. Only the query, the
+ * category, the lookup tier, and the result count are kept; nothing about
+ * the client or session is recorded. Queries are truncated (`truncate`)
+ * or replaced by a hash (`hash`), which still groups repeated queries.
+ *
+ * `telemetry/summary` aggregates the log.
+ *
+ * @example
+ * ```bash
+ * O8_QUERY_LOG=truncate O8_QUERY_LOG_SAMPLE=0.1   # one query in ten, first 64 chars
+ * ```
+ */
+
+import { promises as fs } from "fs";
+import { createHash } from "crypto";
+import { dirname, join } from "path";
+import { defaultStateDir } from "./snapshotStore.js";
+import { compareStrings } from "../utils/deterministic.js";
+import { Logger } from "../utils/logger.js";
+import { redactor } from "../utils/redact.js";
+
+const logger = new Logger("QueryLog");
+
+export const QUERY_LOG_MODES = ["truncate", "hash"] as const;
+
+export type QueryLogMode = (typeof QUERY_LOG_MODES)[number];
+
+/** Characters of a query kept in truncate mode */
+export const MAX_QUERY_CHARS = 64;
+
+/**
+ * One logged query
+ */
+export interface QueryLogEntry {
+  timestamp: string;
+  /** Truncated query, or `sha256:<hex>` in hash mode */
+  query: string;
+  category?: string;
+  /** How the query was answered (index, quick, fuzzy, fuzzy-fallback, cache) */
+  tier: string;
+  results: number;
+}
+
+/**
+ * Query log settings
+ */
+export interface QueryLogOptions {
+  mode: QueryLogMode;
+  /** Share of queries logged (0-1) */
+  sampleRate: number;
+  /** JSONL file */
+  path: string;
+}
+
+/**
+ * Aggregate of the query log
+ */
+export interface QueryLogSummary {
+  enabled: boolean;
+  mode?: QueryLogMode;
+  sampleRate?: number;
+  entries: number;
+  zeroResults: number;
+  since?: string;
+  until?: string;
+  /** Most frequent queries */
+  topQueries: Array<{ query: string; count: number; zeroResults: number }>;
+  /** Most frequent queries that found nothing */
+  topZeroResultQueries: Array<{ query: string; count: number }>;
+}
+
+/**
+ * Query log settings from O8_QUERY_LOG, O8_QUERY_LOG_SAMPLE, and
+ * O8_QUERY_LOG_PATH, or null when logging is off
+ *
+ * @param env - Environment (default: process.env)
+ * @throws {Error} If the mode or sample rate is invalid
+ */
+export function queryLogOptions(
+  env: NodeJS.ProcessEnv = process.env,
+): QueryLogOptions | null {
+  const mode = env.O8_QUERY_LOG;
+  if (!mode || mode === "off") {
+    return null;
+  }
+  if (!(QUERY_LOG_MODES as readonly string[]).includes(mode)) {
+    throw new Error(
+      `Invalid O8_QUERY_LOG: ${mode} (expected ${QUERY_LOG_MODES.join(", ")}, off)`,
+    );
+  }
+
+  const sampleRate = Number(env.O8_QUERY_LOG_SAMPLE ?? "1");
+  if (!Number.isFinite(sampleRate) || sampleRate < 0 || sampleRate > 1) {
+    throw new Error(
+      `Invalid O8_QUERY_LOG_SAMPLE: ${env.O8_QUERY_LOG_SAMPLE} (expected 0-1)`,
+    );
+  }
+
+  return {
+    mode: mode as QueryLogMode,
+    sampleRate,
+    path: env.O8_QUERY_LOG_PATH || join(defaultStateDir(env), "queries.jsonl"),
+  };
+}
+
+/**
+ * Anonymize a query for the log
+ */
+export function anonymizeQuery(query: string, mode: QueryLogMode): string {
+  const normalized = query.trim().replace(/\s+/g, " ");
+  if (mode === "hash") {
+    return `sha256:${createHash("sha256").update(normalized.toLowerCase()).digest("hex").slice(0, 16)}`;
+  }
+  return Array.from(normalized).slice(0, MAX_QUERY_CHARS).join("");
+}
+
+/**
+ * Sampled, anonymized query log in the state directory
+ */
+export class QueryLog {
+  private pending: Promise<void> = Promise.resolve();
+
+  constructor(
+    private readonly options: QueryLogOptions | null,
+    private readonly random: () => number = Math.random,
+  ) {}
+
+  /**
+   * Log a query if logging is on and it is sampled
+   *
+   * Writes happen in the background; failures are logged and ignored.
+   */
+  record(query: string, results: number, tier: string, category?: string): void {
+    const options = this.options;
+    if (!options || this.random() >= options.sampleRate) {
+      return;
+    }
+
+    const entry: QueryLogEntry = {
+      timestamp: new Date().toISOString(),
+      query: anonymizeQuery(redactor().redactText(query), options.mode),
+      ...(category ? { category } : {}),
+      tier,
+      results,
+    };
+    this.pending = this.pending
+      .then(async () => {
+        await fs.mkdir(dirname(options.path), { recursive: true });
+        await fs.appendFile(options.path, JSON.stringify(entry) + "\n");
+      })
+      .catch((error) => {
+        logger.warn(`Failed to write query log ${options.path}:`, error);
+      });
+  }
+
+  /**
+   * Wait for queued writes
+   */
+  async flush(): Promise<void> {
+    await this.pending;
+  }
+
+  /**
+   * Aggregate the log
+   *
+   * @param limit - Entries per top list (default: 20)
+   */
+  async summary(limit: number = 20): Promise<QueryLogSummary> {
+    const summary: QueryLogSummary = {
+      enabled: this.options !== null,
+      ...(this.options
+        ? { mode: this.options.mode, sampleRate: this.options.sampleRate }
+        : {}),
+      entries: 0,
+      zeroResults: 0,
+      topQueries: [],
+      topZeroResultQueries: [],
+    };
+    if (!this.options) {
+      return summary;
+    }
+
+    await this.flush();
+    let text: string;
+    try {
+      text = await fs.readFile(this.options.path, "utf-8");
+    } catch {
+      return summary;
+    }
+
+    const counts = new Map<string, { count: number; zeroResults: number }>();
+    for (const line of text.split("\n")) {
+      if (!line.trim()) continue;
+      let entry: QueryLogEntry;
+      try {
+        entry = JSON.parse(line);
+      } catch {
+        continue;
+      }
+
+      summary.entries++;
+      summary.since ??= entry.timestamp;
+      summary.until = entry.timestamp;
+      const count = counts.get(entry.query) ?? { count: 0, zeroResults: 0 };
+      count.count++;
+      if (entry.results === 0) {
+        count.zeroResults++;
+        summary.zeroResults++;
+      }
+      counts.set(entry.query, count);
+    }
+
+    const ranked = [...counts.entries()].map(([query, count]) => ({
+      query,
+      ...count,
+    }));
+    const byCount = (
+      a: { query: string; count: number },
+      b: { query: string; count: number },
+    ) => b.count - a.count || compareStrings(a.query, b.query);
+    summary.topQueries = ranked.sort(byCount).slice(0, limit);
+    summary.topZeroResultQueries = ranked
+      .filter((entry) => entry.zeroResults > 0)
+      .map((entry) => ({ query: entry.query, count: entry.zeroResults }))
+      .sort(byCount)
+      .slice(0, limit);
+    return summary;
+  }
+}
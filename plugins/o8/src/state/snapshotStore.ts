@@ -0,0 +1,256 @@
+/**
+ * Catalog snapshot store
+ *
+ * A snapshot records every resource file by content hash. File contents are
+ * stored once per hash under blobs/, so repeated snapshots of a mostly
+ * unchanged catalog stay small. Serving a snapshot reads from the store and
+ * never touches the resources directory, which makes rolling back a bad bulk
+ * edit instant.
+ *
+ * Layout under the state directory:
+ * - blobs/<sha256>          file contents
+ * - snapshots/<name>.json   manifest (path, hash, size, modifiedAt)
+ * - pinned.json             snapshot currently served, if any
+ *
+ * Old snapshots are removed by garbage collection (see gc.ts).
+ */
+
+import { promises as fs } from "fs";
+import { createHash } from "crypto";
+import { homedir } from "os";
+import { join } from "path";
+
+/**
+ * One resource file in a snapshot
+ */
+export interface SnapshotFile {
+  /** Path relative to the resources root, with forward slashes */
+  path: string;
+  /** SHA-256 of the content */
+  hash: string;
+  size: number;
+  modifiedAt?: string;
+}
+
+/**
+ * Snapshot manifest
+ */
+export interface SnapshotManifest {
+  name: string;
+  createdAt: string;
+  files: SnapshotFile[];
+}
+
+/**
+ * Snapshot listing entry
+ */
+export interface SnapshotInfo {
+  name: string;
+  createdAt: string;
+  files: number;
+}
+
+/**
+ * Error raised for unknown or invalid snapshots
+ */
+export class SnapshotError extends Error {
+  public readonly code: "SNAPSHOT_NOT_FOUND" | "INVALID_SNAPSHOT_NAME";
+
+  constructor(
+    code: "SNAPSHOT_NOT_FOUND" | "INVALID_SNAPSHOT_NAME",
+    message: string,
+  ) {
+    super(message);
+    this.name = "SnapshotError";
+    this.code = code;
+  }
+}
+
+/**
+ * Default state directory (O8_STATE_DIR or ~/.orchestr8/state)
+ */
+export function defaultStateDir(env: NodeJS.ProcessEnv = process.env): string {
+  return env.O8_STATE_DIR || join(homedir(), ".orchestr8", "state");
+}
+
+/**
+ * Content-addressed snapshot storage on the local filesystem
+ */
+export class SnapshotStore {
+  constructor(private readonly dir: string) {}
+
+  /**
+   * Save a snapshot, replacing any snapshot with the same name
+   *
+   * @param name - Snapshot name (letters, digits, '.', '_', '-')
+   * @param files - Resource files with content
+   * @returns The saved manifest
+   */
+  async save(
+    name: string,
+    files: Array<{ path: string; content: string; modifiedAt?: string }>,
+  ): Promise<SnapshotManifest> {
+    this.validateName(name);
+    await fs.mkdir(join(this.dir, "blobs"), { recursive: true });
+    await fs.mkdir(join(this.dir, "snapshots"), { recursive: true });
+
+    const entries: SnapshotFile[] = [];
+    for (const file of files) {
+      const hash = createHash("sha256").update(file.content).digest("hex");
+      const blobPath = join(this.dir, "blobs", hash);
+      try {
+        // Touched so garbage collection doesn't take it for an orphan
+        const now = new Date();
+        await fs.utimes(blobPath, now, now);
+      } catch {
+        await this.writeAtomic(blobPath, file.content);
+      }
+      entries.push({
+        path: file.path,
+        hash,
+        size: Buffer.byteLength(file.content),
+        ...(file.modifiedAt ? { modifiedAt: file.modifiedAt } : {}),
+      });
+    }
+
+    const manifest: SnapshotManifest = {
+      name,
+      createdAt: new Date().toISOString(),
+      files: entries,
+    };
+    await this.writeAtomic(
+      this.manifestPath(name),
+      JSON.stringify(manifest, null, 2),
+    );
+    return manifest;
+  }
+
+  /**
+   * Load a snapshot manifest
+   *
+   * @throws {SnapshotError} If the snapshot does not exist
+   */
+  async load(name: string): Promise<SnapshotManifest> {
+    this.validateName(name);
+    try {
+      return JSON.parse(await fs.readFile(this.manifestPath(name), "utf-8"));
+    } catch (error: any) {
+      if (error?.code === "ENOENT") {
+        throw new SnapshotError(
+          "SNAPSHOT_NOT_FOUND",
+          `Snapshot not found: ${name}`,
+        );
+      }
+      throw error;
+    }
+  }
+
+  /**
+   * List snapshots, oldest first
+   */
+  async list(): Promise<SnapshotInfo[]> {
+    let names: string[];
+    try {
+      names = await fs.readdir(join(this.dir, "snapshots"));
+    } catch {
+      return [];
+    }
+
+    const infos: SnapshotInfo[] = [];
+    for (const file of names.filter((n) => n.endsWith(".json")).sort()) {
+      const manifest = await this.load(file.replace(/\.json$/, ""));
+      infos.push({
+        name: manifest.name,
+        createdAt: manifest.createdAt,
+        files: manifest.files.length,
+      });
+    }
+    return infos.sort((a, b) => a.createdAt.localeCompare(b.createdAt));
+  }
+
+  /**
+   * Latest snapshot taken at or before a point in time
+   *
+   * @throws {SnapshotError} If no snapshot is that old
+   */
+  async findAsOf(asOf: Date): Promise<string> {
+    const taken = (await this.list()).filter(
+      (info) => new Date(info.createdAt).getTime() <= asOf.getTime(),
+    );
+    if (taken.length === 0) {
+      throw new SnapshotError(
+        "SNAPSHOT_NOT_FOUND",
+        `No snapshot taken at or before ${asOf.toISOString()}`,
+      );
+    }
+    return taken[taken.length - 1].name;
+  }
+
+  /**
+   * Delete a snapshot manifest; its blobs are left to garbage collection
+   */
+  async remove(name: string): Promise<void> {
+    this.validateName(name);
+    await fs.rm(this.manifestPath(name), { force: true });
+  }
+
+  /**
+   * Read file content by hash
+   */
+  async readBlob(hash: string): Promise<string> {
+    return fs.readFile(join(this.dir, "blobs", hash), "utf-8");
+  }
+
+  /**
+   * Name of the pinned snapshot, or null when serving live files
+   */
+  async getPinned(): Promise<string | null> {
+    try {
+      const state = JSON.parse(
+        await fs.readFile(join(this.dir, "pinned.json"), "utf-8"),
+      );
+      return typeof state.name === "string" ? state.name : null;
+    } catch {
+      return null;
+    }
+  }
+
+  /**
+   * Persist the pinned snapshot (null to serve live files)
+   */
+  async setPinned(name: string | null): Promise<void> {
+    if (name === null) {
+      await fs.rm(join(this.dir, "pinned.json"), { force: true });
+      return;
+    }
+    this.validateName(name);
+    await fs.mkdir(this.dir, { recursive: true });
+    await this.writeAtomic(
+      join(this.dir, "pinned.json"),
+      JSON.stringify({ name }),
+    );
+  }
+
+  private manifestPath(name: string): string {
+    return join(this.dir, "snapshots", `${name}.json`);
+  }
+
+  private validateName(name: string): void {
+    if (
+      typeof name !== "string" ||
+      !/^[A-Za-z0-9._-]+$/.test(name) ||
+      name.startsWith(".")
+    ) {
+      throw new SnapshotError(
+        "INVALID_SNAPSHOT_NAME",
+        `Invalid snapshot name: ${name}`,
+      );
+    }
+  }
+
+  private async writeAtomic(path: string, content: string): Promise<void> {
+    const tmp = `${path}.${process.pid}.tmp`;
+    await fs.writeFile(tmp, content);
+    await fs.rename(tmp, path);
+  }
+}
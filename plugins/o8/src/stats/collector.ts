@@ -6,6 +6,8 @@
  */
 
 import type { TokenMetrics } from '../token/metrics.js';
+import { isDeterministic } from '../utils/deterministic.js';
+import { redactor } from '../utils/redact.js';
 
 export interface ActivityEvent {
   type: string;
@@ -18,6 +20,8 @@ export interface StatsSnapshot {
   requests: {
     total: number;
     byMethod: Record<string, number>;
+    /** Requests by client ("name/version") */
+    byClient: Record<string, number>;
   };
   cache: {
     hits: number;
@@ -56,7 +60,8 @@ export class StatsCollector {
   private stats = {
     requests: {
       total: 0,
-      byMethod: {} as Record<string, number>
+      byMethod: {} as Record<string, number>,
+      byClient: {} as Record<string, number>
     },
     cache: {
       hits: 0,
@@ -76,18 +81,35 @@ export class StatsCollector {
   // Token metrics integration
   private tokenMetrics: TokenMetrics | null = null;
 
-  constructor(tokenMetrics?: TokenMetrics) {
+  // Zero clock-derived values for reproducible snapshots
+  private readonly deterministic: boolean;
+
+  constructor(tokenMetrics?: TokenMetrics, deterministic = isDeterministic()) {
     this.startTime = Date.now();
     this.tokenMetrics = tokenMetrics || null;
+    this.deterministic = deterministic;
   }
 
   /**
    * Track a request to the MCP server
+   *
+   * @param client - Client label ("name/version") for per-client counts
+   * @param identity - Verified client identity (certificate subject), if any
    */
-  trackRequest(method: string, latencyMs: number, params?: any): void {
+  trackRequest(
+    method: string,
+    latencyMs: number,
+    params?: any,
+    client?: string,
+    identity?: string,
+  ): void {
     this.stats.requests.total++;
     this.stats.requests.byMethod[method] =
       (this.stats.requests.byMethod[method] || 0) + 1;
+    if (client) {
+      this.stats.requests.byClient[client] =
+        (this.stats.requests.byClient[client] || 0) + 1;
+    }
 
     this.stats.latencies.push(latencyMs);
     if (this.stats.latencies.length > this.maxLatencies) {
@@ -100,7 +122,9 @@ export class StatsCollector {
     this.logActivity('mcp_request', {
       method,
       latency: latencyMs,
-      params
+      params,
+      ...(client && { client }),
+      ...(identity && { identity })
     });
 
     this.notifySubscribers();
@@ -139,10 +163,12 @@ export class StatsCollector {
    * Log an activity event
    */
   logActivity(type: string, data?: any): void {
+    // Params and errors can carry credentials; the log is served to the
+    // dashboard and copied into crash reports
     const event: ActivityEvent = {
       type,
       timestamp: Date.now(),
-      data
+      data: redactor().redact(data)
     };
 
     this.activityLog.push(event);
@@ -177,7 +203,8 @@ export class StatsCollector {
       uptime: Math.floor((Date.now() - this.startTime) / 1000),
       requests: {
         total: this.stats.requests.total,
-        byMethod: { ...this.stats.requests.byMethod }
+        byMethod: { ...this.stats.requests.byMethod },
+        byClient: { ...this.stats.requests.byClient }
       },
       cache: {
         hits: this.stats.cache.hits,
@@ -217,6 +244,13 @@ export class StatsCollector {
       }
     }
 
+    if (this.deterministic) {
+      snapshot.uptime = 0;
+      snapshot.latency = { p50: 0, p95: 0, p99: 0, avg: 0 };
+      snapshot.memory = { heapUsed: 0, heapTotal: 0, external: 0, rss: 0 };
+      snapshot.lastActivity = 0;
+    }
+
     return snapshot;
   }
 
@@ -264,7 +298,8 @@ export class StatsCollector {
     this.stats = {
       requests: {
         total: 0,
-        byMethod: {}
+        byMethod: {},
+        byClient: {}
       },
       cache: {
         hits: 0,
@@ -0,0 +1,99 @@
+/**
+ * Backpressure gate for WebSocket sessions
+ *
+ * A dashboard tab that stops reading (backgrounded, suspended laptop) would
+ * otherwise make the server buffer every stats and activity update forever.
+ * Above the high-water mark, notifications are dropped; a session that stays
+ * behind for too many consecutive messages is closed.
+ */
+
+/**
+ * Backpressure limits
+ */
+export interface BackpressureLimits {
+  /** Buffered bytes above which notifications are dropped (default: 1MB) */
+  highWaterMark: number;
+  /** Consecutive dropped messages before the session is closed (default: 50) */
+  maxDroppedMessages: number;
+}
+
+/**
+ * Default backpressure limits
+ */
+export const DEFAULT_BACKPRESSURE_LIMITS: BackpressureLimits = {
+  highWaterMark: 1024 * 1024,
+  maxDroppedMessages: 50,
+};
+
+/**
+ * WebSocket close code sent to sessions that fall too far behind
+ * (1013: Try Again Later)
+ */
+export const SLOW_CONSUMER_CLOSE_CODE = 1013;
+
+/**
+ * Outcome of a gated send
+ */
+export type BackpressureDecision = "sent" | "dropped" | "closed";
+
+/**
+ * Socket surface used by the gate (matches `ws` WebSocket)
+ */
+export interface BufferedSocket {
+  bufferedAmount: number;
+  send(data: string): void;
+  close(code?: number, reason?: string): void;
+}
+
+/**
+ * Gates outgoing notifications on each session's buffered byte count
+ */
+export class BackpressureGate {
+  private limits: BackpressureLimits;
+  private consecutiveDrops: WeakMap<BufferedSocket, number> = new WeakMap();
+  private droppedTotal = 0;
+  private closedTotal = 0;
+
+  constructor(limits: Partial<BackpressureLimits> = {}) {
+    this.limits = { ...DEFAULT_BACKPRESSURE_LIMITS, ...limits };
+  }
+
+  /**
+   * Send a notification unless the session is behind
+   *
+   * @param socket - Open WebSocket session
+   * @param message - Serialized message
+   * @returns What happened to the message
+   */
+  send(socket: BufferedSocket, message: string): BackpressureDecision {
+    if (socket.bufferedAmount <= this.limits.highWaterMark) {
+      this.consecutiveDrops.delete(socket);
+      socket.send(message);
+      return "sent";
+    }
+
+    const drops = (this.consecutiveDrops.get(socket) ?? 0) + 1;
+    this.droppedTotal++;
+
+    if (drops > this.limits.maxDroppedMessages) {
+      this.consecutiveDrops.delete(socket);
+      this.closedTotal++;
+      socket.close(SLOW_CONSUMER_CLOSE_CODE, "Client is not reading updates");
+      return "closed";
+    }
+
+    this.consecutiveDrops.set(socket, drops);
+    return "dropped";
+  }
+
+  /**
+   * Get gate statistics
+   */
+  getStats(): { dropped: number; closed: number; limits: BackpressureLimits } {
+    return {
+      dropped: this.droppedTotal,
+      closed: this.closedTotal,
+      limits: { ...this.limits },
+    };
+  }
+}
@@ -0,0 +1,151 @@
+/**
+ * Connection limits for the HTTP transport
+ *
+ * The server is shared by every orchestrator on a host or cluster; one that
+ * opens connections in a loop would otherwise take all of them. Open
+ * WebSocket sessions and in-flight API requests count against a limit per
+ * client IP and a limit per bearer token, and connections past either limit
+ * are refused.
+ */
+
+import { createHash } from "crypto";
+
+/**
+ * Connection limits (0: unlimited)
+ */
+export interface ConnectionLimits {
+  /** Concurrent connections per client IP (default: 0) */
+  perIp: number;
+  /** Concurrent connections per bearer token (default: 0) */
+  perToken: number;
+}
+
+/**
+ * Default connection limits
+ */
+export const DEFAULT_CONNECTION_LIMITS: ConnectionLimits = {
+  perIp: 0,
+  perToken: 0,
+};
+
+/**
+ * WebSocket close code for refused sessions (1008: Policy Violation)
+ */
+export const CONNECTION_LIMIT_CLOSE_CODE = 1008;
+
+/**
+ * Error raised when a client is at its connection limit
+ */
+export class ConnectionLimitError extends Error {
+  public readonly code = "TOO_MANY_CONNECTIONS";
+
+  constructor(
+    public readonly scope: "ip" | "token",
+    public readonly limit: number,
+  ) {
+    super(
+      scope === "ip"
+        ? `Too many connections from this address (limit ${limit})`
+        : `Too many connections for this token (limit ${limit})`,
+    );
+    this.name = "ConnectionLimitError";
+  }
+}
+
+/**
+ * Connection limits from O8_MAX_CONNECTIONS_PER_IP and
+ * O8_MAX_CONNECTIONS_PER_TOKEN
+ */
+export function connectionLimitsFromEnv(
+  env: NodeJS.ProcessEnv = process.env,
+): ConnectionLimits {
+  const read = (name: string, fallback: number): number => {
+    const value = parseInt(env[name] || "", 10);
+    return value > 0 ? value : fallback;
+  };
+
+  return {
+    perIp: read("O8_MAX_CONNECTIONS_PER_IP", DEFAULT_CONNECTION_LIMITS.perIp),
+    perToken: read(
+      "O8_MAX_CONNECTIONS_PER_TOKEN",
+      DEFAULT_CONNECTION_LIMITS.perToken,
+    ),
+  };
+}
+
+/**
+ * Counts open connections per client IP and per token
+ */
+export class ConnectionLimiter {
+  private limits: ConnectionLimits;
+  // Tokens are counted by hash so they are not kept in memory as given
+  private open = {
+    ip: new Map<string, number>(),
+    token: new Map<string, number>(),
+  };
+  private rejectedTotal = 0;
+
+  constructor(limits: Partial<ConnectionLimits> = {}) {
+    this.limits = { ...DEFAULT_CONNECTION_LIMITS, ...limits };
+  }
+
+  /**
+   * Count a new connection
+   *
+   * @param ip - Client address
+   * @param token - Bearer token, if any
+   * @returns Function to call once when the connection ends
+   * @throws {ConnectionLimitError} If the IP or token is at its limit
+   */
+  acquire(ip: string, token?: string | null): () => void {
+    const keys: Array<["ip" | "token", string, number]> = [
+      ["ip", ip, this.limits.perIp],
+    ];
+    if (token) {
+      const hash = createHash("sha256").update(token).digest("hex");
+      keys.push(["token", hash, this.limits.perToken]);
+    }
+
+    for (const [scope, key, limit] of keys) {
+      if (limit > 0 && (this.open[scope].get(key) ?? 0) >= limit) {
+        this.rejectedTotal++;
+        throw new ConnectionLimitError(scope, limit);
+      }
+    }
+    for (const [scope, key] of keys) {
+      this.open[scope].set(key, (this.open[scope].get(key) ?? 0) + 1);
+    }
+
+    let released = false;
+    return () => {
+      if (released) {
+        return;
+      }
+      released = true;
+      for (const [scope, key] of keys) {
+        const count = (this.open[scope].get(key) ?? 1) - 1;
+        if (count > 0) {
+          this.open[scope].set(key, count);
+        } else {
+          this.open[scope].delete(key);
+        }
+      }
+    };
+  }
+
+  /**
+   * Limits, clients with open connections, and refused connections
+   */
+  getStats(): ConnectionLimits & {
+    ips: number;
+    tokens: number;
+    rejectedTotal: number;
+  } {
+    return {
+      ...this.limits,
+      ips: this.open.ip.size,
+      tokens: this.open.token.size,
+      rejectedTotal: this.rejectedTotal,
+    };
+  }
+}
@@ -1,8 +1,52 @@
-import express, { Express, Request, Response } from "express";
-import { Server as HTTPServer } from "http";
+import express, {
+  Express,
+  Request,
+  Response,
+  NextFunction,
+} from "express";
+import { Server as HTTPServer, type IncomingMessage } from "http";
+import { createServer as createHTTPSServer } from "https";
+import type { ServerOptions as HTTPSServerOptions } from "https";
+import { once } from "events";
 import { WebSocketServer, WebSocket } from "ws";
 import path from "path";
 import { StatsCollector } from "../stats/collector.js";
+import { ExtensionError } from "../extensions/registry.js";
+import { SnapshotError } from "../state/snapshotStore.js";
+import { LockError } from "../state/lock.js";
+import { SessionError, SessionRegistry } from "../session/registry.js";
+import { GrepPatternError } from "../utils/grep.js";
+import { RewriteConflictError } from "../utils/rewrite.js";
+import { SectionNotFoundError } from "../utils/sections.js";
+import type { QueryLogSummary } from "../state/queryLog.js";
+import type { IndexingState } from "../utils/indexProgress.js";
+import type { WatchdogState } from "../utils/indexWatchdog.js";
+import { CatalogNotReadyError } from "../utils/readiness.js";
+import type { UpstreamStatus } from "../proxy/upstreams.js";
+import { renderDashboard } from "../web/dashboard.js";
+import { RequestPolicy } from "../utils/policy.js";
+import { clientCertificateSubject } from "./tls.js";
+import { BackpressureGate, type BackpressureLimits } from "./backpressure.js";
+import {
+  CONNECTION_LIMIT_CLOSE_CODE,
+  ConnectionLimitError,
+  ConnectionLimiter,
+  type ConnectionLimits,
+} from "./connectionLimits.js";
+import { isDeterministic } from "../utils/deterministic.js";
+import { parseFields, selectFields } from "../utils/fields.js";
+import {
+  PayloadLimits,
+  PayloadLimitError,
+  InvalidLimitError,
+  DEFAULT_PAYLOAD_LIMITS,
+  checkCount,
+  checkLimit,
+} from "../utils/limits.js";
+import {
+  TemplateError,
+  TemplateSyntaxError,
+} from "../utils/templateEngine.js";
 import type { TokenTracker } from "../token/tracker.js";
 import type { TokenStore } from "../token/store.js";
 import type { TokenMetrics } from "../token/metrics.js";
@@ -12,15 +56,81 @@ export interface HTTPTransportConfig {
   port: number;
   staticPath: string;
   enableCORS?: boolean;
+  /** Outgoing WebSocket limits for clients that stop reading */
+  backpressure?: Partial<BackpressureLimits>;
+  /** Request size and parameter limits */
+  limits?: Partial<PayloadLimits>;
+  /** Concurrent connections per client IP and per token */
+  connections?: Partial<ConnectionLimits>;
+  /** Registry WebSocket connections are added to */
+  sessions?: SessionRegistry;
+  /** Bearer token for admin methods; unset disables them */
+  adminToken?: string;
+  /** Authorization policy (default: admin methods need adminToken) */
+  policy?: RequestPolicy;
+  /** Serve HTTPS (and require client certificates when `ca` is set) */
+  tls?: HTTPSServerOptions;
+  /** Serve the read-only admin dashboard at /admin */
+  dashboard?: boolean;
+}
+
+/**
+ * Who made an /api/mcp/request call
+ */
+export interface RequestContext {
+  /** Policy role, with the client certificate subject if one was used */
+  caller: string;
 }
 
 export interface MCPServerInterface {
-  handleRequest(method: string, params: any): Promise<any>;
+  handleRequest(
+    method: string,
+    params: any,
+    context?: RequestContext,
+  ): Promise<any>;
   getAvailableAgents(): Promise<any[]>;
   getAvailableSkills(): Promise<any[]>;
   getAvailableWorkflows(): Promise<any[]>;
   getAvailablePatterns(): Promise<any[]>;
   searchResources(query: string): Promise<any[]>;
+  searchBodies(
+    query: string,
+    options?: { category?: string; limit?: number },
+  ): Promise<any[]>;
+  sampleResources(options?: {
+    count?: number;
+    perCategory?: number;
+    category?: string;
+    seed?: number;
+  }): Promise<{ seed: number; resources: any[] }>;
+  streamResources(options?: {
+    query?: string;
+    category?: string;
+    minScore?: number;
+  }): AsyncIterable<Record<string, unknown>>;
+  getCatalogSummary(options?: {
+    category?: string;
+    topTags?: number;
+  }): Promise<any>;
+  getResourceHistory(id: string, limit?: number): Promise<any[]>;
+  getResourceProvenance(id: string): Promise<any | null>;
+  resolveAlias(id: string): Promise<string | null>;
+  getQueryLogSummary(limit?: number): Promise<QueryLogSummary>;
+  getCatalogState(): {
+    generation: number;
+    loadedAt: string | null;
+    resources: number;
+    pinned: string | null;
+    reloading: boolean;
+    indexing: IndexingState | null;
+    watchdog: WatchdogState;
+  };
+  getUpstreamStatus(): Record<string, UpstreamStatus>;
+  diffResource(
+    id: string,
+    from?: string,
+    to?: string,
+  ): Promise<{ id: string; from: string; to: string; diff: string }>;
   getResourceContent(uri: string): Promise<string>;
 
   // Provider methods
@@ -42,11 +152,25 @@ export interface MCPServerInterface {
   };
 }
 
+/**
+ * Token from an `Authorization: Bearer` header, if any
+ */
+function bearerToken(req: {
+  headers: { authorization?: string };
+}): string | null {
+  const header = req.headers.authorization ?? "";
+  return header.startsWith("Bearer ") ? header.slice(7) : null;
+}
+
 export class HTTPTransport {
   private app: Express;
   private httpServer: HTTPServer | null = null;
   private wsServer: WebSocketServer | null = null;
   private wsClients: Set<WebSocket> = new Set();
+  private backpressure: BackpressureGate;
+  private limits: PayloadLimits;
+  private connections: ConnectionLimiter;
+  private policy: RequestPolicy;
   private config: HTTPTransportConfig;
   private mcpServer: MCPServerInterface;
   private stats: StatsCollector;
@@ -60,6 +184,11 @@ export class HTTPTransport {
     this.config = config;
     this.mcpServer = mcpServer;
     this.stats = stats;
+    this.backpressure = new BackpressureGate(config.backpressure);
+    this.limits = { ...DEFAULT_PAYLOAD_LIMITS, ...config.limits };
+    this.connections = new ConnectionLimiter(config.connections);
+    this.policy =
+      config.policy ?? new RequestPolicy({ adminToken: config.adminToken });
     this.app = express();
     this.setupMiddleware();
     this.setupRoutes();
@@ -67,7 +196,7 @@ export class HTTPTransport {
 
   private setupMiddleware(): void {
     // Parse JSON bodies
-    this.app.use(express.json());
+    this.app.use(express.json({ limit: this.limits.maxRequestBytes }));
 
     // CORS support
     if (this.config.enableCORS) {
@@ -83,6 +212,30 @@ export class HTTPTransport {
       });
     }
 
+    // API requests in flight count against the connection limits
+    this.app.use("/api", (req, res, next) => {
+      let release: () => void;
+      try {
+        release = this.connections.acquire(
+          req.socket.remoteAddress ?? "unknown",
+          bearerToken(req),
+        );
+      } catch (error) {
+        if (error instanceof ConnectionLimitError) {
+          res.status(429).json({
+            error: error.message,
+            code: error.code,
+            scope: error.scope,
+            limit: error.limit,
+          });
+          return;
+        }
+        throw error;
+      }
+      res.on("close", release);
+      next();
+    });
+
     // Serve static files
     this.app.use(express.static(this.config.staticPath));
   }
@@ -90,7 +243,21 @@ export class HTTPTransport {
   private setupRoutes(): void {
     // Health check
     this.app.get("/health", (req: Request, res: Response) => {
-      res.json({ status: "ok", uptime: process.uptime() });
+      const upstreams = this.mcpServer.getUpstreamStatus();
+      const degraded = Object.values(upstreams).some(
+        (upstream) => upstream.circuit?.state !== "closed",
+      );
+      res.json({
+        status: degraded ? "degraded" : "ok",
+        uptime: isDeterministic() ? 0 : process.uptime(),
+        catalog: this.mcpServer.getCatalogState(),
+        upstreams,
+        websocket: {
+          clients: this.wsClients.size,
+          backpressure: this.backpressure.getStats(),
+        },
+        connections: this.connections.getStats(),
+      });
     });
 
     // MCP API endpoints
@@ -102,14 +269,84 @@ export class HTTPTransport {
           res.status(400).json({ error: "Missing method parameter" });
           return;
         }
+        const caller = this.authorize(req, res, method, params);
+        if (caller === null) {
+          return;
+        }
 
-        const result = await this.mcpServer.handleRequest(method, params);
+        const result = await this.mcpServer.handleRequest(method, params, {
+          caller,
+        });
         const latency = Date.now() - startTime;
-        this.stats.trackRequest(method, latency);
+        this.stats.trackRequest(
+          method,
+          latency,
+          undefined,
+          req.get("user-agent") ?? "unknown",
+          clientCertificateSubject(req.socket) ?? undefined,
+        );
 
         res.json({ result });
       } catch (error: any) {
         this.stats.trackError();
+        if (
+          error instanceof ExtensionError &&
+          error.code === "METHOD_NOT_FOUND"
+        ) {
+          res.status(404).json({ error: error.message, code: error.code });
+          return;
+        }
+        if (error instanceof SnapshotError) {
+          res
+            .status(error.code === "SNAPSHOT_NOT_FOUND" ? 404 : 400)
+            .json({ error: error.message, code: error.code });
+          return;
+        }
+        if (error instanceof PayloadLimitError) {
+          this.sendLimitError(res, error);
+          return;
+        }
+        if (error instanceof InvalidLimitError) {
+          res.status(400).json({ error: error.message, code: error.code });
+          return;
+        }
+        if (error instanceof GrepPatternError) {
+          res.status(400).json({ error: error.message, code: error.code });
+          return;
+        }
+        if (error instanceof LockError) {
+          res.status(409).json({
+            error: error.message,
+            code: error.code,
+            holder: error.holder,
+          });
+          return;
+        }
+        if (error instanceof RewriteConflictError) {
+          res.status(409).json({
+            error: error.message,
+            code: error.code,
+            conflicts: error.conflicts,
+          });
+          return;
+        }
+        if (error instanceof SessionError) {
+          res
+            .status(error.code === "SESSION_NOT_FOUND" ? 404 : 409)
+            .json({ error: error.message, code: error.code });
+          return;
+        }
+        if (error instanceof CatalogNotReadyError) {
+          res
+            .status(503)
+            .set("Retry-After", String(Math.ceil(error.retryAfterMs / 1000)))
+            .json({
+              error: error.message,
+              code: "CATALOG_NOT_READY",
+              retryAfterMs: error.retryAfterMs,
+            });
+          return;
+        }
         res.status(500).json({ error: error.message });
       }
     });
@@ -121,7 +358,7 @@ export class HTTPTransport {
         const agents = await this.mcpServer.getAvailableAgents();
         const latency = Date.now() - startTime;
         this.stats.trackRequest("list_agents", latency);
-        res.json({ agents });
+        res.json({ agents: selectFields(agents, this.fieldsParam(req)) });
       } catch (error: any) {
         this.stats.trackError();
         res.status(500).json({ error: error.message });
@@ -134,7 +371,7 @@ export class HTTPTransport {
         const skills = await this.mcpServer.getAvailableSkills();
         const latency = Date.now() - startTime;
         this.stats.trackRequest("list_skills", latency);
-        res.json({ skills });
+        res.json({ skills: selectFields(skills, this.fieldsParam(req)) });
       } catch (error: any) {
         this.stats.trackError();
         res.status(500).json({ error: error.message });
@@ -147,7 +384,7 @@ export class HTTPTransport {
         const workflows = await this.mcpServer.getAvailableWorkflows();
         const latency = Date.now() - startTime;
         this.stats.trackRequest("list_workflows", latency);
-        res.json({ workflows });
+        res.json({ workflows: selectFields(workflows, this.fieldsParam(req)) });
       } catch (error: any) {
         this.stats.trackError();
         res.status(500).json({ error: error.message });
@@ -160,7 +397,7 @@ export class HTTPTransport {
         const patterns = await this.mcpServer.getAvailablePatterns();
         const latency = Date.now() - startTime;
         this.stats.trackRequest("list_patterns", latency);
-        res.json({ patterns });
+        res.json({ patterns: selectFields(patterns, this.fieldsParam(req)) });
       } catch (error: any) {
         this.stats.trackError();
         res.status(500).json({ error: error.message });
@@ -175,13 +412,272 @@ export class HTTPTransport {
           res.status(400).json({ error: "Missing query parameter" });
           return;
         }
+        checkLimit("query", query.length, this.limits.maxQueryLength);
 
         const results = await this.mcpServer.searchResources(query);
         const latency = Date.now() - startTime;
         this.stats.trackRequest("search_resources", latency);
-        res.json({ results });
+        res.json({ results: selectFields(results, this.fieldsParam(req)) });
+      } catch (error: any) {
+        this.stats.trackError();
+        if (error instanceof PayloadLimitError) {
+          this.sendLimitError(res, error);
+          return;
+        }
+        res.status(500).json({ error: error.message });
+      }
+    });
+
+    // GET /api/search/body - Full-text search inside resource bodies
+    this.app.get("/api/search/body", async (req: Request, res: Response) => {
+      const startTime = Date.now();
+      try {
+        const query = req.query.q as string;
+        const category = req.query.category as string | undefined;
+        const limit = this.limitParam(req, 10);
+        if (!query) {
+          res.status(400).json({ error: "Missing query parameter" });
+          return;
+        }
+        if (limit === null) {
+          res.status(400).json({ error: "limit must be a positive integer" });
+          return;
+        }
+        checkLimit("query", query.length, this.limits.maxQueryLength);
+        checkLimit("limit", limit, this.limits.maxResults);
+
+        const hits = await this.mcpServer.searchBodies(query, {
+          category,
+          limit,
+        });
+        const latency = Date.now() - startTime;
+        this.stats.trackRequest("search_bodies", latency);
+        res.json({
+          query,
+          total: hits.length,
+          hits: selectFields(hits, this.fieldsParam(req)),
+        });
+      } catch (error: any) {
+        this.stats.trackError();
+        if (error instanceof PayloadLimitError) {
+          this.sendLimitError(res, error);
+          return;
+        }
+        res.status(500).json({ error: error.message });
+      }
+    });
+
+    // GET /api/sample - Random or stratified sample for catalog QA
+    this.app.get("/api/sample", async (req: Request, res: Response) => {
+      const startTime = Date.now();
+      try {
+        const intParam = (key: string) =>
+          req.query[key] !== undefined
+            ? parseInt(req.query[key] as string, 10)
+            : undefined;
+        const countParam = (key: string) =>
+          req.query[key] !== undefined
+            ? checkCount(key, Number(req.query[key]), this.limits.maxResults)
+            : undefined;
+        const count = countParam("count") ?? 10;
+        const perCategory = countParam("perCategory");
+
+        const sample = await this.mcpServer.sampleResources({
+          count,
+          perCategory,
+          category: req.query.category as string | undefined,
+          seed: intParam("seed"),
+        });
+        const latency = Date.now() - startTime;
+        this.stats.trackRequest("sample_resources", latency);
+        res.json({
+          ...sample,
+          resources: selectFields(sample.resources, this.fieldsParam(req)),
+        });
+      } catch (error: any) {
+        this.stats.trackError();
+        if (error instanceof PayloadLimitError) {
+          this.sendLimitError(res, error);
+          return;
+        }
+        if (error instanceof InvalidLimitError) {
+          res.status(400).json({ error: error.message, code: error.code });
+          return;
+        }
+        res.status(500).json({ error: error.message });
+      }
+    });
+
+    // GET /api/stream - NDJSON stream of matches, or the whole catalog
+    this.app.get("/api/stream", async (req: Request, res: Response) => {
+      const startTime = Date.now();
+      const query = req.query.q as string | undefined;
+      const minScore =
+        req.query.minScore !== undefined
+          ? parseInt(req.query.minScore as string, 10)
+          : undefined;
+      const fields = this.fieldsParam(req);
+      let closed = false;
+      res.on("close", () => {
+        closed = true;
+      });
+
+      try {
+        if (query) {
+          checkLimit("query", query.length, this.limits.maxQueryLength);
+        }
+        res.setHeader("Content-Type", "application/x-ndjson");
+
+        for await (const item of this.mcpServer.streamResources({
+          query,
+          category: req.query.category as string | undefined,
+          minScore,
+        })) {
+          if (closed) {
+            break;
+          }
+          const [line] = selectFields([item], fields);
+          if (!res.write(JSON.stringify(line) + "\n")) {
+            await Promise.race([once(res, "drain"), once(res, "close")]);
+          }
+        }
+        res.end();
+
+        const latency = Date.now() - startTime;
+        this.stats.trackRequest("stream_resources", latency);
       } catch (error: any) {
         this.stats.trackError();
+        if (res.headersSent) {
+          // Status is already 200; report the failure as a final line
+          res.end(JSON.stringify({ error: error.message }) + "\n");
+          return;
+        }
+        if (error instanceof PayloadLimitError) {
+          this.sendLimitError(res, error);
+          return;
+        }
+        res.status(500).json({ error: error.message });
+      }
+    });
+
+    // GET /admin - Read-only admin dashboard (ORCHESTR8_ADMIN_DASHBOARD)
+    if (this.config.dashboard) {
+      this.app.get("/admin", async (req: Request, res: Response) => {
+        try {
+          const [summary, queries] = await Promise.all([
+            this.mcpServer.getCatalogSummary(),
+            this.mcpServer.getQueryLogSummary(10),
+          ]);
+          res.type("html").send(
+            renderDashboard({
+              catalog: this.mcpServer.getCatalogState(),
+              byCategory: summary.byCategory ?? {},
+              failures: summary.validation?.failures ?? [],
+              topQueries: queries.enabled ? queries.topQueries : null,
+              activity: this.stats.getActivityLog(50),
+            }),
+          );
+        } catch (error: any) {
+          this.stats.trackError();
+          res.status(500).json({ error: error.message });
+        }
+      });
+    }
+
+    // GET /api/summary - Catalog statistics for dashboards
+    this.app.get("/api/summary", async (req: Request, res: Response) => {
+      const startTime = Date.now();
+      try {
+        const summary = await this.mcpServer.getCatalogSummary({
+          category: req.query.category as string | undefined,
+          topTags:
+            req.query.topTags !== undefined
+              ? checkCount(
+                  "topTags",
+                  Number(req.query.topTags),
+                  this.limits.maxResults,
+                )
+              : undefined,
+        });
+        const latency = Date.now() - startTime;
+        this.stats.trackRequest("catalog_summary", latency);
+        res.json(summary);
+      } catch (error: any) {
+        this.stats.trackError();
+        if (error instanceof PayloadLimitError) {
+          this.sendLimitError(res, error);
+          return;
+        }
+        if (error instanceof InvalidLimitError) {
+          res.status(400).json({ error: error.message, code: error.code });
+          return;
+        }
+        res.status(500).json({ error: error.message });
+      }
+    });
+
+    // GET /api/history - Git commits that changed a resource
+    this.app.get("/api/history", async (req: Request, res: Response) => {
+      const startTime = Date.now();
+      try {
+        const id = req.query.id as string;
+        const limit = this.limitParam(req, 20);
+        if (!id) {
+          res.status(400).json({ error: "Missing id parameter" });
+          return;
+        }
+        if (limit === null) {
+          res.status(400).json({ error: "limit must be a positive integer" });
+          return;
+        }
+        checkLimit("limit", limit, this.limits.maxResults);
+
+        const commits = await this.mcpServer.getResourceHistory(id, limit);
+        const latency = Date.now() - startTime;
+        this.stats.trackRequest("resource_history", latency);
+        res.json({ id, commits });
+      } catch (error: any) {
+        this.stats.trackError();
+        if (error instanceof PayloadLimitError) {
+          this.sendLimitError(res, error);
+          return;
+        }
+        if (error?.code === "NOT_A_GIT_REPO") {
+          res.status(404).json({ error: error.message, code: error.code });
+          return;
+        }
+        res.status(500).json({ error: error.message });
+      }
+    });
+
+    // GET /api/diff - Unified diff of a resource between two versions
+    this.app.get("/api/diff", async (req: Request, res: Response) => {
+      const startTime = Date.now();
+      try {
+        const id = req.query.id as string;
+        if (!id) {
+          res.status(400).json({ error: "Missing id parameter" });
+          return;
+        }
+
+        const result = await this.mcpServer.diffResource(
+          id,
+          req.query.from as string | undefined,
+          req.query.to as string | undefined,
+        );
+        const latency = Date.now() - startTime;
+        this.stats.trackRequest("resource_diff", latency);
+        res.json(result);
+      } catch (error: any) {
+        this.stats.trackError();
+        if (error?.code === "NOT_A_GIT_REPO") {
+          res.status(404).json({ error: error.message, code: error.code });
+          return;
+        }
+        if (error?.code === "GIT_FAILED" || error?.code === "ENOENT") {
+          res.status(400).json({ error: error.message, code: error.code });
+          return;
+        }
         res.status(500).json({ error: error.message });
       }
     });
@@ -196,11 +692,38 @@ export class HTTPTransport {
         }
 
         const content = await this.mcpServer.getResourceContent(uri);
+        const provenance = await this.mcpServer.getResourceProvenance(uri);
+        const canonical = await this.mcpServer.resolveAlias(uri);
         const latency = Date.now() - startTime;
         this.stats.trackRequest("get_resource", latency);
-        res.json({ content });
+        res.json({ content, provenance, ...(canonical ? { canonical } : {}) });
       } catch (error: any) {
         this.stats.trackError();
+        if (error instanceof TemplateError) {
+          res.status(400).json({
+            error: error.message,
+            code: error.code,
+            missing: error.missing,
+            invalid: error.invalid,
+          });
+          return;
+        }
+        if (error instanceof TemplateSyntaxError) {
+          res.status(400).json({ error: error.message, code: error.code });
+          return;
+        }
+        if (error instanceof SectionNotFoundError) {
+          res.status(404).json({
+            error: error.message,
+            code: error.code,
+            available: error.available,
+          });
+          return;
+        }
+        if (error instanceof PayloadLimitError) {
+          this.sendLimitError(res, error);
+          return;
+        }
         res.status(500).json({ error: error.message });
       }
     });
@@ -280,6 +803,8 @@ export class HTTPTransport {
           res.status(400).json({ error: "Missing query parameter" });
           return;
         }
+        checkLimit("query", query.length, this.limits.maxQueryLength);
+        checkLimit("maxResults", maxResults, this.limits.maxResults);
 
         const results = await this.mcpServer.searchAllProviders(query, {
           sources,
@@ -311,6 +836,10 @@ export class HTTPTransport {
         });
       } catch (error: any) {
         this.stats.trackError();
+        if (error instanceof PayloadLimitError) {
+          this.sendLimitError(res, error);
+          return;
+        }
         res.status(500).json({ error: error.message });
       }
     });
@@ -569,47 +1098,173 @@ export class HTTPTransport {
     this.app.get("*", (req: Request, res: Response) => {
       res.sendFile(path.join(this.config.staticPath, "index.html"));
     });
+
+    // Oversized JSON bodies rejected by express.json()
+    this.app.use(
+      (error: any, req: Request, res: Response, next: NextFunction) => {
+        if (error?.type === "entity.too.large") {
+          this.sendLimitError(
+            res,
+            new PayloadLimitError("body", error.limit, error.length),
+          );
+          return;
+        }
+        next(error);
+      },
+    );
+  }
+
+  /**
+   * Respond 413 with the exceeded limit
+   * @private
+   */
+  private sendLimitError(res: Response, error: PayloadLimitError): void {
+    res.status(413).json({
+      error: error.message,
+      code: error.code,
+      field: error.field,
+      limit: error.limit,
+    });
+  }
+
+  /**
+   * Check a request against the authorization policy, answering 401 for
+   * unknown tokens and 403 for requests the policy refuses
+   *
+   * @returns The caller (role, and certificate subject if any), or null
+   *   when the request was refused
+   */
+  private authorize(
+    req: Request,
+    res: Response,
+    method: string,
+    params: unknown,
+  ): string | null {
+    const token = bearerToken(req);
+    const subject = clientCertificateSubject(req.socket);
+    const role =
+      !token && subject
+        ? this.policy.roleForCertificate(subject)
+        : this.policy.roleFor(token);
+    if (role === null) {
+      res.status(401).json({ error: "Invalid token", code: "UNAUTHORIZED" });
+      return null;
+    }
+
+    const decision = this.policy.authorize({ method, role, params });
+    if (decision.allowed) {
+      return subject ? `${role} (${subject})` : role;
+    }
+    if (decision.rule === null && !this.policy.adminEnabled) {
+      res.status(403).json({
+        error: "Admin methods are disabled; set O8_ADMIN_TOKEN to enable them",
+        code: "ADMIN_DISABLED",
+      });
+      return null;
+    }
+    console.error(
+      `[HTTP Transport] Refused ${method} for role ${role} by policy` +
+        (subject ? ` (client certificate ${subject})` : ""),
+    );
+    res.status(403).json({
+      error: `Role ${role} may not call ${method}`,
+      code: "FORBIDDEN",
+    });
+    return null;
+  }
+
+  /**
+   * The `limit` query parameter, or the default when it is absent
+   *
+   * @returns null if it is not a positive integer
+   * @private
+   */
+  private limitParam(req: Request, fallback: number): number | null {
+    if (req.query.limit === undefined) {
+      return fallback;
+    }
+    const limit = Number(req.query.limit);
+    return Number.isInteger(limit) && limit > 0 ? limit : null;
+  }
+
+  /**
+   * Sparse fieldset from the `fields` query parameter
+   * @private
+   */
+  private fieldsParam(req: Request): string[] | undefined {
+    return parseFields(req.query.fields as string | undefined);
   }
 
   private setupWebSocket(server: HTTPServer): void {
     this.wsServer = new WebSocketServer({ server });
 
-    this.wsServer.on("connection", async (ws: WebSocket) => {
-      console.error("[HTTP Transport] WebSocket client connected");
-      this.wsClients.add(ws);
+    this.wsServer.on(
+      "connection",
+      async (ws: WebSocket, req: IncomingMessage) => {
+        let release: () => void;
+        try {
+          release = this.connections.acquire(
+            req.socket.remoteAddress ?? "unknown",
+            bearerToken(req),
+          );
+        } catch (error) {
+          if (!(error instanceof ConnectionLimitError)) {
+            throw error;
+          }
+          console.error(`[HTTP Transport] WebSocket refused: ${error.message}`);
+          ws.close(CONNECTION_LIMIT_CLOSE_CODE, error.message);
+          return;
+        }
+        ws.once("close", release);
 
-      // Send initial stats snapshot
-      try {
-        const snapshot = await this.stats.getSnapshot();
-        ws.send(
+        console.error("[HTTP Transport] WebSocket client connected");
+        this.wsClients.add(ws);
+        const sessionId = this.config.sessions?.open("websocket", null, () =>
+          ws.terminate(),
+        );
+        const closeSession = () => {
+          if (sessionId) {
+            this.config.sessions?.close(sessionId);
+          }
+        };
+
+        // Send initial stats snapshot
+        try {
+          const snapshot = await this.stats.getSnapshot();
+          this.sendToClient(
+            ws,
+            JSON.stringify({
+              type: "stats",
+              data: snapshot,
+            }),
+          );
+        } catch (error) {
+          console.error("[HTTP Transport] Error sending initial stats:", error);
+        }
+
+        // Send activity history
+        const activityHistory = this.stats.getActivityLog(100);
+        this.sendToClient(
+          ws,
           JSON.stringify({
-            type: "stats",
-            data: snapshot,
+            type: "activity_history",
+            data: activityHistory,
           }),
         );
-      } catch (error) {
-        console.error("[HTTP Transport] Error sending initial stats:", error);
-      }
-
-      // Send activity history
-      const activityHistory = this.stats.getActivityLog(100);
-      ws.send(
-        JSON.stringify({
-          type: "activity_history",
-          data: activityHistory,
-        }),
-      );
 
-      ws.on("close", () => {
-        console.error("[HTTP Transport] WebSocket client disconnected");
-        this.wsClients.delete(ws);
-      });
+        ws.on("close", () => {
+          console.error("[HTTP Transport] WebSocket client disconnected");
+          this.wsClients.delete(ws);
+          closeSession();
+        });
 
-      ws.on("error", (error) => {
-        console.error("[HTTP Transport] WebSocket error:", error);
-        this.wsClients.delete(ws);
-      });
-    });
+        ws.on("error", (error) => {
+          console.error("[HTTP Transport] WebSocket error:", error);
+          this.wsClients.delete(ws);
+          closeSession();
+        });
+      },
+    );
 
     // Subscribe to stats updates and broadcast to all clients
     this.stats.subscribe((snapshot) => {
@@ -644,11 +1299,7 @@ export class HTTPTransport {
       data: snapshot,
     });
 
-    this.wsClients.forEach((client) => {
-      if (client.readyState === WebSocket.OPEN) {
-        client.send(message);
-      }
-    });
+    this.wsClients.forEach((client) => this.sendToClient(client, message));
   }
 
   private broadcastActivity(event: any): void {
@@ -657,22 +1308,39 @@ export class HTTPTransport {
       data: event,
     });
 
-    this.wsClients.forEach((client) => {
-      if (client.readyState === WebSocket.OPEN) {
-        client.send(message);
-      }
-    });
+    this.wsClients.forEach((client) => this.sendToClient(client, message));
+  }
+
+  /**
+   * Send a message to one client, dropping it if the client is not reading
+   * @private
+   */
+  private sendToClient(client: WebSocket, message: string): void {
+    if (client.readyState !== WebSocket.OPEN) {
+      return;
+    }
+
+    if (this.backpressure.send(client, message) === "closed") {
+      console.error(
+        "[HTTP Transport] Closed WebSocket client that stopped reading updates",
+      );
+      this.wsClients.delete(client);
+    }
   }
 
   async start(): Promise<void> {
     return new Promise((resolve, reject) => {
       try {
-        this.httpServer = this.app.listen(this.config.port, () => {
+        const scheme = this.config.tls ? "https" : "http";
+        const onListening = () => {
           console.error(
             `[HTTP Transport] Server listening on port ${this.config.port}`,
           );
+          if (this.config.tls?.requestCert) {
+            console.error("[HTTP Transport] Client certificates required");
+          }
           console.error(
-            `[HTTP Transport] Web UI: http://localhost:${this.config.port}`,
+            `[HTTP Transport] Web UI: ${scheme}://localhost:${this.config.port}`,
           );
 
           if (this.httpServer) {
@@ -680,7 +1348,13 @@ export class HTTPTransport {
           }
 
           resolve();
-        });
+        };
+        this.httpServer = this.config.tls
+          ? createHTTPSServer(this.config.tls, this.app).listen(
+              this.config.port,
+              onListening,
+            )
+          : this.app.listen(this.config.port, onListening);
 
         this.httpServer.on("error", (error) => {
           console.error("[HTTP Transport] Server error:", error);
@@ -0,0 +1,201 @@
+/**
+ * stdio lifecycle monitor
+ *
+ * Defines what happens when the MCP client goes away: stdin reaches EOF or
+ * a write to stdout fails (EPIPE). In-flight requests are allowed to finish,
+ * the reason is logged, and the process exits with a distinct code. With
+ * stay-alive enabled the server keeps serving its other transports instead.
+ */
+
+import { Logger } from "../utils/logger.js";
+
+/**
+ * Process exit codes for stdio shutdown
+ */
+export const EXIT_CODES = {
+  /** stdin reached EOF (client disconnected) */
+  STDIN_CLOSED: 3,
+  /** Writing to stdout failed (broken pipe) */
+  STDOUT_BROKEN: 4,
+} as const;
+
+/**
+ * Reason the stdio channel closed
+ */
+export type StdioCloseReason = "stdin_closed" | "stdout_broken";
+
+/**
+ * Minimal transport surface wrapped for in-flight tracking
+ */
+export interface TrackableTransport {
+  onmessage?: (message: any, ...rest: any[]) => void;
+  send(message: any, ...rest: any[]): Promise<void>;
+}
+
+/**
+ * stdio lifecycle options
+ */
+export interface StdioLifecycleOptions {
+  /** Keep the process running when stdio closes */
+  stayAlive?: boolean;
+  /** Maximum time to wait for in-flight requests (default: 5000ms) */
+  drainTimeoutMs?: number;
+  /** Called once when stdio closes and the process should exit */
+  onExit: (reason: StdioCloseReason, code: number) => Promise<void> | void;
+  /** Input stream (default: process.stdin) */
+  input?: NodeJS.ReadableStream;
+  /** Output stream (default: process.stdout) */
+  output?: NodeJS.WritableStream;
+}
+
+/**
+ * Watches stdin/stdout and coordinates shutdown when either closes
+ */
+export class StdioLifecycle {
+  private pending: Set<string | number> = new Set();
+  private idleWaiters: Array<() => void> = [];
+  private closed: StdioCloseReason | null = null;
+  private options: Required<Omit<StdioLifecycleOptions, "input" | "output">>;
+  private input: NodeJS.ReadableStream;
+  private output: NodeJS.WritableStream;
+
+  constructor(
+    private logger: Logger,
+    options: StdioLifecycleOptions,
+  ) {
+    this.options = {
+      stayAlive: options.stayAlive ?? false,
+      drainTimeoutMs: options.drainTimeoutMs ?? 5000,
+      onExit: options.onExit,
+    };
+    this.input = options.input ?? process.stdin;
+    this.output = options.output ?? process.stdout;
+  }
+
+  /**
+   * Start monitoring a connected transport
+   *
+   * Must be called after the MCP server has connected the transport, since
+   * connecting installs the transport's message handler.
+   *
+   * @param transport - Connected stdio transport
+   */
+  attach(transport: TrackableTransport): void {
+    const onmessage = transport.onmessage;
+    transport.onmessage = (message: any, ...rest: any[]) => {
+      if (message && "method" in message && message.id !== undefined) {
+        this.pending.add(message.id);
+      }
+      onmessage?.(message, ...rest);
+    };
+
+    const send = transport.send.bind(transport);
+    transport.send = async (message: any, ...rest: any[]) => {
+      if (message && !("method" in message) && message.id !== undefined) {
+        this.pending.delete(message.id);
+        this.notifyIfIdle();
+      }
+      if (this.closed === "stdout_broken") {
+        return;
+      }
+      return send(message, ...rest);
+    };
+
+    this.input.on("end", () => this.handleClose("stdin_closed"));
+    this.output.on("error", (error: NodeJS.ErrnoException) => {
+      if (error.code === "EPIPE" || error.code === "ERR_STREAM_DESTROYED") {
+        this.handleClose("stdout_broken");
+      } else {
+        this.logger.error("stdout write failed:", error);
+      }
+    });
+  }
+
+  /**
+   * Number of requests received but not yet answered
+   */
+  get inFlight(): number {
+    return this.pending.size;
+  }
+
+  /**
+   * Reason stdio closed, or null while still open
+   */
+  get closeReason(): StdioCloseReason | null {
+    return this.closed;
+  }
+
+  /**
+   * Wait until all in-flight requests have been answered
+   *
+   * @param timeoutMs - Maximum time to wait
+   * @returns true if idle, false if the timeout elapsed first
+   */
+  waitForIdle(timeoutMs: number): Promise<boolean> {
+    if (this.pending.size === 0) {
+      return Promise.resolve(true);
+    }
+
+    return new Promise((resolve) => {
+      const timer = setTimeout(() => {
+        this.idleWaiters = this.idleWaiters.filter((w) => w !== waiter);
+        resolve(false);
+      }, timeoutMs);
+      const waiter = () => {
+        clearTimeout(timer);
+        resolve(true);
+      };
+      this.idleWaiters.push(waiter);
+    });
+  }
+
+  /**
+   * Handle stdio closing (first reason wins)
+   * @private
+   */
+  private async handleClose(reason: StdioCloseReason): Promise<void> {
+    if (this.closed) {
+      return;
+    }
+    this.closed = reason;
+
+    const description =
+      reason === "stdin_closed" ? "stdin reached EOF" : "stdout pipe is broken";
+
+    if (this.options.stayAlive) {
+      this.logger.warn(
+        `stdio closed (${description}); staying alive for other transports`,
+      );
+      return;
+    }
+
+    this.logger.info(
+      `stdio closed (${description}); finishing ${this.pending.size} in-flight requests`,
+    );
+    const drained = await this.waitForIdle(this.options.drainTimeoutMs);
+    if (!drained) {
+      this.logger.warn(
+        `Exiting with ${this.pending.size} requests still in flight after ${this.options.drainTimeoutMs}ms`,
+      );
+    }
+
+    const code =
+      reason === "stdin_closed"
+        ? EXIT_CODES.STDIN_CLOSED
+        : EXIT_CODES.STDOUT_BROKEN;
+    await this.options.onExit(reason, code);
+  }
+
+  /**
+   * Resolve idle waiters once nothing is pending
+   * @private
+   */
+  private notifyIfIdle(): void {
+    if (this.pending.size > 0) {
+      return;
+    }
+    const waiters = this.idleWaiters;
+    this.idleWaiters = [];
+    waiters.forEach((waiter) => waiter());
+  }
+}
@@ -0,0 +1,109 @@
+/**
+ * stdout hygiene for the stdio transport
+ *
+ * Over stdio, stdout carries JSON-RPC frames and nothing else: a stray
+ * `console.log` from a dependency or an extension corrupts the stream and
+ * the client drops the connection. The guard gives the transport a private
+ * channel to the real stdout and redirects every other write to stderr. In
+ * strict mode (`ORCHESTR8_STRICT_STDOUT`, for tests and debugging) a stray
+ * write throws instead, so the code that made it shows up in the stack
+ * trace.
+ */
+
+import { Writable } from "stream";
+
+/**
+ * Raised in strict mode when something other than the transport writes to
+ * stdout
+ */
+export class StdoutViolationError extends Error {
+  constructor(public readonly output: string) {
+    super(
+      `Non-protocol write to stdout: ${JSON.stringify(output.slice(0, 200))}`,
+    );
+    this.name = "StdoutViolationError";
+  }
+}
+
+export interface StdoutGuardOptions {
+  /** Throw on stray writes instead of redirecting them (default: false) */
+  strict?: boolean;
+  /** Stream carrying the protocol (default: process.stdout) */
+  stdout?: NodeJS.WriteStream;
+  /** Where stray writes go (default: process.stderr) */
+  stderr?: NodeJS.WritableStream;
+}
+
+type WriteFn = NodeJS.WriteStream["write"];
+
+/**
+ * Reserves stdout for the stdio transport
+ */
+export class StdoutGuard {
+  /** Stream to hand to the stdio transport */
+  readonly protocolOut: Writable;
+
+  private readonly strict: boolean;
+  private readonly stdout: NodeJS.WriteStream;
+  private readonly stderr: NodeJS.WritableStream;
+  private readonly originalWrite: WriteFn;
+  private violations: string[] = [];
+  private installed = false;
+
+  constructor(options: StdoutGuardOptions = {}) {
+    this.strict = options.strict ?? false;
+    this.stdout = options.stdout ?? process.stdout;
+    this.stderr = options.stderr ?? process.stderr;
+    this.originalWrite = this.stdout.write;
+
+    const write = this.originalWrite.bind(this.stdout) as (
+      chunk: any,
+      callback: (error?: Error | null) => void,
+    ) => boolean;
+    this.protocolOut = new Writable({
+      write: (chunk, _encoding, callback) => {
+        write(chunk, callback);
+      },
+    });
+    // Write failures (EPIPE) also surface on stdout itself, where
+    // StdioLifecycle handles them
+    this.protocolOut.on("error", () => {});
+  }
+
+  /**
+   * Redirect (or, in strict mode, reject) every write to stdout that does
+   * not come from `protocolOut`
+   */
+  install(): void {
+    if (this.installed) {
+      return;
+    }
+    this.installed = true;
+    const guarded = (chunk: any, encoding?: any, callback?: any): boolean => {
+      const text = typeof chunk === "string" ? chunk : Buffer.from(chunk).toString();
+      this.violations.push(text);
+      if (this.strict) {
+        throw new StdoutViolationError(text);
+      }
+      return this.stderr.write(chunk, encoding, callback);
+    };
+    this.stdout.write = guarded as WriteFn;
+  }
+
+  /**
+   * Restore the original stdout
+   */
+  uninstall(): void {
+    if (this.installed) {
+      this.stdout.write = this.originalWrite;
+      this.installed = false;
+    }
+  }
+
+  /**
+   * Stray writes seen since the guard was installed
+   */
+  getViolations(): string[] {
+    return [...this.violations];
+  }
+}
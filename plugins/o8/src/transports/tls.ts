@@ -0,0 +1,67 @@
+/**
+ * TLS for the HTTP transport
+ *
+ * With O8_TLS_CERT and O8_TLS_KEY the transport serves HTTPS. Adding
+ * O8_TLS_CLIENT_CA turns on mutual TLS: clients must present a certificate
+ * signed by that CA, and connections without one are refused during the
+ * handshake. The certificate's subject common name identifies the client
+ * to the authorization policy and in the activity log.
+ */
+
+import { readFileSync } from "fs";
+import type { ServerOptions } from "https";
+import type { TLSSocket } from "tls";
+
+/**
+ * HTTPS server options from O8_TLS_CERT, O8_TLS_KEY, and O8_TLS_CLIENT_CA,
+ * or null when TLS is not configured
+ *
+ * @throws {Error} If only some of the files are set or one cannot be read
+ */
+export function tlsFromEnv(
+  env: NodeJS.ProcessEnv = process.env,
+): ServerOptions | null {
+  const { O8_TLS_CERT: cert, O8_TLS_KEY: key, O8_TLS_CLIENT_CA: ca } = env;
+  if (!cert && !key && !ca) {
+    return null;
+  }
+  if (!cert || !key) {
+    throw new Error(
+      "Invalid TLS configuration: O8_TLS_CERT and O8_TLS_KEY must both be set",
+    );
+  }
+
+  const read = (variable: string, file: string) => {
+    try {
+      return readFileSync(file);
+    } catch (error) {
+      throw new Error(
+        `Cannot read ${variable} ${file}: ${(error as Error).message}`,
+      );
+    }
+  };
+  return {
+    cert: read("O8_TLS_CERT", cert),
+    key: read("O8_TLS_KEY", key),
+    ...(ca
+      ? {
+          ca: read("O8_TLS_CLIENT_CA", ca),
+          requestCert: true,
+          rejectUnauthorized: true,
+        }
+      : {}),
+  };
+}
+
+/**
+ * Subject common name of a verified client certificate, or null for plain
+ * HTTP connections and clients without a verified certificate
+ */
+export function clientCertificateSubject(socket: unknown): string | null {
+  const tls = socket as Partial<TLSSocket>;
+  if (!tls.encrypted || !tls.authorized || !tls.getPeerCertificate) {
+    return null;
+  }
+  const subject = tls.getPeerCertificate().subject?.CN;
+  return Array.isArray(subject) ? (subject[0] ?? null) : (subject ?? null);
+}
@@ -0,0 +1,194 @@
+/**
+ * Routing decisions: one recommended agent for a task
+ *
+ * Match URIs return a ranked list and leave the choice to the client, so
+ * every orchestrator ends up reimplementing the same selection policy.
+ * `agents/route` applies it on the server: agents are scored against the
+ * task with the fuzzy matcher, agents that are excluded, lack a required
+ * capability, or exceed the token budget are dropped, and the best one is
+ * returned with a confidence and the runners-up.
+ *
+ * Confidence is a heuristic in [0, 1]: the score relative to a strong match
+ * (STRONG_SCORE points), discounted when the next candidate scores almost
+ * as high, since the choice between them is then close to arbitrary.
+ */
+
+import type { FuzzyMatcher, ResourceFragment } from "./fuzzyMatcher.js";
+import {
+  matchesCapabilities,
+  parseCapabilityFilter,
+  type CapabilityFilter,
+} from "./capabilityMatrix.js";
+import { compareStrings } from "./deterministic.js";
+
+/**
+ * Score at which a match counts as fully confident
+ */
+export const STRONG_SCORE = 60;
+
+export interface RouteRequest {
+  /** Task description */
+  task: string;
+  /** Declared capabilities the agent must have */
+  capabilities?: CapabilityFilter;
+  /** Largest agent, in estimated tokens */
+  maxTokens?: number;
+  /** Agents not to recommend (names or ids) */
+  exclude?: string[];
+  /** Runners-up to return (default: 3) */
+  alternatives?: number;
+  /** Lowest score considered a match (default: 10) */
+  minScore?: number;
+}
+
+export interface RouteCandidate {
+  id: string;
+  score: number;
+  confidence: number;
+  estimatedTokens: number;
+}
+
+export interface RouteDecision {
+  /** Recommended agent; null if no agent qualifies */
+  agent: RouteCandidate | null;
+  alternatives: RouteCandidate[];
+  /** True when no agent matched and a designated fallback agent was chosen */
+  fallback?: boolean;
+  /** Agents dropped before ranking, by reason */
+  filtered: { excluded: number; capabilities: number; budget: number };
+}
+
+/**
+ * Routing request from the params of a custom method
+ *
+ * @param method - Method name, for error messages
+ * @throws Error if "task" is missing or "exclude" is not a list of strings
+ */
+export function parseRouteRequest(
+  method: string,
+  params: Record<string, any> | undefined,
+): RouteRequest {
+  if (typeof params?.task !== "string" || !params.task.trim()) {
+    throw new Error(`${method} requires "task": a description`);
+  }
+  const exclude = params.exclude;
+  if (
+    exclude !== undefined &&
+    (!Array.isArray(exclude) ||
+      !exclude.every((id: unknown) => typeof id === "string"))
+  ) {
+    throw new Error(`${method} "exclude" must be a list of agent ids`);
+  }
+  const number = (value: unknown) =>
+    typeof value === "number" ? value : undefined;
+  return {
+    task: params.task,
+    capabilities: parseCapabilityFilter(params.capabilities),
+    maxTokens: number(params.maxTokens),
+    exclude,
+    alternatives: number(params.alternatives),
+  };
+}
+
+/**
+ * Agent id for a name or id ("rust-expert" is agents/rust-expert)
+ */
+function agentId(name: string): string {
+  const id = name.replace("o8://", "");
+  return id.includes("/") ? id : `agents/${id}`;
+}
+
+function confidence(score: number, next: number | undefined): number {
+  const strength = Math.min(1, score / STRONG_SCORE);
+  const margin =
+    next === undefined || score === 0 ? 1 : (score - next) / score;
+  return Math.round(strength * (0.5 + 0.5 * margin) * 100) / 100;
+}
+
+/**
+ * Choose an agent for a task
+ *
+ * @param fragments - Resource index
+ * @param matcher - Matcher used for scoring
+ */
+export function routeAgent(
+  fragments: ResourceFragment[],
+  matcher: FuzzyMatcher,
+  request: RouteRequest,
+): RouteDecision {
+  const excluded = new Set((request.exclude ?? []).map(agentId));
+  const filtered = { excluded: 0, capabilities: 0, budget: 0 };
+  const keywords = matcher.extractKeywords(request.task);
+  const minScore = request.minScore ?? 10;
+
+  const eligible: ResourceFragment[] = [];
+  const scored: Array<{ resource: ResourceFragment; score: number }> = [];
+  for (const resource of fragments) {
+    if (resource.category !== "agent") {
+      continue;
+    }
+    if (excluded.has(resource.id)) {
+      filtered.excluded++;
+      continue;
+    }
+    if (
+      request.maxTokens !== undefined &&
+      resource.estimatedTokens > request.maxTokens
+    ) {
+      filtered.budget++;
+      continue;
+    }
+    if (
+      !matchesCapabilities(resource.capabilityMatrix, request.capabilities)
+    ) {
+      filtered.capabilities++;
+      continue;
+    }
+    eligible.push(resource);
+    // No category in the request: its bonus would apply to every agent
+    const score = matcher.calculateScore(resource, keywords, {
+      query: request.task,
+    });
+    if (score >= minScore && score >= (resource.minConfidence ?? 0)) {
+      scored.push({ resource, score });
+    }
+  }
+
+  scored.sort(
+    (a, b) => b.score - a.score || compareStrings(a.resource.id, b.resource.id),
+  );
+  const candidates = scored
+    .slice(0, 1 + (request.alternatives ?? 3))
+    .map(({ resource, score }, index) => ({
+      id: resource.id,
+      score,
+      confidence: confidence(score, scored[index + 1]?.score),
+      estimatedTokens: resource.estimatedTokens,
+    }));
+
+  if (candidates.length > 0) {
+    return {
+      agent: candidates[0],
+      alternatives: candidates.slice(1),
+      filtered,
+    };
+  }
+
+  // Nothing matched: the first designated fallback agent that qualifies
+  const fallback = eligible
+    .filter((resource) => resource.fallback)
+    .sort((a, b) => compareStrings(a.id, b.id))[0];
+  return fallback
+    ? {
+        agent: {
+          id: fallback.id,
+          score: 0,
+          confidence: 0,
+          estimatedTokens: fallback.estimatedTokens,
+        },
+        alternatives: [],
+        fallback: true,
+        filtered,
+      }
+    : { agent: null, alternatives: [], filtered };
+}
@@ -0,0 +1,165 @@
+/**
+ * Convention-based configuration for `--auto`
+ *
+ * Lets `orchestr8 --auto` (or ORCHESTR8_AUTO=true) start from any directory
+ * in a project without long absolute paths: the project root is the nearest
+ * directory with `.orchestr8` or `.git`, and resources and prompts are
+ * found by convention under it. Explicit RESOURCES_PATH and PROMPTS_PATH
+ * always win.
+ *
+ * Resource directories, first match wins:
+ * - <root>/.orchestr8            (if it has agents/, skills/, ...)
+ * - <root>/resources
+ * - <root>/plugins/<name>/resources
+ */
+
+import { promises as fs } from "fs";
+import { dirname, join } from "path";
+import { sortByName } from "./deterministic.js";
+
+const CATEGORY_DIRS = ["agents", "skills", "patterns", "examples", "workflows"];
+
+/**
+ * Configuration found by discovery
+ */
+export interface DiscoveredConfig {
+  /** Project root */
+  root: string;
+  /** Resources directory, if one was found */
+  resourcesPath?: string;
+  /** Prompts directory, if one was found */
+  promptsPath?: string;
+  /** Every directory checked for resources, in order */
+  checked: string[];
+}
+
+/**
+ * Find the project root and resource/prompt directories
+ *
+ * @param cwd - Directory to start from (default: process.cwd())
+ */
+export async function discoverConfig(
+  cwd: string = process.cwd(),
+): Promise<DiscoveredConfig> {
+  const root = await findRoot(cwd);
+  const candidates = [
+    join(root, ".orchestr8"),
+    join(root, "resources"),
+    ...(await pluginDirs(root)).map((plugin) => join(plugin, "resources")),
+  ];
+
+  const checked: string[] = [];
+  let resourcesPath: string | undefined;
+  for (const candidate of candidates) {
+    checked.push(candidate);
+    if (await hasCategoryDir(candidate)) {
+      resourcesPath = candidate;
+      break;
+    }
+  }
+
+  const promptCandidates = [
+    ...(resourcesPath ? [join(dirname(resourcesPath), "prompts")] : []),
+    join(root, ".orchestr8", "prompts"),
+    join(root, "prompts"),
+  ];
+  let promptsPath: string | undefined;
+  for (const candidate of promptCandidates) {
+    if (await isDirectory(candidate)) {
+      promptsPath = candidate;
+      break;
+    }
+  }
+
+  return { root, resourcesPath, promptsPath, checked };
+}
+
+/**
+ * Apply discovered paths to the environment unless already set
+ *
+ * @returns Discovered configuration and a printable summary
+ */
+export async function applyAutoConfig(
+  cwd: string = process.cwd(),
+  env: NodeJS.ProcessEnv = process.env,
+): Promise<{ config: DiscoveredConfig; summary: string }> {
+  const config = await discoverConfig(cwd);
+  const line = (
+    label: string,
+    variable: string,
+    discovered: string | undefined,
+  ) => {
+    if (env[variable]) {
+      return `  ${label}: ${env[variable]} (from ${variable})`;
+    }
+    if (discovered) {
+      env[variable] = discovered;
+      return `  ${label}: ${discovered}`;
+    }
+    return `  ${label}: not found (using default)`;
+  };
+
+  const summary = [
+    "orchestr8 --auto configuration",
+    `  root: ${config.root}`,
+    line("resources", "RESOURCES_PATH", config.resourcesPath),
+    line("prompts", "PROMPTS_PATH", config.promptsPath),
+    ...(config.resourcesPath
+      ? []
+      : [`  checked: ${config.checked.join(", ")}`]),
+  ].join("\n");
+
+  return { config, summary };
+}
+
+/**
+ * Nearest directory at or above cwd with `.orchestr8` or `.git`, else cwd
+ */
+export async function findRoot(cwd: string): Promise<string> {
+  let dir = cwd;
+  for (;;) {
+    for (const marker of [".orchestr8", ".git"]) {
+      try {
+        await fs.access(join(dir, marker));
+        return dir;
+      } catch {
+        // Keep looking
+      }
+    }
+    const parent = dirname(dir);
+    if (parent === dir) {
+      return cwd;
+    }
+    dir = parent;
+  }
+}
+
+async function pluginDirs(root: string): Promise<string[]> {
+  try {
+    const entries = await fs.readdir(join(root, "plugins"), {
+      withFileTypes: true,
+    });
+    return sortByName(entries)
+      .filter((entry) => entry.isDirectory())
+      .map((entry) => join(root, "plugins", entry.name));
+  } catch {
+    return [];
+  }
+}
+
+async function hasCategoryDir(dir: string): Promise<boolean> {
+  for (const category of CATEGORY_DIRS) {
+    if (await isDirectory(join(dir, category))) {
+      return true;
+    }
+  }
+  return false;
+}
+
+async function isDirectory(path: string): Promise<boolean> {
+  try {
+    return (await fs.stat(path)).isDirectory();
+  } catch {
+    return false;
+  }
+}
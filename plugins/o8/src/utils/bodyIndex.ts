@@ -0,0 +1,282 @@
+/**
+ * Full-text index over resource bodies
+ *
+ * Metadata matching (tags, capabilities, useWhen) can't answer "which agent
+ * mentions `cargo deny`?". This inverted index covers the markdown bodies
+ * and returns highlighted snippets for each hit.
+ *
+ * @example
+ * ```typescript
+ * const index = new BodyIndex();
+ * index.build(fragments);
+ * index.search('cargo deny', { category: 'agent', limit: 5 });
+ * // [{ id: 'agents/rust-expert', score: 7.1, snippets: ['... run **cargo** **deny** ...'] }]
+ * ```
+ */
+
+import type { ResourceFragment } from "./fuzzyMatcher.js";
+import { compareStrings } from "./deterministic.js";
+import { withProvenance, type Provenance } from "./provenance.js";
+import { isUnspaced, normalizeText } from "./tokenize.js";
+import { parseSections } from "./sections.js";
+
+/**
+ * Body search options
+ */
+export interface BodySearchOptions {
+  /** Restrict results to one fragment category (e.g. 'agent') */
+  category?: ResourceFragment["category"];
+  /** Maximum number of hits (default: 10) */
+  limit?: number;
+  /** Maximum snippets per hit (default: 3) */
+  snippets?: number;
+}
+
+/**
+ * Body search hit
+ */
+export interface BodySearchHit {
+  id: string;
+  uri: string;
+  category: ResourceFragment["category"];
+  /** TF-IDF relevance score */
+  score: number;
+  /** Total occurrences of the query terms in the body */
+  occurrences: number;
+  /** Body excerpts with matched terms marked as **term** */
+  snippets: string[];
+  /**
+   * Sections whose own text mentions a query term, with URIs that load
+   * just that section
+   */
+  sections?: Array<{ heading: string; uri: string }>;
+  /** Layer and file the resource came from */
+  provenance?: Provenance;
+}
+
+/**
+ * Split text into case-folded word tokens without markdown or stop words
+ * (see tokenize.ts)
+ *
+ * @param text - Text to tokenize
+ * @returns Tokens of two or more characters, or single characters in
+ *   unspaced scripts
+ */
+export function tokenize(text: string): string[] {
+  return normalizeText(text);
+}
+
+/**
+ * Escape a string for use in a regular expression
+ */
+function escapeRegExp(value: string): string {
+  return value.replace(/[.*+?^${}()|[\]\\]/g, "\\$&");
+}
+
+/**
+ * Build excerpts around matched terms with the terms marked as **term**
+ *
+ * @param text - Source text (body, description)
+ * @param terms - Lowercase terms to highlight
+ * @param maxSnippets - Maximum number of excerpts
+ * @param radius - Characters of context on each side of a match (default: 60)
+ * @returns Excerpts in document order; overlapping windows are merged
+ */
+export function buildSnippets(
+  text: string,
+  terms: string[],
+  maxSnippets: number,
+  radius: number = 60,
+): string[] {
+  if (terms.length === 0 || maxSnippets <= 0) {
+    return [];
+  }
+
+  // Unicode word boundaries; terms in unspaced scripts match anywhere
+  const pattern = new RegExp(
+    `(${terms
+      .map((term) =>
+        isUnspaced(term)
+          ? escapeRegExp(term)
+          : `(?<![\\p{L}\\p{N}_])${escapeRegExp(term)}(?![\\p{L}\\p{N}_])`,
+      )
+      .join("|")})`,
+    "giu",
+  );
+
+  const windows: Array<[number, number]> = [];
+  for (const match of text.matchAll(pattern)) {
+    const index = match.index ?? 0;
+    const start = Math.max(0, index - radius);
+    const end = Math.min(text.length, index + match[0].length + radius);
+    const last = windows[windows.length - 1];
+    if (last && start <= last[1]) {
+      last[1] = end;
+    } else if (windows.length < maxSnippets) {
+      windows.push([start, end]);
+    } else {
+      break;
+    }
+  }
+
+  return windows.map(([start, end]) => {
+    const excerpt = text
+      .slice(start, end)
+      .replace(/\s+/g, " ")
+      .trim()
+      .replace(pattern, "**$1**");
+    return `${start > 0 ? "... " : ""}${excerpt}${end < text.length ? " ..." : ""}`;
+  });
+}
+
+/**
+ * Single excerpt from the first text that mentions any of the terms
+ *
+ * Used to show why a resource matched without sending its full body.
+ *
+ * @param texts - Candidate texts in order of preference (body, description, ...)
+ * @param terms - Lowercase terms to highlight
+ * @param radius - Characters of context on each side of a match (default: 60)
+ * @returns Excerpt with matched terms marked, or undefined if nothing matched
+ */
+export function matchSnippet(
+  texts: Array<string | undefined>,
+  terms: string[],
+  radius: number = 60,
+): string | undefined {
+  for (const text of texts) {
+    if (text) {
+      const [snippet] = buildSnippets(text, terms, 1, radius);
+      if (snippet) {
+        return snippet;
+      }
+    }
+  }
+  return undefined;
+}
+
+/**
+ * Sections of a body whose own text (without subsections) mentions any of
+ * the terms
+ *
+ * @param fragment - Resource with its body
+ * @param terms - Lowercase terms
+ * @returns Headings with `?section=` URIs, in document order
+ */
+export function matchingSections(
+  fragment: ResourceFragment,
+  terms: string[],
+): Array<{ heading: string; uri: string }> {
+  return parseSections(fragment.content)
+    .filter((section) => {
+      const tokens = new Set(tokenize(section.ownContent));
+      return section.slug && terms.some((term) => tokens.has(term));
+    })
+    .map((section) => ({
+      heading: section.heading,
+      uri: `o8://${fragment.id}?section=${encodeURIComponent(section.slug)}`,
+    }));
+}
+
+/**
+ * Inverted index from body terms to fragments
+ */
+export class BodyIndex {
+  private fragments: ResourceFragment[] = [];
+  private postings: Map<string, Map<number, number>> = new Map();
+
+  /**
+   * (Re)build the index from fragments
+   *
+   * @param fragments - Resource fragments with content
+   */
+  build(fragments: ResourceFragment[]): void {
+    this.fragments = fragments;
+    this.postings = new Map();
+
+    fragments.forEach((fragment, docId) => {
+      for (const term of tokenize(fragment.content)) {
+        let docs = this.postings.get(term);
+        if (!docs) {
+          docs = new Map();
+          this.postings.set(term, docs);
+        }
+        docs.set(docId, (docs.get(docId) ?? 0) + 1);
+      }
+    });
+  }
+
+  /**
+   * Number of indexed fragments
+   */
+  get size(): number {
+    return this.fragments.length;
+  }
+
+  /**
+   * Find fragments whose bodies contain every query term
+   *
+   * @param query - Free-text query
+   * @param options - Category filter and limits
+   * @returns Hits ordered by score (ties by id)
+   */
+  search(query: string, options: BodySearchOptions = {}): BodySearchHit[] {
+    const terms = [...new Set(tokenize(query))];
+    if (terms.length === 0) {
+      return [];
+    }
+
+    const postings = terms.map((term) => this.postings.get(term));
+    if (postings.some((docs) => !docs)) {
+      return [];
+    }
+
+    // Intersect starting from the rarest term
+    const ordered = (postings as Map<number, number>[])
+      .map((docs, i) => ({ docs, term: terms[i] }))
+      .sort((a, b) => a.docs.size - b.docs.size);
+    let candidates = [...ordered[0].docs.keys()];
+    for (const { docs } of ordered.slice(1)) {
+      candidates = candidates.filter((docId) => docs.has(docId));
+    }
+
+    const total = this.fragments.length;
+    const hits = candidates
+      .filter(
+        (docId) =>
+          !options.category ||
+          this.fragments[docId].category === options.category,
+      )
+      .map((docId) => {
+        let score = 0;
+        let occurrences = 0;
+        for (const { docs } of ordered) {
+          const tf = docs.get(docId) ?? 0;
+          occurrences += tf;
+          score += (1 + Math.log(tf)) * Math.log(1 + total / docs.size);
+        }
+        return { docId, score, occurrences };
+      })
+      .sort(
+        (a, b) =>
+          b.score - a.score ||
+          compareStrings(this.fragments[a.docId].id, this.fragments[b.docId].id),
+      )
+      .slice(0, options.limit ?? 10);
+
+    return hits.map(({ docId, score, occurrences }) => {
+      const fragment = this.fragments[docId];
+      const sections = matchingSections(fragment, terms);
+      return {
+        id: fragment.id,
+        uri: `o8://${fragment.id}`,
+        category: fragment.category,
+        score: Math.round(score * 100) / 100,
+        occurrences,
+        snippets: buildSnippets(fragment.content, terms, options.snippets ?? 3),
+        ...(sections.length > 0 ? { sections } : {}),
+        ...withProvenance(fragment),
+      };
+    });
+  }
+}
@@ -0,0 +1,87 @@
+/**
+ * What is running (`--version --verbose`)
+ *
+ * Operators verifying a deployment need more than the version number: the
+ * Node.js runtime and the OpenSSL it was built with (HTTPS and client
+ * certificates depend on it), the platform, and which optional subsystems
+ * this configuration turns on.
+ *
+ * @example
+ * ```bash
+ * node dist/index.js --version            # 1.0.0
+ * node dist/index.js --version --verbose  # runtime, target, features
+ * ```
+ */
+
+import { flagEnabled, flagValue } from "./flags.js";
+
+/**
+ * Version, runtime, and enabled features
+ */
+export interface BuildInfo {
+  version: string;
+  /** Node.js version */
+  runtime: string;
+  /** Platform and CPU architecture (e.g. linux-x64) */
+  target: string;
+  openssl: string;
+  /** Optional subsystems and whether the environment enables them */
+  features: Record<string, boolean>;
+}
+
+/**
+ * Describe this process
+ *
+ * Settings are only checked for presence, so invalid values are reported
+ * as enabled; `orchestr8 doctor` validates them.
+ *
+ * @param version - Server version
+ * @param argv - Arguments (default: process.argv)
+ * @param env - Environment (default: process.env)
+ */
+export function buildInfo(
+  version: string,
+  argv: string[] = process.argv,
+  env: NodeJS.ProcessEnv = process.env,
+): BuildInfo {
+  const set = (name: string) => Boolean(env[name]);
+  return {
+    version,
+    runtime: `node ${process.version}`,
+    target: `${process.platform}-${process.arch}`,
+    openssl: process.versions.openssl ?? "none",
+    features: {
+      http: flagEnabled("http", argv, env),
+      tls: set("O8_TLS_CERT"),
+      clientCertificates: set("O8_TLS_CLIENT_CA"),
+      admin: set("O8_ADMIN_TOKEN") || set("O8_POLICY"),
+      polling: flagValue("watch-mode", argv, env) === "poll",
+      indexManifest: Boolean(flagValue("index-manifest", argv, env)),
+      layers: env.O8_LAYERS !== "none",
+      canary: Boolean(flagValue("canary-dir", argv, env)),
+      extensions: set("O8_EXTENSIONS"),
+      webhooks: set("O8_WEBHOOK_URL"),
+      queryLog: set("O8_QUERY_LOG") && env.O8_QUERY_LOG !== "off",
+    },
+  };
+}
+
+/**
+ * Text for --version, one `key: value` line per field with --verbose
+ */
+export function formatBuildInfo(info: BuildInfo, verbose: boolean): string {
+  if (!verbose) {
+    return `${info.version}\n`;
+  }
+  const features = Object.entries(info.features)
+    .map(([name, enabled]) => `${enabled ? "+" : "-"}${name}`)
+    .join(" ");
+  return [
+    `version: ${info.version}`,
+    `runtime: ${info.runtime}`,
+    `target: ${info.target}`,
+    `openssl: ${info.openssl}`,
+    `features: ${features}`,
+    "",
+  ].join("\n");
+}
@@ -0,0 +1,123 @@
+/**
+ * Canary catalog
+ *
+ * A large reorganization of the resources directory (renamed ids, merged
+ * agents, rewritten useWhen) changes what queries return in ways that are
+ * hard to predict. With `--canary-dir`, a staged copy of the catalog
+ * answers `--canary-percent` of fuzzy match queries. For each of those,
+ * the live catalog is matched too after the response has been sent, and
+ * when the two top-k rankings differ the disagreement is appended as one
+ * JSON line to O8_CANARY_LOG (default: <state dir>/canary.jsonl), with
+ * secrets in the query redacted.
+ *
+ * Queries are assigned by a hash of the query text, so a query is always
+ * answered by the same catalog and cached responses stay consistent.
+ *
+ * @example
+ * node dist/index.js --canary-dir ./resources-next --canary-percent 10
+ */
+
+import { createHash } from "crypto";
+import { appendFileSync, mkdirSync } from "fs";
+import { dirname, join, resolve } from "path";
+import { compareRankings, type RankDisagreement } from "./shadowRanking.js";
+import { defaultStateDir } from "../state/snapshotStore.js";
+import { flagValue } from "./flags.js";
+import { Logger } from "./logger.js";
+import { redactor } from "./redact.js";
+
+const logger = new Logger("Canary");
+
+/**
+ * Default share of queries answered by the canary catalog
+ */
+export const DEFAULT_CANARY_PERCENT = 10;
+
+export interface CanaryOptions {
+  /** Staged resources directory */
+  dir: string;
+  /** Share of queries it answers (0-100) */
+  percent: number;
+  /** Where disagreements are logged */
+  logPath: string;
+}
+
+/**
+ * Canary settings from --canary-dir and --canary-percent
+ * (ORCHESTR8_CANARY_DIR, ORCHESTR8_CANARY_PERCENT), or null without a
+ * canary directory
+ *
+ * @throws {Error} If the percentage is not a number from 0 to 100
+ */
+export function canaryOptions(
+  argv: string[] = process.argv,
+  env: NodeJS.ProcessEnv = process.env,
+): CanaryOptions | null {
+  const dir = flagValue("canary-dir", argv, env);
+  if (!dir) {
+    return null;
+  }
+  const percentValue = flagValue("canary-percent", argv, env);
+  const percent =
+    percentValue === undefined ? DEFAULT_CANARY_PERCENT : Number(percentValue);
+  if (!Number.isFinite(percent) || percent < 0 || percent > 100) {
+    throw new Error(
+      `Invalid --canary-percent: ${percentValue} (expected 0 to 100)`,
+    );
+  }
+  return {
+    dir: resolve(dir),
+    percent,
+    logPath: env.O8_CANARY_LOG || join(defaultStateDir(env), "canary.jsonl"),
+  };
+}
+
+/**
+ * Whether a query is answered by the canary catalog
+ *
+ * @param query - Query text
+ * @param percent - Share of queries for the canary (0-100)
+ */
+export function inCanary(query: string, percent: number): boolean {
+  const hash = createHash("sha256").update(query).digest();
+  // 0-9999, so fractional percentages are honored
+  return hash.readUInt32BE(0) % 10000 < percent * 100;
+}
+
+/**
+ * Log a query where the canary and live rankings differ
+ *
+ * Secrets in the query are redacted in the log. Logging failures are
+ * reported and otherwise ignored.
+ *
+ * @param logPath - JSONL file to append to
+ * @returns The disagreement (`primary` is live, `shadow` is canary), or
+ *   null if the rankings agree
+ */
+export function recordCanaryDiff(
+  logPath: string,
+  query: string,
+  live: string[],
+  canary: string[],
+  k: number,
+): RankDisagreement | null {
+  const disagreement = compareRankings(query, live, canary, k);
+  if (!disagreement) {
+    return null;
+  }
+
+  logger.info(
+    `Canary results differ for "${query}" (overlap ${disagreement.overlap.toFixed(2)}${disagreement.topChanged ? ", top result changed" : ""})`,
+  );
+  try {
+    mkdirSync(dirname(logPath), { recursive: true });
+    const entry = {
+      ...disagreement,
+      query: redactor().redactText(disagreement.query),
+    };
+    appendFileSync(logPath, JSON.stringify(entry) + "\n");
+  } catch (error) {
+    logger.warn(`Failed to write canary log ${logPath}:`, error);
+  }
+  return disagreement;
+}
@@ -0,0 +1,157 @@
+/**
+ * Structured agent capabilities
+ *
+ * Besides free-text `capabilities`, a resource can declare what it works on
+ * in frontmatter, so orchestrators can route by exact criteria instead of
+ * fuzzy text:
+ *
+ * ```yaml
+ * languages: [rust, ts]
+ * domains: [backend]
+ * operations: [refactor, review]
+ * ```
+ *
+ * Values are case-folded, and common language abbreviations are expanded
+ * (`ts` is `typescript`), so `languages=TS` matches `typescript`.
+ */
+
+import { foldCase } from "./tokenize.js";
+import { compareStrings } from "./deterministic.js";
+
+export const CAPABILITY_DIMENSIONS = [
+  "languages",
+  "domains",
+  "operations",
+] as const;
+
+export type CapabilityDimension = (typeof CAPABILITY_DIMENSIONS)[number];
+
+/**
+ * Declared capabilities of a resource
+ */
+export type CapabilityMatrix = Record<CapabilityDimension, string[]>;
+
+/**
+ * Required capabilities: a resource must have every listed value
+ */
+export type CapabilityFilter = Partial<Record<CapabilityDimension, string[]>>;
+
+/**
+ * Language abbreviations and their canonical names
+ */
+const LANGUAGE_ALIASES: Record<string, string> = {
+  ts: "typescript",
+  js: "javascript",
+  py: "python",
+  rs: "rust",
+  golang: "go",
+  rb: "ruby",
+  kt: "kotlin",
+  "c#": "csharp",
+  "c++": "cpp",
+};
+
+/**
+ * Canonical form of a capability value
+ */
+export function normalizeCapability(
+  dimension: CapabilityDimension,
+  value: string,
+): string {
+  const folded = foldCase(value.trim());
+  return dimension === "languages"
+    ? (LANGUAGE_ALIASES[folded] ?? folded)
+    : folded;
+}
+
+/**
+ * Canonical, de-duplicated values from a frontmatter or request value
+ * (a list or a comma-separated string)
+ */
+function normalizeValues(
+  dimension: CapabilityDimension,
+  value: unknown,
+): string[] {
+  const values = Array.isArray(value)
+    ? value.map(String)
+    : typeof value === "string"
+      ? value.split(",")
+      : [];
+  return [
+    ...new Set(
+      values
+        .map((item) => normalizeCapability(dimension, item))
+        .filter(Boolean),
+    ),
+  ];
+}
+
+/**
+ * Capability matrix declared in frontmatter, if any
+ */
+export function parseCapabilityMatrix(
+  frontmatter: Record<string, unknown>,
+): CapabilityMatrix | undefined {
+  if (!CAPABILITY_DIMENSIONS.some((dimension) => dimension in frontmatter)) {
+    return undefined;
+  }
+  return {
+    languages: normalizeValues("languages", frontmatter.languages),
+    domains: normalizeValues("domains", frontmatter.domains),
+    operations: normalizeValues("operations", frontmatter.operations),
+  };
+}
+
+/**
+ * Capability filter from request parameters (lists or comma-separated
+ * strings); undefined if no dimension is given
+ */
+export function parseCapabilityFilter(
+  params: Record<string, unknown> | undefined,
+): CapabilityFilter | undefined {
+  const filter: CapabilityFilter = {};
+  for (const dimension of CAPABILITY_DIMENSIONS) {
+    const values = normalizeValues(dimension, params?.[dimension]);
+    if (values.length > 0) {
+      filter[dimension] = values;
+    }
+  }
+  return Object.keys(filter).length > 0 ? filter : undefined;
+}
+
+/**
+ * Whether a matrix has every value the filter requires
+ *
+ * Resources without a matrix match only an empty filter.
+ */
+export function matchesCapabilities(
+  matrix: CapabilityMatrix | undefined,
+  filter: CapabilityFilter | undefined,
+): boolean {
+  return CAPABILITY_DIMENSIONS.every((dimension) =>
+    (filter?.[dimension] ?? []).every(
+      (value) => matrix?.[dimension].includes(value) ?? false,
+    ),
+  );
+}
+
+/**
+ * Number of resources per value, per dimension, in name order
+ */
+export function capabilityFacets(
+  matrices: CapabilityMatrix[],
+): Record<CapabilityDimension, Record<string, number>> {
+  const facets = {} as Record<CapabilityDimension, Record<string, number>>;
+  for (const dimension of CAPABILITY_DIMENSIONS) {
+    const counts = new Map<string, number>();
+    for (const matrix of matrices) {
+      for (const value of matrix[dimension]) {
+        counts.set(value, (counts.get(value) ?? 0) + 1);
+      }
+    }
+    facets[dimension] = Object.fromEntries(
+      [...counts].sort(([a], [b]) => compareStrings(a, b)),
+    );
+  }
+  return facets;
+}
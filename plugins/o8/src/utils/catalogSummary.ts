@@ -0,0 +1,121 @@
+/**
+ * Catalog statistics
+ *
+ * One call that answers "what is in the catalog and how healthy is it"
+ * for dashboards, instead of exporting every resource and aggregating
+ * client-side.
+ */
+
+import type { ResourceFragment } from "./fuzzyMatcher.js";
+import { compareStrings } from "./deterministic.js";
+
+/**
+ * Resource file that failed to load
+ */
+export interface ScanFailure {
+  path: string;
+  error: string;
+}
+
+/**
+ * Catalog summary
+ */
+export interface CatalogSummary {
+  total: number;
+  byCategory: Record<string, number>;
+  /** Counts by frontmatter model; resources without one count as "unspecified" */
+  byModel: Record<string, number>;
+  /** Most used tags, most frequent first */
+  topTags: Array<{ tag: string; count: number }>;
+  /** Distinct tags across the catalog */
+  distinctTags: number;
+  averageBodyChars: number;
+  averageTokens: number;
+  oldest: { id: string; modifiedAt: string } | null;
+  newest: { id: string; modifiedAt: string } | null;
+  validation: {
+    /** Files that failed to parse and are missing from the catalog */
+    failed: number;
+    missingTags: number;
+    missingCapabilities: number;
+    missingUseWhen: number;
+    failures: ScanFailure[];
+  };
+}
+
+/**
+ * Increment a counter in a record
+ */
+function increment(counts: Record<string, number>, key: string): void {
+  counts[key] = (counts[key] ?? 0) + 1;
+}
+
+/**
+ * Summarize a catalog
+ *
+ * @param fragments - Loaded resource fragments
+ * @param failures - Files that failed to load
+ * @param topTags - Number of tags to list (default: 50)
+ * @returns Counts, sizes, modification range, and validation problems
+ */
+export function summarizeCatalog(
+  fragments: ResourceFragment[],
+  failures: ScanFailure[] = [],
+  topTags: number = 50,
+): CatalogSummary {
+  const byCategory: Record<string, number> = {};
+  const byModel: Record<string, number> = {};
+  const tagCounts: Record<string, number> = {};
+  let bodyChars = 0;
+  let tokens = 0;
+  let oldest: CatalogSummary["oldest"] = null;
+  let newest: CatalogSummary["newest"] = null;
+  const validation = {
+    failed: failures.length,
+    missingTags: 0,
+    missingCapabilities: 0,
+    missingUseWhen: 0,
+    failures,
+  };
+
+  for (const fragment of fragments) {
+    increment(byCategory, fragment.category);
+    increment(byModel, fragment.model ?? "unspecified");
+    for (const tag of fragment.tags) {
+      increment(tagCounts, tag);
+    }
+    bodyChars += fragment.content.length;
+    tokens += fragment.estimatedTokens;
+
+    if (fragment.modifiedAt) {
+      const entry = { id: fragment.id, modifiedAt: fragment.modifiedAt };
+      if (!oldest || fragment.modifiedAt < oldest.modifiedAt) {
+        oldest = entry;
+      }
+      if (!newest || fragment.modifiedAt > newest.modifiedAt) {
+        newest = entry;
+      }
+    }
+
+    if (fragment.tags.length === 0) validation.missingTags++;
+    if (fragment.capabilities.length === 0) validation.missingCapabilities++;
+    if (fragment.useWhen.length === 0) validation.missingUseWhen++;
+  }
+
+  const total = fragments.length;
+  return {
+    total,
+    byCategory,
+    byModel,
+    topTags: Object.entries(tagCounts)
+      .sort(([a, x], [b, y]) => y - x || compareStrings(a, b))
+      .slice(0, topTags)
+      .map(([tag, count]) => ({ tag, count })),
+    distinctTags: Object.keys(tagCounts).length,
+    averageBodyChars: total ? Math.round(bodyChars / total) : 0,
+    averageTokens: total ? Math.round(tokens / total) : 0,
+    oldest,
+    newest,
+    validation,
+  };
+}
@@ -0,0 +1,119 @@
+/**
+ * Output formats for CLI subcommands
+ *
+ * Every subcommand accepts `--format text|json|github`. `text` is for
+ * people, `json` is one object on stdout for scripts, and `github` writes
+ * workflow commands so a CI run annotates the pull request.
+ *
+ * @example
+ * ```typescript
+ * githubAnnotation({ level: "error", file: "agents/a.md", message: "Invalid YAML" });
+ * // "::error file=agents/a.md::Invalid YAML"
+ * ```
+ */
+
+import { parseArgs } from "./flags.js";
+
+export const OUTPUT_FORMATS = ["text", "json", "github"] as const;
+
+export type OutputFormat = (typeof OUTPUT_FORMATS)[number];
+
+/**
+ * Something a subcommand wants to point out, optionally tied to a file
+ */
+export interface Finding {
+  level: "error" | "warning" | "notice";
+  message: string;
+  file?: string;
+  title?: string;
+}
+
+/**
+ * Result of a subcommand, rendered according to the output format
+ */
+export interface CommandReport {
+  /** Progress lines for text output */
+  lines: string[];
+  /** One-line summary, printed last in text and github output */
+  summary: string;
+  /** Findings to annotate in github output */
+  findings: Finding[];
+  /** Machine-readable result for json output */
+  data: Record<string, unknown>;
+}
+
+/**
+ * Remove `--format <value>` from the arguments
+ *
+ * @param args - Subcommand arguments
+ * @param env - Environment for the ORCHESTR8_FORMAT fallback (default:
+ *   process.env)
+ * @returns The format (default: text) and the remaining arguments
+ * @throws {Error} If the format is unknown
+ */
+export function takeFormat(
+  args: string[],
+  env: NodeJS.ProcessEnv = process.env,
+): {
+  format: OutputFormat;
+  rest: string[];
+} {
+  const { values, positional } = parseArgs(args, { values: ["format"] }, env);
+  const value = values.format ?? "text";
+  if (!(OUTPUT_FORMATS as readonly string[]).includes(value)) {
+    throw new Error(
+      `Invalid --format: ${value} (expected ${OUTPUT_FORMATS.join(", ")})`,
+    );
+  }
+  return { format: value as OutputFormat, rest: positional };
+}
+
+/**
+ * Format a finding as a GitHub Actions workflow command
+ */
+export function githubAnnotation(finding: Finding): string {
+  const properties = [
+    finding.file && `file=${escapeProperty(finding.file)}`,
+    finding.title && `title=${escapeProperty(finding.title)}`,
+  ].filter(Boolean);
+  return `::${finding.level}${properties.length > 0 ? ` ${properties.join(",")}` : ""}::${escapeData(finding.message)}`;
+}
+
+/**
+ * Render a report for stdout
+ *
+ * @param format - Output format
+ * @param report - Subcommand result
+ * @returns Text ending in a newline
+ */
+export function renderReport(format: OutputFormat, report: CommandReport): string {
+  switch (format) {
+    case "json":
+      return JSON.stringify(report.data, null, 2) + "\n";
+    case "github":
+      return [...report.findings.map(githubAnnotation), report.summary]
+        .map((line) => `${line}\n`)
+        .join("");
+    default:
+      return [...report.lines, report.summary].map((line) => `${line}\n`).join("");
+  }
+}
+
+/**
+ * Render a fatal error for stdout in json and github formats
+ */
+export function renderError(format: OutputFormat, error: unknown): string {
+  const message = error instanceof Error ? error.message : String(error);
+  const code = (error as { code?: unknown })?.code;
+  return format === "json"
+    ? JSON.stringify({ error: message, ...(code ? { code } : {}) }) + "\n"
+    : githubAnnotation({ level: "error", message }) + "\n";
+}
+
+function escapeData(value: string): string {
+  return value.replace(/%/g, "%25").replace(/\r/g, "%0D").replace(/\n/g, "%0A");
+}
+
+function escapeProperty(value: string): string {
+  return escapeData(value).replace(/:/g, "%3A").replace(/,/g, "%2C");
+}
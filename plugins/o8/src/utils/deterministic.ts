@@ -0,0 +1,45 @@
+/**
+ * Deterministic output helpers
+ *
+ * Directory listings and score ties are always ordered by name so results
+ * do not depend on filesystem order. Deterministic mode (`--deterministic`
+ * or ORCHESTR8_DETERMINISTIC=true) additionally zeroes clock-derived values
+ * such as uptime and memory usage, so integration tests and golden-file
+ * snapshots produce identical output across runs.
+ */
+
+import { flagEnabled } from "./flags.js";
+
+/**
+ * Check whether deterministic mode is enabled
+ *
+ * @param argv - Process arguments (default: process.argv)
+ * @param env - Environment (default: process.env)
+ */
+export function isDeterministic(
+  argv: string[] = process.argv,
+  env: NodeJS.ProcessEnv = process.env,
+): boolean {
+  return flagEnabled("deterministic", argv, env);
+}
+
+/**
+ * Compare strings by code point (locale-independent)
+ */
+export function compareStrings(a: string, b: string): number {
+  return a < b ? -1 : a > b ? 1 : 0;
+}
+
+/**
+ * Sort directory entries by name
+ *
+ * @param entries - Entries from fs.readdir (names or Dirents)
+ * @returns New array ordered by name
+ */
+export function sortByName<T extends string | { name: string }>(
+  entries: T[],
+): T[] {
+  const nameOf = (entry: T) =>
+    typeof entry === "string" ? entry : entry.name;
+  return [...entries].sort((a, b) => compareStrings(nameOf(a), nameOf(b)));
+}
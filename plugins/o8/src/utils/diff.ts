@@ -0,0 +1,135 @@
+/**
+ * Line-based unified diff
+ *
+ * Resource files are a few hundred lines at most, so a plain LCS table is
+ * fast enough and keeps this dependency-free.
+ */
+
+/**
+ * Diff options
+ */
+export interface DiffOptions {
+  /** Label for the old version (default: 'a') */
+  fromLabel?: string;
+  /** Label for the new version (default: 'b') */
+  toLabel?: string;
+  /** Unchanged lines around each change (default: 3) */
+  context?: number;
+}
+
+type Op = { kind: " " | "-" | "+"; line: string };
+
+/**
+ * Edit script from the longest common subsequence of lines
+ */
+function editScript(a: string[], b: string[]): Op[] {
+  const n = a.length;
+  const m = b.length;
+  // lcs[i][j] = LCS length of a[i..] and b[j..]
+  const lcs: Uint32Array[] = Array.from(
+    { length: n + 1 },
+    () => new Uint32Array(m + 1),
+  );
+  for (let i = n - 1; i >= 0; i--) {
+    for (let j = m - 1; j >= 0; j--) {
+      lcs[i][j] =
+        a[i] === b[j]
+          ? lcs[i + 1][j + 1] + 1
+          : Math.max(lcs[i + 1][j], lcs[i][j + 1]);
+    }
+  }
+
+  const ops: Op[] = [];
+  let i = 0;
+  let j = 0;
+  while (i < n || j < m) {
+    if (i < n && j < m && a[i] === b[j]) {
+      ops.push({ kind: " ", line: a[i] });
+      i++;
+      j++;
+    } else if (i < n && (j === m || lcs[i + 1][j] >= lcs[i][j + 1])) {
+      ops.push({ kind: "-", line: a[i] });
+      i++;
+    } else {
+      ops.push({ kind: "+", line: b[j] });
+      j++;
+    }
+  }
+  return ops;
+}
+
+/**
+ * Split text into lines, ignoring the final newline
+ */
+function splitLines(text: string): string[] {
+  if (text === "") {
+    return [];
+  }
+  return text.replace(/\n$/, "").split("\n");
+}
+
+/**
+ * Produce a unified diff
+ *
+ * @param oldText - Old version
+ * @param newText - New version
+ * @param options - Labels and context size
+ * @returns Unified diff, or an empty string if the texts are identical
+ */
+export function unifiedDiff(
+  oldText: string,
+  newText: string,
+  options: DiffOptions = {},
+): string {
+  const context = options.context ?? 3;
+  const ops = editScript(splitLines(oldText), splitLines(newText));
+  if (ops.every((op) => op.kind === " ")) {
+    return "";
+  }
+
+  const output = [
+    `--- ${options.fromLabel ?? "a"}`,
+    `+++ ${options.toLabel ?? "b"}`,
+  ];
+
+  // Line numbers (1-based) of each op in the old and new text
+  const oldLine: number[] = [];
+  const newLine: number[] = [];
+  let o = 1;
+  let n = 1;
+  for (const op of ops) {
+    oldLine.push(o);
+    newLine.push(n);
+    if (op.kind !== "+") o++;
+    if (op.kind !== "-") n++;
+  }
+
+  let index = 0;
+  while (index < ops.length) {
+    // Find the next change and grow the hunk while changes are close together
+    while (index < ops.length && ops[index].kind === " ") index++;
+    if (index === ops.length) break;
+
+    const start = Math.max(0, index - context);
+    let end = index;
+    let lastChange = index;
+    while (end < ops.length && end - lastChange <= context * 2) {
+      if (ops[end].kind !== " ") lastChange = end;
+      end++;
+    }
+    end = Math.min(ops.length, lastChange + context + 1);
+
+    const hunk = ops.slice(start, end);
+    const oldCount = hunk.filter((op) => op.kind !== "+").length;
+    const newCount = hunk.filter((op) => op.kind !== "-").length;
+    const oldStart = oldCount ? oldLine[start] : oldLine[start] - 1;
+    const newStart = newCount ? newLine[start] : newLine[start] - 1;
+    output.push(`@@ -${oldStart},${oldCount} +${newStart},${newCount} @@`);
+    for (const op of hunk) {
+      output.push(`${op.kind}${op.line}`);
+    }
+    index = end;
+  }
+
+  return output.join("\n") + "\n";
+}
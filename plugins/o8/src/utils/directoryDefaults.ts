@@ -0,0 +1,73 @@
+/**
+ * Per-directory frontmatter defaults (_defaults.yaml)
+ *
+ * Agent families kept in one directory tend to repeat the same model,
+ * tags, and policies in every file. A `_defaults.yaml` in any directory
+ * under a category supplies frontmatter fields for every resource beneath
+ * it. Deeper files override shallower ones, and a resource's own
+ * frontmatter overrides them all, field by field.
+ *
+ * @example
+ * ```yaml
+ * # resources/agents/infra/_defaults.yaml
+ * model: sonnet
+ * tags: [infrastructure, ops]
+ * ```
+ */
+
+import matter from "gray-matter";
+
+/**
+ * File name of directory defaults
+ */
+export const DEFAULTS_FILE = "_defaults.yaml";
+
+/**
+ * Fields that identify one resource and are never inherited
+ */
+export const NON_INHERITED_FIELDS: readonly string[] = ["aliases", "fallback"];
+
+export type FrontmatterDefaults = Record<string, unknown>;
+
+/**
+ * Parse a _defaults.yaml file
+ *
+ * @param content - YAML mapping
+ * @throws {Error} If the YAML is invalid or not a mapping
+ */
+export function parseDefaults(content: string): FrontmatterDefaults {
+  // gray-matter parses the same YAML as resource frontmatter
+  const data: unknown = matter(`---\n${content}\n---\n`).data;
+  if (data === null || typeof data !== "object" || Array.isArray(data)) {
+    throw new Error(
+      `${DEFAULTS_FILE} must be a mapping of frontmatter fields`,
+    );
+  }
+  const defaults = { ...(data as FrontmatterDefaults) };
+  for (const field of NON_INHERITED_FIELDS) {
+    delete defaults[field];
+  }
+  return defaults;
+}
+
+/**
+ * Defaults in effect for a file, from every _defaults.yaml above it
+ *
+ * @param filePath - Path relative to the layer root, with forward slashes
+ *   (e.g. 'agents/infra/k8s/operator.md')
+ * @param byDir - Parsed defaults by directory (e.g. 'agents/infra')
+ */
+export function inheritedDefaults(
+  filePath: string,
+  byDir: Map<string, FrontmatterDefaults>,
+): FrontmatterDefaults {
+  const dirs = filePath.split("/").slice(0, -1);
+  let defaults: FrontmatterDefaults = {};
+  for (let depth = 1; depth <= dirs.length; depth++) {
+    const own = byDir.get(dirs.slice(0, depth).join("/"));
+    if (own) {
+      defaults = { ...defaults, ...own };
+    }
+  }
+  return defaults;
+}
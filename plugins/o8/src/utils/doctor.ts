@@ -0,0 +1,275 @@
+/**
+ * Environment self-test (`orchestr8 doctor`)
+ *
+ * Checks what the server needs before it starts: readable resource and
+ * prompt directories, parseable config files and O8_ settings, a writable
+ * and unlocked state directory, a free HTTP port, a working file watcher,
+ * and a sane clock. Every problem comes with a remediation hint. Failures
+ * stop the server from working; warnings only degrade it.
+ */
+
+import { createServer } from "net";
+import { constants, promises as fs, watch } from "fs";
+import { homedir } from "os";
+import { join } from "path";
+import { safeValidateConfig } from "../config/schema.js";
+import { defaultStateDir } from "../state/snapshotStore.js";
+import { queryLogOptions } from "../state/queryLog.js";
+import { lockPath, lockTimeout, readLockHolder } from "../state/lock.js";
+import { shadowRankerFromEnv } from "./shadowRanking.js";
+
+/**
+ * Resource categories the loader scans
+ */
+const CATEGORY_DIRS = ["agents", "skills", "examples", "patterns", "guides", "workflows"];
+
+export type CheckStatus = "ok" | "warn" | "fail";
+
+/**
+ * Outcome of one check
+ */
+export interface DoctorCheck {
+  name: string;
+  status: CheckStatus;
+  message: string;
+  /** What to do about a warning or failure */
+  remedy?: string;
+}
+
+export interface DoctorOptions {
+  env?: NodeJS.ProcessEnv;
+  cwd?: string;
+  home?: string;
+  /** HTTP port to probe; null skips the check (stdio-only) */
+  port?: number | null;
+  now?: Date;
+}
+
+function ok(name: string, message: string): DoctorCheck {
+  return { name, status: "ok", message };
+}
+
+function problem(
+  status: "warn" | "fail",
+  name: string,
+  message: string,
+  remedy: string,
+): DoctorCheck {
+  return { name, status, message, remedy };
+}
+
+async function isReadableDir(path: string): Promise<boolean> {
+  try {
+    const stat = await fs.stat(path);
+    await fs.access(path, constants.R_OK | constants.X_OK);
+    return stat.isDirectory();
+  } catch {
+    return false;
+  }
+}
+
+async function checkResources(resourcesPath: string): Promise<DoctorCheck> {
+  const name = "resources";
+  if (!(await isReadableDir(resourcesPath))) {
+    return problem(
+      "fail",
+      name,
+      `Resources directory ${resourcesPath} is missing or not readable`,
+      "Set RESOURCES_PATH to a directory with agents/, skills/, ... or run with --auto",
+    );
+  }
+  const present: string[] = [];
+  for (const dir of CATEGORY_DIRS) {
+    if (await isReadableDir(join(resourcesPath, dir))) {
+      present.push(dir);
+    }
+  }
+  if (present.length === 0) {
+    return problem(
+      "warn",
+      name,
+      `${resourcesPath} has none of ${CATEGORY_DIRS.join(", ")}`,
+      "Add resource files under agents/ or skills/, or point RESOURCES_PATH elsewhere",
+    );
+  }
+  return ok(name, `${resourcesPath} (${present.join(", ")})`);
+}
+
+async function checkPrompts(promptsPath: string): Promise<DoctorCheck> {
+  if (await isReadableDir(promptsPath)) {
+    return ok("prompts", promptsPath);
+  }
+  return problem(
+    "warn",
+    "prompts",
+    `Prompts directory ${promptsPath} is missing or not readable; no prompts will be registered`,
+    "Set PROMPTS_PATH to the prompts directory",
+  );
+}
+
+async function checkConfigFiles(cwd: string, home: string): Promise<DoctorCheck[]> {
+  const candidates = [
+    join(cwd, ".orchestr8rc.json"),
+    join(cwd, "orchestr8.config.json"),
+    join(home, ".orchestr8", "config.json"),
+    join(home, "orchestr8.config.json"),
+  ];
+  const checks: DoctorCheck[] = [];
+  for (const path of candidates) {
+    let content: string;
+    try {
+      content = await fs.readFile(path, "utf-8");
+    } catch {
+      continue;
+    }
+    let parsed: unknown;
+    try {
+      parsed = JSON.parse(content);
+    } catch (error: any) {
+      checks.push(
+        problem(
+          "fail",
+          "config",
+          `${path} is not valid JSON: ${error.message}`,
+          `Fix the JSON syntax in ${path}`,
+        ),
+      );
+      continue;
+    }
+    const result = safeValidateConfig(parsed);
+    checks.push(
+      result.success
+        ? ok("config", path)
+        : problem(
+            "fail",
+            "config",
+            `${path} is invalid: ${result.errors?.map((e) => `${e.path}: ${e.message}`).join(", ")}`,
+            "See CONFIGURATION.md for the configuration schema",
+          ),
+    );
+  }
+  return checks.length > 0 ? checks : [ok("config", "No config files; using defaults")];
+}
+
+function checkSettings(env: NodeJS.ProcessEnv): DoctorCheck {
+  const errors: string[] = [];
+  for (const read of [
+    () => queryLogOptions(env),
+    () => shadowRankerFromEnv(env),
+    () => lockTimeout(env),
+  ]) {
+    try {
+      read();
+    } catch (error: any) {
+      errors.push(error.message);
+    }
+  }
+  return errors.length === 0
+    ? ok("settings", "O8_ environment settings parse")
+    : problem(
+        "fail",
+        "settings",
+        errors.join("; "),
+        "Fix or unset the environment variables named above",
+      );
+}
+
+async function checkStateDir(stateDir: string): Promise<DoctorCheck> {
+  const probe = join(stateDir, `.doctor-${process.pid}`);
+  try {
+    await fs.mkdir(stateDir, { recursive: true });
+    await fs.writeFile(probe, "");
+    await fs.rm(probe);
+  } catch (error: any) {
+    return problem(
+      "fail",
+      "state",
+      `State directory ${stateDir} is not writable: ${error.code ?? error.message}`,
+      "Set O8_STATE_DIR to a writable directory",
+    );
+  }
+
+  const holder = await readLockHolder(stateDir);
+  if (holder) {
+    return problem(
+      "warn",
+      "state",
+      `State is locked by ${holder.operation} in process ${holder.pid} on ${holder.host} since ${holder.acquiredAt}; writes wait for it`,
+      `Wait for the other orchestr8 instance, or remove ${lockPath(stateDir)} if that process is gone`,
+    );
+  }
+  return ok("state", `${stateDir} is writable`);
+}
+
+function checkPort(port: number): Promise<DoctorCheck> {
+  return new Promise((resolve) => {
+    const server = createServer();
+    server.once("error", (error: NodeJS.ErrnoException) => {
+      resolve(
+        problem(
+          "fail",
+          "port",
+          `HTTP port ${port} is not available (${error.code ?? error.message})`,
+          "Stop the other process, pass --http-port <port>, or run stdio-only",
+        ),
+      );
+    });
+    server.listen(port, () => {
+      server.close(() => resolve(ok("port", `HTTP port ${port} is free`)));
+    });
+  });
+}
+
+function checkWatcher(dir: string): DoctorCheck {
+  try {
+    watch(dir).close();
+    return ok("watcher", "File watching is available");
+  } catch (error: any) {
+    return problem(
+      "warn",
+      "watcher",
+      `File watching failed (${error.code ?? error.message}); changes need a restart`,
+      "Raise the inotify watch limit (fs.inotify.max_user_watches) or set NODE_ENV=production to disable watching",
+    );
+  }
+}
+
+function checkClock(now: Date): DoctorCheck {
+  const year = now.getUTCFullYear();
+  if (year < 2024 || year > 2100) {
+    return problem(
+      "warn",
+      "clock",
+      `System clock reads ${now.toISOString()}`,
+      "Sync the system clock (NTP); snapshots, logs, and cache expiry use it",
+    );
+  }
+  return ok("clock", now.toISOString());
+}
+
+/**
+ * Run every check
+ *
+ * @returns Checks in a fixed order
+ */
+export async function runDoctor(options: DoctorOptions = {}): Promise<DoctorCheck[]> {
+  const env = options.env ?? process.env;
+  const cwd = options.cwd ?? process.cwd();
+  const home = options.home ?? homedir();
+  const resourcesPath = env.RESOURCES_PATH || join(cwd, "resources");
+  const promptsPath = env.PROMPTS_PATH || join(cwd, "prompts");
+
+  const checks: DoctorCheck[] = [
+    await checkResources(resourcesPath),
+    await checkPrompts(promptsPath),
+    ...(await checkConfigFiles(cwd, home)),
+    checkSettings(env),
+    await checkStateDir(defaultStateDir(env)),
+  ];
+  if (options.port !== null) {
+    checks.push(await checkPort(options.port ?? 1337));
+  }
+  checks.push(checkWatcher((await isReadableDir(resourcesPath)) ? resourcesPath : cwd));
+  checks.push(checkClock(options.now ?? new Date()));
+  return checks;
+}
@@ -0,0 +1,56 @@
+/**
+ * Sparse fieldsets for JSON responses
+ *
+ * Orchestrators that only need identifiers can ask for `fields=uri,score`
+ * instead of receiving tags, capabilities, and snippets for every result.
+ * Unknown field names are ignored so the same selection works across
+ * endpoints with slightly different result shapes.
+ *
+ * @example
+ * ```typescript
+ * selectFields([{ id: "a", tags: ["x"], tokens: 10 }], parseFields("id,tokens"));
+ * // [{ id: "a", tokens: 10 }]
+ * ```
+ */
+
+/**
+ * Parse a comma-separated field list
+ *
+ * @param spec - Value of the `fields` parameter
+ * @returns Field names, or undefined to keep every field
+ */
+export function parseFields(spec: string | null | undefined): string[] | undefined {
+  if (!spec) {
+    return undefined;
+  }
+  const fields = spec
+    .split(",")
+    .map((field) => field.trim())
+    .filter(Boolean);
+  return fields.length > 0 ? fields : undefined;
+}
+
+/**
+ * Keep only the requested top-level fields of each item
+ *
+ * @param items - Result objects
+ * @param fields - Field names from parseFields (undefined keeps everything)
+ * @returns Items restricted to the requested fields, in request order
+ */
+export function selectFields<T extends object>(
+  items: T[],
+  fields: string[] | undefined,
+): Array<Partial<T>> {
+  if (!fields) {
+    return items;
+  }
+  return items.map((item) => {
+    const picked: Record<string, unknown> = {};
+    for (const field of fields) {
+      if (Object.prototype.hasOwnProperty.call(item, field)) {
+        picked[field] = (item as Record<string, unknown>)[field];
+      }
+    }
+    return picked as Partial<T>;
+  });
+}
@@ -0,0 +1,125 @@
+/**
+ * Command-line flags with environment variable equivalents
+ *
+ * Every flag `--some-flag` can also be set as `ORCHESTR8_SOME_FLAG`, like
+ * the ORCHESTR8_PROVIDER_* and ORCHESTR8_GITHUB_* settings, so the server
+ * can be configured in a container without wrapping the entrypoint in a
+ * shell script. The shorter `O8_SOME_FLAG` is accepted too; when both are
+ * set, `ORCHESTR8_` wins.
+ *
+ * For every flag, precedence is command line > environment > config file
+ * > default: `--some-flag` wins over ORCHESTR8_SOME_FLAG, and a setting that
+ * can also come from a config file (see ConfigLoader) only reads the file
+ * when neither is given.
+ *
+ * @example
+ * ```typescript
+ * envName("stay-alive");                          // "ORCHESTR8_STAY_ALIVE"
+ * flagValue("record", ["--record", "s.jsonl"]);   // "s.jsonl"
+ * flagEnabled("deterministic", [], { O8_DETERMINISTIC: "1" }); // true
+ * ```
+ */
+
+const TRUE_VALUES = new Set(["true", "1", "yes", "on"]);
+
+/**
+ * Environment variable for a flag name
+ */
+export function envName(flag: string): string {
+  return `ORCHESTR8_${flag.toUpperCase().replace(/-/g, "_")}`;
+}
+
+/**
+ * Value of ORCHESTR8_FLAG, else of the short O8_FLAG
+ */
+function envValue(flag: string, env: NodeJS.ProcessEnv): string | undefined {
+  const name = envName(flag);
+  return env[name] || env[name.replace(/^ORCHESTR8_/, "O8_")] || undefined;
+}
+
+/**
+ * Value of `--flag <value>` or `--flag=<value>`, else of ORCHESTR8_FLAG
+ * (or O8_FLAG)
+ *
+ * @param flag - Flag name without dashes
+ * @param argv - Arguments (default: process.argv)
+ * @param env - Environment (default: process.env)
+ */
+export function flagValue(
+  flag: string,
+  argv: string[] = process.argv,
+  env: NodeJS.ProcessEnv = process.env,
+): string | undefined {
+  for (let i = 0; i < argv.length; i++) {
+    if (argv[i] === `--${flag}` && i + 1 < argv.length) {
+      return argv[i + 1];
+    }
+    if (argv[i].startsWith(`--${flag}=`)) {
+      return argv[i].slice(flag.length + 3);
+    }
+  }
+  return envValue(flag, env);
+}
+
+/**
+ * Whether a boolean flag is on: `--flag` or `--no-flag`, else
+ * ORCHESTR8_FLAG or O8_FLAG (true, 1, yes, on)
+ *
+ * @param flag - Flag name without dashes
+ * @param argv - Arguments (default: process.argv)
+ * @param env - Environment (default: process.env)
+ */
+export function flagEnabled(
+  flag: string,
+  argv: string[] = process.argv,
+  env: NodeJS.ProcessEnv = process.env,
+): boolean {
+  if (argv.includes(`--${flag}`)) {
+    return true;
+  }
+  if (argv.includes(`--no-${flag}`)) {
+    return false;
+  }
+  return TRUE_VALUES.has((envValue(flag, env) ?? "").toLowerCase());
+}
+
+/**
+ * Parse subcommand arguments into flag values, switches, and positionals
+ *
+ * @param args - Arguments after the subcommand name
+ * @param spec - Flags that take a value and flags that are switches
+ * @param env - Environment for fallbacks (default: process.env)
+ */
+export function parseArgs(
+  args: string[],
+  spec: { values?: string[]; switches?: string[] },
+  env: NodeJS.ProcessEnv = process.env,
+): {
+  values: Record<string, string | undefined>;
+  switches: Record<string, boolean>;
+  positional: string[];
+} {
+  const valueFlags = new Set((spec.values ?? []).map((flag) => `--${flag}`));
+  const switchFlags = new Set(
+    (spec.switches ?? []).flatMap((flag) => [`--${flag}`, `--no-${flag}`]),
+  );
+  const positional: string[] = [];
+  for (let i = 0; i < args.length; i++) {
+    const name = args[i].split("=")[0];
+    if (valueFlags.has(name)) {
+      if (!args[i].includes("=")) i++;
+    } else if (!switchFlags.has(args[i])) {
+      positional.push(args[i]);
+    }
+  }
+
+  return {
+    values: Object.fromEntries(
+      (spec.values ?? []).map((flag) => [flag, flagValue(flag, args, env)]),
+    ),
+    switches: Object.fromEntries(
+      (spec.switches ?? []).map((flag) => [flag, flagEnabled(flag, args, env)]),
+    ),
+    positional,
+  };
+}
@@ -7,6 +7,19 @@ import { Logger } from "./logger.js";
 import { promises as fs } from "fs";
 import { join } from "path";
 import matter from "gray-matter";
+import { compareStrings, sortByName } from "./deterministic.js";
+import { matchSnippet } from "./bodyIndex.js";
+import { selectFields } from "./fields.js";
+import { withProvenance } from "./provenance.js";
+import { foldCase, normalizeText } from "./tokenize.js";
+import { isDirectoryEntry } from "./paths.js";
+import type { ShadowRanker } from "./shadowRanking.js";
+import {
+  matchesCapabilities,
+  type CapabilityFilter,
+  type CapabilityMatrix,
+} from "./capabilityMatrix.js";
+import type { HandoffRule } from "./handoffs.js";
 
 const logger = new Logger("FuzzyMatcher");
 
@@ -28,6 +41,38 @@ export interface ResourceFragment {
   estimatedTokens: number;
   /** The actual content of the resource */
   content: string;
+  /** Model requested in frontmatter (agents), if any */
+  model?: string;
+  /** Source file modification time (ISO 8601), for local resources */
+  modifiedAt?: string;
+  /** Layer and file the resource was loaded from, for local resources */
+  origin?: ResourceOrigin;
+  /** Lowest score at which this resource is returned (frontmatter minConfidence) */
+  minConfidence?: number;
+  /** Returned for its category when nothing matches confidently (frontmatter fallback) */
+  fallback?: boolean;
+  /** Former ids that still resolve to this resource (frontmatter aliases) */
+  aliases?: string[];
+  /** Declared languages, domains, and operations (frontmatter) */
+  capabilityMatrix?: CapabilityMatrix;
+  /** Agents this one hands off to (frontmatter handoff_to) */
+  handoffs?: HandoffRule[];
+}
+
+/**
+ * Where a local resource came from in the layered resource directories
+ */
+export interface ResourceOrigin {
+  /** Layer name (builtin, system, user, project, ...) */
+  layer: string;
+  /** Layer directory */
+  sourceDir: string;
+  /** Resource file */
+  filePath: string;
+  /** SHA-256 of the file content (hex) */
+  hash: string;
+  /** Lower layers with a file for the same id, lowest first */
+  overrides: Array<{ layer: string; sourceDir: string }>;
 }
 
 /**
@@ -44,12 +89,18 @@ export interface MatchRequest {
   maxTokens?: number;
   /** Tags that must be present in matched resources */
   requiredTags?: string[];
+  /** Declared capabilities that must be present in matched resources */
+  requiredCapabilities?: CapabilityFilter;
   /** Response mode: 'full' returns content, 'catalog' returns lightweight index, 'index' uses useWhen index, 'minimal' returns ultra-compact JSON */
   mode?: 'full' | 'catalog' | 'index' | 'minimal';
   /** Maximum number of results to return in catalog mode */
   maxResults?: number;
   /** Minimum relevance score threshold (0-100) */
   minScore?: number;
+  /** Stop scoring after this many milliseconds and return the best results so far */
+  timeoutMs?: number;
+  /** Result fields to include in minimal mode (default: all) */
+  fields?: string[];
 }
 
 /**
@@ -64,6 +115,66 @@ export interface MatchResult {
   matchScores: number[];
   /** Assembled content ready for use */
   assembledContent: string;
+  /** Matched region of each fragment with query terms marked as **term** */
+  snippets: Array<string | undefined>;
+  /** True when timeoutMs expired before every resource was scored */
+  partial?: boolean;
+  /** True when no resource reached minScore and its own minConfidence */
+  noConfidentMatch?: boolean;
+  /** True when the fragments are designated fallbacks, not matches */
+  fallback?: boolean;
+  /** Closest tags and agent names when nothing matched confidently */
+  didYouMean?: string[];
+}
+
+/**
+ * Points awarded by calculateScore
+ */
+export interface ScoringWeights {
+  /** Tag equal to a keyword */
+  tagExact: number;
+  /** Tag containing a keyword */
+  tagSubstring: number;
+  /** Capability equal to a keyword */
+  capabilityExact: number;
+  /** Capability containing a keyword */
+  capabilitySubstring: number;
+  /** useWhen scenario containing a keyword */
+  useWhen: number;
+  /** Field containing two consecutive keywords */
+  phrase: number;
+  /** Best fuzzy (typo-tolerant) match, scaled by similarity */
+  fuzzy: number;
+  /** Resource in the requested category */
+  category: number;
+  /** Resource under 1000 tokens */
+  small: number;
+}
+
+export const DEFAULT_WEIGHTS: Readonly<ScoringWeights> = {
+  tagExact: 15,
+  tagSubstring: 10,
+  capabilityExact: 12,
+  capabilitySubstring: 8,
+  useWhen: 5,
+  phrase: 20,
+  fuzzy: 8,
+  category: 15,
+  small: 5,
+};
+
+/**
+ * Conditions reported alongside the assembled results
+ */
+export interface AssemblyFlags {
+  /** Scoring stopped early on timeout */
+  partial?: boolean;
+  /** No resource was confident enough to return */
+  noConfidentMatch?: boolean;
+  /** The results are designated fallbacks */
+  fallback?: boolean;
+  /** Suggested tags and agent names for a query with no confident match */
+  didYouMean?: string[];
 }
 
 /**
@@ -72,6 +183,8 @@ export interface MatchResult {
 interface ScoredResource {
   resource: ResourceFragment;
   score: number;
+  /** Matched region with query terms marked, set for selected resources */
+  snippet?: string;
 }
 
 /**
@@ -85,6 +198,11 @@ export class FuzzyMatcher {
   private indexLoaded: boolean = false;
   private indexLoadPromise: Promise<ResourceFragment[]> | null = null;
   private resourcesPath: string;
+  private fieldCache = new WeakMap<
+    ResourceFragment,
+    { capabilities: string[]; useWhen: string[] }
+  >();
+  private shadowRanker: ShadowRanker | null = null;
 
   constructor() {
     this.resourcesPath =
@@ -118,26 +236,62 @@ export class FuzzyMatcher {
     const keywords = this.extractKeywords(request.query);
     logger.debug("Extracted keywords", { keywords });
 
-    // 3. Score each resource
-    const scored = allResources.map((resource) => ({
-      resource,
-      score: this.calculateScore(resource, keywords, request),
-    }));
+    // 3. Score each resource, stopping early if the deadline passes
+    const deadline =
+      request.timeoutMs !== undefined ? Date.now() + request.timeoutMs : undefined;
+    const scored: ScoredResource[] = [];
+    let partial = false;
+    for (const resource of allResources) {
+      scored.push({
+        resource,
+        score: this.calculateScore(resource, keywords, request),
+      });
+      if (
+        deadline !== undefined &&
+        scored.length < allResources.length &&
+        Date.now() >= deadline
+      ) {
+        partial = true;
+        logger.warn(
+          `Match timed out after ${request.timeoutMs}ms, scored ${scored.length}/${allResources.length} resources`,
+        );
+        break;
+      }
+    }
 
-    // Filter by minimum score threshold
+    // Filter by minimum score threshold and each resource's own minConfidence
     const minScore = request.minScore ?? 10;
-    const validScored = scored.filter((s) => s.score >= minScore);
+    const validScored = scored.filter((s) => this.isConfident(s, minScore));
+    const noConfidentMatch = validScored.length === 0;
     logger.debug(`Filtered to ${validScored.length} matches above threshold (${minScore})`);
 
-    // 4. Sort by relevance (highest score first)
-    validScored.sort((a, b) => b.score - a.score);
+    // 4. Sort by relevance (highest score first, ties by id)
+    validScored.sort(
+      (a, b) =>
+        b.score - a.score || compareStrings(a.resource.id, b.resource.id),
+    );
 
     // 5. Select top resources (catalog mode: by maxResults, full mode: by token budget)
+    // With no confident match, fall back to the designated fallback resources
     const mode = request.mode || 'catalog';
-    const selected = mode === 'catalog'
+    const fallbacks = noConfidentMatch
+      ? this.selectFallbacks(allResources, scored, request)
+      : [];
+    const selected = fallbacks.length > 0
+      ? fallbacks
+      : mode === 'catalog'
       ? validScored.slice(0, request.maxResults || 15)
       : this.selectWithinBudget(validScored, request.maxTokens || 3000);
 
+    if (this.shadowRanker) {
+      this.runShadowRanking(this.shadowRanker, request, keywords, scored, validScored);
+    }
+
+    // Explain each selection with the region of the body that matched
+    for (const item of selected) {
+      item.snippet = this.buildSnippet(item.resource, keywords);
+    }
+
     logger.info(`Selected ${selected.length} resources (mode: ${mode})`, {
       totalTokens: selected.reduce(
         (sum, s) => sum + s.resource.estimatedTokens,
@@ -146,26 +300,222 @@ export class FuzzyMatcher {
     });
 
     // 6. Assemble content (minimal, catalog, or full)
+    const didYouMean = noConfidentMatch
+      ? this.suggestTerms(keywords, allResources)
+      : [];
+    const flags: AssemblyFlags = {
+      ...(partial ? { partial } : {}),
+      ...(noConfidentMatch ? { noConfidentMatch } : {}),
+      ...(fallbacks.length > 0 ? { fallback: true } : {}),
+      ...(didYouMean.length > 0 ? { didYouMean } : {}),
+    };
     const assembled =
-      mode === 'minimal' ? this.assembleMinimal(selected) :
-      mode === 'catalog' ? this.assembleCatalog(selected) :
-      this.assembleContent(selected);
+      mode === 'minimal' ? this.assembleMinimal(selected, flags, request.fields) :
+      mode === 'catalog' ? this.assembleCatalog(selected, flags) :
+      this.assembleContent(selected, flags);
 
     return {
       fragments: selected.map((s) => s.resource),
       totalTokens: assembled.tokens,
       matchScores: selected.map((s) => s.score),
       assembledContent: assembled.content,
+      snippets: selected.map((s) => s.snippet),
+      ...flags,
     };
   }
 
+  /**
+   * Fallback resources for the requested categories (agents when there is
+   * no category filter), one per category, first by id if several are marked
+   */
+  private selectFallbacks(
+    allResources: ResourceFragment[],
+    scored: ScoredResource[],
+    request: MatchRequest,
+  ): ScoredResource[] {
+    const categories = request.category
+      ? [request.category]
+      : request.categories?.length
+      ? request.categories
+      : ["agent"];
+    const scores = new Map(scored.map((s) => [s.resource, s.score]));
+
+    const byCategory = new Map<string, ResourceFragment>();
+    const candidates = allResources
+      .filter((r) => r.fallback && categories.includes(r.category))
+      .sort((a, b) => compareStrings(a.id, b.id));
+    for (const resource of candidates) {
+      if (!byCategory.has(resource.category)) {
+        byCategory.set(resource.category, resource);
+      }
+    }
+
+    return [...byCategory.values()].map((resource) => ({
+      resource,
+      score: scores.get(resource) ?? 0,
+    }));
+  }
+
+  /**
+   * Tags and agent names close to the words of a query
+   *
+   * @param query - Query that found nothing
+   * @param limit - Maximum suggestions (default: 5)
+   * @returns Suggestions, closest first
+   */
+  async suggest(query: string, limit: number = 5): Promise<string[]> {
+    const allResources = await this.loadResourceIndex();
+    return this.suggestTerms(this.extractKeywords(query), allResources, limit);
+  }
+
+  /**
+   * Tags and agent names within edit distance of a keyword (or of the
+   * whole query as a hyphenated name), closest first
+   */
+  private suggestTerms(
+    keywords: string[],
+    resources: ResourceFragment[],
+    limit: number = 5,
+  ): string[] {
+    const candidates = new Set<string>();
+    for (const resource of resources) {
+      resource.tags.forEach((tag) => candidates.add(tag));
+      if (resource.category === "agent") {
+        candidates.add(foldCase(resource.id.split("/").pop() ?? resource.id));
+      }
+    }
+
+    const terms = keywords.length > 1 ? [...keywords, keywords.join("-")] : keywords;
+    const best = new Map<string, number>();
+    for (const candidate of candidates) {
+      for (const term of terms) {
+        if (term === candidate || term.length < 3) continue;
+        const similarity = this.calculateLevenshteinSimilarity(term, candidate);
+        if (similarity >= 0.6 && similarity > (best.get(candidate) ?? 0)) {
+          best.set(candidate, similarity);
+        }
+      }
+    }
+
+    return [...best.entries()]
+      .sort((a, b) => b[1] - a[1] || compareStrings(a[0], b[0]))
+      .slice(0, limit)
+      .map(([candidate]) => candidate);
+  }
+
+  /**
+   * Score matches with experimental weights alongside the primary ones
+   *
+   * Disagreements are logged by the ranker; responses are unaffected.
+   *
+   * @param ranker - Experimental weights and log, or null to disable
+   */
+  setShadowRanker(ranker: ShadowRanker | null): void {
+    this.shadowRanker = ranker;
+  }
+
+  /**
+   * Re-rank the scored resources with the shadow weights once the response
+   * has been returned, and record any top-k disagreement
+   */
+  private runShadowRanking(
+    ranker: ShadowRanker,
+    request: MatchRequest,
+    keywords: string[],
+    scored: ScoredResource[],
+    validScored: ScoredResource[],
+  ): void {
+    const minScore = request.minScore ?? 10;
+    const k = request.maxResults || 15;
+    const primary = validScored.map((s) => s.resource.id);
+    setImmediate(() => {
+      try {
+        const shadow = scored
+          .map(({ resource }) => ({
+            resource,
+            score: this.calculateScore(resource, keywords, request, ranker.weights),
+          }))
+          .filter((s) => this.isConfident(s, minScore))
+          .sort(
+            (a, b) =>
+              b.score - a.score || compareStrings(a.resource.id, b.resource.id),
+          )
+          .map((s) => s.resource.id);
+        ranker.record(request.query, primary, shadow, k);
+      } catch (error) {
+        logger.warn("Shadow ranking failed:", error);
+      }
+    });
+  }
+
+  /**
+   * Whether a score clears both the request's minScore and the resource's
+   * own minConfidence
+   */
+  private isConfident(scored: ScoredResource, minScore: number): boolean {
+    return (
+      scored.score >= minScore &&
+      scored.score >= (scored.resource.minConfidence ?? 0)
+    );
+  }
+
+  /**
+   * Score resources one at a time without collecting or sorting them
+   *
+   * Yields every resource at or above minScore (and its own minConfidence)
+   * in index order, so callers
+   * can stream very large result sets instead of materializing the array.
+   *
+   * @param request - The match request with query and filters
+   * @returns Async iterator of scored resources
+   */
+  async *scoreEach(
+    request: MatchRequest,
+  ): AsyncGenerator<{ resource: ResourceFragment; score: number }> {
+    const allResources = await this.loadResourceIndex();
+    const keywords = this.extractKeywords(request.query);
+    const minScore = request.minScore ?? 10;
+
+    for (const resource of allResources) {
+      const score = this.calculateScore(resource, keywords, request);
+      if (this.isConfident({ resource, score }, minScore)) {
+        yield { resource, score };
+      }
+    }
+  }
+
+  /**
+   * Build a snippet showing where a resource matched the query
+   *
+   * Looks in the body first, then falls back to useWhen and capabilities
+   * for resources that only matched on metadata.
+   *
+   * @param resource - Matched resource
+   * @param keywords - Keywords extracted from the query
+   * @returns Excerpt with keywords marked as **keyword**, or undefined
+   */
+  buildSnippet(
+    resource: ResourceFragment,
+    keywords: string[],
+  ): string | undefined {
+    return matchSnippet(
+      [
+        resource.content,
+        (resource.useWhen || []).join("; "),
+        (resource.capabilities || []).join("; "),
+      ],
+      keywords,
+    );
+  }
+
   /**
    * Extract keywords from user query
    *
    * Normalizes query text and extracts meaningful keywords by:
-   * - Converting to lowercase
-   * - Removing common stop words
+   * - Stripping markdown
+   * - Case folding
    * - Splitting on whitespace and punctuation
+   * - Removing stop words (O8_STOP_WORDS)
    * - Filtering out short words
    *
    * @param query - User's query string
@@ -178,64 +528,9 @@ export class FuzzyMatcher {
    * ```
    */
   extractKeywords(query: string): string[] {
-    // Normalize: lowercase and remove special characters
-    const normalized = query.toLowerCase().replace(/[^\w\s-]/g, " ");
-
-    // Common stop words to filter out
-    const stopWords = new Set([
-      "a",
-      "an",
-      "the",
-      "and",
-      "or",
-      "but",
-      "in",
-      "on",
-      "at",
-      "to",
-      "for",
-      "of",
-      "with",
-      "by",
-      "from",
-      "as",
-      "is",
-      "was",
-      "are",
-      "were",
-      "be",
-      "been",
-      "being",
-      "have",
-      "has",
-      "had",
-      "do",
-      "does",
-      "did",
-      "will",
-      "would",
-      "should",
-      "could",
-      "may",
-      "might",
-      "can",
-      "i",
-      "you",
-      "he",
-      "she",
-      "it",
-      "we",
-      "they",
-      "this",
-      "that",
-      "these",
-      "those",
-    ]);
-
-    // Split and filter
-    const words = normalized
-      .split(/\s+/)
-      .filter((word) => word.length > 1 && !stopWords.has(word));
+    // Strip markdown, tokenize (case-folded, punctuation removed), and
+    // drop stop words - the same normalization applied to resource fields
+    const words = normalizeText(query);
 
     // Return unique keywords
     return Array.from(new Set(words));
@@ -264,6 +559,7 @@ export class FuzzyMatcher {
    * @param resource - Resource fragment to score
    * @param keywords - Extracted keywords from query
    * @param request - Original match request
+   * @param weights - Points per kind of match (default: DEFAULT_WEIGHTS)
    * @returns Relevance score (0 = no match, higher = more relevant)
    *
    * @example
@@ -276,6 +572,7 @@ export class FuzzyMatcher {
     resource: ResourceFragment,
     keywords: string[],
     request: MatchRequest,
+    weights: Readonly<ScoringWeights> = DEFAULT_WEIGHTS,
   ): number {
     // Required tags check (must have all or disqualified) - early exit
     if (request.requiredTags && request.requiredTags.length > 0) {
@@ -287,6 +584,16 @@ export class FuzzyMatcher {
         return 0; // Disqualified
       }
     }
+    if (
+      request.requiredCapabilities &&
+      !matchesCapabilities(
+        resource.capabilityMatrix,
+        request.requiredCapabilities,
+      )
+    ) {
+      logger.debug(`Resource ${resource.id} missing required capabilities`);
+      return 0; // Disqualified
+    }
 
     let score = 0;
 
@@ -297,15 +604,15 @@ export class FuzzyMatcher {
       (request.categories && request.categories.includes(resource.category));
 
     if (matchesCategory) {
-      score += 15;
+      score += weights.category;
       logger.debug(`Category match for ${resource.id}`);
     }
 
     // Pre-convert resource fields to lowercase once for all keyword checks
     // This reduces repeated toLowerCase() calls from O(n*m) to O(n+m)
-    const tagsLower = resource.tags; // Already lowercase from parsing
-    const capabilitiesLower = resource.capabilities.map((c) => c.toLowerCase());
-    const useWhenLower = resource.useWhen.map((u) => u.toLowerCase());
+    const tagsLower = resource.tags; // Already case-folded from parsing
+    const { capabilities: capabilitiesLower, useWhen: useWhenLower } =
+      this.normalizedFields(resource);
 
     // Track which keywords found exact matches (for fuzzy fallback)
     const keywordsWithoutExactMatch = new Set(keywords);
@@ -320,12 +627,12 @@ export class FuzzyMatcher {
       // EXACT Tag matches (+15 each) - higher weight for exact matches
       for (const tag of tagsLower) {
         if (tag === keyword) {
-          score += 15;
+          score += weights.tagExact;
           foundExact = true;
           logger.debug(`EXACT tag match for "${keyword}" in ${resource.id}`);
           break;
         } else if (tag.includes(keyword)) {
-          score += 10;
+          score += weights.tagSubstring;
           foundExact = true;
           logger.debug(`Substring tag match for "${keyword}" in ${resource.id}`);
           break;
@@ -335,12 +642,12 @@ export class FuzzyMatcher {
       // EXACT Capability matches (+12 each)
       for (const cap of capabilitiesLower) {
         if (cap === keyword) {
-          score += 12;
+          score += weights.capabilityExact;
           foundExact = true;
           logger.debug(`EXACT capability match for "${keyword}" in ${resource.id}`);
           break;
         } else if (cap.includes(keyword)) {
-          score += 8;
+          score += weights.capabilitySubstring;
           foundExact = true;
           logger.debug(`Substring capability match for "${keyword}" in ${resource.id}`);
           break;
@@ -350,7 +657,7 @@ export class FuzzyMatcher {
       // EXACT Use-when matches (+8 each)
       for (const useCase of useWhenLower) {
         if (useCase.includes(keyword)) {
-          score += 5;
+          score += weights.useWhen;
           foundExact = true;
           logger.debug(`Use-when match for "${keyword}" in ${resource.id}`);
           break;
@@ -371,7 +678,7 @@ export class FuzzyMatcher {
         for (let i = 0; i < keywords.length - 1; i++) {
           const phrase = `${keywords[i]} ${keywords[i + 1]}`;
           if (field.includes(phrase)) {
-            score += 20;
+            score += weights.phrase;
             logger.debug(`Multi-word phrase match: "${phrase}" in ${resource.id}`);
             break; // Only count once per field
           }
@@ -390,6 +697,7 @@ export class FuzzyMatcher {
 
         for (const field of allFields) {
           // Split field into words for word-level fuzzy matching
+          // (fields are already normalized to space-separated tokens)
           const fieldWords = field.split(/\s+/);
 
           for (const word of fieldWords) {
@@ -398,7 +706,7 @@ export class FuzzyMatcher {
 
               // Only count if similarity is high enough (>70%)
               if (similarity > 0.7) {
-                const fuzzyScore = Math.floor(similarity * 8); // Max +8 points for perfect fuzzy match
+                const fuzzyScore = Math.floor(similarity * weights.fuzzy); // Max +8 points (default) for perfect fuzzy match
                 if (fuzzyScore > bestFuzzyScore) {
                   bestFuzzyScore = fuzzyScore;
                 }
@@ -416,13 +724,33 @@ export class FuzzyMatcher {
 
     // Prefer smaller, more focused resources (+5 if < 1000 tokens)
     if (resource.estimatedTokens < 1000) {
-      score += 5;
+      score += weights.small;
     }
 
     logger.debug(`Final score for ${resource.id}: ${score}`);
     return score;
   }
 
+  /**
+   * Capabilities and useWhen normalized like queries (markdown stripped,
+   * case-folded, stop words removed), computed once per resource
+   */
+  private normalizedFields(resource: ResourceFragment): {
+    capabilities: string[];
+    useWhen: string[];
+  } {
+    let fields = this.fieldCache.get(resource);
+    if (!fields) {
+      const normalize = (text: string) => normalizeText(text).join(" ");
+      fields = {
+        capabilities: resource.capabilities.map(normalize),
+        useWhen: resource.useWhen.map(normalize),
+      };
+      this.fieldCache.set(resource, fields);
+    }
+    return fields;
+  }
+
   /**
    * Calculate Levenshtein-based similarity between two strings
    * Returns a value between 0 (no similarity) and 1 (identical)
@@ -562,6 +890,7 @@ export class FuzzyMatcher {
    * and formats them with clear separators and metadata.
    *
    * @param fragments - Selected scored resources
+   * @param flags - Conditions to report (no confident match, fallback)
    * @returns Object with assembled content and total token count
    *
    * @example
@@ -571,10 +900,18 @@ export class FuzzyMatcher {
    * console.log(assembled.tokens);  // Total token count
    * ```
    */
-  assembleContent(fragments: ScoredResource[]): {
+  assembleContent(
+    fragments: ScoredResource[],
+    flags: AssemblyFlags = {},
+  ): {
     content: string;
     tokens: number;
   } {
+    if (flags.noConfidentMatch && fragments.length === 0) {
+      const content = `**No Confident Match:** no resource scored high enough for this query${this.didYouMeanLine(flags)}`;
+      return { content, tokens: Math.ceil(content.length / 4) };
+    }
+
     // Sort by category for logical ordering
     const categoryOrder: Record<ResourceFragment["category"], number> = {
       agent: 0,
@@ -602,7 +939,10 @@ ${resource.content}
 `;
     });
 
-    const content = contentParts.join("\n---\n");
+    const content =
+      (flags.fallback
+        ? "**Fallback:** no confident match; returning the designated fallback resource\n"
+        : "") + contentParts.join("\n---\n");
 
     const tokens = ordered.reduce(
       (sum, { resource }) => sum + resource.estimatedTokens,
@@ -619,9 +959,10 @@ ${resource.content}
    * Each entry includes: title, tags, capabilities, estimated tokens, and MCP URI.
    *
    * @param fragments - Selected scored resources
+   * @param flags - Conditions to report (partial results, no confident match)
    * @returns Object with catalog content and total token count
    */
-  assembleCatalog(fragments: ScoredResource[]): {
+  assembleCatalog(fragments: ScoredResource[], flags: AssemblyFlags = {}): {
     content: string;
     tokens: number;
   } {
@@ -644,7 +985,7 @@ ${resource.content}
     const header = `# 📚 Orchestr8 Resource Catalog
 
 **Query Results:** ${ordered.length} matched resources
-**Total Tokens Available:** ${ordered.reduce((sum, s) => sum + s.resource.estimatedTokens, 0)}
+**Total Tokens Available:** ${ordered.reduce((sum, s) => sum + s.resource.estimatedTokens, 0)}${flags.partial ? '\n**Partial Results:** search timed out before the whole catalog was scored' : ''}${flags.noConfidentMatch ? '\n**No Confident Match:** no resource scored high enough for this query; try a refined query or a lower minScore' : ''}${flags.fallback ? '\n**Fallback:** returning the designated fallback resource instead' : ''}${this.didYouMeanLine(flags)}
 
 ## How to Use This Catalog
 
@@ -682,7 +1023,7 @@ Reference: o8://match?query=<refined-search>&categories=<cats>&minScore=15
 `;
 
     // Build catalog entries
-    const entries = ordered.map(({ resource, score }, index) => {
+    const entries = ordered.map(({ resource, score, snippet }, index) => {
       const categoryLabel = this.categoryLabel(resource.category);
       const resourceId = resource.id.split('/').pop();
       const mcpUri = `o8://${resource.category}s/${resourceId}`;
@@ -700,8 +1041,8 @@ ${resource.useWhen.slice(0, 4).map(use => `  - ${use}`).join('\n')}${resource.us
 **Tags:** ${resource.tags.slice(0, 8).join(", ")}${resource.tags.length > 8 ? '...' : ''}
 **Capabilities:**
 ${resource.capabilities.slice(0, 4).map(cap => `  - ${cap}`).join('\n')}${resource.capabilities.length > 4 ? '\n  - ...' : ''}
-${useWhenSection}
-**Estimated Tokens:** ~${resource.estimatedTokens}
+${useWhenSection}${snippet ? `\n**Matched:** ${snippet}` : ''}
+**Estimated Tokens:** ~${resource.estimatedTokens}${resource.origin ? `\n**Source:** ${resource.origin.filePath} (${resource.origin.layer})` : ''}
 
 **Load this resource:** o8://${resource.category}s/${resourceId}
 `;
@@ -724,21 +1065,30 @@ ${useWhenSection}
    * Token cost: ~300-500 tokens (vs ~1500 for catalog mode)
    *
    * @param fragments - Selected scored resources
+   * @param flags - Conditions to report (partial results, no confident match)
+   * @param fields - Result fields to keep (default: all)
    * @returns Object with minimal JSON content and token count
    */
-  assembleMinimal(fragments: ScoredResource[]): { content: string; tokens: number } {
-    const results = fragments.map(({ resource, score }) => ({
+  assembleMinimal(
+    fragments: ScoredResource[],
+    flags: AssemblyFlags = {},
+    fields?: string[],
+  ): { content: string; tokens: number } {
+    const results = fragments.map(({ resource, score, snippet }) => ({
       uri: `o8://${resource.category}s/${resource.id.split('/').pop()}`,
       category: resource.category,
       score,
       tokens: resource.estimatedTokens,
-      tags: resource.tags.slice(0, 5)  // Top 5 tags only
+      tags: resource.tags.slice(0, 5),  // Top 5 tags only
+      ...(snippet ? { snippet } : {}),
+      ...withProvenance(resource)
     }));
 
     const output = {
       matches: results.length,
       totalTokens: results.reduce((sum, r) => sum + r.tokens, 0),
-      results,
+      results: selectFields(results, fields),
+      ...flags,
       usage: "Load resources via ReadMcpResourceTool using the uri field"
     };
 
@@ -858,12 +1208,14 @@ ${useWhenSection}
     fragments: ResourceFragment[],
   ): Promise<void> {
     try {
-      const entries = await fs.readdir(dirPath, { withFileTypes: true });
+      const entries = sortByName(
+        await fs.readdir(dirPath, { withFileTypes: true }),
+      );
 
       for (const entry of entries) {
         const fullPath = join(dirPath, entry.name);
 
-        if (entry.isDirectory()) {
+        if (await isDirectoryEntry(entry, fullPath)) {
           // Recursively scan subdirectories
           await this._scanFragmentsDirectory(
             fullPath,
@@ -905,7 +1257,9 @@ ${useWhenSection}
   ): ResourceFragment {
     // Parse frontmatter
     const parsed = matter(content);
-    const frontmatter = parsed.data;
+    // YAML that parses to a scalar or null (e.g. "---\nnull\n---") has no fields
+    const frontmatter: any =
+      parsed.data && typeof parsed.data === "object" ? parsed.data : {};
     const body = parsed.content;
 
     // Extract metadata from frontmatter with fallbacks
@@ -922,7 +1276,7 @@ ${useWhenSection}
       "unknown";
 
     const tags = Array.isArray(frontmatter.tags)
-      ? frontmatter.tags.map((tag: any) => String(tag).toLowerCase())
+      ? frontmatter.tags.map((tag: any) => foldCase(String(tag)))
       : [];
 
     const capabilities = Array.isArray(frontmatter.capabilities)
@@ -960,6 +1314,15 @@ ${useWhenSection}
     logger.info(`Resource index set with ${resources.length} resources`);
   }
 
+  /**
+   * "Did You Mean" line for assembled output, if there are suggestions
+   */
+  private didYouMeanLine(flags: AssemblyFlags): string {
+    return flags.didYouMean
+      ? `\n**Did You Mean:** ${flags.didYouMean.join(", ")}`
+      : "";
+  }
+
   /**
    * Get human-readable label for resource category
    *
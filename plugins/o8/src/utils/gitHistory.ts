@@ -0,0 +1,128 @@
+/**
+ * Git history for resource files
+ *
+ * When the resources directory is inside a git repository, users can see
+ * who changed a prompt and when without leaving their MCP client. Shells
+ * out to the `git` binary; no repository, no history.
+ *
+ * @example
+ * ```typescript
+ * await fileHistory("/repo/resources", "agents/rust-expert.md", 5);
+ * // [{ commit: "3f2c...", author: "Ada", date: "2025-03-01T10:00:00+00:00", message: "Tighten review checklist" }]
+ * ```
+ */
+
+import { execFile } from "child_process";
+import { promisify } from "util";
+
+const execFileAsync = promisify(execFile);
+
+/**
+ * One commit touching a file
+ */
+export interface HistoryEntry {
+  commit: string;
+  author: string;
+  email: string;
+  /** Author date (ISO 8601) */
+  date: string;
+  /** Commit subject line */
+  message: string;
+}
+
+/**
+ * Error raised when git history is unavailable
+ */
+export class GitHistoryError extends Error {
+  public readonly code: "NOT_A_GIT_REPO" | "GIT_FAILED";
+
+  constructor(code: "NOT_A_GIT_REPO" | "GIT_FAILED", message: string) {
+    super(message);
+    this.name = "GitHistoryError";
+    this.code = code;
+  }
+}
+
+/**
+ * Run a git command and return stdout
+ *
+ * @param cwd - Working directory inside the repository
+ * @param args - git arguments
+ * @throws {GitHistoryError} If cwd is not in a repository or git fails
+ */
+export async function runGit(cwd: string, args: string[]): Promise<string> {
+  try {
+    const { stdout } = await execFileAsync("git", args, {
+      cwd,
+      maxBuffer: 16 * 1024 * 1024,
+    });
+    return stdout;
+  } catch (error: any) {
+    const stderr = String(error?.stderr ?? error?.message ?? error);
+    if (/not a git repository/i.test(stderr)) {
+      throw new GitHistoryError(
+        "NOT_A_GIT_REPO",
+        `Resources at ${cwd} are not in a git repository`,
+      );
+    }
+    throw new GitHistoryError("GIT_FAILED", stderr.trim());
+  }
+}
+
+/**
+ * List commits that touched a file, newest first (follows renames)
+ *
+ * @param root - Directory inside the repository (the resources root)
+ * @param relativePath - File path relative to root
+ * @param limit - Maximum number of commits (default: 20)
+ * @returns Commits touching the file; empty if it was never committed
+ * @throws {RangeError} If limit is not a positive integer (git would read
+ *   `--max-count=-1` as no limit)
+ */
+export async function fileHistory(
+  root: string,
+  relativePath: string,
+  limit: number = 20,
+): Promise<HistoryEntry[]> {
+  if (!Number.isInteger(limit) || limit < 1) {
+    throw new RangeError(`History limit must be a positive integer: ${limit}`);
+  }
+  const output = await runGit(root, [
+    "log",
+    "--follow",
+    `--max-count=${limit}`,
+    "--format=%H%x1f%an%x1f%ae%x1f%aI%x1f%s%x1e",
+    "--",
+    relativePath,
+  ]);
+
+  return output
+    .split("\x1e")
+    .map((record) => record.trim())
+    .filter(Boolean)
+    .map((record) => {
+      const [commit, author, email, date, message] = record.split("\x1f");
+      return { commit, author, email, date, message };
+    });
+}
+
+/**
+ * Read a file as it was at a git revision
+ *
+ * @param root - Directory inside the repository (the resources root)
+ * @param relativePath - File path relative to root
+ * @param revision - Commit, branch, tag, or expression like HEAD~2
+ * @returns File content at that revision
+ * @throws {GitHistoryError} If the revision or file does not exist
+ */
+export async function fileAtRevision(
+  root: string,
+  relativePath: string,
+  revision: string,
+): Promise<string> {
+  if (!revision || revision.startsWith("-") || revision.includes(":")) {
+    throw new GitHistoryError("GIT_FAILED", `Invalid revision: ${revision}`);
+  }
+  const path = relativePath.replace(/\\/g, "/");
+  return runGit(root, ["show", `${revision}:./${path}`]);
+}
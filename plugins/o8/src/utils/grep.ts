@@ -0,0 +1,104 @@
+/**
+ * Line-level regex search over resource files
+ *
+ * Body search (bodyIndex.ts) ranks resources by words. Finding every
+ * remaining reference to a deprecated tool or URL needs exact hits
+ * instead: the file, line number, and line for each match, frontmatter
+ * included.
+ *
+ * @example
+ * ```typescript
+ * const regex = compilePattern("deploy-v1", { ignoreCase: true });
+ * grepLines("agents/release-manager", content, regex, 100);
+ * // [{ id: 'agents/release-manager', line: 4, text: 'tools: [deploy-v1]' }]
+ * ```
+ */
+
+/**
+ * Default maximum number of hits per request
+ */
+export const DEFAULT_GREP_LIMIT = 100;
+
+/**
+ * Longest pattern accepted, in characters
+ */
+export const MAX_GREP_PATTERN = 500;
+
+/**
+ * Matched lines are cut to this many characters
+ */
+export const MAX_GREP_LINE = 300;
+
+/**
+ * One matching line
+ */
+export interface GrepHit {
+  /** Resource id (e.g. 'agents/rust-expert') */
+  id: string;
+  /** 1-based line number in the resource file */
+  line: number;
+  /** The matching line, cut to MAX_GREP_LINE characters */
+  text: string;
+}
+
+/**
+ * Error raised for a pattern that is not a valid regular expression
+ */
+export class GrepPatternError extends Error {
+  public readonly code = "INVALID_PATTERN";
+
+  constructor(message: string) {
+    super(message);
+    this.name = "GrepPatternError";
+  }
+}
+
+/**
+ * Compile a search pattern
+ *
+ * @param pattern - JavaScript regular expression source
+ * @param options - ignoreCase for a case-insensitive search
+ * @throws {GrepPatternError} If the pattern is empty, too long, or invalid
+ */
+export function compilePattern(
+  pattern: string,
+  options: { ignoreCase?: boolean } = {},
+): RegExp {
+  if (!pattern) {
+    throw new GrepPatternError("Pattern must not be empty");
+  }
+  if (pattern.length > MAX_GREP_PATTERN) {
+    throw new GrepPatternError(
+      `Pattern exceeds ${MAX_GREP_PATTERN} characters`,
+    );
+  }
+  try {
+    return new RegExp(pattern, options.ignoreCase ? "i" : "");
+  } catch (error: any) {
+    throw new GrepPatternError(`Invalid pattern: ${error?.message ?? error}`);
+  }
+}
+
+/**
+ * Lines of one file matching a pattern
+ *
+ * @param id - Resource id reported in each hit
+ * @param content - File content
+ * @param regex - Compiled pattern (without the g flag)
+ * @param max - Stop after this many hits
+ */
+export function grepLines(
+  id: string,
+  content: string,
+  regex: RegExp,
+  max: number,
+): GrepHit[] {
+  const hits: GrepHit[] = [];
+  const lines = content.split(/\r?\n/);
+  for (let i = 0; i < lines.length && hits.length < max; i++) {
+    if (regex.test(lines[i])) {
+      hits.push({ id, line: i + 1, text: lines[i].slice(0, MAX_GREP_LINE) });
+    }
+  }
+  return hits;
+}
@@ -0,0 +1,105 @@
+/**
+ * Agent handoff rules
+ *
+ * An agent can name the agents it hands off to when its part is done, so
+ * orchestrators can follow the graph instead of hardcoding sequences:
+ *
+ * ```yaml
+ * handoff_to:
+ *   - code-reviewer
+ *   - to: security-auditor
+ *     when: the change touches authentication
+ * ```
+ *
+ * Bare names are agents in the same category directory as the resource
+ * ("code-reviewer" on agents/code-writer is agents/code-reviewer).
+ */
+
+import { compareStrings } from "./deterministic.js";
+
+export interface HandoffRule {
+  /** Target resource id */
+  to: string;
+  /** When to hand off, if not always */
+  when?: string;
+}
+
+export interface HandoffEdge extends HandoffRule {
+  from: string;
+}
+
+export interface HandoffGraph {
+  /** Resources on an edge, in id order */
+  nodes: string[];
+  edges: HandoffEdge[];
+  /** Edges whose target is not in the catalog */
+  dangling: HandoffEdge[];
+}
+
+/**
+ * Handoff rules declared in `handoff_to:` frontmatter
+ *
+ * @param value - Frontmatter value: a name, or a list of names and
+ *   `{ to, when }` entries
+ * @param id - Id of the declaring resource
+ */
+export function parseHandoffs(value: unknown, id: string): HandoffRule[] {
+  const category = id.split("/")[0];
+  const rules: HandoffRule[] = [];
+  const entries: unknown[] = Array.isArray(value) ? value : [value];
+  for (const entry of entries) {
+    const rule =
+      entry && typeof entry === "object"
+        ? (entry as { to?: unknown; when?: unknown })
+        : { to: entry, when: undefined };
+    const name =
+      typeof rule.to === "string" ? rule.to.trim().replace(/\.md$/, "") : "";
+    if (!name) {
+      continue;
+    }
+    const when = typeof rule.when === "string" ? rule.when.trim() : "";
+    rules.push({
+      to: name.includes("/") ? name : `${category}/${name}`,
+      ...(when ? { when } : {}),
+    });
+  }
+  return rules;
+}
+
+/**
+ * Handoff graph of a catalog
+ *
+ * @param fragments - Resources with their parsed handoff rules
+ * @param resolve - Canonical id for a target (aliases), or null if the
+ *   target does not exist
+ * @param agent - Only edges from or to this resource
+ */
+export function handoffGraph(
+  fragments: Array<{ id: string; handoffs?: HandoffRule[] }>,
+  resolve: (id: string) => string | null,
+  agent?: string,
+): HandoffGraph {
+  const edges: HandoffEdge[] = [];
+  const dangling: HandoffEdge[] = [];
+  for (const fragment of fragments) {
+    for (const rule of fragment.handoffs ?? []) {
+      const to = resolve(rule.to);
+      const edge: HandoffEdge = {
+        from: fragment.id,
+        to: to ?? rule.to,
+        ...(rule.when ? { when: rule.when } : {}),
+      };
+      if (agent && edge.from !== agent && edge.to !== agent) {
+        continue;
+      }
+      (to ? edges : dangling).push(edge);
+    }
+  }
+
+  const byEdge = (a: HandoffEdge, b: HandoffEdge) =>
+    compareStrings(a.from, b.from) || compareStrings(a.to, b.to);
+  edges.sort(byEdge);
+  dangling.sort(byEdge);
+  const nodes = [...new Set(edges.flatMap((edge) => [edge.from, edge.to]))];
+  return { nodes: nodes.sort(compareStrings), edges, dangling };
+}
@@ -13,6 +13,7 @@ import { createHash } from "crypto";
 import matter from "gray-matter";
 import { Logger } from "./logger.js";
 import { ResourceFragment } from "./fuzzyMatcher.js";
+import { isDirectoryEntry } from "./paths.js";
 
 const logger = new Logger("IndexBuilder");
 
@@ -182,7 +183,7 @@ export class IndexBuilder {
       for (const entry of entries) {
         const fullPath = join(dirPath, entry.name);
 
-        if (entry.isDirectory()) {
+        if (await isDirectoryEntry(entry, fullPath)) {
           // Recursively scan subdirectories
           await this.scanFragmentsDirectory(fullPath, category, categoryName, fragments);
         } else if (entry.name.endsWith(".md")) {
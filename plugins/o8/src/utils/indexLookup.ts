@@ -14,6 +14,8 @@ import { promises as fs } from "fs";
 import { join } from "path";
 import { Logger } from "./logger.js";
 import { FuzzyMatcher, MatchRequest } from "./fuzzyMatcher.js";
+import { compareStrings } from "./deterministic.js";
+import { normalizeText } from "./tokenize.js";
 
 const logger = new Logger("IndexLookup");
 
@@ -70,12 +72,13 @@ export interface LookupOptions {
 interface CachedResult {
   content: string;
   timestamp: number;
+  resultsCount: number;
 }
 
 /**
  * Query metrics for monitoring
  */
-interface QueryMetrics {
+export interface QueryMetrics {
   query: string;
   timestamp: number;
   tier: "quick" | "index" | "fuzzy-fallback";
@@ -94,6 +97,7 @@ export class IndexLookup {
   private fuzzyMatcher: FuzzyMatcher;
   private resourcesPath: string;
   private cacheTTL: number = 1000 * 60 * 15; // 15 minutes
+  private metricsListener: ((metrics: QueryMetrics) => void) | null = null;
 
   constructor(resourcesPath?: string) {
     this.resourcesPath =
@@ -132,7 +136,7 @@ export class IndexLookup {
           timestamp: Date.now(),
           tier,
           latencyMs,
-          resultsCount: 0, // Cached, don't count
+          resultsCount: cached.resultsCount,
           tokenCost: Math.ceil(cached.content.length / 4),
         });
 
@@ -150,11 +154,13 @@ export class IndexLookup {
       const minResults = 2;
       if (matches.length >= minResults) {
         const result = this.formatCompactResult(matches, options);
+        const resultsCount = Math.min(matches.length, options.maxResults || 5);
 
         // Cache for next time
         this.quickLookupCache.set(normalized, {
           content: result,
           timestamp: Date.now(),
+          resultsCount,
         });
 
         const latencyMs = Date.now() - startTime;
@@ -163,7 +169,7 @@ export class IndexLookup {
           timestamp: Date.now(),
           tier,
           latencyMs,
-          resultsCount: Math.min(matches.length, options.maxResults || 5),
+          resultsCount,
           tokenCost: Math.ceil(result.length / 4),
         });
 
@@ -341,7 +347,7 @@ export class IndexLookup {
         return { ...scenario, score };
       })
       .filter((s) => s.score > 0) // Only return matches with some score
-      .sort((a, b) => b.score - a.score);
+      .sort((a, b) => b.score - a.score || compareStrings(a.uri, b.uri));
   }
 
   /**
@@ -403,7 +409,7 @@ export class IndexLookup {
       timestamp: Date.now(),
       tier: "fuzzy-fallback",
       latencyMs,
-      resultsCount: result.fragments.length,
+      resultsCount: result.noConfidentMatch ? 0 : result.fragments.length,
       tokenCost: Math.ceil(result.assembledContent.length / 4),
     });
 
@@ -414,21 +420,7 @@ export class IndexLookup {
    * Extract keywords from query
    */
   private extractKeywords(query: string): string[] {
-    const normalized = query.toLowerCase().replace(/[^\w\s-]/g, " ");
-
-    const stopWords = new Set([
-      "a", "an", "the", "and", "or", "but", "in", "on", "at", "to", "for",
-      "of", "with", "by", "from", "as", "is", "was", "are", "were", "be",
-      "been", "being", "have", "has", "had", "do", "does", "did", "will",
-      "would", "should", "could", "may", "might", "can", "i", "you", "he",
-      "she", "it", "we", "they", "this", "that", "these", "those",
-    ]);
-
-    const words = normalized
-      .split(/\s+/)
-      .filter((word) => word.length > 1 && !stopWords.has(word));
-
-    return Array.from(new Set(words));
+    return Array.from(new Set(normalizeText(query)));
   }
 
   /**
@@ -450,6 +442,14 @@ export class IndexLookup {
    */
   private logMetrics(metrics: QueryMetrics): void {
     logger.info("IndexLookup query metrics", metrics);
+    this.metricsListener?.(metrics);
+  }
+
+  /**
+   * Receive metrics for every lookup (null to stop)
+   */
+  setMetricsListener(listener: ((metrics: QueryMetrics) => void) | null): void {
+    this.metricsListener = listener;
   }
 
   /**
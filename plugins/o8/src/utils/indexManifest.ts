@@ -0,0 +1,98 @@
+/**
+ * Index manifests (--index-manifest)
+ *
+ * On a slow network filesystem, walking every category directory at
+ * startup costs more than parsing the files. `orchestr8 manifest` (run in
+ * CI, next to the resources) writes a manifest listing every resource and
+ * _defaults.yaml file with its hash. With `--index-manifest <path>` the
+ * server reads exactly the listed files from RESOURCES_PATH and never lists
+ * a directory there. Other layers are still scanned.
+ *
+ * Files added after the manifest was generated are not served until it is
+ * regenerated; files whose content no longer matches their hash are served
+ * with their current content and counted as stale.
+ *
+ * @example
+ * ```bash
+ * orchestr8 manifest --out resources/index-manifest.json
+ * orchestr8 --index-manifest resources/index-manifest.json
+ * ```
+ */
+
+import { promises as fs } from "fs";
+import { resolve } from "path";
+import { flagValue } from "./flags.js";
+import type { SnapshotFile } from "../state/snapshotStore.js";
+
+export const INDEX_MANIFEST_VERSION = 1;
+
+/**
+ * Resource files of the catalog as of a build
+ */
+export interface IndexManifest {
+  version: typeof INDEX_MANIFEST_VERSION;
+  generatedAt: string;
+  /** Paths relative to RESOURCES_PATH, sorted */
+  files: SnapshotFile[];
+}
+
+/**
+ * Manifest path from --index-manifest (ORCHESTR8_INDEX_MANIFEST), or null
+ */
+export function indexManifestPath(
+  argv: string[] = process.argv,
+  env: NodeJS.ProcessEnv = process.env,
+): string | null {
+  const path = flagValue("index-manifest", argv, env);
+  return path ? resolve(path) : null;
+}
+
+/**
+ * Parse and validate a manifest
+ *
+ * @param text - Manifest JSON
+ * @param source - File name for error messages
+ * @throws {Error} If the manifest is not valid JSON, has another version,
+ *   or lists a file without a relative path and hash
+ */
+export function parseIndexManifest(
+  text: string,
+  source: string,
+): IndexManifest {
+  let manifest: any;
+  try {
+    manifest = JSON.parse(text);
+  } catch (error: any) {
+    throw new Error(`Invalid index manifest ${source}: ${error.message}`);
+  }
+  if (manifest?.version !== INDEX_MANIFEST_VERSION) {
+    throw new Error(
+      `Unsupported index manifest version in ${source}: ${manifest?.version} (expected ${INDEX_MANIFEST_VERSION}); regenerate it with orchestr8 manifest`,
+    );
+  }
+  if (!Array.isArray(manifest.files)) {
+    throw new Error(`Invalid index manifest ${source}: "files" is missing`);
+  }
+  for (const file of manifest.files) {
+    if (
+      typeof file?.path !== "string" ||
+      typeof file.hash !== "string" ||
+      file.path.startsWith("/") ||
+      file.path.split("/").includes("..")
+    ) {
+      throw new Error(
+        `Invalid index manifest ${source}: bad entry ${JSON.stringify(file)}`,
+      );
+    }
+  }
+  return manifest as IndexManifest;
+}
+
+/**
+ * Read a manifest file
+ *
+ * @throws {Error} If the file cannot be read or is invalid
+ */
+export async function readIndexManifest(path: string): Promise<IndexManifest> {
+  return parseIndexManifest(await fs.readFile(path, "utf-8"), path);
+}
@@ -0,0 +1,83 @@
+/**
+ * Progress of a catalog index build
+ *
+ * Indexing tens of thousands of files takes long enough that a silent
+ * server looks hung. The loader counts the files first, then advances the
+ * tracker for every file parsed; reports are throttled to one per interval
+ * so logging stays cheap. A build that finishes within the first interval
+ * reports nothing, so small catalogs start as quietly as before.
+ */
+
+/**
+ * Indexing progress, as reported by health and progress notifications
+ */
+export interface IndexingState {
+  processed: number;
+  total: number;
+  /** 0-100, rounded down */
+  percent: number;
+}
+
+export type IndexProgressListener = (state: IndexingState) => void;
+
+/**
+ * Progress token of the `notifications/progress` sent while indexing
+ */
+export const INDEX_PROGRESS_TOKEN = "orchestr8/indexing";
+
+/**
+ * Throttled progress tracker for one index build
+ */
+export class IndexProgress {
+  private processed = 0;
+  private lastReport = 0;
+  private reported = false;
+
+  /**
+   * @param total - Files to index
+   * @param onReport - Called at most once per interval, and when a build
+   *   that has reported finishes
+   * @param intervalMs - Minimum time between reports (default: 2000ms)
+   * @param now - Clock (for tests)
+   */
+  constructor(
+    private readonly total: number,
+    private readonly onReport: IndexProgressListener,
+    private readonly intervalMs: number = 2000,
+    private readonly now: () => number = Date.now,
+  ) {
+    this.lastReport = this.now();
+  }
+
+  /**
+   * Count indexed files
+   */
+  advance(count: number = 1): void {
+    this.processed = Math.min(this.total, this.processed + count);
+    const now = this.now();
+    if (now - this.lastReport >= this.intervalMs) {
+      this.lastReport = now;
+      this.reported = true;
+      this.onReport(this.state());
+    }
+  }
+
+  /**
+   * Mark the build complete, reporting 100% if progress was reported
+   */
+  finish(): void {
+    this.processed = this.total;
+    if (this.reported) {
+      this.onReport(this.state());
+    }
+  }
+
+  state(): IndexingState {
+    return {
+      processed: this.processed,
+      total: this.total,
+      percent:
+        this.total === 0 ? 100 : Math.floor((this.processed / this.total) * 100),
+    };
+  }
+}
@@ -0,0 +1,117 @@
+/**
+ * Index build watchdog
+ *
+ * An index build that stops making progress - a read hanging on an
+ * unresponsive network mount, a parser stuck on a pathological file -
+ * would otherwise leave the server serving its old index (or none) with
+ * `reloading: true` forever. The watchdog samples the build's progress and
+ * fails the build once it has not advanced for O8_INDEX_STALL_TIMEOUT ms,
+ * so the loader can abandon it and start over.
+ */
+
+/**
+ * Default stall timeout (O8_INDEX_STALL_TIMEOUT, ms)
+ */
+export const DEFAULT_STALL_TIMEOUT = 60_000;
+
+/**
+ * Restarts of a stalled build before the reload fails
+ */
+export const MAX_INDEX_RESTARTS = 2;
+
+/**
+ * Error raised when an index build stops making progress
+ */
+export class IndexStallError extends Error {
+  public readonly code = "INDEX_STALLED";
+
+  constructor(
+    public readonly progress: number | null,
+    public readonly timeoutMs: number,
+  ) {
+    super(
+      `Index build made no progress for ${timeoutMs}ms` +
+        (progress === null ? "" : ` (${progress} files indexed)`),
+    );
+    this.name = "IndexStallError";
+  }
+}
+
+/**
+ * Stalls the watchdog has handled, for health
+ */
+export interface WatchdogState {
+  /** Stalled builds that were restarted */
+  restarts: number;
+  /** Most recent stall */
+  lastStall: { at: string; progress: number | null } | null;
+}
+
+/**
+ * Stall timeout from O8_INDEX_STALL_TIMEOUT (ms; 0 disables the watchdog)
+ *
+ * @throws {Error} If the value is not a non-negative integer
+ */
+export function stallTimeout(env: NodeJS.ProcessEnv = process.env): number {
+  const raw = env.O8_INDEX_STALL_TIMEOUT;
+  if (raw === undefined || raw === "") {
+    return DEFAULT_STALL_TIMEOUT;
+  }
+  const value = Number(raw);
+  if (!Number.isInteger(value) || value < 0) {
+    throw new Error(
+      `Invalid O8_INDEX_STALL_TIMEOUT: ${raw} (expected milliseconds, or 0)`,
+    );
+  }
+  return value;
+}
+
+/**
+ * Fail a build that stops making progress
+ *
+ * @param build - Index build in flight
+ * @param progress - Current progress (files indexed; null before counting
+ *   is done); any change counts as progress
+ * @param timeoutMs - Time without progress before the build is failed (0:
+ *   never)
+ * @returns The build's result
+ * @throws {IndexStallError} If the build stalls; the build itself keeps
+ *   running and its result is ignored
+ */
+export function watchBuild<T>(
+  build: Promise<T>,
+  progress: () => number | null,
+  timeoutMs: number,
+): Promise<T> {
+  if (timeoutMs <= 0) {
+    return build;
+  }
+  return new Promise<T>((resolve, reject) => {
+    let last = progress();
+    let lastChange = Date.now();
+    const timer = setInterval(
+      () => {
+        const current = progress();
+        if (current !== last) {
+          last = current;
+          lastChange = Date.now();
+        } else if (Date.now() - lastChange >= timeoutMs) {
+          clearInterval(timer);
+          reject(new IndexStallError(current, timeoutMs));
+        }
+      },
+      Math.min(1000, Math.max(10, Math.floor(timeoutMs / 4))),
+    );
+    timer.unref();
+    build.then(
+      (value) => {
+        clearInterval(timer);
+        resolve(value);
+      },
+      (error) => {
+        clearInterval(timer);
+        reject(error);
+      },
+    );
+  });
+}
@@ -0,0 +1,94 @@
+/**
+ * Resource directory layers
+ *
+ * Resources are read from several directories, merged by id with later
+ * layers overriding earlier ones, so a machine or a project can replace a
+ * bundled agent without forking the catalog:
+ *
+ * 1. builtin  - RESOURCES_PATH
+ * 2. system   - /etc/orchestr8 (%ProgramData%\orchestr8 on Windows)
+ * 3. user     - $XDG_CONFIG_HOME/orchestr8 (default ~/.config/orchestr8)
+ * 4. project  - <root>/.orchestr8, where root is the nearest directory
+ *               with .orchestr8 or .git
+ *
+ * Each layer has the usual category directories (agents/, skills/, ...).
+ * O8_LAYERS replaces layers 2-4 with a comma-separated list of name=path
+ * pairs, or disables them with "none".
+ *
+ * @example
+ * ```typescript
+ * await resourceLayers("/app/resources", { O8_LAYERS: "team=/srv/agents" });
+ * // [{ name: "builtin", path: "/app/resources" }, { name: "team", path: "/srv/agents" }]
+ * ```
+ */
+
+import { homedir } from "os";
+import { findRoot } from "./autoDiscovery.js";
+import { pathApi, pathKey } from "./paths.js";
+
+/**
+ * A directory resources are read from
+ */
+export interface ResourceLayer {
+  name: string;
+  path: string;
+}
+
+/**
+ * Layers in order, lowest priority first
+ *
+ * Directories are not checked for existence; a missing layer is simply
+ * empty. A layer pointing at the same directory as an earlier one is
+ * dropped, so `--auto` setting RESOURCES_PATH to <root>/.orchestr8 does
+ * not read it twice. Paths are compared case-insensitively on Windows.
+ *
+ * @param builtin - Built-in resources directory (RESOURCES_PATH)
+ * @param env - Environment (default: process.env)
+ * @param cwd - Directory to find the project root from (default: process.cwd())
+ * @param platform - Platform for the system layer and path comparison
+ *   (default: process.platform)
+ * @throws {Error} If O8_LAYERS has an entry without name=path
+ */
+export async function resourceLayers(
+  builtin: string,
+  env: NodeJS.ProcessEnv = process.env,
+  cwd: string = process.cwd(),
+  platform: NodeJS.Platform = process.platform,
+): Promise<ResourceLayer[]> {
+  const layers: ResourceLayer[] = [{ name: "builtin", path: builtin }];
+
+  if (env.O8_LAYERS !== undefined) {
+    const spec = env.O8_LAYERS.trim();
+    if (spec !== "none") {
+      for (const entry of spec.split(",").filter((e) => e.trim())) {
+        const [name, ...path] = entry.trim().split("=");
+        if (!name || path.length === 0 || !path.join("=")) {
+          throw new Error(`Invalid O8_LAYERS entry: ${entry} (expected name=path)`);
+        }
+        layers.push({ name, path: path.join("=") });
+      }
+    }
+  } else {
+    const api = pathApi(platform);
+    const config = env.XDG_CONFIG_HOME || api.join(homedir(), ".config");
+    const system =
+      platform === "win32"
+        ? api.join(env.ProgramData || "C:\\ProgramData", "orchestr8")
+        : "/etc/orchestr8";
+    layers.push(
+      { name: "system", path: system },
+      { name: "user", path: api.join(config, "orchestr8") },
+      { name: "project", path: api.join(await findRoot(cwd), ".orchestr8") },
+    );
+  }
+
+  const seen = new Set<string>();
+  return layers.filter((layer) => {
+    const key = pathKey(layer.path, platform);
+    if (seen.has(key)) {
+      return false;
+    }
+    seen.add(key);
+    return true;
+  });
+}
@@ -0,0 +1,138 @@
+/**
+ * Request payload limits
+ *
+ * Guards against accidentally huge inputs (a pasted megabyte of context in
+ * a match query, maxResults=1000000) before they reach fuzzy matching or
+ * template rendering.
+ */
+
+/**
+ * Payload limits
+ */
+export interface PayloadLimits {
+  /** Maximum HTTP request body size in bytes (default: 1MB) */
+  maxRequestBytes: number;
+  /** Maximum search query length in characters (default: 2000) */
+  maxQueryLength: number;
+  /** Maximum maxResults value (default: 100) */
+  maxResults: number;
+  /** Maximum maxTokens value (default: 200000) */
+  maxTokens: number;
+  /** Maximum combined size of template variables in bytes (default: 8KB) */
+  maxVariableBytes: number;
+  /** Maximum ids in one agents/batch_get (default: 50) */
+  maxBatchSize: number;
+}
+
+/**
+ * Default payload limits
+ */
+export const DEFAULT_PAYLOAD_LIMITS: PayloadLimits = {
+  maxRequestBytes: 1024 * 1024,
+  maxQueryLength: 2000,
+  maxResults: 100,
+  maxTokens: 200000,
+  maxVariableBytes: 8 * 1024,
+  maxBatchSize: 50,
+};
+
+/**
+ * Error raised when a request exceeds a payload limit
+ */
+export class PayloadLimitError extends Error {
+  public code = "PAYLOAD_TOO_LARGE";
+
+  constructor(
+    public field: string,
+    public limit: number,
+    public actual: number,
+  ) {
+    super(`${field} exceeds limit: ${actual} > ${limit}`);
+    this.name = "PayloadLimitError";
+  }
+}
+
+/**
+ * Error raised when a count or limit is not a positive integer
+ */
+export class InvalidLimitError extends Error {
+  public code = "INVALID_LIMIT";
+
+  constructor(public field: string) {
+    super(`${field} must be a positive integer`);
+    this.name = "InvalidLimitError";
+  }
+}
+
+/**
+ * Throw if a value exceeds its limit
+ *
+ * @param field - Name reported in the error (e.g. 'maxResults')
+ * @param actual - Provided value
+ * @param limit - Maximum allowed value
+ * @throws {PayloadLimitError} If actual > limit
+ */
+export function checkLimit(field: string, actual: number, limit: number): void {
+  if (actual > limit) {
+    throw new PayloadLimitError(field, limit, actual);
+  }
+}
+
+/**
+ * Check a requested count (limit, count, topTags, ...): a positive integer
+ * no larger than its limit
+ *
+ * Negative counts must not reach `slice`, where they mean "all but the
+ * last n".
+ *
+ * @param field - Name reported in the error
+ * @param value - Provided value (query string values converted with Number)
+ * @param limit - Maximum allowed value
+ * @returns The value
+ * @throws {InvalidLimitError} If value is not a positive integer
+ * @throws {PayloadLimitError} If value > limit
+ */
+export function checkCount(
+  field: string,
+  value: unknown,
+  limit: number,
+): number {
+  if (typeof value !== "number" || !Number.isInteger(value) || value < 1) {
+    throw new InvalidLimitError(field);
+  }
+  checkLimit(field, value, limit);
+  return value;
+}
+
+/**
+ * Read payload limits from O8_MAX_* environment variables
+ *
+ * @param env - Environment (default: process.env)
+ * @returns Limits with defaults for unset or invalid values
+ */
+export function payloadLimitsFromEnv(
+  env: NodeJS.ProcessEnv = process.env,
+): PayloadLimits {
+  const read = (name: string, fallback: number): number => {
+    const value = parseInt(env[name] || "", 10);
+    return value > 0 ? value : fallback;
+  };
+
+  return {
+    maxRequestBytes: read(
+      "O8_MAX_REQUEST_BYTES",
+      DEFAULT_PAYLOAD_LIMITS.maxRequestBytes,
+    ),
+    maxQueryLength: read(
+      "O8_MAX_QUERY_LENGTH",
+      DEFAULT_PAYLOAD_LIMITS.maxQueryLength,
+    ),
+    maxResults: read("O8_MAX_RESULTS", DEFAULT_PAYLOAD_LIMITS.maxResults),
+    maxTokens: read("O8_MAX_TOKENS", DEFAULT_PAYLOAD_LIMITS.maxTokens),
+    maxVariableBytes: read(
+      "O8_MAX_VARIABLE_BYTES",
+      DEFAULT_PAYLOAD_LIMITS.maxVariableBytes,
+    ),
+    maxBatchSize: read("O8_MAX_BATCH_SIZE", DEFAULT_PAYLOAD_LIMITS.maxBatchSize),
+  };
+}
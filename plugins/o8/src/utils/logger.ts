@@ -1,8 +1,11 @@
 /**
  * Logger utility for MCP server
  * IMPORTANT: Must write to stderr to avoid corrupting MCP protocol on stdout
+ * Messages and data are redacted (see redact.ts) before they are written.
  */
 
+import { redactor } from "./redact.js";
+
 type LogLevel = "debug" | "info" | "warn" | "error";
 
 export class Logger {
@@ -24,15 +27,20 @@ export class Logger {
   private formatMessage(level: LogLevel, message: string, data?: any): string {
     const timestamp = new Date().toISOString();
     const prefix = `[${timestamp}] [${level.toUpperCase()}] [${this.name}]`;
+    const redact = redactor();
+    const text = redact.redactText(message);
 
     if (data !== undefined) {
       if (data instanceof Error) {
-        return `${prefix} ${message}: ${data.message}\n${data.stack}`;
+        return `${prefix} ${text}: ${redact.redactText(
+          `${data.message}\n${data.stack}`,
+        )}`;
       }
-      return `${prefix} ${message}: ${JSON.stringify(data, null, 2)}`;
+      const json = JSON.stringify(redact.redact(data), null, 2);
+      return `${prefix} ${text}: ${json}`;
     }
 
-    return `${prefix} ${message}`;
+    return `${prefix} ${text}`;
   }
 
   debug(message: string, data?: any): void {
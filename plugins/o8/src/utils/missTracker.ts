@@ -0,0 +1,86 @@
+/**
+ * Zero-result query tracking
+ *
+ * Counts queries that found no confident match so catalog maintainers can
+ * see which gaps to close (`agents/misses`). Queries are grouped after
+ * case folding and whitespace collapsing. Only the most recently seen
+ * queries are kept, so memory stays bounded on a long-running server.
+ * Secrets in queries are redacted before they are stored, since
+ * `agents/misses` is open to every caller.
+ */
+
+import { compareStrings } from "./deterministic.js";
+import { redactor } from "./redact.js";
+import { foldCase } from "./tokenize.js";
+
+/**
+ * A query that found nothing
+ */
+export interface QueryMiss {
+  query: string;
+  count: number;
+  firstSeen: string;
+  lastSeen: string;
+}
+
+/**
+ * In-memory counts of zero-result queries
+ */
+export class MissTracker {
+  private misses = new Map<string, QueryMiss>();
+
+  /**
+   * @param maxEntries - Distinct queries kept; the least recently seen is
+   *   dropped first (default: 500)
+   */
+  constructor(private readonly maxEntries: number = 500) {}
+
+  /**
+   * Count a query that returned no results
+   */
+  record(query: string): void {
+    const key = foldCase(
+      redactor().redactText(query).trim().replace(/\s+/g, " "),
+    );
+    if (!key) {
+      return;
+    }
+
+    const now = new Date().toISOString();
+    const miss = this.misses.get(key) ?? {
+      query: key,
+      count: 0,
+      firstSeen: now,
+      lastSeen: now,
+    };
+    miss.count++;
+    miss.lastSeen = now;
+    // Re-insert so iteration order is least recently seen first
+    this.misses.delete(key);
+    this.misses.set(key, miss);
+
+    if (this.misses.size > this.maxEntries) {
+      const oldest = this.misses.keys().next().value;
+      if (oldest !== undefined) {
+        this.misses.delete(oldest);
+      }
+    }
+  }
+
+  /**
+   * Most frequent misses, then most recent
+   *
+   * @param limit - Maximum entries (default: 50)
+   */
+  list(limit: number = 50): QueryMiss[] {
+    return [...this.misses.values()]
+      .sort(
+        (a, b) =>
+          b.count - a.count ||
+          compareStrings(b.lastSeen, a.lastSeen) ||
+          compareStrings(a.query, b.query),
+      )
+      .slice(0, limit)
+      .map((miss) => ({ ...miss }));
+  }
+}
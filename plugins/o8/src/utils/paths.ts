@@ -0,0 +1,138 @@
+/**
+ * Cross-platform path helpers
+ *
+ * Resource ids always use `/`, but the directories they live in may be
+ * Windows paths with drive letters, UNC shares (`\\server\share`), or
+ * long-path prefixes (`\\?\C:\...`, `\\?\UNC\server\share`). Comparisons
+ * go through a canonical key that strips long-path prefixes and, on
+ * Windows, ignores case, so containment and duplicate-layer checks give
+ * the same answer however a path was spelled. The prefixes are only
+ * stripped for comparison; paths handed to the filesystem keep them.
+ *
+ * Every helper takes the platform as a parameter (default:
+ * process.platform) so Windows behavior can be tested anywhere.
+ */
+
+import path from "path";
+import { promises as fs, type Dirent } from "fs";
+
+type PathApi = typeof path.posix;
+
+/**
+ * Path functions for a platform
+ */
+export function pathApi(platform: NodeJS.Platform = process.platform): PathApi {
+  return platform === "win32" ? path.win32 : path.posix;
+}
+
+/**
+ * Remove a Windows long-path prefix: `\\?\C:\x` becomes `C:\x` and
+ * `\\?\UNC\server\share` becomes `\\server\share`
+ */
+export function stripLongPathPrefix(p: string): string {
+  if (/^\\\\\?\\UNC\\/i.test(p)) {
+    return `\\\\${p.slice(8)}`;
+  }
+  if (p.startsWith("\\\\?\\")) {
+    return p.slice(4);
+  }
+  return p;
+}
+
+/**
+ * Comparison key for a path: absolute, normalized, without a long-path
+ * prefix, and lower-cased on Windows
+ */
+export function pathKey(
+  p: string,
+  platform: NodeJS.Platform = process.platform,
+): string {
+  if (platform !== "win32") {
+    return path.posix.resolve(p);
+  }
+  return path.win32.resolve(stripLongPathPrefix(p)).toLowerCase();
+}
+
+/**
+ * Whether `candidate` is inside `root` (and not `root` itself)
+ */
+export function isWithin(
+  root: string,
+  candidate: string,
+  platform: NodeJS.Platform = process.platform,
+): boolean {
+  const api = pathApi(platform);
+  const rel = api.relative(pathKey(root, platform), pathKey(candidate, platform));
+  return (
+    rel !== "" &&
+    rel !== ".." &&
+    !rel.startsWith(`..${api.sep}`) &&
+    !api.isAbsolute(rel)
+  );
+}
+
+/**
+ * Find a file under `root` whose path matches `relativePath` ignoring case
+ *
+ * Each segment prefers an exact match, then the only case-insensitive
+ * match; several case-insensitive matches are ambiguous and give null.
+ *
+ * @param relativePath - Path below root, with `/` or the platform separator
+ * @returns The path as spelled on disk, or null
+ */
+export async function findCaseInsensitive(
+  root: string,
+  relativePath: string,
+): Promise<string | null> {
+  let dir = root;
+  for (const segment of relativePath.split(/[\\/]/).filter(Boolean)) {
+    let entries: string[];
+    try {
+      entries = await fs.readdir(dir);
+    } catch {
+      return null;
+    }
+    const wanted = segment.toLowerCase();
+    const matches = entries.includes(segment)
+      ? [segment]
+      : entries.filter((entry) => entry.toLowerCase() === wanted);
+    if (matches.length !== 1) {
+      return null;
+    }
+    dir = path.join(dir, matches[0]);
+  }
+  return dir;
+}
+
+/**
+ * Whether a directory entry is a directory, following symlinks
+ *
+ * A symlink to one of its own ancestors is not followed, so a link loop
+ * cannot make a recursive scan run forever.
+ *
+ * @param fullPath - Path of the entry
+ */
+export async function isDirectoryEntry(
+  entry: Dirent,
+  fullPath: string,
+): Promise<boolean> {
+  if (entry.isDirectory()) {
+    return true;
+  }
+  if (!entry.isSymbolicLink()) {
+    return false;
+  }
+  try {
+    const [target, parent] = await Promise.all([
+      fs.realpath(fullPath),
+      fs.realpath(path.dirname(fullPath)),
+    ]);
+    if (parent === target || isWithin(target, parent)) {
+      return false;
+    }
+    return (await fs.stat(target)).isDirectory();
+  } catch {
+    // Dangling link
+    return false;
+  }
+}
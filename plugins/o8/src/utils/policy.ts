@@ -0,0 +1,483 @@
+/**
+ * Request authorization policy
+ *
+ * Every request to /api/mcp/request is checked before it is dispatched.
+ * The caller's role comes from its bearer token: O8_ADMIN_TOKEN is the
+ * `admin` role, tokens listed in the policy file have the role given
+ * there, and requests without a token are `anonymous`. Over mutual TLS,
+ * a request without a token has the role the policy file gives its client
+ * certificate's subject name.
+ *
+ * O8_POLICY names a JSON file with token roles and rules. Token values are
+ * read from environment variables, so the file itself holds no secrets:
+ *
+ * ```json
+ * {
+ *   "tokens": { "O8_CI_TOKEN": "reader", "O8_OPS_TOKEN": "operator" },
+ *   "certificates": { "orchestrator.internal": "operator" },
+ *   "rules": [
+ *     { "methods": ["catalog/*", "state/gc"], "allow": "role in ['operator', 'admin']" },
+ *     { "methods": ["agents/route"], "allow": "role != 'anonymous' || params.maxTokens < 2000" }
+ *   ]
+ * }
+ * ```
+ *
+ * The first rule whose methods match decides. Rules are expressions over
+ * `role`, `method`, and `params` with `==`, `!=`, `<`, `<=`, `>`, `>=`,
+ * `in`, `&&`, `||`, `!`, parentheses, `startsWith(...)`, and string,
+ * number, boolean, null, and list literals. Without a matching rule, the
+ * built-in rule applies: admin methods need the `admin` role and every
+ * other method is allowed.
+ */
+
+import { readFileSync } from "fs";
+import { timingSafeEqual } from "crypto";
+
+export const ADMIN_ROLE = "admin";
+export const ANONYMOUS_ROLE = "anonymous";
+
+/**
+ * Methods that need the admin role unless a policy rule says otherwise
+ */
+export const ADMIN_METHODS: readonly string[] = [
+  "sessions/list",
+  "sessions/terminate",
+  "agents/rewrite",
+  "agents/journal",
+  "agents/grep",
+  "catalog/snapshot",
+  "catalog/rollback",
+  "catalog/reload",
+  "state/gc",
+];
+
+/**
+ * Error raised for invalid policy files and expressions
+ */
+export class PolicyError extends Error {
+  public readonly code = "INVALID_POLICY";
+
+  constructor(message: string) {
+    super(message);
+    this.name = "PolicyError";
+  }
+}
+
+export interface PolicyContext {
+  method: string;
+  role: string;
+  params?: unknown;
+}
+
+export interface PolicyDecision {
+  allowed: boolean;
+  role: string;
+  /** Methods of the deciding rule, or null for the built-in rule */
+  rule: string[] | null;
+}
+
+type Evaluate = (context: PolicyContext) => unknown;
+
+interface Token {
+  type: "punct" | "string" | "number" | "ident";
+  value: string;
+}
+
+const TOKEN =
+  /(==|!=|<=|>=|&&|\|\||[!<>()[\].,])|'((?:[^'\\]|\\.)*)'|"((?:[^"\\]|\\.)*)"|(-?\d+(?:\.\d+)?)|([A-Za-z_][A-Za-z0-9_]*)/y;
+
+function tokenize(source: string): Token[] {
+  const tokens: Token[] = [];
+  let index = 0;
+  while (index < source.length) {
+    if (/\s/.test(source[index])) {
+      index++;
+      continue;
+    }
+    TOKEN.lastIndex = index;
+    const match = TOKEN.exec(source);
+    if (!match) {
+      throw new PolicyError(
+        `Unexpected character at ${index} in policy rule: ${source}`,
+      );
+    }
+    index = TOKEN.lastIndex;
+    if (match[1]) {
+      tokens.push({ type: "punct", value: match[1] });
+    } else if (match[2] !== undefined || match[3] !== undefined) {
+      const raw = match[2] ?? match[3];
+      tokens.push({ type: "string", value: raw.replace(/\\(.)/g, "$1") });
+    } else if (match[4]) {
+      tokens.push({ type: "number", value: match[4] });
+    } else {
+      tokens.push({ type: "ident", value: match[5] });
+    }
+  }
+  return tokens;
+}
+
+/**
+ * Result of a binary operator; ordering needs two numbers or two strings
+ */
+function compare(operator: string, a: unknown, b: unknown): boolean {
+  switch (operator) {
+    case "==":
+      return a === b;
+    case "!=":
+      return a !== b;
+    case "in":
+      return Array.isArray(b) && b.includes(a);
+  }
+  if (
+    typeof a !== typeof b ||
+    (typeof a !== "number" && typeof a !== "string")
+  ) {
+    return false;
+  }
+  const [x, y] = [a as number, b as number];
+  return operator === "<"
+    ? x < y
+    : operator === "<="
+      ? x <= y
+      : operator === ">"
+        ? x > y
+        : x >= y;
+}
+
+/**
+ * Compile a rule expression
+ *
+ * @throws {PolicyError} If the expression is not valid
+ */
+export function compileExpression(source: string): Evaluate {
+  const tokens = tokenize(source);
+  let position = 0;
+
+  const fail = (message: string): never => {
+    throw new PolicyError(`${message} in policy rule: ${source}`);
+  };
+  const peek = (value: string) =>
+    tokens[position]?.type === "punct" && tokens[position].value === value;
+  const expect = (value: string) => {
+    if (!peek(value)) {
+      fail(`Expected "${value}"`);
+    }
+    position++;
+  };
+
+  const parseOr = (): Evaluate => {
+    let left = parseAnd();
+    while (peek("||")) {
+      position++;
+      const a = left;
+      const b = parseAnd();
+      left = (context) => Boolean(a(context)) || Boolean(b(context));
+    }
+    return left;
+  };
+
+  const parseAnd = (): Evaluate => {
+    let left = parseUnary();
+    while (peek("&&")) {
+      position++;
+      const a = left;
+      const b = parseUnary();
+      left = (context) => Boolean(a(context)) && Boolean(b(context));
+    }
+    return left;
+  };
+
+  const parseUnary = (): Evaluate => {
+    if (peek("!")) {
+      position++;
+      const operand = parseUnary();
+      return (context) => !operand(context);
+    }
+    return parseComparison();
+  };
+
+  const parseComparison = (): Evaluate => {
+    const left = parsePrimary();
+    const token = tokens[position];
+    const operator =
+      token?.type === "punct" &&
+      ["==", "!=", "<", "<=", ">", ">="].includes(token.value)
+        ? token.value
+        : token?.type === "ident" && token.value === "in"
+          ? "in"
+          : null;
+    if (!operator) {
+      return left;
+    }
+    position++;
+    const right = parsePrimary();
+    return (context) => compare(operator, left(context), right(context));
+  };
+
+  const parsePrimary = (): Evaluate => {
+    const token = tokens[position++];
+    if (!token) {
+      return fail("Unexpected end");
+    }
+    if (token.type === "string") {
+      return () => token.value;
+    }
+    if (token.type === "number") {
+      const value = Number(token.value);
+      return () => value;
+    }
+    if (token.type === "punct" && token.value === "(") {
+      const inner = parseOr();
+      expect(")");
+      return inner;
+    }
+    if (token.type === "punct" && token.value === "[") {
+      const items: Evaluate[] = [];
+      while (!peek("]")) {
+        items.push(parsePrimary());
+        if (!peek("]")) {
+          expect(",");
+        }
+      }
+      position++;
+      return (context) => items.map((item) => item(context));
+    }
+    if (token.type !== "ident") {
+      return fail(`Unexpected "${token.value}"`);
+    }
+
+    switch (token.value) {
+      case "true":
+        return () => true;
+      case "false":
+        return () => false;
+      case "null":
+        return () => null;
+      case "role":
+      case "method":
+      case "params":
+        break;
+      default:
+        return fail(`Unknown name "${token.value}"`);
+    }
+
+    let value: Evaluate = (context) => context[token.value as "role"] ?? null;
+    while (peek(".")) {
+      position++;
+      const name = tokens[position++];
+      if (name?.type !== "ident") {
+        return fail('Expected a name after "."');
+      }
+      const target = value;
+      if (peek("(")) {
+        if (name.value !== "startsWith") {
+          return fail(`Unknown function "${name.value}"`);
+        }
+        position++;
+        const argument = parsePrimary();
+        expect(")");
+        value = (context) => {
+          const text = target(context);
+          const prefix = argument(context);
+          return (
+            typeof text === "string" &&
+            typeof prefix === "string" &&
+            text.startsWith(prefix)
+          );
+        };
+      } else {
+        value = (context) => {
+          const object = target(context);
+          return object && typeof object === "object"
+            ? ((object as Record<string, unknown>)[name.value] ?? null)
+            : null;
+        };
+      }
+    }
+    return value;
+  };
+
+  const evaluate = parseOr();
+  if (position < tokens.length) {
+    fail(`Unexpected "${tokens[position].value}"`);
+  }
+  return evaluate;
+}
+
+/**
+ * Whether a method matches a rule pattern ("catalog/*", "*", or a name)
+ */
+function matchesMethod(pattern: string, method: string): boolean {
+  return pattern.endsWith("*")
+    ? method.startsWith(pattern.slice(0, -1))
+    : pattern === method;
+}
+
+function sameToken(given: string, expected: string): boolean {
+  const a = Buffer.from(given);
+  const b = Buffer.from(expected);
+  return a.length === b.length && timingSafeEqual(a, b);
+}
+
+/**
+ * Roles and rules for request authorization
+ */
+export class RequestPolicy {
+  private rules: Array<{ methods: string[]; allow: Evaluate }>;
+  private tokens: Array<{ token: string; role: string }>;
+  private certificates: Map<string, string>;
+
+  /** Whether an admin token is configured */
+  readonly adminEnabled: boolean;
+
+  /**
+   * @param options.rules - Rules in order; the first matching one decides
+   * @param options.tokens - Token value to role
+   * @param options.adminToken - Token of the admin role
+   * @param options.certificates - Client certificate subject name to role
+   */
+  constructor(
+    options: {
+      rules?: Array<{ methods: string[]; allow: string }>;
+      tokens?: Array<{ token: string; role: string }>;
+      adminToken?: string;
+      certificates?: Record<string, string>;
+    } = {},
+  ) {
+    this.certificates = new Map(Object.entries(options.certificates ?? {}));
+    this.rules = (options.rules ?? []).map((rule) => ({
+      methods: rule.methods,
+      allow: compileExpression(rule.allow),
+    }));
+    this.tokens = [
+      ...(options.adminToken
+        ? [{ token: options.adminToken, role: ADMIN_ROLE }]
+        : []),
+      ...(options.tokens ?? []),
+    ];
+    this.adminEnabled = Boolean(options.adminToken);
+  }
+
+  /**
+   * Role for a bearer token
+   *
+   * @param token - Token from the Authorization header, if any
+   * @returns The role, or null for a token that is not known when tokens
+   *   are configured
+   */
+  roleFor(token: string | null | undefined): string | null {
+    // Without configured tokens, an Authorization header meant for
+    // something else (a proxy) is not an error
+    if (!token || this.tokens.length === 0) {
+      return ANONYMOUS_ROLE;
+    }
+    // Compare against every token so timing doesn't reveal which matched
+    let role: string | null = null;
+    for (const entry of this.tokens) {
+      if (sameToken(token, entry.token) && role === null) {
+        role = entry.role;
+      }
+    }
+    return role;
+  }
+
+  /**
+   * Role for a verified client certificate
+   *
+   * @param subject - Subject common name of the certificate
+   * @returns The mapped role, or anonymous for certificates not listed
+   */
+  roleForCertificate(subject: string): string {
+    return this.certificates.get(subject) ?? ANONYMOUS_ROLE;
+  }
+
+  /**
+   * Decide whether a request may be dispatched
+   */
+  authorize(context: PolicyContext): PolicyDecision {
+    for (const rule of this.rules) {
+      const matches = rule.methods.some((pattern) =>
+        matchesMethod(pattern, context.method),
+      );
+      if (matches) {
+        return {
+          allowed: Boolean(rule.allow(context)),
+          role: context.role,
+          rule: rule.methods,
+        };
+      }
+    }
+    return {
+      allowed:
+        !ADMIN_METHODS.includes(context.method) || context.role === ADMIN_ROLE,
+      role: context.role,
+      rule: null,
+    };
+  }
+}
+
+/**
+ * Policy from O8_ADMIN_TOKEN and the O8_POLICY file
+ *
+ * @param env - Environment (default: process.env)
+ * @throws {PolicyError} If the policy file cannot be read or is invalid
+ */
+export function policyFromEnv(
+  env: NodeJS.ProcessEnv = process.env,
+): RequestPolicy {
+  const adminToken = env.O8_ADMIN_TOKEN || undefined;
+  if (!env.O8_POLICY) {
+    return new RequestPolicy({ adminToken });
+  }
+
+  let file: any;
+  try {
+    file = JSON.parse(readFileSync(env.O8_POLICY, "utf-8"));
+  } catch (error) {
+    throw new PolicyError(
+      `Cannot read policy file ${env.O8_POLICY}: ${(error as Error).message}`,
+    );
+  }
+  if (!file || typeof file !== "object") {
+    throw new PolicyError(`Policy file ${env.O8_POLICY} must hold an object`);
+  }
+
+  const tokens: Array<{ token: string; role: string }> = [];
+  for (const [variable, role] of Object.entries(file.tokens ?? {})) {
+    if (typeof role !== "string") {
+      throw new PolicyError(`Role for ${variable} must be a string`);
+    }
+    // A token whose variable is unset is left out, not an error, so one
+    // policy file can serve environments that only set some tokens
+    const token = env[variable];
+    if (token) {
+      tokens.push({ token, role });
+    }
+  }
+
+  const certificates: Record<string, string> = {};
+  for (const [subject, role] of Object.entries(file.certificates ?? {})) {
+    if (typeof role !== "string") {
+      throw new PolicyError(
+        `Role for certificate ${subject} must be a string`,
+      );
+    }
+    certificates[subject] = role;
+  }
+
+  const rules = file.rules ?? [];
+  if (
+    !Array.isArray(rules) ||
+    !rules.every(
+      (rule) =>
+        Array.isArray(rule?.methods) &&
+        rule.methods.every((method: unknown) => typeof method === "string") &&
+        typeof rule.allow === "string",
+    )
+  ) {
+    throw new PolicyError(
+      'Policy rules must be a list of { "methods": [...], "allow": "..." }',
+    );
+  }
+  return new RequestPolicy({ rules, tokens, adminToken, certificates });
+}
@@ -0,0 +1,62 @@
+/**
+ * Provenance of local resources in query results
+ *
+ * With several resource layers (see layers.ts) it is not obvious which
+ * file produced a suggestion. Every result carries the layer, file,
+ * content hash, and modification time of the resource it came from.
+ *
+ * @example
+ * ```typescript
+ * provenanceOf(fragment);
+ * // { layer: "project", sourceDir: "/repo/.orchestr8",
+ * //   filePath: "/repo/.orchestr8/agents/rust-expert.md",
+ * //   hash: "9f2c...", lastModified: "2026-10-01T12:00:00.000Z" }
+ * ```
+ */
+
+import type { ResourceFragment } from "./fuzzyMatcher.js";
+
+/**
+ * Where a result came from
+ */
+export interface Provenance {
+  /** Layer name (builtin, system, user, project, or snapshot:<name>) */
+  layer: string;
+  /** Layer directory */
+  sourceDir: string;
+  /** Resource file */
+  filePath: string;
+  /** SHA-256 of the file content (hex) */
+  hash: string;
+  /** File modification time (ISO 8601) */
+  lastModified?: string;
+}
+
+/**
+ * Provenance of a resource, or undefined for resources without a local file
+ */
+export function provenanceOf(
+  fragment: ResourceFragment,
+): Provenance | undefined {
+  if (!fragment.origin) {
+    return undefined;
+  }
+  const { layer, sourceDir, filePath, hash } = fragment.origin;
+  return {
+    layer,
+    sourceDir,
+    filePath,
+    hash,
+    ...(fragment.modifiedAt ? { lastModified: fragment.modifiedAt } : {}),
+  };
+}
+
+/**
+ * `{ provenance }` for spreading into a result, or nothing
+ */
+export function withProvenance(
+  fragment: ResourceFragment,
+): { provenance?: Provenance } {
+  const provenance = provenanceOf(fragment);
+  return provenance ? { provenance } : {};
+}
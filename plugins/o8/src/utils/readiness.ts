@@ -0,0 +1,90 @@
+/**
+ * Catalog readiness
+ *
+ * The server answers `initialize` before the resource index is built, so
+ * clients no longer need to sleep after starting it. Queries that arrive
+ * while the index is still being built wait for it for a bounded time
+ * (`--ready-timeout`, `ORCHESTR8_READY_TIMEOUT`); if it is still not ready
+ * they fail with a retriable `CatalogNotReadyError`.
+ */
+
+import { McpError } from "@modelcontextprotocol/sdk/types.js";
+import { flagValue } from "./flags.js";
+
+/**
+ * JSON-RPC error code of `CatalogNotReadyError` (implementation-defined
+ * server error range)
+ */
+export const CATALOG_NOT_READY_CODE = -32010;
+
+/**
+ * Default milliseconds a query waits for the index
+ */
+export const DEFAULT_READY_TIMEOUT = 10000;
+
+/**
+ * Raised when a query times out waiting for the index; retry after
+ * `retryAfterMs`
+ */
+export class CatalogNotReadyError extends McpError {
+  constructor(public readonly retryAfterMs: number) {
+    super(CATALOG_NOT_READY_CODE, "Resource catalog is still loading", {
+      reason: "CATALOG_NOT_READY",
+      retryable: true,
+      retryAfterMs,
+    });
+    this.name = "CatalogNotReadyError";
+  }
+}
+
+/**
+ * Milliseconds to wait for the index from --ready-timeout
+ * (ORCHESTR8_READY_TIMEOUT); 0 fails at once while the index is loading
+ *
+ * @throws {Error} If the value is not a non-negative integer
+ */
+export function readyTimeout(
+  argv: string[] = process.argv,
+  env: NodeJS.ProcessEnv = process.env,
+): number {
+  const value = flagValue("ready-timeout", argv, env);
+  if (value === undefined) {
+    return DEFAULT_READY_TIMEOUT;
+  }
+  const timeout = Number(value);
+  if (!Number.isInteger(timeout) || timeout < 0) {
+    throw new Error(
+      `Invalid --ready-timeout: ${value} (expected milliseconds, 0 or more)`,
+    );
+  }
+  return timeout;
+}
+
+/**
+ * Wait for `ready`, at most `timeoutMs`
+ *
+ * @throws {CatalogNotReadyError} If `ready` has not settled in time
+ */
+export async function waitForReady(
+  ready: Promise<unknown>,
+  timeoutMs: number,
+): Promise<void> {
+  let timer: NodeJS.Timeout | undefined;
+  const timedOut = new Promise<boolean>((resolve) => {
+    timer = setTimeout(() => resolve(true), timeoutMs);
+  });
+  try {
+    const expired = await Promise.race([
+      ready.then(
+        () => false,
+        () => false,
+      ),
+      timedOut,
+    ]);
+    if (expired) {
+      throw new CatalogNotReadyError(Math.max(timeoutMs, 1000));
+    }
+  } finally {
+    clearTimeout(timer);
+  }
+}
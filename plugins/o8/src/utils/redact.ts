@@ -0,0 +1,184 @@
+/**
+ * Secret redaction
+ *
+ * Request params, error messages, and client payloads can carry API keys
+ * and tokens. Everything the server writes down - log lines, session
+ * recordings, the activity log, crash reports, and the query log - goes
+ * through a redactor first, which replaces:
+ *
+ * - string values of fields whose name looks secret (`token`, `apiKey`,
+ *   `password`, `authorization`, ...), at any depth
+ * - substrings matching a secret pattern: common API key formats, bearer
+ *   tokens, JWTs, and the patterns in O8_REDACT_PATTERNS
+ *
+ * O8_REDACT_PATTERNS is a regular expression, or a JSON array of them:
+ *
+ * @example
+ * ```bash
+ * O8_REDACT_PATTERNS='["acme_[a-z0-9]{32}", "(?<=password=)[^&\\s]+"]'
+ * ```
+ */
+
+/**
+ * What a redacted value is replaced with
+ */
+export const REDACTED = "[REDACTED]";
+
+/**
+ * Formats of well-known credentials
+ */
+export const DEFAULT_SECRET_PATTERNS: readonly RegExp[] = [
+  // OpenAI, Anthropic, and similar "sk-" keys
+  /\bsk-[A-Za-z0-9_-]{16,}/g,
+  // GitHub tokens
+  /\b(?:gh[pousr]_[A-Za-z0-9]{20,}|github_pat_[A-Za-z0-9_]{20,})/g,
+  // AWS access key ids
+  /\b(?:AKIA|ASIA)[0-9A-Z]{16}\b/g,
+  // Slack tokens
+  /\bxox[abprs]-[A-Za-z0-9-]{10,}/g,
+  // JWTs
+  /\beyJ[A-Za-z0-9_-]{8,}\.eyJ[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]+/g,
+  // Bearer and basic credentials in headers
+  /(?<=\b(?:Bearer|Basic)\s+)[A-Za-z0-9._~+/-]{8,}=*/gi,
+];
+
+/**
+ * Names of fields whose string values are always redacted (`token`,
+ * `accessToken`, `api_key`, ...; not `maxTokens`)
+ */
+const SECRET_KEY =
+  /(?:token|secret|password|passwd|api[-_]?key|authorization|credentials?|private[-_]?key|cookie)$/i;
+
+/**
+ * Masks secrets in text and structured values
+ */
+export class Redactor {
+  constructor(
+    private readonly patterns: readonly RegExp[] = DEFAULT_SECRET_PATTERNS,
+  ) {}
+
+  /**
+   * Text with every secret pattern replaced
+   */
+  redactText(text: string): string {
+    let result = text;
+    for (const pattern of this.patterns) {
+      result = result.replace(pattern, REDACTED);
+    }
+    return result;
+  }
+
+  /**
+   * Copy of a value with secrets replaced, at any depth
+   *
+   * Errors become `{ name, message, stack }`; cycles become "[Circular]".
+   */
+  redact<T>(value: T): T {
+    return this.walk(value, new WeakSet()) as T;
+  }
+
+  private walk(value: unknown, seen: WeakSet<object>): unknown {
+    if (typeof value === "string") {
+      return this.redactText(value);
+    }
+    if (!value || typeof value !== "object") {
+      return value;
+    }
+    if (seen.has(value)) {
+      return "[Circular]";
+    }
+    seen.add(value);
+    try {
+      if (value instanceof Error) {
+        return {
+          name: value.name,
+          message: this.redactText(value.message),
+          ...(value.stack ? { stack: this.redactText(value.stack) } : {}),
+        };
+      }
+      if (Array.isArray(value)) {
+        return value.map((item) => this.walk(item, seen));
+      }
+      if (value instanceof Date) {
+        return value;
+      }
+      const copy: Record<string, unknown> = {};
+      for (const [key, item] of Object.entries(value)) {
+        copy[key] =
+          typeof item === "string" && SECRET_KEY.test(key)
+            ? REDACTED
+            : this.walk(item, seen);
+      }
+      return copy;
+    } finally {
+      seen.delete(value);
+    }
+  }
+}
+
+/**
+ * Secret patterns from O8_REDACT_PATTERNS, after the defaults
+ *
+ * @throws {Error} If a pattern is not a valid regular expression
+ */
+export function redactionPatterns(
+  env: NodeJS.ProcessEnv = process.env,
+): RegExp[] {
+  const spec = env.O8_REDACT_PATTERNS?.trim();
+  if (!spec) {
+    return [...DEFAULT_SECRET_PATTERNS];
+  }
+  let sources: unknown;
+  try {
+    sources = spec.startsWith("[") ? JSON.parse(spec) : [spec];
+  } catch (error) {
+    throw new Error(`Invalid O8_REDACT_PATTERNS: ${(error as Error).message}`);
+  }
+  if (
+    !Array.isArray(sources) ||
+    !sources.every((source) => typeof source === "string")
+  ) {
+    throw new Error(
+      "Invalid O8_REDACT_PATTERNS: expected a regular expression or a JSON array of them",
+    );
+  }
+  return [
+    ...DEFAULT_SECRET_PATTERNS,
+    ...sources.map((source) => {
+      try {
+        return new RegExp(source, "g");
+      } catch (error) {
+        throw new Error(
+          `Invalid O8_REDACT_PATTERNS: ${(error as Error).message}`,
+        );
+      }
+    }),
+  ];
+}
+
+const redactors = new Map<string, Redactor>();
+
+/**
+ * Redactor for the configured patterns, shared by all writers
+ *
+ * Invalid O8_REDACT_PATTERNS are reported on stderr and the defaults are
+ * used, since the logger itself depends on the redactor.
+ *
+ * @param env - Environment (default: process.env)
+ */
+export function redactor(env: NodeJS.ProcessEnv = process.env): Redactor {
+  const spec = env.O8_REDACT_PATTERNS ?? "";
+  let instance = redactors.get(spec);
+  if (!instance) {
+    let patterns: readonly RegExp[];
+    try {
+      patterns = redactionPatterns(env);
+    } catch (error) {
+      console.error(`${(error as Error).message}; using the default patterns`);
+      patterns = DEFAULT_SECRET_PATTERNS;
+    }
+    instance = new Redactor(patterns);
+    redactors.set(spec, instance);
+  }
+  return instance;
+}
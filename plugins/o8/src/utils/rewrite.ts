@@ -0,0 +1,204 @@
+/**
+ * Bulk find-and-replace across resource files
+ *
+ * Renaming a tool or moving a documentation URL touches dozens of agents.
+ * A rewrite applies one regex or literal replacement to every selected
+ * file. Changed files are staged next to their targets first and only
+ * renamed into place once all of them were written, so a failure (disk
+ * full, permissions) leaves the catalog as it was.
+ *
+ * @example
+ * ```typescript
+ * const regex = compileRewrite("deploy-v1", { literal: true });
+ * applyRewrite("tools: [deploy-v1]", regex, "deploy-v2");
+ * // { content: 'tools: [deploy-v2]', replacements: 1 }
+ * ```
+ */
+
+import { promises as fs } from "fs";
+import { compilePattern } from "./grep.js";
+
+/**
+ * Rewrite request
+ */
+export interface RewriteRequest {
+  /** Regular expression, or literal text with `literal` */
+  pattern: string;
+  /** Replacement; `$1`, `$&`, ... refer to groups unless `literal` */
+  replacement: string;
+  /** Match and replace the pattern as plain text */
+  literal?: boolean;
+  ignoreCase?: boolean;
+  /** Only these resources (names or ids; default: the whole category) */
+  ids?: string[];
+  /** Category to rewrite (default: agents) */
+  category?: string;
+  /** Report the diff without writing */
+  dryRun?: boolean;
+  /**
+   * Content hash each resource must still have, by id (the `hash` of a dry
+   * run's changes); any mismatch fails the whole rewrite
+   */
+  expectedHashes?: Record<string, string>;
+}
+
+/**
+ * One file changed by a rewrite
+ */
+export interface RewriteChange {
+  id: string;
+  /** File that was (or would be) written */
+  path: string;
+  replacements: number;
+  /** Unified diff of the change */
+  diff: string;
+  /** SHA-256 of the file before the rewrite */
+  hash: string;
+}
+
+/**
+ * A resource changed since its expected hash was taken
+ */
+export interface RewriteConflict {
+  id: string;
+  expected: string;
+  /** Current hash, or null if the resource is gone */
+  actual: string | null;
+}
+
+/**
+ * Error raised when expectedHashes do not match, before anything is written
+ */
+export class RewriteConflictError extends Error {
+  public readonly code = "CONFLICT";
+
+  constructor(public readonly conflicts: RewriteConflict[]) {
+    super(
+      `Resources changed since they were read: ${conflicts.map((c) => c.id).join(", ")}`,
+    );
+    this.name = "RewriteConflictError";
+  }
+}
+
+/**
+ * Rewrite request from the params of agents/rewrite
+ *
+ * @throws Error if "pattern" or "replacement" is missing, "ids" is not a
+ *   list of strings, or "expectedHashes" is not a map of strings
+ */
+export function parseRewriteRequest(
+  params: Record<string, any> | undefined,
+): RewriteRequest {
+  if (typeof params?.pattern !== "string" || !params.pattern) {
+    throw new Error('agents/rewrite requires "pattern"');
+  }
+  if (typeof params.replacement !== "string") {
+    throw new Error('agents/rewrite requires "replacement": a string');
+  }
+  const ids = params.ids;
+  if (
+    ids !== undefined &&
+    (!Array.isArray(ids) || !ids.every((id: unknown) => typeof id === "string"))
+  ) {
+    throw new Error('agents/rewrite "ids" must be a list of names or ids');
+  }
+  const expectedHashes = params.expectedHashes;
+  if (
+    expectedHashes !== undefined &&
+    (expectedHashes === null ||
+      typeof expectedHashes !== "object" ||
+      Array.isArray(expectedHashes) ||
+      !Object.values(expectedHashes).every((hash) => typeof hash === "string"))
+  ) {
+    throw new Error(
+      'agents/rewrite "expectedHashes" must map resource ids to hashes',
+    );
+  }
+  return {
+    pattern: params.pattern,
+    replacement: params.replacement,
+    literal: params.literal === true,
+    ignoreCase: params.ignoreCase === true,
+    ...(ids ? { ids } : {}),
+    ...(typeof params.category === "string"
+      ? { category: params.category }
+      : {}),
+    dryRun: params.dryRun === true,
+    ...(expectedHashes ? { expectedHashes } : {}),
+  };
+}
+
+/**
+ * Compile a rewrite pattern for replacing every occurrence
+ *
+ * @throws {GrepPatternError} If the pattern is empty, too long, or invalid
+ */
+export function compileRewrite(
+  pattern: string,
+  options: { literal?: boolean; ignoreCase?: boolean } = {},
+): RegExp {
+  const source = options.literal
+    ? pattern.replace(/[.*+?^${}()|[\]\\]/g, "\\$&")
+    : pattern;
+  const regex = compilePattern(source, options);
+  return new RegExp(regex.source, `${regex.flags}g`);
+}
+
+/**
+ * Replace every match in one file's content
+ *
+ * @param regex - Pattern from compileRewrite
+ * @param literal - Insert the replacement as is, without `$` references
+ */
+export function applyRewrite(
+  content: string,
+  regex: RegExp,
+  replacement: string,
+  literal: boolean = false,
+): { content: string; replacements: number } {
+  const replacements = content.match(regex)?.length ?? 0;
+  if (replacements === 0) {
+    return { content, replacements };
+  }
+  return {
+    content: literal
+      ? content.replace(regex, () => replacement)
+      : content.replace(regex, replacement),
+    replacements,
+  };
+}
+
+/**
+ * Temporary file a new version of `path` is staged in
+ */
+export function stagingPath(path: string): string {
+  return `${path}.${process.pid}.rewrite.tmp`;
+}
+
+/**
+ * Write several files so that either all or none of them change
+ *
+ * Contents are written to temporary files beside their targets, then
+ * renamed over them. If any temporary file cannot be written, the others
+ * are removed and no target is touched.
+ *
+ * @throws The first write error
+ */
+export async function writeFilesAtomically(
+  files: Array<{ path: string; content: string }>,
+): Promise<void> {
+  const staged: Array<{ tmp: string; path: string }> = [];
+  try {
+    for (const file of files) {
+      const tmp = stagingPath(file.path);
+      staged.push({ tmp, path: file.path });
+      await fs.writeFile(tmp, file.content);
+    }
+  } catch (error) {
+    await Promise.all(staged.map(({ tmp }) => fs.rm(tmp, { force: true })));
+    throw error;
+  }
+  for (const { tmp, path } of staged) {
+    await fs.rename(tmp, path);
+  }
+}
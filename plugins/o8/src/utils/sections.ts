@@ -0,0 +1,127 @@
+/**
+ * Markdown sections of resource bodies
+ *
+ * Agents are long, and an orchestrator with a tight context window often
+ * needs one part of them, such as the "## Output Format" instructions.
+ * A section runs from its heading to the next heading of the same or a
+ * higher level, so it includes its subsections. Headings inside fenced
+ * code blocks are not sections.
+ *
+ * @example
+ * ```typescript
+ * findSection(body, "output-format")?.content;
+ * // '## Output Format\n\nReturn a JSON object ...'
+ * ```
+ */
+
+/**
+ * One heading and the text under it
+ */
+export interface MarkdownSection {
+  /** Heading text without the leading #s */
+  heading: string;
+  /** Heading level (1-6) */
+  level: number;
+  /** GitHub-style anchor (e.g. 'output-format') */
+  slug: string;
+  /** Heading line through the end of the section, subsections included */
+  content: string;
+  /** Heading line up to the next heading of any level */
+  ownContent: string;
+}
+
+/**
+ * Error raised when a requested section does not exist
+ */
+export class SectionNotFoundError extends Error {
+  public readonly code = "SECTION_NOT_FOUND";
+
+  constructor(
+    public readonly section: string,
+    public readonly available: string[],
+  ) {
+    super(
+      `Section not found: ${section}` +
+        (available.length > 0 ? ` (sections: ${available.join(", ")})` : ""),
+    );
+    this.name = "SectionNotFoundError";
+  }
+}
+
+/**
+ * GitHub-style anchor for a heading
+ */
+export function slugify(heading: string): string {
+  return heading
+    .toLowerCase()
+    .replace(/[^\p{L}\p{N}\s_-]/gu, "")
+    .trim()
+    .replace(/\s+/g, "-");
+}
+
+/**
+ * Split a markdown body into sections, in document order
+ *
+ * @param body - Markdown without frontmatter
+ * @returns Every heading's section; nested sections appear both on their
+ *   own and inside their parent's content
+ */
+export function parseSections(body: string): MarkdownSection[] {
+  const lines = body.split("\n");
+  const headings: Array<{ line: number; level: number; heading: string }> =
+    [];
+  let fence: string | null = null;
+  lines.forEach((line, i) => {
+    const marker = /^\s*(`{3,}|~{3,})/.exec(line)?.[1];
+    if (marker) {
+      if (fence === null) {
+        fence = marker[0];
+      } else if (marker[0] === fence) {
+        fence = null;
+      }
+      return;
+    }
+    const match =
+      fence === null ? /^(#{1,6})\s+(.+?)(?:\s+#+)?\s*$/.exec(line) : null;
+    if (match) {
+      headings.push({ line: i, level: match[1].length, heading: match[2] });
+    }
+  });
+
+  return headings.map((current, index) => {
+    const next = headings
+      .slice(index + 1)
+      .find((heading) => heading.level <= current.level);
+    const end = next ? next.line : lines.length;
+    const ownEnd = headings[index + 1]?.line ?? lines.length;
+    return {
+      heading: current.heading,
+      level: current.level,
+      slug: slugify(current.heading),
+      content: lines.slice(current.line, end).join("\n").trimEnd(),
+      ownContent: lines.slice(current.line, ownEnd).join("\n").trimEnd(),
+    };
+  });
+}
+
+/**
+ * First section matching a name
+ *
+ * @param body - Markdown without frontmatter
+ * @param name - Heading text with or without #s, or its slug; case is
+ *   ignored ('## Output Format', 'output format', 'output-format')
+ * @returns The section, or null if no heading matches
+ */
+export function findSection(
+  body: string,
+  name: string,
+): MarkdownSection | null {
+  const wanted = name.replace(/^#+\s*/, "").trim().toLowerCase();
+  const slug = slugify(wanted);
+  return (
+    parseSections(body).find(
+      (section) =>
+        section.heading.toLowerCase() === wanted || section.slug === slug,
+    ) ?? null
+  );
+}
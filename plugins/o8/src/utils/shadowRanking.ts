@@ -0,0 +1,182 @@
+/**
+ * Shadow ranking for scoring experiments
+ *
+ * With O8_SHADOW_WEIGHTS set, every fuzzy match is scored a second time
+ * with the experimental weights after the response has been built. When
+ * the two top-k rankings differ, the disagreement is appended as one JSON
+ * line to O8_SHADOW_LOG (default: <state dir>/shadow-ranking.jsonl), with
+ * secrets in the query redacted.
+ * Responses always use the primary weights, so a weight change can be
+ * evaluated on real traffic before it is switched on.
+ *
+ * O8_SHADOW_WEIGHTS is a JSON object of weights to override, or the path
+ * of a file containing one:
+ *
+ * @example
+ * ```bash
+ * O8_SHADOW_WEIGHTS='{"phrase": 30, "useWhen": 8}'
+ * ```
+ */
+
+import { appendFileSync, mkdirSync, readFileSync } from "fs";
+import { dirname, join } from "path";
+import { DEFAULT_WEIGHTS, ScoringWeights } from "./fuzzyMatcher.js";
+import { defaultStateDir } from "../state/snapshotStore.js";
+import { Logger } from "./logger.js";
+import { redactor } from "./redact.js";
+
+const logger = new Logger("ShadowRanking");
+
+/**
+ * A query where the shadow ranking differed from the primary one
+ */
+export interface RankDisagreement {
+  timestamp: string;
+  query: string;
+  /** Number of top results compared */
+  k: number;
+  /** Top-k ids by primary score */
+  primary: string[];
+  /** Top-k ids by shadow score */
+  shadow: string[];
+  /** Whether the first result differs */
+  topChanged: boolean;
+  /** Share of ids in both top-k lists (0-1) */
+  overlap: number;
+  /** Ids whose rank changed (null = not in that top k) */
+  moved: Array<{ id: string; primary: number | null; shadow: number | null }>;
+}
+
+/**
+ * Compare two rankings of resource ids
+ *
+ * @param query - Query that produced the rankings
+ * @param primary - Ids by primary score, best first
+ * @param shadow - Ids by shadow score, best first
+ * @param k - Number of top results to compare
+ * @returns The disagreement, or null if the top k are identical
+ */
+export function compareRankings(
+  query: string,
+  primary: string[],
+  shadow: string[],
+  k: number,
+): RankDisagreement | null {
+  const top = primary.slice(0, k);
+  const shadowTop = shadow.slice(0, k);
+  if (
+    top.length === shadowTop.length &&
+    top.every((id, i) => id === shadowTop[i])
+  ) {
+    return null;
+  }
+
+  const ids = [...new Set([...top, ...shadowTop])];
+  const shared = top.filter((id) => shadowTop.includes(id)).length;
+  const rank = (list: string[], id: string) =>
+    list.includes(id) ? list.indexOf(id) + 1 : null;
+
+  return {
+    timestamp: new Date().toISOString(),
+    query,
+    k,
+    primary: top,
+    shadow: shadowTop,
+    topChanged: top[0] !== shadowTop[0],
+    overlap: ids.length === 0 ? 1 : shared / ids.length,
+    moved: ids
+      .map((id) => ({ id, primary: rank(top, id), shadow: rank(shadowTop, id) }))
+      .filter((entry) => entry.primary !== entry.shadow),
+  };
+}
+
+/**
+ * Parse experimental weights: a JSON object or a path to a JSON file
+ *
+ * @returns DEFAULT_WEIGHTS with the given overrides
+ * @throws {Error} If the JSON is invalid or names an unknown weight
+ */
+export function parseWeights(spec: string): ScoringWeights {
+  const text = spec.trim().startsWith("{") ? spec : readFileSync(spec, "utf-8");
+  const overrides: unknown = JSON.parse(text);
+  if (!overrides || typeof overrides !== "object" || Array.isArray(overrides)) {
+    throw new Error("Shadow weights must be a JSON object");
+  }
+
+  const weights: ScoringWeights = { ...DEFAULT_WEIGHTS };
+  for (const [name, value] of Object.entries(overrides)) {
+    if (!Object.hasOwn(DEFAULT_WEIGHTS, name)) {
+      throw new Error(
+        `Unknown scoring weight: ${name} (expected ${Object.keys(DEFAULT_WEIGHTS).join(", ")})`,
+      );
+    }
+    if (typeof value !== "number" || !Number.isFinite(value)) {
+      throw new Error(`Scoring weight ${name} must be a number`);
+    }
+    weights[name as keyof ScoringWeights] = value;
+  }
+  return weights;
+}
+
+/**
+ * Experimental weights and where to log disagreements
+ */
+export class ShadowRanker {
+  constructor(
+    public readonly weights: ScoringWeights,
+    private readonly logPath: string,
+  ) {}
+
+  /**
+   * Compare rankings and log a disagreement
+   *
+   * Secrets in the query are redacted in the log. Logging failures are
+   * reported and otherwise ignored.
+   *
+   * @returns The disagreement, or null if the rankings agree
+   */
+  record(
+    query: string,
+    primary: string[],
+    shadow: string[],
+    k: number,
+  ): RankDisagreement | null {
+    const disagreement = compareRankings(query, primary, shadow, k);
+    if (!disagreement) {
+      return null;
+    }
+
+    logger.info(
+      `Shadow ranking differs for "${query}" (overlap ${disagreement.overlap.toFixed(2)}${disagreement.topChanged ? ", top result changed" : ""})`,
+    );
+    try {
+      mkdirSync(dirname(this.logPath), { recursive: true });
+      const entry = {
+        ...disagreement,
+        query: redactor().redactText(disagreement.query),
+      };
+      appendFileSync(this.logPath, JSON.stringify(entry) + "\n");
+    } catch (error) {
+      logger.warn(`Failed to write shadow ranking log ${this.logPath}:`, error);
+    }
+    return disagreement;
+  }
+}
+
+/**
+ * Shadow ranker from O8_SHADOW_WEIGHTS and O8_SHADOW_LOG, if configured
+ *
+ * @param env - Environment (default: process.env)
+ * @throws {Error} If O8_SHADOW_WEIGHTS is invalid
+ */
+export function shadowRankerFromEnv(
+  env: NodeJS.ProcessEnv = process.env,
+): ShadowRanker | null {
+  if (!env.O8_SHADOW_WEIGHTS) {
+    return null;
+  }
+  return new ShadowRanker(
+    parseWeights(env.O8_SHADOW_WEIGHTS),
+    env.O8_SHADOW_LOG || join(defaultStateDir(env), "shadow-ranking.jsonl"),
+  );
+}
@@ -0,0 +1,97 @@
+/**
+ * Cold-start profile (--profile-startup)
+ *
+ * With `--profile-startup` (ORCHESTR8_PROFILE_STARTUP) the server times each
+ * startup phase and, once the resource index is ready, writes one JSON
+ * line to stderr:
+ *
+ * ```json
+ * {"startupProfile":{"totalMs":812.4,"sinceProcessStartMs":1034.9,
+ *   "phases":{"prompts":12.1,"resources":40.3,"index.state":1.2,
+ *   "index.walk":310.5,"index.read":402.8,"index.parse":95.0,...},
+ *   "counts":{"files":1204,"resources":1198,"failures":6}}}
+ * ```
+ *
+ * Server phases (prompts, resources, extensions, providers, upstreams)
+ * run one after another. Index phases are prefixed with `index.`: `walk`
+ * lists directories, `read` reads files, and `parse` parses frontmatter;
+ * these three add up the time of every call, and categories are scanned
+ * in parallel, so together they can exceed `index.scan`, the wall-clock
+ * time of the whole scan. `index.state` is journal recovery and the
+ * persisted pin, and `index.validate` checks aliases for conflicts.
+ */
+
+import { performance } from "perf_hooks";
+import { flagEnabled } from "./flags.js";
+
+/**
+ * Startup profile as written to stderr
+ */
+export interface StartupReport {
+  /** From creating the profile to the report */
+  totalMs: number;
+  /** Including module loading before the profile was created */
+  sinceProcessStartMs: number;
+  /** Milliseconds per phase, in the order phases first ran */
+  phases: Record<string, number>;
+  counts: Record<string, number>;
+}
+
+/**
+ * Whether --profile-startup (ORCHESTR8_PROFILE_STARTUP) is on
+ */
+export function startupProfilingEnabled(
+  argv: string[] = process.argv,
+  env: NodeJS.ProcessEnv = process.env,
+): boolean {
+  return flagEnabled("profile-startup", argv, env);
+}
+
+const round = (ms: number) => Math.round(ms * 10) / 10;
+
+/**
+ * Accumulates phase timings and counts
+ */
+export class StartupProfile {
+  private readonly startedAt = performance.now();
+  private readonly phases = new Map<string, number>();
+  private readonly counts = new Map<string, number>();
+
+  /**
+   * Time an async phase; a phase that runs several times adds up
+   */
+  async time<T>(phase: string, fn: () => Promise<T>): Promise<T> {
+    const start = performance.now();
+    try {
+      return await fn();
+    } finally {
+      this.add(phase, performance.now() - start);
+    }
+  }
+
+  /**
+   * Add milliseconds to a phase
+   */
+  add(phase: string, ms: number): void {
+    this.phases.set(phase, (this.phases.get(phase) ?? 0) + ms);
+  }
+
+  /**
+   * Set a count (files, resources, ...)
+   */
+  count(name: string, value: number): void {
+    this.counts.set(name, value);
+  }
+
+  report(): StartupReport {
+    const now = performance.now();
+    return {
+      totalMs: round(now - this.startedAt),
+      sinceProcessStartMs: round(now),
+      phases: Object.fromEntries(
+        [...this.phases].map(([phase, ms]) => [phase, round(ms)]),
+      ),
+      counts: Object.fromEntries(this.counts),
+    };
+  }
+}
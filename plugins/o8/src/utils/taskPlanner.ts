@@ -0,0 +1,108 @@
+/**
+ * Multi-agent plans for a task
+ *
+ * `orchestrate/plan` splits a task description into phases and routes each
+ * phase to an agent with `routeAgent`, so a client gets a reviewable plan
+ * instead of a single recommendation. Phases come from the structure of the
+ * description: numbered or bulleted lines, sentences, and "then" clauses
+ * ("implement the parser, then review it for security").
+ *
+ * The plan is a DAG of steps. Phases written in sequence depend on the step
+ * before them; the client may edit steps, agents, and dependencies before
+ * running them.
+ */
+
+import type { FuzzyMatcher, ResourceFragment } from "./fuzzyMatcher.js";
+import {
+  routeAgent,
+  type RouteCandidate,
+  type RouteRequest,
+} from "./agentRouter.js";
+
+/**
+ * Most phases a plan has; longer descriptions are merged into the last one
+ */
+export const MAX_PLAN_STEPS = 10;
+
+export type PlanRequest = Omit<RouteRequest, "alternatives">;
+
+export interface PlanStep {
+  /** Step id ("step-1", ...), referenced by dependsOn */
+  id: string;
+  /** Phase of the task this step covers */
+  task: string;
+  /** Recommended agent; null if no agent qualifies */
+  agent: RouteCandidate | null;
+  /** Runners-up the client can swap in */
+  alternatives: RouteCandidate[];
+  fallback?: boolean;
+  /** Steps that must finish first */
+  dependsOn: string[];
+}
+
+export interface TaskPlan {
+  task: string;
+  steps: PlanStep[];
+  /** Sum of the recommended agents' estimated tokens */
+  estimatedTokens: number;
+  /** Steps without a recommended agent */
+  unassigned: string[];
+}
+
+/**
+ * Phases of a task description, in order
+ */
+export function splitPhases(task: string): string[] {
+  const phases = task
+    // List items and sentences
+    .split(/\n+\s*(?:[-*]|\d+[.)])?\s*|(?<=[.;!?])\s+/)
+    // "then" clauses
+    .flatMap((part) => part.split(/,?\s*\b(?:and\s+)?then\b\s*/i))
+    .map((phase) => phase.replace(/^\s*(?:[-*]|\d+[.)])\s*/, ""))
+    .map((phase) => phase.replace(/[\s.;!?,]+$/, "").trim())
+    .filter((phase) => phase.length > 0);
+
+  if (phases.length > MAX_PLAN_STEPS) {
+    const rest = phases.splice(MAX_PLAN_STEPS - 1);
+    phases.push(rest.join("; "));
+  }
+  return phases.length > 0 ? phases : [task.trim()];
+}
+
+/**
+ * Plan a task as a sequence of agent steps
+ *
+ * @param fragments - Resource index
+ * @param matcher - Matcher used for scoring
+ */
+export function planTask(
+  fragments: ResourceFragment[],
+  matcher: FuzzyMatcher,
+  request: PlanRequest,
+): TaskPlan {
+  const steps = splitPhases(request.task).map((phase, index): PlanStep => {
+    const decision = routeAgent(fragments, matcher, {
+      ...request,
+      task: phase,
+      alternatives: 2,
+    });
+    return {
+      id: `step-${index + 1}`,
+      task: phase,
+      agent: decision.agent,
+      alternatives: decision.alternatives,
+      ...(decision.fallback ? { fallback: true } : {}),
+      dependsOn: index > 0 ? [`step-${index}`] : [],
+    };
+  });
+
+  return {
+    task: request.task,
+    steps,
+    estimatedTokens: steps.reduce(
+      (sum, step) => sum + (step.agent?.estimatedTokens ?? 0),
+      0,
+    ),
+    unassigned: steps.filter((step) => !step.agent).map((step) => step.id),
+  };
+}
@@ -0,0 +1,420 @@
+/**
+ * Template engine for resource bodies
+ * Renders {{var}} placeholders against variables declared in frontmatter
+ *
+ * @example Frontmatter declaration
+ * ---
+ * variables:
+ *   - name: lang
+ *     type: string
+ *     required: true
+ *   - name: maxFiles
+ *     type: number
+ *     default: 10
+ * ---
+ *
+ * @example Resource URI with variables
+ * o8://agents/code-reviewer?lang=rust&maxFiles=5
+ *
+ * @example Conditional sections
+ * {{#if lang == "rust"}}Run cargo clippy.{{else}}Run the linter.{{/if}}
+ *
+ * @example Partial include (resolved against resources/partials/)
+ * {{> shared/code-style}}
+ */
+
+/**
+ * Supported template variable types
+ */
+export type TemplateVariableType = "string" | "number" | "boolean";
+
+/**
+ * Template variable declared in resource frontmatter
+ */
+export interface TemplateVariable {
+  /** Variable name referenced as {{name}} in the body */
+  name: string;
+  /** Expected value type (default: 'string') */
+  type?: TemplateVariableType;
+  /** Value used when the caller does not provide one */
+  default?: string | number | boolean;
+  /** Whether the caller must provide a value (ignored when a default exists) */
+  required?: boolean;
+  /** Human-readable description of the variable */
+  description?: string;
+}
+
+/**
+ * Invalid variable value reported by TemplateError
+ */
+export interface InvalidTemplateVariable {
+  name: string;
+  expected: TemplateVariableType;
+  received: string;
+}
+
+/**
+ * Error raised when provided variables don't satisfy the declarations
+ */
+export class TemplateError extends Error {
+  public code = "TEMPLATE_VARIABLES_INVALID";
+
+  constructor(
+    public missing: string[],
+    public invalid: InvalidTemplateVariable[],
+  ) {
+    const problems: string[] = [];
+    if (missing.length > 0) {
+      problems.push(`missing required variables: ${missing.join(", ")}`);
+    }
+    if (invalid.length > 0) {
+      problems.push(
+        `invalid variables: ${invalid
+          .map((v) => `${v.name} (expected ${v.expected}, got "${v.received}")`)
+          .join(", ")}`,
+      );
+    }
+    super(`Template rendering failed - ${problems.join("; ")}`);
+    this.name = "TemplateError";
+  }
+}
+
+/**
+ * Error raised when conditional blocks are malformed
+ */
+export class TemplateSyntaxError extends Error {
+  public code = "TEMPLATE_SYNTAX_ERROR";
+
+  constructor(message: string) {
+    super(`Template syntax error - ${message}`);
+    this.name = "TemplateSyntaxError";
+  }
+}
+
+/**
+ * Parsed conditional block
+ */
+interface ConditionalBlock {
+  condition: string;
+  then: TemplateNode[];
+  otherwise: TemplateNode[];
+}
+
+/**
+ * Node in a parsed template: literal text or a conditional block
+ */
+type TemplateNode = string | ConditionalBlock;
+
+/**
+ * Value available to placeholders and conditions
+ */
+type TemplateValue = string | number | boolean;
+
+/**
+ * TemplateEngine renders resource bodies with declared variables
+ *
+ * Only placeholders for declared variables are substituted, so bodies that
+ * contain unrelated `{{ }}` syntax (GitHub Actions, Handlebars examples)
+ * are left untouched.
+ */
+export class TemplateEngine {
+  private static readonly PLACEHOLDER = /\{\{\s*([A-Za-z_][\w-]*)\s*\}\}/g;
+  private static readonly BLOCK_TAG = /\{\{\s*(#if\s+([^}]*?)|else|\/if)\s*\}\}/g;
+  private static readonly PARTIAL = /\{\{>\s*([\w./-]+)\s*\}\}/g;
+  private static readonly CONDITION =
+    /^(!)?\s*([A-Za-z_][\w-]*)\s*(?:(==|!=)\s*(.+))?$/;
+
+  /**
+   * Parse variable declarations from frontmatter
+   *
+   * @param frontmatter - Parsed frontmatter data
+   * @returns Array of valid variable declarations (empty if none)
+   */
+  parseDeclarations(frontmatter: any): TemplateVariable[] {
+    if (!frontmatter || !Array.isArray(frontmatter.variables)) {
+      return [];
+    }
+
+    return frontmatter.variables
+      .filter((v: any) => v && typeof v.name === "string" && v.name.length > 0)
+      .map((v: any) => ({
+        name: v.name,
+        type: ["string", "number", "boolean"].includes(v.type)
+          ? v.type
+          : "string",
+        default: v.default,
+        required: v.required === true,
+        description: v.description,
+      }));
+  }
+
+  /**
+   * Resolve provided values against declarations
+   *
+   * @param declared - Variable declarations from frontmatter
+   * @param provided - Raw values supplied by the caller
+   * @returns Map of variable name to typed value
+   * @throws {TemplateError} If required variables are missing or values have the wrong type
+   */
+  resolve(
+    declared: TemplateVariable[],
+    provided: Record<string, string>,
+  ): Record<string, TemplateValue> {
+    const values: Record<string, TemplateValue> = {};
+    const missing: string[] = [];
+    const invalid: InvalidTemplateVariable[] = [];
+
+    for (const variable of declared) {
+      const type = variable.type || "string";
+      const raw = provided[variable.name];
+
+      if (raw === undefined) {
+        if (variable.default !== undefined) {
+          values[variable.name] = variable.default;
+        } else if (variable.required) {
+          missing.push(variable.name);
+        }
+        continue;
+      }
+
+      const coerced = this.coerce(raw, type);
+      if (coerced === undefined) {
+        invalid.push({ name: variable.name, expected: type, received: raw });
+      } else {
+        values[variable.name] = coerced;
+      }
+    }
+
+    if (missing.length > 0 || invalid.length > 0) {
+      throw new TemplateError(missing, invalid);
+    }
+
+    return values;
+  }
+
+  /**
+   * Render a body with declared variables
+   *
+   * Conditional blocks are evaluated first against the resolved variables
+   * (plus any undeclared values the caller provided), then placeholders
+   * are substituted in the surviving text.
+   *
+   * @param body - Resource body containing {{var}} placeholders
+   * @param declared - Variable declarations from frontmatter
+   * @param provided - Raw values supplied by the caller
+   * @returns Rendered body
+   * @throws {TemplateError} If variables fail validation
+   * @throws {TemplateSyntaxError} If conditional blocks are malformed
+   */
+  render(
+    body: string,
+    declared: TemplateVariable[],
+    provided: Record<string, string>,
+  ): string {
+    const values = this.resolve(declared, provided);
+    const names = new Set(declared.map((v) => v.name));
+    const context: Record<string, TemplateValue> = { ...provided, ...values };
+
+    const selected = this.evaluateNodes(this.parseBlocks(body), context);
+
+    return selected.replace(TemplateEngine.PLACEHOLDER, (placeholder, name) => {
+      if (!names.has(name)) {
+        return placeholder;
+      }
+      const value = values[name];
+      return value === undefined ? "" : String(value);
+    });
+  }
+
+  /**
+   * Check whether a body references any partials
+   *
+   * @param body - Resource body
+   * @returns true if the body contains {{> name}} includes
+   */
+  hasPartials(body: string): boolean {
+    return body.includes("{{>");
+  }
+
+  /**
+   * Expand {{> name}} includes recursively
+   *
+   * @param body - Resource body containing partial includes
+   * @param loadPartial - Loads the raw content of a named partial
+   * @param stack - Chain of partials currently being expanded (for cycle detection)
+   * @returns Body with all includes replaced by partial content
+   * @throws {TemplateSyntaxError} If partials include each other in a cycle
+   */
+  async expandPartials(
+    body: string,
+    loadPartial: (name: string) => Promise<string>,
+    stack: string[] = [],
+  ): Promise<string> {
+    if (!this.hasPartials(body)) {
+      return body;
+    }
+
+    let result = "";
+    let lastIndex = 0;
+    for (const match of body.matchAll(TemplateEngine.PARTIAL)) {
+      const index = match.index ?? 0;
+      const name = match[1];
+
+      if (stack.includes(name)) {
+        throw new TemplateSyntaxError(
+          `partial cycle detected: ${[...stack, name].join(" -> ")}`,
+        );
+      }
+
+      const partial = await loadPartial(name);
+      const expanded = await this.expandPartials(partial, loadPartial, [
+        ...stack,
+        name,
+      ]);
+
+      result += body.slice(lastIndex, index) + expanded;
+      lastIndex = index + match[0].length;
+    }
+
+    return result + body.slice(lastIndex);
+  }
+
+  /**
+   * Parse conditional blocks into a node tree
+   * @private
+   */
+  private parseBlocks(body: string): TemplateNode[] {
+    const root: TemplateNode[] = [];
+    const stack: Array<{ block: ConditionalBlock; inElse: boolean }> = [];
+    const current = (): TemplateNode[] => {
+      const top = stack[stack.length - 1];
+      if (!top) return root;
+      return top.inElse ? top.block.otherwise : top.block.then;
+    };
+
+    let lastIndex = 0;
+    for (const match of body.matchAll(TemplateEngine.BLOCK_TAG)) {
+      const index = match.index ?? 0;
+      if (index > lastIndex) {
+        current().push(body.slice(lastIndex, index));
+      }
+      lastIndex = index + match[0].length;
+
+      if (match[1].startsWith("#if")) {
+        const block: ConditionalBlock = {
+          condition: match[2].trim(),
+          then: [],
+          otherwise: [],
+        };
+        current().push(block);
+        stack.push({ block, inElse: false });
+      } else if (match[1] === "else") {
+        const top = stack[stack.length - 1];
+        if (!top || top.inElse) {
+          throw new TemplateSyntaxError("{{else}} without matching {{#if}}");
+        }
+        top.inElse = true;
+      } else {
+        if (!stack.pop()) {
+          throw new TemplateSyntaxError("{{/if}} without matching {{#if}}");
+        }
+      }
+    }
+
+    if (stack.length > 0) {
+      throw new TemplateSyntaxError(
+        `unclosed {{#if ${stack[stack.length - 1].block.condition}}}`,
+      );
+    }
+
+    if (lastIndex < body.length) {
+      root.push(body.slice(lastIndex));
+    }
+
+    return root;
+  }
+
+  /**
+   * Flatten a node tree by evaluating each conditional block
+   * @private
+   */
+  private evaluateNodes(
+    nodes: TemplateNode[],
+    context: Record<string, TemplateValue>,
+  ): string {
+    return nodes
+      .map((node) => {
+        if (typeof node === "string") {
+          return node;
+        }
+        const branch = this.evaluateCondition(node.condition, context)
+          ? node.then
+          : node.otherwise;
+        return this.evaluateNodes(branch, context);
+      })
+      .join("");
+  }
+
+  /**
+   * Evaluate a condition such as `lang`, `!strict`, or `lang == "rust"`
+   * @private
+   */
+  private evaluateCondition(
+    condition: string,
+    context: Record<string, TemplateValue>,
+  ): boolean {
+    const match = condition.match(TemplateEngine.CONDITION);
+    if (!match) {
+      throw new TemplateSyntaxError(`invalid condition "${condition}"`);
+    }
+
+    const [, negate, name, operator, literal] = match;
+    const value = context[name];
+
+    let result: boolean;
+    if (operator) {
+      const equal =
+        value !== undefined && String(value) === this.parseLiteral(literal);
+      result = operator === "==" ? equal : !equal;
+    } else {
+      result =
+        value !== undefined && value !== "" && value !== false && value !== 0;
+    }
+
+    return negate ? !result : result;
+  }
+
+  /**
+   * Parse a comparison literal ("rust", 'rust', 5, true) into its string form
+   * @private
+   */
+  private parseLiteral(literal: string): string {
+    const trimmed = literal.trim();
+    const quoted = trimmed.match(/^(["'])(.*)\1$/);
+    return quoted ? quoted[2] : trimmed;
+  }
+
+  /**
+   * Coerce a raw string value into the declared type
+   * @private
+   */
+  private coerce(
+    raw: string,
+    type: TemplateVariableType,
+  ): TemplateValue | undefined {
+    switch (type) {
+      case "number": {
+        const num = Number(raw);
+        return raw.trim() !== "" && !isNaN(num) ? num : undefined;
+      }
+      case "boolean":
+        if (raw === "true") return true;
+        if (raw === "false") return false;
+        return undefined;
+      default:
+        return raw;
+    }
+  }
+}
+
+export default TemplateEngine;
@@ -0,0 +1,215 @@
+/**
+ * Unicode-aware tokenization shared by matching and body search
+ *
+ * Text is NFKC-normalized and case-folded, then split on anything that is
+ * not a letter, number, mark, underscore, or hyphen, so "CAFÉ" and "café"
+ * are the same keyword and Cyrillic or Greek descriptions match queries in
+ * the same language. Scripts written without spaces (Chinese, Japanese,
+ * Thai) and Korean are split with Intl.Segmenter, or into overlapping
+ * bigrams with O8_CJK_BIGRAMS=true, which needs no dictionary and favours
+ * recall. O8_LOCALE sets the locale for case folding and segmentation
+ * (e.g. `tr` so "I" folds to "ı").
+ *
+ * normalizeText() is the full pipeline used on both sides of a match:
+ * markdown is stripped, text is tokenized, and stop words are removed, so
+ * "the react app" and "React app" produce the same keywords and
+ * "Build the `React` app" contains the phrase "react app". O8_STOP_WORDS
+ * replaces the stop-word list (comma-separated; include `default` to
+ * extend it, or `none` to disable it).
+ *
+ * @example
+ * ```typescript
+ * tokenizeText("Déploiement Kubernetes");           // ["déploiement", "kubernetes"]
+ * tokenizeText("日本語の翻訳", { cjkBigrams: true }); // ["日本", "本語", "語の", "の翻", "翻訳"]
+ * ```
+ */
+
+/**
+ * Tokenizer settings
+ */
+export interface TokenizerOptions {
+  /** BCP 47 locale for case folding and segmentation (default: root) */
+  locale?: string;
+  /** Split unspaced scripts into bigrams instead of dictionary words */
+  cjkBigrams?: boolean;
+}
+
+const WORD = /[\p{L}\p{N}_][\p{L}\p{N}\p{M}_-]*/gu;
+
+const UNSPACED_CLASS =
+  "\\p{Script_Extensions=Han}\\p{Script_Extensions=Hiragana}\\p{Script_Extensions=Katakana}\\p{Script_Extensions=Hangul}\\p{Script_Extensions=Thai}\\p{Script_Extensions=Lao}\\p{Script_Extensions=Khmer}\\p{Script_Extensions=Myanmar}";
+const UNSPACED = new RegExp(`[${UNSPACED_CLASS}]`, "u");
+const SCRIPT_RUNS = new RegExp(
+  `[${UNSPACED_CLASS}]+|[^${UNSPACED_CLASS}]+`,
+  "gu",
+);
+
+const TRUE_VALUES = new Set(["true", "1", "yes", "on"]);
+
+/** Stop words removed from queries and indexed text by default */
+export const DEFAULT_STOP_WORDS: readonly string[] = [
+  "a", "an", "the", "and", "or", "but", "in", "on", "at", "to", "for",
+  "of", "with", "by", "from", "as", "is", "was", "are", "were", "be",
+  "been", "being", "have", "has", "had", "do", "does", "did", "will",
+  "would", "should", "could", "may", "might", "can", "i", "you", "he",
+  "she", "it", "we", "they", "this", "that", "these", "those",
+];
+
+const stopWordCache = new Map<string, ReadonlySet<string>>();
+
+const segmenters = new Map<string, Intl.Segmenter>();
+
+/**
+ * Tokenizer settings from O8_LOCALE and O8_CJK_BIGRAMS
+ *
+ * @param env - Environment (default: process.env)
+ */
+export function tokenizerOptions(
+  env: NodeJS.ProcessEnv = process.env,
+): TokenizerOptions {
+  return {
+    locale: env.O8_LOCALE || undefined,
+    cjkBigrams: TRUE_VALUES.has((env.O8_CJK_BIGRAMS ?? "").toLowerCase()),
+  };
+}
+
+/**
+ * Case-fold text for comparison
+ *
+ * NFKC folds compatibility forms (full-width Latin, ligatures) and the
+ * result is lowercased, with ß as ss and final sigma as σ.
+ *
+ * @param text - Text to fold
+ * @param locale - Locale for lowercasing (default: O8_LOCALE, else root)
+ */
+export function foldCase(
+  text: string,
+  locale: string | undefined = tokenizerOptions().locale,
+): string {
+  const normalized = text.normalize("NFKC");
+  const lower = locale
+    ? normalized.toLocaleLowerCase(locale)
+    : normalized.toLowerCase();
+  return lower.replace(/ß/g, "ss").replace(/ς/g, "σ");
+}
+
+/**
+ * Whether a token is in a script written without spaces
+ */
+export function isUnspaced(token: string): boolean {
+  return UNSPACED.test(token);
+}
+
+/**
+ * Split text into case-folded word tokens
+ *
+ * Single-character tokens are dropped except in unspaced scripts, where
+ * one character can be a whole word.
+ *
+ * @param text - Text to tokenize
+ * @param options - Locale and bigram settings (default: from environment)
+ * @returns Tokens in document order (may repeat)
+ */
+export function tokenizeText(
+  text: string,
+  options: TokenizerOptions = tokenizerOptions(),
+): string[] {
+  const tokens: string[] = [];
+  for (const word of foldCase(text, options.locale).match(WORD) ?? []) {
+    if (!UNSPACED.test(word)) {
+      if (word.length > 1) tokens.push(word);
+      continue;
+    }
+    for (const run of word.match(SCRIPT_RUNS) ?? []) {
+      if (!UNSPACED.test(run)) {
+        const trimmed = run.replace(/^-+|-+$/g, "");
+        if (trimmed.length > 1) tokens.push(trimmed);
+      } else {
+        tokens.push(...splitUnspaced(run, options));
+      }
+    }
+  }
+  return tokens;
+}
+
+/**
+ * Split a run of an unspaced script into words or bigrams
+ */
+function splitUnspaced(run: string, options: TokenizerOptions): string[] {
+  const chars = Array.from(run);
+  if (options.cjkBigrams || typeof Intl.Segmenter !== "function") {
+    if (chars.length === 1) {
+      return chars;
+    }
+    return chars.slice(0, -1).map((char, i) => char + chars[i + 1]);
+  }
+
+  const key = options.locale ?? "";
+  let segmenter = segmenters.get(key);
+  if (!segmenter) {
+    segmenter = new Intl.Segmenter(options.locale, { granularity: "word" });
+    segmenters.set(key, segmenter);
+  }
+  return Array.from(segmenter.segment(run))
+    .filter((segment) => segment.isWordLike)
+    .map((segment) => segment.segment);
+}
+
+/**
+ * Stop words from O8_STOP_WORDS, else the defaults
+ *
+ * @param env - Environment (default: process.env)
+ */
+export function stopWords(
+  env: NodeJS.ProcessEnv = process.env,
+): ReadonlySet<string> {
+  const spec = env.O8_STOP_WORDS ?? "default";
+  let words = stopWordCache.get(spec);
+  if (!words) {
+    const entries = spec
+      .split(",")
+      .map((entry) => foldCase(entry.trim()))
+      .filter(Boolean);
+    words = new Set(
+      entries.flatMap((entry) =>
+        entry === "default" ? DEFAULT_STOP_WORDS : entry === "none" ? [] : [entry],
+      ),
+    );
+    stopWordCache.set(spec, words);
+  }
+  return words;
+}
+
+/**
+ * Remove markdown syntax, keeping the text a reader would see
+ *
+ * Drops fenced code markers, link and image targets, HTML tags, heading
+ * and list markers, and emphasis characters (underscores only at word
+ * edges, so snake_case survives).
+ */
+export function stripMarkdown(text: string): string {
+  return text
+    .replace(/^\s*(```|~~~).*$/gm, " ")
+    .replace(/!?\[([^\]]*)\]\([^)]*\)/g, "$1")
+    .replace(/<[^>\n]+>/g, " ")
+    .replace(/^\s{0,3}(#{1,6}|>|[-*+]|\d+[.)])\s+/gm, "")
+    .replace(/[`*~]+/g, " ")
+    .replace(/(^|\s)_+(?=\S)|(?<=\S)_+(?=\s|$)/gm, "$1");
+}
+
+/**
+ * Tokens for matching: markdown stripped, tokenized, stop words removed
+ *
+ * @param text - Query or indexed text
+ * @param options - Locale and bigram settings (default: from environment)
+ * @returns Tokens in document order (may repeat)
+ */
+export function normalizeText(
+  text: string,
+  options: TokenizerOptions = tokenizerOptions(),
+): string[] {
+  const stop = stopWords();
+  return tokenizeText(stripMarkdown(text), options).filter(
+    (token) => !stop.has(token),
+  );
+}
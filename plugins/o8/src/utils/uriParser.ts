@@ -7,8 +7,25 @@
  *
  * @example Dynamic URI
  * o8://agents/match?query=build+api&maxTokens=2000&tags=typescript,async
+ *
+ * @example Dynamic URI with capability filters
+ * o8://agents/match?query=review&languages=rust&operations=review
+ *
+ * @example Static URI with template variables
+ * o8://agents/code-reviewer?lang=rust
  */
 
+import {
+  PayloadLimits,
+  DEFAULT_PAYLOAD_LIMITS,
+  checkLimit,
+} from "./limits.js";
+import { parseFields } from "./fields.js";
+import {
+  parseCapabilityFilter,
+  type CapabilityFilter,
+} from "./capabilityMatrix.js";
+
 /**
  * Query parameters for dynamic URI matching
  */
@@ -27,6 +44,25 @@ export interface MatchParams {
   maxResults?: number;
   /** Minimum relevance score threshold (0-100, default: 10) */
   minScore?: number;
+  /** Fuzzy scoring deadline in milliseconds (not index lookups); results are partial if it expires */
+  timeoutMs?: number;
+  /** Result fields to include in minimal mode (comma-separated) */
+  fields?: string[];
+  /** Required languages, domains, and operations (comma-separated each) */
+  capabilities?: CapabilityFilter;
+  /** Match against this catalog snapshot instead of the served catalog */
+  snapshot?: string;
+  /** Match against the latest snapshot taken at or before this time (ISO 8601) */
+  asOf?: string;
+}
+
+/**
+ * Defaults for match parameters a URI leaves out
+ */
+export interface MatchDefaults {
+  maxResults?: number;
+  maxTokens?: number;
+  mode?: 'full' | 'catalog' | 'index';
 }
 
 /**
@@ -39,6 +75,10 @@ export interface StaticURI {
   category: string;
   /** Specific resource identifier */
   resourceId: string;
+  /** Template variables from the query string (e.g., '?lang=rust') */
+  variables?: Record<string, string>;
+  /** Only this section of the body (e.g., '?section=output-format') */
+  section?: string;
 }
 
 /**
@@ -81,12 +121,33 @@ export class URIParser {
   private static readonly MATCH_PATH = "/match";
   private static readonly DEFAULT_MAX_TOKENS = 3000;
 
+  private limits: PayloadLimits;
+  private defaults: MatchDefaults = {};
+
+  /**
+   * @param limits - Payload limits for queries, result counts, and variables
+   */
+  constructor(limits: Partial<PayloadLimits> = {}) {
+    this.limits = { ...DEFAULT_PAYLOAD_LIMITS, ...limits };
+  }
+
+  /**
+   * Replace the defaults for match parameters a URI leaves out
+   *
+   * @param defaults - maxResults, maxTokens, and mode (unset keeps the
+   *   built-in default)
+   */
+  setMatchDefaults(defaults: MatchDefaults): void {
+    this.defaults = { ...defaults };
+  }
+
   /**
    * Parse an o8:// URI into its components
    *
    * @param uri - The URI to parse (e.g., 'o8://agents/typescript-developer')
    * @returns Parsed URI with discriminated type
    * @throws {Error} If URI format is invalid
+   * @throws {PayloadLimitError} If a parameter exceeds its limit
    */
   parse(uri: string): ParsedURI {
     // Validate protocol
@@ -121,7 +182,9 @@ export class URIParser {
    * @private
    */
   private parseStaticURI(path: string): StaticURI {
-    const parts = path.split("/");
+    // Split off optional template variables
+    const [resourcePath, queryString] = path.split("?");
+    const parts = resourcePath.split("/");
 
     if (parts.length < 2) {
       throw new Error(
@@ -138,6 +201,29 @@ export class URIParser {
       );
     }
 
+    if (queryString) {
+      checkLimit(
+        "variables",
+        Buffer.byteLength(queryString, "utf8"),
+        this.limits.maxVariableBytes,
+      );
+      // "section" selects part of the body and is not a template variable
+      const params = new URLSearchParams(queryString);
+      const section = params.get("section") || undefined;
+      params.delete("section");
+      const variables: Record<string, string> = {};
+      for (const [key, value] of params) {
+        variables[key] = value;
+      }
+      return {
+        type: "static",
+        category,
+        resourceId,
+        variables,
+        ...(section ? { section } : {}),
+      };
+    }
+
     return {
       type: "static",
       category,
@@ -202,18 +288,20 @@ export class URIParser {
 
     // Decode plus signs and URI encoding
     const decodedQuery = decodeURIComponent(query.replace(/\+/g, " "));
+    checkLimit("query", decodedQuery.length, this.limits.maxQueryLength);
 
     // Parse optional maxTokens
     const maxTokensStr = params.get("maxTokens");
     const maxTokens = maxTokensStr
       ? parseInt(maxTokensStr, 10)
-      : URIParser.DEFAULT_MAX_TOKENS;
+      : this.defaults.maxTokens ?? URIParser.DEFAULT_MAX_TOKENS;
 
     if (maxTokensStr && isNaN(maxTokens)) {
       throw new Error(
         `Invalid maxTokens parameter. Expected number, got: ${maxTokensStr}`,
       );
     }
+    checkLimit("maxTokens", maxTokens, this.limits.maxTokens);
 
     // Parse optional tags (comma-separated)
     const tagsStr = params.get("tags");
@@ -235,22 +323,27 @@ export class URIParser {
 
     // Parse optional mode (default: 'catalog')
     const modeStr = params.get("mode");
-    let mode: 'full' | 'catalog' | 'index' = 'catalog';
+    let mode: 'full' | 'catalog' | 'index' = this.defaults.mode ?? 'catalog';
     if (modeStr === 'full') {
       mode = 'full';
     } else if (modeStr === 'index') {
       mode = 'index';
+    } else if (modeStr === 'catalog') {
+      mode = 'catalog';
     }
 
     // Parse optional maxResults (default: 15)
     const maxResultsStr = params.get("maxResults");
-    const maxResults = maxResultsStr ? parseInt(maxResultsStr, 10) : 15;
+    const maxResults = maxResultsStr
+      ? parseInt(maxResultsStr, 10)
+      : this.defaults.maxResults ?? 15;
 
     if (maxResultsStr && isNaN(maxResults)) {
       throw new Error(
         `Invalid maxResults parameter. Expected number, got: ${maxResultsStr}`,
       );
     }
+    checkLimit("maxResults", maxResults, this.limits.maxResults);
 
     // Parse optional minScore (default: 10)
     const minScoreStr = params.get("minScore");
@@ -262,6 +355,40 @@ export class URIParser {
       );
     }
 
+    // Parse optional timeoutMs (default: no deadline)
+    const timeoutMsStr = params.get("timeoutMs");
+    const timeoutMs = timeoutMsStr ? parseInt(timeoutMsStr, 10) : undefined;
+
+    if (timeoutMs !== undefined && (isNaN(timeoutMs) || timeoutMs < 1)) {
+      throw new Error(
+        `Invalid timeoutMs parameter. Expected positive number, got: ${timeoutMsStr}`,
+      );
+    }
+
+    // Parse optional fields (comma-separated sparse fieldset)
+    const fields = parseFields(params.get("fields"));
+
+    // Parse optional capability filters (languages, domains, operations)
+    const capabilities = parseCapabilityFilter({
+      languages: params.get("languages") ?? undefined,
+      domains: params.get("domains") ?? undefined,
+      operations: params.get("operations") ?? undefined,
+    });
+
+    // Parse optional snapshot or asOf (time-travel queries)
+    const snapshot = params.get("snapshot") || undefined;
+    const asOf = params.get("asOf") || undefined;
+    if (snapshot && asOf) {
+      throw new Error(
+        "Invalid match URI. Use either snapshot or asOf, not both.",
+      );
+    }
+    if (asOf && isNaN(Date.parse(asOf))) {
+      throw new Error(
+        `Invalid asOf parameter. Expected ISO 8601 time, got: ${asOf}`,
+      );
+    }
+
     return {
       query: decodedQuery,
       maxTokens,
@@ -270,6 +397,11 @@ export class URIParser {
       mode,
       maxResults,
       minScore,
+      ...(timeoutMs !== undefined ? { timeoutMs } : {}),
+      ...(fields ? { fields } : {}),
+      ...(capabilities ? { capabilities } : {}),
+      ...(snapshot ? { snapshot } : {}),
+      ...(asOf ? { asOf } : {}),
     };
   }
 
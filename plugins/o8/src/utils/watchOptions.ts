@@ -0,0 +1,149 @@
+/**
+ * File watcher settings
+ *
+ * Native file events (inotify, FSEvents) are not delivered for bind mounts,
+ * NFS, and many dev container volumes, so edits there never trigger a
+ * reload. `--watch-mode poll` stats files on an interval instead; `auto`
+ * (the default) polls when it detects a dev container. Watchers follow
+ * symlinked directories in both modes.
+ *
+ * Native watchers hold a descriptor (an inotify watch or an open directory
+ * handle) per watched directory. With `--max-watch-descriptors`, a tree
+ * that needs more falls back to polling rather than exhausting the
+ * process's file descriptors.
+ *
+ * @example
+ * node dist/index.js --watch-mode poll --poll-interval 2000
+ */
+
+import { existsSync, readdirSync } from "fs";
+import { join } from "path";
+import type { ChokidarOptions } from "chokidar";
+import { flagValue } from "./flags.js";
+
+export const WATCH_MODES = ["auto", "native", "poll"] as const;
+
+export type WatchMode = (typeof WATCH_MODES)[number];
+
+export interface WatchSettings {
+  /** Resolved mode: auto has been decided */
+  mode: "native" | "poll";
+  /** Milliseconds between polls */
+  pollInterval: number;
+  /** Most directories a native watcher may watch (unset: unlimited) */
+  maxDescriptors?: number;
+}
+
+/**
+ * Default milliseconds between polls
+ */
+export const DEFAULT_POLL_INTERVAL = 1000;
+
+/**
+ * Whether the process looks like it runs in a dev container or Codespace
+ */
+function inDevContainer(env: NodeJS.ProcessEnv): boolean {
+  return (
+    env.REMOTE_CONTAINERS === "true" ||
+    env.CODESPACES === "true" ||
+    existsSync("/.dockerenv")
+  );
+}
+
+/**
+ * Watcher settings from --watch-mode, --poll-interval, and
+ * --max-watch-descriptors (ORCHESTR8_WATCH_MODE, ORCHESTR8_POLL_INTERVAL,
+ * ORCHESTR8_MAX_WATCH_DESCRIPTORS)
+ *
+ * @throws {Error} If the mode, interval, or limit is invalid
+ */
+export function watchSettings(
+  argv: string[] = process.argv,
+  env: NodeJS.ProcessEnv = process.env,
+  detectContainer: (env: NodeJS.ProcessEnv) => boolean = inDevContainer,
+): WatchSettings {
+  const mode = flagValue("watch-mode", argv, env) ?? "auto";
+  if (!(WATCH_MODES as readonly string[]).includes(mode)) {
+    throw new Error(
+      `Invalid --watch-mode: ${mode} (expected ${WATCH_MODES.join(", ")})`,
+    );
+  }
+
+  const intervalValue = flagValue("poll-interval", argv, env);
+  const pollInterval =
+    intervalValue === undefined ? DEFAULT_POLL_INTERVAL : Number(intervalValue);
+  if (!Number.isInteger(pollInterval) || pollInterval < 100) {
+    throw new Error(
+      `Invalid --poll-interval: ${intervalValue} (expected milliseconds, at least 100)`,
+    );
+  }
+
+  const limitValue = flagValue("max-watch-descriptors", argv, env);
+  const maxDescriptors =
+    limitValue === undefined ? undefined : Number(limitValue);
+  if (
+    maxDescriptors !== undefined &&
+    (!Number.isInteger(maxDescriptors) || maxDescriptors < 1)
+  ) {
+    throw new Error(
+      `Invalid --max-watch-descriptors: ${limitValue} (expected a positive integer)`,
+    );
+  }
+
+  return {
+    mode: mode === "auto" ? (detectContainer(env) ? "poll" : "native") : mode,
+    pollInterval,
+    ...(maxDescriptors !== undefined ? { maxDescriptors } : {}),
+  };
+}
+
+/**
+ * Settings for watching one directory tree: polling when a native watcher
+ * would need more than `maxDescriptors` descriptors
+ *
+ * @param settings - Configured settings
+ * @param root - Directory to watch
+ */
+export function settingsForTree(
+  settings: WatchSettings,
+  root: string,
+): WatchSettings {
+  const limit = settings.maxDescriptors;
+  if (settings.mode !== "native" || limit === undefined) {
+    return settings;
+  }
+
+  // Count directories, stopping as soon as the limit is exceeded
+  let count = 0;
+  const pending = [root];
+  while (pending.length > 0 && count <= limit) {
+    const dir = pending.pop()!;
+    let entries;
+    try {
+      entries = readdirSync(dir, { withFileTypes: true });
+    } catch {
+      continue;
+    }
+    count++;
+    for (const entry of entries) {
+      if (entry.isDirectory()) {
+        pending.push(join(dir, entry.name));
+      }
+    }
+  }
+  return count > limit ? { ...settings, mode: "poll" } : settings;
+}
+
+/**
+ * chokidar options for the settings
+ */
+export function chokidarOptions(settings: WatchSettings): ChokidarOptions {
+  return {
+    persistent: true,
+    ignoreInitial: true,
+    followSymlinks: true,
+    usePolling: settings.mode === "poll",
+    interval: settings.pollInterval,
+    binaryInterval: settings.pollInterval,
+  };
+}
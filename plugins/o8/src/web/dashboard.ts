@@ -0,0 +1,130 @@
+/**
+ * Read-only admin dashboard
+ *
+ * A single self-contained HTML page (inline styles, no scripts or external
+ * assets) served at `/admin` when `ORCHESTR8_ADMIN_DASHBOARD` is on, so
+ * operators can glance at catalog size, recent requests and queries, and
+ * load errors without an MCP client or the full web UI.
+ */
+
+import type { ActivityEvent } from "../stats/collector.js";
+import type { ScanFailure } from "../utils/catalogSummary.js";
+
+/**
+ * Everything the dashboard shows
+ */
+export interface DashboardData {
+  catalog: {
+    generation: number;
+    loadedAt: string | null;
+    resources: number;
+    pinned: string | null;
+    reloading: boolean;
+  };
+  byCategory: Record<string, number>;
+  failures: ScanFailure[];
+  /** Most frequent queries from the query log; null when it is off */
+  topQueries: Array<{ query: string; count: number; zeroResults: number }> | null;
+  activity: ActivityEvent[];
+}
+
+/**
+ * Escape text for HTML element content and attribute values
+ */
+export function escapeHtml(text: string): string {
+  return text
+    .replace(/&/g, "&amp;")
+    .replace(/</g, "&lt;")
+    .replace(/>/g, "&gt;")
+    .replace(/"/g, "&quot;")
+    .replace(/'/g, "&#39;");
+}
+
+function table(headers: string[], rows: string[][], empty: string): string {
+  if (rows.length === 0) {
+    return `<p class="empty">${escapeHtml(empty)}</p>`;
+  }
+  const head = headers.map((header) => `<th>${escapeHtml(header)}</th>`).join("");
+  const body = rows
+    .map((row) => `<tr>${row.map((cell) => `<td>${escapeHtml(cell)}</td>`).join("")}</tr>`)
+    .join("\n");
+  return `<table><thead><tr>${head}</tr></thead><tbody>\n${body}\n</tbody></table>`;
+}
+
+/**
+ * Render the dashboard page
+ */
+export function renderDashboard(data: DashboardData): string {
+  const { catalog } = data;
+  const categories = Object.entries(data.byCategory).map(([category, count]) => [
+    category,
+    String(count),
+  ]);
+  const requests = data.activity
+    .filter((event) => event.type === "mcp_request")
+    .reverse()
+    .map((event) => [
+      new Date(event.timestamp).toISOString(),
+      String(event.data?.method ?? ""),
+      String(event.data?.client ?? ""),
+      `${event.data?.latency ?? 0} ms`,
+    ]);
+
+  return `<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>orchestr8 admin</title>
+<style>
+body { font-family: system-ui, sans-serif; margin: 2rem; color: #222; }
+h1 { font-size: 1.4rem; }
+h2 { font-size: 1.1rem; margin-top: 2rem; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.25rem 0.75rem; text-align: left; }
+.empty { color: #777; }
+.error { color: #b00020; }
+</style>
+</head>
+<body>
+<h1>orchestr8 admin</h1>
+<h2>Catalog</h2>
+${table(
+  ["Resources", "Generation", "Loaded", "Pinned snapshot", "Reloading"],
+  [
+    [
+      String(catalog.resources),
+      String(catalog.generation),
+      catalog.loadedAt ?? "-",
+      catalog.pinned ?? "-",
+      catalog.reloading ? "yes" : "no",
+    ],
+  ],
+  "",
+)}
+${table(["Category", "Resources"], categories, "No resources loaded.")}
+<h2 class="${data.failures.length > 0 ? "error" : ""}">Load errors (${data.failures.length})</h2>
+${table(
+  ["File", "Error"],
+  data.failures.map((failure) => [failure.path, failure.error]),
+  "No load errors.",
+)}
+<h2>Top queries</h2>
+${
+  data.topQueries === null
+    ? `<p class="empty">The query log is off (set O8_QUERY_LOG to record queries).</p>`
+    : table(
+        ["Query", "Count", "No results"],
+        data.topQueries.map((entry) => [
+          entry.query,
+          String(entry.count),
+          String(entry.zeroResults),
+        ]),
+        "No queries logged yet.",
+      )
+}
+<h2>Recent requests</h2>
+${table(["Time", "Method", "Client", "Latency"], requests, "No requests yet.")}
+</body>
+</html>
+`;
+}
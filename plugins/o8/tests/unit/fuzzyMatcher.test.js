@@ -220,6 +220,26 @@ describe("FuzzyMatcher", () => {
           (keywords.includes("machine") && keywords.includes("learning")),
       );
     });
+
+    it("should keep accented and non-Latin words", () => {
+      setup();
+      const keywords = matcher.extractKeywords("Déploiement DOCKER für Straße, развёртывание!");
+
+      assert.deepStrictEqual(keywords, [
+        "déploiement",
+        "docker",
+        "für",
+        "strasse",
+        "развёртывание",
+      ]);
+    });
+
+    it("should split Japanese queries into words", () => {
+      setup();
+      const keywords = matcher.extractKeywords("翻訳エージェント");
+
+      assert.deepStrictEqual(keywords, ["翻訳", "エージェント"]);
+    });
   });
 
   describe("Score Calculation", () => {
@@ -712,6 +732,162 @@ describe("FuzzyMatcher", () => {
       assert.ok(result.assembledContent.length > 0);
       assert.ok(result.assembledContent.includes("##"));
     });
+
+    it("should return a highlighted snippet per fragment", async () => {
+      setup();
+      const result = await matcher.match({
+        query: "typescript",
+        mode: "minimal",
+      });
+
+      assert.strictEqual(result.snippets.length, result.fragments.length);
+      const index = result.fragments.findIndex(
+        (f) => f.id === "typescript-developer",
+      );
+      assert.match(result.snippets[index], /\*\*TypeScript\*\*/);
+
+      const output = JSON.parse(result.assembledContent);
+      assert.match(output.results[index].snippet, /\*\*TypeScript\*\*/);
+    });
+
+    it("should fall back to useWhen when the body does not match", () => {
+      setup();
+      const [resource] = createTestResources();
+      const snippet = matcher.buildSnippet(resource, ["building"]);
+
+      assert.match(snippet, /\*\*Building\*\* TypeScript applications/);
+      assert.strictEqual(matcher.buildSnippet(resource, ["kubernetes"]), undefined);
+    });
+
+    it("should return best-so-far results flagged partial on timeout", async () => {
+      setup();
+      const score = matcher.calculateScore.bind(matcher);
+      matcher.calculateScore = (...args) => {
+        const until = Date.now() + 5;
+        while (Date.now() < until);
+        return score(...args);
+      };
+
+      const result = await matcher.match({
+        query: "typescript",
+        mode: "minimal",
+        minScore: 0,
+        timeoutMs: 1,
+      });
+
+      assert.strictEqual(result.partial, true);
+      assert.strictEqual(result.fragments.length, 1);
+      assert.strictEqual(JSON.parse(result.assembledContent).partial, true);
+    });
+
+    it("should restrict minimal results to the requested fields", async () => {
+      setup();
+      const result = await matcher.match({
+        query: "typescript",
+        mode: "minimal",
+        fields: ["uri", "score"],
+      });
+
+      const output = JSON.parse(result.assembledContent);
+      assert.ok(output.results.length > 0);
+      for (const entry of output.results) {
+        assert.deepStrictEqual(Object.keys(entry), ["uri", "score"]);
+      }
+      assert.ok(output.totalTokens > 0);
+    });
+
+    it("should not flag results when scoring finishes in time", async () => {
+      setup();
+      const result = await matcher.match({
+        query: "typescript",
+        timeoutMs: 10000,
+      });
+
+      assert.strictEqual(result.partial, undefined);
+      assert.ok(!result.assembledContent.includes("Partial Results"));
+    });
+
+    it("should drop resources below their own minConfidence", async () => {
+      const resources = createTestResources();
+      resources[0].minConfidence = 1000;
+      matcher = new FuzzyMatcher();
+      matcher.setResourceIndex(resources);
+
+      const result = await matcher.match({ query: "typescript async" });
+
+      assert.ok(result.fragments.length > 0);
+      assert.ok(!result.fragments.some((f) => f.id === "typescript-developer"));
+    });
+
+    it("should flag an explicit no confident match", async () => {
+      setup();
+      const minimal = await matcher.match({
+        query: "kubernetes helm",
+        mode: "minimal",
+      });
+
+      assert.strictEqual(minimal.noConfidentMatch, true);
+      assert.deepStrictEqual(minimal.fragments, []);
+      const output = JSON.parse(minimal.assembledContent);
+      assert.strictEqual(output.noConfidentMatch, true);
+      assert.deepStrictEqual(output.results, []);
+
+      const catalog = await matcher.match({ query: "kubernetes helm" });
+      assert.match(catalog.assembledContent, /\*\*No Confident Match:\*\*/);
+
+      const full = await matcher.match({ query: "kubernetes helm", mode: "full" });
+      assert.match(full.assembledContent, /No Confident Match/);
+
+      const found = await matcher.match({ query: "typescript" });
+      assert.strictEqual(found.noConfidentMatch, undefined);
+    });
+
+    it("should suggest close tags and agent names when nothing matches", async () => {
+      setup();
+      const minimal = await matcher.match({ query: "pythno", mode: "minimal" });
+      assert.strictEqual(minimal.noConfidentMatch, true);
+      assert.deepStrictEqual(minimal.didYouMean, ["python"]);
+      assert.deepStrictEqual(JSON.parse(minimal.assembledContent).didYouMean, ["python"]);
+
+      const catalog = await matcher.match({ query: "pythno" });
+      assert.match(catalog.assembledContent, /\*\*Did You Mean:\*\* python/);
+
+      assert.deepStrictEqual(
+        await matcher.suggest("error-handlng"),
+        ["error-handling"],
+      );
+
+      const found = await matcher.match({ query: "python" });
+      assert.strictEqual(found.didYouMean, undefined);
+    });
+
+    it("should return the category fallback when nothing matches", async () => {
+      const resources = createTestResources();
+      resources.push(
+        { ...resources[0], id: "generalist", fallback: true, minConfidence: 1000 },
+        { ...resources[1], id: "async-basics", fallback: true },
+      );
+      matcher = new FuzzyMatcher();
+      matcher.setResourceIndex(resources);
+
+      const agents = await matcher.match({ query: "kubernetes helm", mode: "minimal" });
+      assert.strictEqual(agents.noConfidentMatch, true);
+      assert.strictEqual(agents.fallback, true);
+      assert.deepStrictEqual(agents.fragments.map((f) => f.id), ["generalist"]);
+      assert.strictEqual(JSON.parse(agents.assembledContent).fallback, true);
+
+      const skills = await matcher.match({
+        query: "kubernetes helm",
+        category: "skill",
+        minScore: 25,
+      });
+      assert.deepStrictEqual(skills.fragments.map((f) => f.id), ["async-basics"]);
+      assert.match(skills.assembledContent, /\*\*Fallback:\*\*/);
+
+      const found = await matcher.match({ query: "typescript" });
+      assert.strictEqual(found.fallback, undefined);
+      assert.ok(!found.fragments.some((f) => f.id === "generalist"));
+    });
   });
 
   describe("Edge Cases and Error Handling", () => {
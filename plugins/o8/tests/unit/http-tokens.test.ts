@@ -62,6 +62,62 @@ class MockMCPServer {
     return [];
   }
 
+  async searchBodies(query: string, options?: any) {
+    return [];
+  }
+
+  async sampleResources(options?: any) {
+    return { seed: 0, resources: [] };
+  }
+
+  async *streamResources(options?: any) {}
+
+  async getCatalogSummary(options?: any) {
+    return { total: 0 };
+  }
+
+  async getResourceHistory(id: string, limit?: number) {
+    return [];
+  }
+
+  async getResourceProvenance(id: string) {
+    return null;
+  }
+
+  async resolveAlias(id: string) {
+    return null;
+  }
+
+  async getQueryLogSummary(limit?: number) {
+    return {
+      enabled: false,
+      entries: 0,
+      zeroResults: 0,
+      topQueries: [],
+      topZeroResultQueries: [],
+    };
+  }
+
+  getCatalogState() {
+    return {
+      generation: 1,
+      loadedAt: null,
+      resources: 0,
+      pinned: null,
+      reloading: false,
+      indexing: null,
+      watchdog: { restarts: 0, lastStall: null },
+    };
+  }
+
+  getUpstreamStatus() {
+    return {};
+  }
+
+  async diffResource(id: string, from?: string, to?: string) {
+    return { id, from: from ?? "HEAD", to: to ?? "working", diff: "" };
+  }
+
   async getResourceContent(uri: string) {
     return 'test content';
   }
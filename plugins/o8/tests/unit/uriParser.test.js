@@ -51,6 +51,25 @@ describe("URIParser", () => {
       });
     });
 
+    it("should parse template variables from static URI query string", () => {
+      setup();
+      const uri = "o8://agents/code-reviewer?lang=rust&strict=true";
+      const result = parser.parse(uri);
+
+      assert.strictEqual(result.type, "static");
+      assert.strictEqual(result.category, "agents");
+      assert.strictEqual(result.resourceId, "code-reviewer");
+      assert.deepStrictEqual(result.variables, { lang: "rust", strict: "true" });
+    });
+
+    it("should parse section apart from template variables", () => {
+      setup();
+      const result = parser.parse("o8://agents/code-reviewer?lang=rust&section=output-format");
+
+      assert.strictEqual(result.section, "output-format");
+      assert.deepStrictEqual(result.variables, { lang: "rust" });
+    });
+
     it("should throw error for static URI with missing resource ID", () => {
       setup();
       const uri = "o8://agents/";
@@ -237,6 +256,54 @@ describe("URIParser", () => {
         parser.parse(uri);
       }, /Invalid maxTokens parameter/);
     });
+
+    it("should parse timeoutMs", () => {
+      setup();
+      const result = parser.parse("o8://match?query=test&timeoutMs=250");
+
+      assert.strictEqual(result.matchParams.timeoutMs, 250);
+      assert.strictEqual(
+        parser.parse("o8://match?query=test").matchParams.timeoutMs,
+        undefined,
+      );
+    });
+
+    it("should parse fields", () => {
+      setup();
+      const result = parser.parse("o8://match?query=test&mode=minimal&fields=uri,score");
+
+      assert.deepStrictEqual(result.matchParams.fields, ["uri", "score"]);
+    });
+
+    it("should throw error for invalid timeoutMs", () => {
+      setup();
+
+      assert.throws(() => {
+        parser.parse("o8://match?query=test&timeoutMs=0");
+      }, /Invalid timeoutMs parameter/);
+      assert.throws(() => {
+        parser.parse("o8://match?query=test&timeoutMs=soon");
+      }, /Invalid timeoutMs parameter/);
+    });
+
+    it("should parse snapshot and asOf", () => {
+      setup();
+
+      assert.strictEqual(
+        parser.parse("o8://match?query=test&snapshot=v1").matchParams.snapshot,
+        "v1",
+      );
+      assert.strictEqual(
+        parser.parse("o8://match?query=test&asOf=2026-01-01").matchParams.asOf,
+        "2026-01-01",
+      );
+      assert.throws(() => {
+        parser.parse("o8://match?query=test&snapshot=v1&asOf=2026-01-01");
+      }, /either snapshot or asOf/);
+      assert.throws(() => {
+        parser.parse("o8://match?query=test&asOf=yesterday");
+      }, /Invalid asOf parameter/);
+    });
   });
 
   describe("Invalid URI Handling", () => {
@@ -448,6 +515,46 @@ describe("URIParser", () => {
     });
   });
 
+  describe("Payload Limits", () => {
+    it("should reject maxResults above the limit", () => {
+      const limited = new URIParser({ maxResults: 50 });
+
+      assert.throws(
+        () => limited.parse("o8://match?query=test&maxResults=51"),
+        (error) => {
+          assert.strictEqual(error.code, "PAYLOAD_TOO_LARGE");
+          assert.strictEqual(error.field, "maxResults");
+          assert.strictEqual(error.limit, 50);
+          return true;
+        },
+      );
+      assert.strictEqual(
+        limited.parse("o8://match?query=test&maxResults=50").matchParams
+          .maxResults,
+        50,
+      );
+    });
+
+    it("should reject oversized queries", () => {
+      const limited = new URIParser({ maxQueryLength: 10 });
+
+      assert.throws(
+        () => limited.parse("o8://match?query=" + "a".repeat(11)),
+        /query exceeds limit/,
+      );
+    });
+
+    it("should reject oversized template variables", () => {
+      const limited = new URIParser({ maxVariableBytes: 16 });
+
+      assert.throws(
+        () =>
+          limited.parse("o8://agents/code-reviewer?context=" + "x".repeat(32)),
+        /variables exceeds limit/,
+      );
+    });
+  });
+
   describe("Real-world URI Examples", () => {
     it("should parse typical agent query URI", () => {
       setup();